@@ -1,7 +1,14 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
-use tonic_lnd::lnrpc::{GetInfoRequest, ListChannelsRequest};
+use tonic_lnd::lnrpc::{
+    channel_point, close_status_update, policy_update_request, ChanInfoRequest,
+    ChannelGraphRequest, ChannelPoint, CloseChannelRequest, ClosedChannelsRequest,
+    FeeReportRequest, ForwardingHistoryRequest, GetInfoRequest, GetTransactionsRequest,
+    ListChannelsRequest, ListInvoiceRequest, ListPaymentsRequest, PolicyUpdateRequest,
+    RoutingPolicy,
+};
 use tracing::{info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +69,172 @@ pub struct LocalChannelBalance {
     pub pending_open_balance: u64,
 }
 
+/// Forward HTLC réussi, tel que rapporté par `ForwardingHistory`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalForwardingEvent {
+    pub timestamp_ns: u64,
+    pub chan_id_in: String,
+    pub chan_id_out: String,
+    pub amt_in_msat: u64,
+    pub amt_out_msat: u64,
+    pub fee_msat: u64,
+}
+
+/// Politique de frais locale d'un canal (`FeeReport`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalChannelFeePolicy {
+    pub channel_id: String,
+    pub channel_point: String,
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u32,
+}
+
+/// Politique de routage annoncée pour un sens d'un canal du graphe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalRoutingPolicy {
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u64,
+    pub time_lock_delta: u32,
+    pub disabled: bool,
+    pub last_update: u32,
+}
+
+/// Nœud public du graphe (`DescribeGraph`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalGraphNode {
+    pub pubkey: String,
+    pub alias: String,
+    pub last_update: u32,
+    /// Bits de fonctionnalités annoncés
+    pub features: Vec<u32>,
+}
+
+/// Canal public du graphe.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalGraphEdge {
+    pub channel_id: String,
+    pub node1_pub: String,
+    pub node2_pub: String,
+    pub capacity: u64,
+    pub node1_policy: Option<LocalRoutingPolicy>,
+    pub node2_policy: Option<LocalRoutingPolicy>,
+}
+
+/// Graphe public du réseau tel que vu par LND.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalNetworkGraph {
+    pub nodes: Vec<LocalGraphNode>,
+    pub edges: Vec<LocalGraphEdge>,
+}
+
+/// Mode de fermeture d'un canal (`ChannelCloseSummary.ClosureType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LocalCloseType {
+    Cooperative,
+    LocalForce,
+    RemoteForce,
+    Breach,
+    FundingCanceled,
+    Abandoned,
+}
+
+impl LocalCloseType {
+    fn from_lnd(value: i32) -> Self {
+        match value {
+            0 => Self::Cooperative,
+            1 => Self::LocalForce,
+            2 => Self::RemoteForce,
+            3 => Self::Breach,
+            4 => Self::FundingCanceled,
+            _ => Self::Abandoned,
+        }
+    }
+
+    pub fn is_force(self) -> bool {
+        matches!(self, Self::LocalForce | Self::RemoteForce | Self::Breach)
+    }
+}
+
+/// Canal fermé (`ClosedChannels`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalClosedChannel {
+    pub channel_id: String,
+    pub remote_pubkey: String,
+    pub capacity: u64,
+    pub close_height: u32,
+    pub close_type: LocalCloseType,
+    pub channel_point: String,
+    pub closing_tx_hash: String,
+}
+
+/// Tentative HTLC d'un de nos paiements, rattachée au canal de premier saut.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalHtlcAttempt {
+    pub first_hop_channel_id: String,
+    pub attempt_time_ns: u64,
+    pub succeeded: bool,
+    /// Échec signalé par notre pair direct (index de source 1)
+    pub failed_at_peer: bool,
+}
+
+/// Paiement circulaire réglé : sorti par un de nos canaux, revenu par un autre.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalRebalance {
+    pub outgoing_channel_id: String,
+    pub incoming_channel_id: String,
+    pub amount_msat: u64,
+    pub fee_msat: u64,
+    pub settled_at_ns: u64,
+}
+
+/// Paiement sortant réglé, rééquilibrages compris.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalPayment {
+    pub payment_hash: String,
+    pub value_msat: u64,
+    pub fee_msat: u64,
+    pub settled_at_ns: u64,
+    /// Paiement revenu jusqu'à nous par un autre canal
+    pub circular: bool,
+}
+
+/// Facture réglée, keysend compris.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalInvoice {
+    pub payment_hash: String,
+    pub memo: String,
+    pub amt_paid_msat: u64,
+    /// Secondes UNIX
+    pub settled_at: i64,
+    pub keysend: bool,
+}
+
+/// Transaction on-chain touchant notre portefeuille.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalWalletTransaction {
+    pub tx_hash: String,
+    /// Variation nette du solde du portefeuille, frais compris
+    pub amount_sat: i64,
+    /// Frais payés par le portefeuille, nuls s'il n'a financé aucune entrée
+    pub fee_sat: u64,
+    /// Secondes UNIX
+    pub timestamp: i64,
+    pub label: String,
+}
+
+/// Nombre de paiements récents examinés pour les tentatives HTLC
+const PAYMENTS_LOOKBACK: u64 = 1_000;
+
+/// Taille de page utilisée pour paginer `ForwardingHistory`
+const FORWARDING_PAGE_SIZE: u32 = 10_000;
+/// Taille des pages de paiements et de factures pour les historiques complets
+const HISTORY_PAGE_SIZE: u64 = 1_000;
+/// `InvoiceState::SETTLED`
+const INVOICE_SETTLED: i32 = 1;
+/// `Payment::PaymentStatus::SUCCEEDED` et `HTLCAttempt::HTLCStatus::SUCCEEDED`
+const PAYMENT_SUCCEEDED: i32 = 2;
+const HTLC_SUCCEEDED: i32 = 1;
+
 pub struct LocalLightningClient {
     client: Option<tonic_lnd::Client>,
     node_uri: String,
@@ -69,6 +242,27 @@ pub struct LocalLightningClient {
     macaroon_path: String,
 }
 
+/// Point de canal `txid:index` au format attendu par LND.
+fn parse_channel_point(channel_point: &str) -> Result<ChannelPoint, String> {
+    let (txid, index) = channel_point
+        .split_once(':')
+        .ok_or_else(|| format!("invalid channel point {}", channel_point))?;
+    let output_index = index
+        .parse()
+        .map_err(|_| format!("invalid channel point {}", channel_point))?;
+    Ok(ChannelPoint {
+        funding_txid: Some(channel_point::FundingTxid::FundingTxidStr(txid.to_string())),
+        output_index,
+    })
+}
+
+/// Identifiant de transaction affiché, à partir des octets renvoyés par LND.
+fn reversed_hex(txid: &[u8]) -> String {
+    let mut bytes = txid.to_vec();
+    bytes.reverse();
+    hex::encode(bytes)
+}
+
 #[allow(dead_code)]
 fn _assert_local_client_bounds()
 where
@@ -103,7 +297,7 @@ impl LocalLightningClient {
         Ok(client_instance)
     }
 
-    async fn connect(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    async fn connect(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         if !Path::new(&self.cert_path).exists() {
             return Err(format!("TLS certificate not found at: {}", self.cert_path).into());
         }
@@ -122,7 +316,7 @@ impl LocalLightningClient {
 
     async fn ensure_connected(
         &mut self,
-    ) -> Result<&mut tonic_lnd::Client, Box<dyn std::error::Error>> {
+    ) -> Result<&mut tonic_lnd::Client, Box<dyn std::error::Error + Send + Sync>> {
         if self.client.is_none() {
             self.connect().await?;
         }
@@ -132,6 +326,11 @@ impl LocalLightningClient {
             .ok_or_else(|| "Failed to establish LND connection".into())
     }
 
+    /// Vrai si une connexion gRPC à LND est établie (faux en mode mock).
+    pub fn is_connected(&self) -> bool {
+        self.client.is_some()
+    }
+
     pub async fn get_local_node_info(
         &mut self,
     ) -> Result<LocalNodeInfo, Box<dyn std::error::Error>> {
@@ -245,6 +444,446 @@ impl LocalLightningClient {
         }
     }
 
+    /// Historique des forwards entre `start` et `end` (secondes UNIX), toutes pages confondues.
+    pub async fn forwarding_history(
+        &mut self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<LocalForwardingEvent>> {
+        info!("Fetching forwarding history from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let mut events = vec![];
+                let mut index_offset = 0;
+                loop {
+                    let request = ForwardingHistoryRequest {
+                        start_time: start,
+                        end_time: end,
+                        index_offset,
+                        num_max_events: FORWARDING_PAGE_SIZE,
+                    };
+                    let response = client
+                        .lightning()
+                        .forwarding_history(request)
+                        .await?
+                        .into_inner();
+
+                    let page_len = response.forwarding_events.len();
+                    events.extend(response.forwarding_events.into_iter().map(|event| {
+                        LocalForwardingEvent {
+                            timestamp_ns: event.timestamp_ns,
+                            chan_id_in: event.chan_id_in.to_string(),
+                            chan_id_out: event.chan_id_out.to_string(),
+                            amt_in_msat: event.amt_in_msat,
+                            amt_out_msat: event.amt_out_msat,
+                            fee_msat: event.fee_msat,
+                        }
+                    }));
+
+                    if page_len < FORWARDING_PAGE_SIZE as usize {
+                        break;
+                    }
+                    index_offset = response.last_offset_index;
+                }
+                Ok(events)
+            }
+            Err(e) => {
+                // Pas d'historique fictif : un modèle entraîné sur des données simulées serait trompeur
+                warn!("Failed to connect to LND, no forwarding history: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Graphe public complet (nœuds et canaux annoncés).
+    pub async fn describe_graph(&mut self) -> Result<LocalNetworkGraph> {
+        info!("Fetching network graph from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let graph = client
+                    .lightning()
+                    .describe_graph(ChannelGraphRequest::default())
+                    .await?
+                    .into_inner();
+
+                let policy = |policy: Option<RoutingPolicy>| {
+                    policy.map(|p| LocalRoutingPolicy {
+                        base_fee_msat: p.fee_base_msat.max(0) as u64,
+                        fee_rate_ppm: p.fee_rate_milli_msat.max(0) as u64,
+                        time_lock_delta: p.time_lock_delta,
+                        disabled: p.disabled,
+                        last_update: p.last_update,
+                    })
+                };
+
+                Ok(LocalNetworkGraph {
+                    nodes: graph
+                        .nodes
+                        .into_iter()
+                        .map(|node| {
+                            let mut features: Vec<u32> = node.features.keys().copied().collect();
+                            features.sort_unstable();
+                            LocalGraphNode {
+                                pubkey: node.pub_key,
+                                alias: node.alias,
+                                last_update: node.last_update,
+                                features,
+                            }
+                        })
+                        .collect(),
+                    edges: graph
+                        .edges
+                        .into_iter()
+                        .map(|edge| LocalGraphEdge {
+                            channel_id: edge.channel_id.to_string(),
+                            node1_pub: edge.node1_pub,
+                            node2_pub: edge.node2_pub,
+                            capacity: edge.capacity.max(0) as u64,
+                            node1_policy: policy(edge.node1_policy),
+                            node2_policy: policy(edge.node2_policy),
+                        })
+                        .collect(),
+                })
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, empty network graph: {}", e);
+                Ok(LocalNetworkGraph::default())
+            }
+        }
+    }
+
+    /// Historique des canaux fermés, tous modes de fermeture confondus.
+    pub async fn closed_channels(&mut self) -> Result<Vec<LocalClosedChannel>> {
+        info!("Fetching closed channels from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let response = client
+                    .lightning()
+                    .closed_channels(ClosedChannelsRequest::default())
+                    .await?
+                    .into_inner();
+
+                Ok(response
+                    .channels
+                    .into_iter()
+                    .map(|channel| LocalClosedChannel {
+                        channel_id: channel.chan_id.to_string(),
+                        remote_pubkey: channel.remote_pubkey,
+                        capacity: channel.capacity.max(0) as u64,
+                        close_height: channel.close_height,
+                        close_type: LocalCloseType::from_lnd(channel.close_type),
+                        channel_point: channel.channel_point,
+                        closing_tx_hash: channel.closing_tx_hash,
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no closed channels: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Tentatives HTLC résolues des paiements récents (réussies ou échouées).
+    pub async fn htlc_attempts(&mut self) -> Result<Vec<LocalHtlcAttempt>> {
+        info!("Fetching recent payment attempts from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let response = client
+                    .lightning()
+                    .list_payments(ListPaymentsRequest {
+                        include_incomplete: true,
+                        max_payments: PAYMENTS_LOOKBACK,
+                        reversed: true,
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                // Statuts HTLCAttempt : 0 en cours, 1 réussi, 2 échoué
+                Ok(response
+                    .payments
+                    .into_iter()
+                    .flat_map(|payment| payment.htlcs)
+                    .filter(|htlc| htlc.status != 0)
+                    .filter_map(|htlc| {
+                        let first_hop = htlc.route.as_ref()?.hops.first()?.chan_id;
+                        Some(LocalHtlcAttempt {
+                            first_hop_channel_id: first_hop.to_string(),
+                            attempt_time_ns: htlc.attempt_time_ns.max(0) as u64,
+                            succeeded: htlc.status == 1,
+                            failed_at_peer: htlc.status == 2
+                                && htlc
+                                    .failure
+                                    .as_ref()
+                                    .is_some_and(|f| f.failure_source_index == 1),
+                        })
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no payment attempts: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Paiements circulaires réglés parmi les paiements récents (rééquilibrages).
+    pub async fn rebalance_payments(&mut self) -> Result<Vec<LocalRebalance>> {
+        info!("Fetching circular payments from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let own_pubkey = client
+                    .lightning()
+                    .get_info(GetInfoRequest {})
+                    .await?
+                    .into_inner()
+                    .identity_pubkey;
+                let response = client
+                    .lightning()
+                    .list_payments(ListPaymentsRequest {
+                        include_incomplete: false,
+                        max_payments: PAYMENTS_LOOKBACK,
+                        reversed: true,
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                // Chaque HTLC réglé d'un paiement fractionné est un rééquilibrage à part entière
+                Ok(response
+                    .payments
+                    .into_iter()
+                    .flat_map(|payment| payment.htlcs)
+                    .filter(|htlc| htlc.status == 1)
+                    .filter_map(|htlc| {
+                        let route = htlc.route.as_ref()?;
+                        let (first, last) = (route.hops.first()?, route.hops.last()?);
+                        if route.hops.len() < 2 || last.pub_key != own_pubkey {
+                            return None;
+                        }
+                        let fee_msat = route.total_fees_msat.max(0) as u64;
+                        Some(LocalRebalance {
+                            outgoing_channel_id: first.chan_id.to_string(),
+                            incoming_channel_id: last.chan_id.to_string(),
+                            amount_msat: (route.total_amt_msat.max(0) as u64)
+                                .saturating_sub(fee_msat),
+                            fee_msat,
+                            settled_at_ns: htlc.resolve_time_ns.max(0) as u64,
+                        })
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no circular payments: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Paiements sortants réglés, toutes pages confondues.
+    pub async fn settled_payments(&mut self) -> Result<Vec<LocalPayment>> {
+        info!("Fetching payment history from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let own_pubkey = client
+                    .lightning()
+                    .get_info(GetInfoRequest {})
+                    .await?
+                    .into_inner()
+                    .identity_pubkey;
+                let mut payments = vec![];
+                let mut index_offset = 0;
+                loop {
+                    let response = client
+                        .lightning()
+                        .list_payments(ListPaymentsRequest {
+                            include_incomplete: false,
+                            index_offset,
+                            max_payments: HISTORY_PAGE_SIZE,
+                            reversed: false,
+                            ..Default::default()
+                        })
+                        .await?
+                        .into_inner();
+
+                    let page_len = response.payments.len();
+                    payments.extend(
+                        response
+                            .payments
+                            .into_iter()
+                            .filter(|payment| payment.status == PAYMENT_SUCCEEDED)
+                            .map(|payment| {
+                                let settled: Vec<_> = payment
+                                    .htlcs
+                                    .iter()
+                                    .filter(|htlc| htlc.status == HTLC_SUCCEEDED)
+                                    .collect();
+                                LocalPayment {
+                                    circular: settled.iter().any(|htlc| {
+                                        htlc.route.as_ref().is_some_and(|route| {
+                                            route.hops.len() >= 2
+                                                && route.hops.last().map(|hop| &hop.pub_key)
+                                                    == Some(&own_pubkey)
+                                        })
+                                    }),
+                                    settled_at_ns: settled
+                                        .iter()
+                                        .map(|htlc| htlc.resolve_time_ns)
+                                        .max()
+                                        .unwrap_or(payment.creation_time_ns)
+                                        .max(0)
+                                        as u64,
+                                    payment_hash: payment.payment_hash,
+                                    value_msat: payment.value_msat.max(0) as u64,
+                                    fee_msat: payment.fee_msat.max(0) as u64,
+                                }
+                            }),
+                    );
+
+                    if page_len < HISTORY_PAGE_SIZE as usize {
+                        break;
+                    }
+                    index_offset = response.last_index_offset;
+                }
+                Ok(payments)
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no payment history: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Factures réglées, toutes pages confondues.
+    pub async fn settled_invoices(&mut self) -> Result<Vec<LocalInvoice>> {
+        info!("Fetching invoices from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let mut invoices = vec![];
+                let mut index_offset = 0;
+                loop {
+                    let response = client
+                        .lightning()
+                        .list_invoices(ListInvoiceRequest {
+                            pending_only: false,
+                            index_offset,
+                            num_max_invoices: HISTORY_PAGE_SIZE,
+                            reversed: false,
+                        })
+                        .await?
+                        .into_inner();
+
+                    let page_len = response.invoices.len();
+                    invoices.extend(
+                        response
+                            .invoices
+                            .into_iter()
+                            .filter(|invoice| invoice.state == INVOICE_SETTLED)
+                            .map(|invoice| LocalInvoice {
+                                payment_hash: hex::encode(&invoice.r_hash),
+                                memo: invoice.memo,
+                                amt_paid_msat: invoice.amt_paid_msat.max(0) as u64,
+                                settled_at: invoice.settle_date,
+                                keysend: invoice.is_keysend,
+                            }),
+                    );
+
+                    if page_len < HISTORY_PAGE_SIZE as usize {
+                        break;
+                    }
+                    index_offset = response.last_index_offset;
+                }
+                Ok(invoices)
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no invoices: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Transactions on-chain du portefeuille, confirmées ou non.
+    pub async fn wallet_transactions(&mut self) -> Result<Vec<LocalWalletTransaction>> {
+        info!("Fetching wallet transactions from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let response = client
+                    .lightning()
+                    .get_transactions(GetTransactionsRequest {
+                        end_height: -1,
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner();
+
+                Ok(response
+                    .transactions
+                    .into_iter()
+                    .map(|tx| LocalWalletTransaction {
+                        tx_hash: tx.tx_hash,
+                        amount_sat: tx.amount,
+                        fee_sat: tx.total_fees.max(0) as u64,
+                        timestamp: tx.time_stamp,
+                        label: tx.label,
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no wallet transactions: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// Frais on-chain payés par le portefeuille, par identifiant de transaction.
+    pub async fn wallet_transaction_fees(&mut self) -> Result<HashMap<String, u64>> {
+        Ok(self
+            .wallet_transactions()
+            .await?
+            .into_iter()
+            .map(|tx| (tx.tx_hash, tx.fee_sat))
+            .collect())
+    }
+
+    /// Politiques de frais actuelles de tous les canaux.
+    pub async fn fee_report(&mut self) -> Result<Vec<LocalChannelFeePolicy>> {
+        info!("Fetching fee report from Umbrel LND");
+
+        match self.ensure_connected().await {
+            Ok(client) => {
+                let response = client
+                    .lightning()
+                    .fee_report(FeeReportRequest {})
+                    .await?
+                    .into_inner();
+
+                Ok(response
+                    .channel_fees
+                    .into_iter()
+                    .map(|fees| LocalChannelFeePolicy {
+                        channel_id: fees.chan_id.to_string(),
+                        channel_point: fees.channel_point,
+                        base_fee_msat: fees.base_fee_msat.max(0) as u64,
+                        fee_rate_ppm: fees.fee_per_mil.clamp(0, u32::MAX as i64) as u32,
+                    })
+                    .collect())
+            }
+            Err(e) => {
+                warn!("Failed to connect to LND, no fee report: {}", e);
+                Ok(vec![])
+            }
+        }
+    }
+
     pub async fn open_local_channel(
         &mut self,
         params: LocalChannelParams,
@@ -267,32 +906,110 @@ impl LocalLightningClient {
         Ok("new_channel_txid_123456789abcdef".to_string())
     }
 
+    /// Demande la fermeture d'un canal et renvoie la transaction de fermeture
+    /// diffusée. `sat_per_vbyte` ne s'applique qu'à une fermeture coopérative.
     pub async fn close_local_channel(
         &mut self,
         channel_point: String,
         force: bool,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        sat_per_vbyte: Option<u64>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
         info!(
-            "Closing local channel: {} (force: {}) (Umbrel)",
-            channel_point, force
+            "Closing local channel: {} (force: {}, sat/vB: {:?}) (Umbrel)",
+            channel_point, force, sat_per_vbyte
         );
 
-        // TODO: Implement real channel closing via tonic-lnd
-        Ok(())
+        let request = CloseChannelRequest {
+            channel_point: Some(parse_channel_point(&channel_point)?),
+            force,
+            sat_per_vbyte: sat_per_vbyte.unwrap_or(0),
+            ..Default::default()
+        };
+        let client = self.ensure_connected().await.map_err(|e| e.to_string())?;
+        let mut updates = client
+            .lightning()
+            .close_channel(request)
+            .await?
+            .into_inner();
+        // La première mise à jour annonce la transaction de fermeture diffusée
+        match updates.message().await?.and_then(|update| update.update) {
+            Some(close_status_update::Update::ClosePending(pending)) => {
+                Ok(reversed_hex(&pending.txid))
+            }
+            Some(close_status_update::Update::ChanClose(closed)) => {
+                Ok(reversed_hex(&closed.closing_txid))
+            }
+            None => Err("LND closed the stream without a closing transaction".into()),
+        }
     }
 
+    /// Met à jour la politique de frais d'un canal ; sans `time_lock_delta`, la
+    /// valeur annoncée actuellement par le nœud pour ce canal est conservée.
     pub async fn update_local_channel_fees(
         &mut self,
+        channel_id: &str,
         channel_point: &str,
         base_fee: u32,
         fee_rate: u32,
+        time_lock_delta: Option<u32>,
     ) -> Result<()> {
         info!(
-            "Updating local channel fees for {}: base={}, rate={} (Umbrel)",
-            channel_point, base_fee, fee_rate
+            "Updating local channel fees for {}: base={}, rate={}, cltv={:?} (Umbrel)",
+            channel_point, base_fee, fee_rate, time_lock_delta
         );
 
-        // TODO: Implement real fee updates via tonic-lnd
+        let client = self
+            .ensure_connected()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let time_lock_delta = match time_lock_delta {
+            Some(delta) => delta,
+            None => {
+                let own_pubkey = client
+                    .lightning()
+                    .get_info(GetInfoRequest {})
+                    .await?
+                    .into_inner()
+                    .identity_pubkey;
+                let edge = client
+                    .lightning()
+                    .get_chan_info(ChanInfoRequest {
+                        chan_id: channel_id.parse()?,
+                    })
+                    .await?
+                    .into_inner();
+                let policy = if edge.node1_pub == own_pubkey {
+                    edge.node1_policy
+                } else {
+                    edge.node2_policy
+                };
+                policy
+                    .map(|policy| policy.time_lock_delta)
+                    .ok_or_else(|| anyhow::anyhow!("no current policy for {}", channel_id))?
+            }
+        };
+
+        let request = PolicyUpdateRequest {
+            scope: Some(policy_update_request::Scope::ChanPoint(
+                parse_channel_point(channel_point).map_err(|e| anyhow::anyhow!(e))?,
+            )),
+            base_fee_msat: base_fee as i64,
+            fee_rate_ppm: fee_rate,
+            time_lock_delta,
+            ..Default::default()
+        };
+        let response = client
+            .lightning()
+            .update_channel_policy(request)
+            .await?
+            .into_inner();
+        if let Some(failed) = response.failed_updates.first() {
+            return Err(anyhow::anyhow!(
+                "fee update rejected for {}: {}",
+                channel_point,
+                failed.update_error
+            ));
+        }
         Ok(())
     }
 
@@ -15,7 +15,7 @@ pub struct MCPRecommendation {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ActionType {
     OpenChannel,
     CloseChannel,
@@ -23,7 +23,7 @@ pub enum ActionType {
     RebalanceChannel,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Priority {
     Low,
     Medium,
@@ -1,4 +1,5 @@
 pub mod lightning_client;
 pub mod local_lightning_client;
 pub mod mcp_client;
+pub mod price_source;
 pub mod umbrel_integrations;
@@ -0,0 +1,62 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use tracing::info;
+
+/// Cours historique du bitcoin auprès de l'instance mempool locale (API
+/// `/api/v1/historical-price`). Devises prises en charge par mempool : USD, EUR,
+/// GBP, CAD, CHF, AUD et JPY.
+#[derive(Clone)]
+pub struct PriceSource {
+    client: Client,
+    base_url: String,
+    currency: String,
+}
+
+impl PriceSource {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: std::env::var("PRICE_SOURCE_URL")
+                .unwrap_or_else(|_| "http://mempool_web_1:3006".to_string()),
+            currency: std::env::var("FIAT_CURRENCY")
+                .map(|currency| currency.to_uppercase())
+                .unwrap_or_else(|_| "EUR".to_string()),
+        }
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Cours d'un bitcoin dans la devise configurée, au plus près de `at`.
+    pub async fn historical_price(&self, at: DateTime<Utc>) -> Result<f64> {
+        info!("Fetching {} BTC price at {}", self.currency, at);
+
+        let response: Value = self
+            .client
+            .get(format!("{}/api/v1/historical-price", self.base_url))
+            .query(&[
+                ("currency", self.currency.clone()),
+                ("timestamp", at.timestamp().to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response["prices"]
+            .get(0)
+            .and_then(|price| price[self.currency.as_str()].as_f64())
+            .filter(|price| *price > 0.0)
+            .ok_or_else(|| anyhow!("No {} price at {}", self.currency, at))
+    }
+}
+
+impl Default for PriceSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
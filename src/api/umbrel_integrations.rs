@@ -171,6 +171,35 @@ impl UmbrelIntegrations {
         Ok(data)
     }
 
+    /// Estimations de frais on-chain via l'API Esplora d'Electrs (`/api/fee-estimates`).
+    pub async fn get_fee_estimates(&self) -> Result<FeeEstimates> {
+        info!("Fetching on-chain fee estimates from Electrs");
+
+        let estimates: std::collections::HashMap<String, f64> = self
+            .client
+            .get(format!("{}/api/fee-estimates", self.electrs_url))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let target = |blocks: &str| -> Result<u32> {
+            estimates
+                .get(blocks)
+                .map(|rate| rate.ceil().max(1.0) as u32)
+                .ok_or_else(|| anyhow::anyhow!("missing fee estimate for {} blocks", blocks))
+        };
+
+        Ok(FeeEstimates {
+            fastest: target("1")?,
+            half_hour: target("3")?,
+            hour: target("6")?,
+            economy: target("144")?,
+        })
+    }
+
     pub async fn get_bitcoin_node_data(&self) -> Result<BitcoinNodeData> {
         info!("Fetching Bitcoin node data from Umbrel");
 
@@ -0,0 +1,77 @@
+//! Backtest en ligne de commande sur la base SQLite de l'application.
+//!
+//! Usage : `backtest [--days N] [--end RFC3339] [--strategy ml_engine|do_nothing] [--step-hours H]`
+
+use chrono::{DateTime, Duration, Utc};
+use dazno_umbrel::storage::forwarding::ForwardingStore;
+use dazno_umbrel::utils::backtest::{
+    strategy_by_name, BacktestConfig, BacktestDataset, Backtester, TRAINING_LOOKBACK_DAYS,
+};
+use sqlx::SqlitePool;
+
+struct Args {
+    days: i64,
+    end: DateTime<Utc>,
+    strategy: String,
+    step_hours: i64,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        days: 30,
+        end: Utc::now(),
+        strategy: "ml_engine".to_string(),
+        step_hours: BacktestConfig::default().step_hours,
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--days" => args.days = value.parse().map_err(|_| "invalid --days")?,
+            "--end" => {
+                args.end = DateTime::parse_from_rfc3339(&value)
+                    .map_err(|_| "invalid --end")?
+                    .with_timezone(&Utc)
+            }
+            "--strategy" => args.strategy = value,
+            "--step-hours" => {
+                args.step_hours = value.parse().map_err(|_| "invalid --step-hours")?
+            }
+            other => return Err(format!("unknown argument {}", other)),
+        }
+    }
+    Ok(args)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = parse_args()?;
+    let strategy = strategy_by_name(&args.strategy)
+        .ok_or_else(|| format!("unknown strategy {}", args.strategy))?;
+
+    let database_url =
+        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:./data/dazno.db".to_string());
+    let store = ForwardingStore::new(SqlitePool::connect(&database_url).await?);
+    store.create_tables().await?;
+
+    let start = args.end - Duration::days(args.days.max(1));
+    let dataset = BacktestDataset::load(
+        &store,
+        start,
+        args.end,
+        Duration::days(TRAINING_LOOKBACK_DAYS),
+    )
+    .await?;
+
+    let backtester = Backtester::new(BacktestConfig {
+        step_hours: args.step_hours,
+        ..BacktestConfig::default()
+    });
+    let report = backtester.run(&dataset, strategy.as_ref(), start, args.end);
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -1,9 +1,34 @@
+use anyhow::anyhow;
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower_sessions::Session;
+use tracing::{error, info, warn};
+
+use crate::api::local_lightning_client::LocalChannelParams;
+use crate::handlers::recommendations::{session_actor, transition_error};
+use crate::middleware::validation::validate_input;
+use crate::models::action_params::ActionParameters;
+use crate::models::automation::AutomationSettings;
+use crate::models::feedback::OperatorDecision;
+use crate::models::recommendation::RecommendationStatus;
+use crate::models::reputation::PeerReputation;
+use crate::utils::action_validation::{ActionValidator, NodeState};
+use crate::utils::peer_reputation::{
+    check_reputation, gated_peers, PeerReputationEngine, REPUTATION_MAX_AGE_HOURS,
+};
+
+/// Part minimale des critères de réputation évalués pour autoriser une action automatique
+const MIN_REPUTATION_CONFIDENCE: f64 = 0.3;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionRequest {
     pub recommendation_id: String,
     pub action: ActionType,
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +41,8 @@ pub enum ActionType {
 pub struct ActionResponse {
     pub success: bool,
     pub message: String,
+    #[serde(default)]
+    pub violations: Vec<String>,
 }
 
 pub async fn process_recommendation_action(request: ActionRequest) -> ActionResponse {
@@ -23,10 +50,384 @@ pub async fn process_recommendation_action(request: ActionRequest) -> ActionResp
         ActionType::Approve => ActionResponse {
             success: true,
             message: format!("Recommendation {} approved", request.recommendation_id),
+            violations: vec![],
         },
         ActionType::Reject => ActionResponse {
             success: true,
             message: format!("Recommendation {} rejected", request.recommendation_id),
+            violations: vec![],
         },
     }
 }
+
+fn action_error(status: StatusCode, message: String) -> (StatusCode, Json<ActionResponse>) {
+    (
+        status,
+        Json(ActionResponse {
+            success: false,
+            message,
+            violations: vec![],
+        }),
+    )
+}
+
+// Approve / reject endpoint. Une approbation exige des paramètres valides sur l'état live du nœud.
+pub async fn execute_action(
+    State(app_state): State<Arc<crate::AppState>>,
+    session: Session,
+    Json(request): Json<ActionRequest>,
+) -> Result<Json<ActionResponse>, (StatusCode, Json<ActionResponse>)> {
+    // SÉCURITÉ: Validation d'entrée
+    if let Err(e) = validate_input("recommendation_id", &request.recommendation_id) {
+        error!("Invalid recommendation_id: {:?}", e);
+        return Err(action_error(
+            StatusCode::BAD_REQUEST,
+            "Invalid recommendation id".to_string(),
+        ));
+    }
+
+    if let Some(reason) = &request.reason {
+        if let Err(e) = validate_input("message", reason) {
+            error!("Invalid action reason: {:?}", e);
+            return Err(action_error(
+                StatusCode::BAD_REQUEST,
+                "Invalid reason".to_string(),
+            ));
+        }
+    }
+
+    if matches!(request.action, ActionType::Approve) {
+        let recommendation = app_state
+            .recommendation_store
+            .get(&request.recommendation_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to load recommendation: {}", e);
+                action_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Recommendation store unavailable".to_string(),
+                )
+            })?
+            .ok_or_else(|| {
+                action_error(
+                    StatusCode::NOT_FOUND,
+                    format!("Recommendation {} not found", request.recommendation_id),
+                )
+            })?;
+
+        let state = {
+            let mut client = app_state.lightning_client.lock().await;
+            NodeState::capture(&mut client).await.map_err(|e| {
+                error!("Failed to capture node state: {}", e);
+                action_error(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Node state unavailable".to_string(),
+                )
+            })?
+        };
+
+        if let Err(violations) = ActionValidator::default().validate(
+            recommendation.action_type,
+            &recommendation.parameters,
+            &state,
+        ) {
+            warn!(
+                "Recommendation {} rejected by pre-execution validation: {:?}",
+                recommendation.id, violations
+            );
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ActionResponse {
+                    success: false,
+                    message: format!(
+                        "Recommendation {} cannot be approved with its current parameters",
+                        recommendation.id
+                    ),
+                    violations: violations.iter().map(|v| v.to_string()).collect(),
+                }),
+            ));
+        }
+    }
+
+    info!(
+        "Processing {:?} for recommendation {}",
+        request.action, request.recommendation_id
+    );
+
+    let target = match request.action {
+        ActionType::Approve => RecommendationStatus::Approved,
+        ActionType::Reject => RecommendationStatus::Rejected,
+    };
+    let actor = session_actor(&session).await;
+    let transitioned = app_state
+        .recommendation_store
+        .transition(
+            &request.recommendation_id,
+            target,
+            &actor,
+            request.reason.clone(),
+            None,
+        )
+        .await
+        .map_err(|e| {
+            let (status, message) = transition_error(e);
+            action_error(status, message)
+        })?;
+
+    let Some((recommendation, trace)) = transitioned else {
+        return Err(action_error(
+            StatusCode::NOT_FOUND,
+            format!("Recommendation {} not found", request.recommendation_id),
+        ));
+    };
+
+    // Retour d'expérience pour l'apprentissage des préférences ; la décision reste acquise en cas d'échec
+    if let Some(decision) = OperatorDecision::from_transition(&recommendation, &trace) {
+        if let Err(e) = app_state.decision_store.record(&decision).await {
+            error!("Failed to record operator decision: {}", e);
+        }
+    }
+
+    Ok(Json(process_recommendation_action(request).await))
+}
+
+/// Motif de refus d'une action automatisée.
+#[derive(Debug, thiserror::Error)]
+pub enum ActionRefusal {
+    #[error("automatisation ou exécution automatique désactivée")]
+    Disabled,
+    #[error("{0}")]
+    Blocked(String),
+    #[error("état du nœud indisponible : {0}")]
+    Unavailable(anyhow::Error),
+}
+
+impl ActionRefusal {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ActionRefusal::Disabled => StatusCode::FORBIDDEN,
+            ActionRefusal::Blocked(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ActionRefusal::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// État live du nœud ; le mode mock est refusé puisque rien n'y serait exécuté.
+pub async fn capture_live_state(app_state: &crate::AppState) -> anyhow::Result<NodeState> {
+    let mut client = app_state.lightning_client.lock().await;
+    if !client.is_connected() {
+        return Err(anyhow!("Lightning node not connected"));
+    }
+    NodeState::capture(&mut client).await
+}
+
+/// Montant engagé par une action, comparé à `max_amount_per_action`.
+fn committed_amount_sat(parameters: &ActionParameters, state: &NodeState) -> u64 {
+    match parameters {
+        ActionParameters::OpenChannel(params) => params.amount_sat,
+        ActionParameters::RebalanceChannel(params) => params.amount_sat,
+        ActionParameters::CloseChannel(params) => params
+            .channel_id
+            .as_deref()
+            .or(params.channel_point.as_deref())
+            .and_then(|key| state.find_channel(key))
+            .map_or(0, |channel| channel.local_balance),
+        ActionParameters::AdjustFees(_) => 0,
+    }
+}
+
+/// Garde-fous de toute exécution automatique : réglages, validation des paramètres,
+/// pairs exclus, montant maximal et réputation des pairs qui reçoivent la liquidité.
+pub async fn check_automated_action(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+    parameters: &ActionParameters,
+    state: &NodeState,
+) -> Result<(), ActionRefusal> {
+    if !settings.enabled || !settings.auto_execution_enabled {
+        return Err(ActionRefusal::Disabled);
+    }
+
+    let violations = ActionValidator::default().check(parameters, state);
+    if !violations.is_empty() {
+        let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        return Err(ActionRefusal::Blocked(format!(
+            "paramètres invalides : {}",
+            violations.join(", ")
+        )));
+    }
+
+    let amount = committed_amount_sat(parameters, state);
+    if amount > settings.max_amount_per_action {
+        return Err(ActionRefusal::Blocked(format!(
+            "montant de {} sats au-delà du maximum de {} sats par action",
+            amount, settings.max_amount_per_action
+        )));
+    }
+
+    let peers = gated_peers(parameters, &state.channels);
+    if let Some(peer) = peers
+        .iter()
+        .find(|peer| settings.blacklisted_peers.contains(peer))
+    {
+        return Err(ActionRefusal::Blocked(format!("pair {} exclu", peer)));
+    }
+
+    if settings.advanced_settings.peer_reputation_checks {
+        for peer in &peers {
+            let reputation = peer_reputation(app_state, peer)
+                .await
+                .map_err(ActionRefusal::Unavailable)?;
+            check_reputation(
+                &reputation,
+                settings.risk_tolerance.min_peer_reputation(),
+                MIN_REPUTATION_CONFIDENCE,
+            )
+            .map_err(ActionRefusal::Blocked)?;
+        }
+    }
+    Ok(())
+}
+
+/// Réputation récente d'un pair, recalculée si le dernier score est trop ancien.
+async fn peer_reputation(
+    app_state: &crate::AppState,
+    peer: &str,
+) -> anyhow::Result<PeerReputation> {
+    let now = Utc::now();
+    if let Some(reputation) = app_state.peer_reputation_store.latest(peer).await? {
+        if now - reputation.computed_at < chrono::Duration::hours(REPUTATION_MAX_AGE_HOURS) {
+            return Ok(reputation);
+        }
+    }
+
+    PeerReputationEngine::default()
+        .refresh(
+            &app_state.lightning_client,
+            &app_state.forwarding_store,
+            &app_state.peer_reputation_store,
+            &[peer.to_string()],
+            now,
+        )
+        .await?
+        .into_iter()
+        .find(|r| r.peer_pubkey == peer)
+        .ok_or_else(|| anyhow!("no reputation computed for {}", peer))
+}
+
+/// Applique une action typée sur le nœud, revalidée sous le verrou du client.
+/// Les changements provoqués ne sont pas signalés comme anomalies.
+pub async fn apply_node_action(
+    app_state: &crate::AppState,
+    parameters: ActionParameters,
+) -> anyhow::Result<Value> {
+    let mut client = app_state.lightning_client.lock().await;
+    if !client.is_connected() {
+        return Err(anyhow!("Lightning node not connected"));
+    }
+    let state = NodeState::capture(&mut client).await?;
+    let violations = ActionValidator::default().check(&parameters, &state);
+    if !violations.is_empty() {
+        let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        return Err(anyhow!("invalid parameters: {}", violations.join(", ")));
+    }
+    let find_channel = |id: &Option<String>, point: &Option<String>| {
+        id.as_deref()
+            .or(point.as_deref())
+            .and_then(|key| state.find_channel(key))
+            .ok_or_else(|| anyhow!("channel not found"))
+    };
+
+    let now = Utc::now();
+    match parameters {
+        ActionParameters::AdjustFees(params) => {
+            let channel = find_channel(&params.channel_id, &params.channel_point)?;
+            let base_fee_msat = params.base_fee_msat.unwrap_or(channel.base_fee_msat);
+            // Annoncée avant l'appel : la surveillance peut observer le changement aussitôt
+            app_state
+                .anomaly_monitor
+                .expect_policy(
+                    &channel.channel_id,
+                    params.fee_rate_ppm,
+                    Some(base_fee_msat),
+                    now,
+                )
+                .await;
+            if let Err(e) = client
+                .update_local_channel_fees(
+                    &channel.channel_id,
+                    &channel.channel_point,
+                    base_fee_msat as u32,
+                    params.fee_rate_ppm,
+                    params.time_lock_delta,
+                )
+                .await
+            {
+                app_state
+                    .anomaly_monitor
+                    .withdraw_policy(&channel.channel_id)
+                    .await;
+                return Err(e);
+            }
+            Ok(json!({
+                "channel_id": channel.channel_id,
+                "fee_rate_ppm": params.fee_rate_ppm,
+                "base_fee_msat": base_fee_msat,
+                "time_lock_delta": params.time_lock_delta,
+            }))
+        }
+        ActionParameters::CloseChannel(params) => {
+            let channel = find_channel(&params.channel_id, &params.channel_point)?;
+            // LND refuse un taux on-chain sur une fermeture forcée
+            if params.force && params.sat_per_vbyte.is_some() {
+                return Err(anyhow!("sat_per_vbyte cannot be set on a force close"));
+            }
+            // Annoncée avant l'appel et conservée jusqu'à la confirmation de la fermeture
+            app_state
+                .anomaly_monitor
+                .expect_close(&channel.channel_id, now)
+                .await;
+            let closing_txid = match client
+                .close_local_channel(
+                    channel.channel_point.clone(),
+                    params.force,
+                    params.sat_per_vbyte,
+                )
+                .await
+                .map_err(|e| anyhow!("{}", e))
+            {
+                Ok(txid) => txid,
+                Err(e) => {
+                    app_state
+                        .anomaly_monitor
+                        .withdraw_close(&channel.channel_id)
+                        .await;
+                    return Err(e);
+                }
+            };
+            Ok(json!({
+                "channel_id": channel.channel_id,
+                "force": params.force,
+                "sat_per_vbyte": params.sat_per_vbyte,
+                "closing_txid": closing_txid,
+            }))
+        }
+        ActionParameters::OpenChannel(params) => {
+            let txid = client
+                .open_local_channel(LocalChannelParams {
+                    peer_pubkey: params.peer_pubkey.clone(),
+                    amount: params.amount_sat,
+                    fee_rate: params.sat_per_vbyte.map(|rate| rate as u32),
+                    private: params.private,
+                    push_sat: Some(params.push_sat),
+                    min_htlc_msat: None,
+                })
+                .await
+                .map_err(|e| anyhow!("{}", e))?;
+            Ok(json!({ "peer_pubkey": params.peer_pubkey, "funding_txid": txid }))
+        }
+        ActionParameters::RebalanceChannel(_) => Err(anyhow!(
+            "circular rebalancing is not supported by the node client"
+        )),
+    }
+}
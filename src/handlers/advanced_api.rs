@@ -1,19 +1,47 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::api::umbrel_integrations::UmbrelIntegrations;
+use crate::handlers::actions::{apply_node_action, capture_live_state, check_automated_action};
 use crate::middleware::validation::validate_input;
+use crate::models::action_params::ActionParameters;
+use crate::models::recommendation::{Recommendation, RecommendationStatus};
+use crate::storage::recommendations::RecommendationStore;
+use crate::utils::action_validation::NodeState;
+use crate::utils::channel_pnl::{ChannelPnlCalculator, ChannelPnlConfig};
+use crate::utils::competitive_positioning::CompetitivePositioning;
+use crate::utils::forecasting::{ForecastInputs, Forecaster};
+use crate::utils::ml_engine::RecommendationSignals;
+use crate::utils::monte_carlo::SimulationContext;
+use crate::utils::node_analytics::NodeAnalyzer;
+use crate::utils::onchain::DEFAULT_ONCHAIN_FEE_RATE;
+use crate::utils::scheduling::{ExecutionScheduler, SchedulingInputs};
+use crate::utils::stress_testing::StressTester;
+
+/// Fenêtre d'historique de forwarding échantillonnée par la simulation
+const SIMULATION_HISTORY_DAYS: u32 = 30;
+/// Historique de forwarding (semaines pleines) pour le profil horaire du trafic
+const SCHEDULING_HISTORY_DAYS: u32 = 28;
+/// Profondeur des relevés de frais et d'instantanés de canaux pour la planification
+const SCHEDULING_FEE_DAYS: i64 = 14;
+/// Scénarios et événements acceptés par requête de stress test
+const MAX_STRESS_SCENARIOS: usize = 10;
+const MAX_STRESS_EVENTS: usize = 10;
 
 use crate::handlers::websocket::AutomationResult;
 use crate::models::{
-    analytics::NodeAnalytics,
-    automation::AutomationSettings,
+    analytics::{
+        ChannelAnalytics, NodeAnalytics, PeerSelection, PredictiveAnalytics, StressEvent,
+        StressScenario, StressTestResult,
+    },
+    automation::{AutomationSettings, RiskTolerance, SmartScheduling},
     ml::{AutomationReadiness, MLScorecard, OptimalWindow, SimulationOutcome, SmartRecommendation},
 };
 
@@ -43,6 +71,10 @@ pub struct AutomationStats {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SimulationRequest {
     pub recommendation_id: String,
+    #[serde(default)]
+    pub horizon_days: Option<u32>,
+    #[serde(default)]
+    pub runs: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,7 +90,10 @@ pub struct ScheduleRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OptimalTimeResponse {
+    /// Début de la fenêtre optimale (RFC 3339)
+    pub optimal_time: String,
     pub window: OptimalWindow,
+    pub scheduling: SmartScheduling,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -85,6 +120,8 @@ pub struct DeepAnalysisResponse {
     pub scorecard: MLScorecard,
     pub recommendations: Vec<SmartRecommendation>,
     pub automation: AutomationReadiness,
+    /// Version du modèle ML qui a produit ces recommandations
+    pub model_version: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -114,41 +151,108 @@ pub async fn auto_execute_recommendation(
         payload.recommendation_id
     );
 
-    let channels = Vec::new();
-
-    let recommendations = app_state.ml_engine.build_recommendations(&channels);
-    let selected = recommendations
-        .iter()
-        .find(|r| r.id == payload.recommendation_id)
-        .or_else(|| recommendations.first())
-        .cloned()
-        .unwrap_or_else(|| SmartRecommendation {
-            id: payload.recommendation_id.clone(),
-            action_type: crate::api::mcp_client::ActionType::AdjustFees,
-            priority: crate::api::mcp_client::Priority::Medium,
-            expected_roi_impact: 2.1,
-            confidence: 0.86,
-            risk_score: 0.32,
-            rationale: vec!["Fallback recommendation (mock)".to_string()],
-            target_channels: vec![],
-        });
+    // Automatisation suspendue par une anomalie critique jusqu'à reprise manuelle
+    if let Some(pause) = app_state.anomaly_monitor.pause().await {
+        error!(
+            "Auto-execution of {} refused: automation paused since {} ({})",
+            payload.recommendation_id, pause.paused_at, pause.reason
+        );
+        return Err(StatusCode::LOCKED);
+    }
 
-    let settings = AutomationSettings::default();
-    let automation = app_state
-        .ml_engine
-        .automation_readiness(&settings, &channels);
+    // Seule la recommandation demandée, persistée et encore actionnable, est exécutée
+    let stored = app_state
+        .recommendation_store
+        .get(&payload.recommendation_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load recommendation: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or_else(|| {
+            error!(
+                "Auto-execution of {} refused: unknown recommendation",
+                payload.recommendation_id
+            );
+            StatusCode::NOT_FOUND
+        })?;
+    if !stored.status.is_actionable() {
+        error!(
+            "Auto-execution of {} refused: status is {:?}",
+            stored.id, stored.status
+        );
+        return Err(StatusCode::CONFLICT);
+    }
+    let parameters =
+        ActionParameters::parse(stored.action_type, &stored.parameters).map_err(|e| {
+            error!("Auto-execution of {} blocked: {}", stored.id, e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+
+    let node_state = capture_live_state(&app_state).await.map_err(|e| {
+        error!("Failed to capture node state for auto-execution: {}", e);
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+
+    // Réglages d'automatisation, validation, pairs exclus, montant et réputation
+    let settings = load_settings(&app_state).await?;
+    if let Err(refusal) =
+        check_automated_action(&app_state, &settings, &parameters, &node_state).await
+    {
+        error!("Auto-execution of {} blocked: {}", stored.id, refusal);
+        return Err(refusal.status_code());
+    }
 
-    let success = automation.ready || selected.confidence > 0.82;
+    if stored.status == RecommendationStatus::Pending {
+        advance_lifecycle(
+            &app_state.recommendation_store,
+            &stored.id,
+            RecommendationStatus::Approved,
+            None,
+        )
+        .await?;
+    }
+    advance_lifecycle(
+        &app_state.recommendation_store,
+        &stored.id,
+        RecommendationStatus::Executing,
+        None,
+    )
+    .await?;
+
+    let started = std::time::Instant::now();
+    let execution_id = Uuid::new_v4().to_string();
+    let outcome = apply_node_action(&app_state, parameters).await;
+    let success = outcome.is_ok();
     let roi_impact = if success {
-        selected.expected_roi_impact
+        stored.expected_roi_impact
     } else {
         0.0
     };
 
-    // Simulate execution time
-    tokio::time::sleep(tokio::time::Duration::from_millis(350)).await;
+    let (status, reason) = match &outcome {
+        Ok(_) => (
+            RecommendationStatus::Executed,
+            format!("Exécution {}", execution_id),
+        ),
+        Err(e) => {
+            error!("Auto-execution of {} failed: {}", stored.id, e);
+            (RecommendationStatus::Failed, e.to_string())
+        }
+    };
+    advance_lifecycle(
+        &app_state.recommendation_store,
+        &stored.id,
+        status,
+        Some(reason),
+    )
+    .await?;
 
-    let execution_id = Uuid::new_v4().to_string();
+    let automation = app_state
+        .ml_models
+        .engine()
+        .await
+        .automation_readiness(&settings, &node_state.channels);
 
     let response = AutoExecuteResponse {
         success,
@@ -158,7 +262,14 @@ pub async fn auto_execute_recommendation(
                 roi_impact
             )
         } else {
-            "Execution failed due to market conditions".to_string()
+            format!(
+                "Échec de l'exécution : {}",
+                outcome
+                    .as_ref()
+                    .err()
+                    .map(|e| e.to_string())
+                    .unwrap_or_default()
+            )
         },
         roi_impact,
         execution_id: execution_id.clone(),
@@ -175,7 +286,7 @@ pub async fn auto_execute_recommendation(
         recommendation_id: payload.recommendation_id.clone(),
         success,
         roi_impact,
-        execution_time_ms: 500,
+        execution_time_ms: started.elapsed().as_millis() as u64,
         message: response.message.clone(),
     };
 
@@ -186,6 +297,105 @@ pub async fn auto_execute_recommendation(
     Ok(Json(response))
 }
 
+/// Transition effectuée par l'automatisation ; un refus de la machine à états devient un 409.
+async fn advance_lifecycle(
+    store: &RecommendationStore,
+    id: &str,
+    to: RecommendationStatus,
+    reason: Option<String>,
+) -> Result<(), StatusCode> {
+    store
+        .transition(id, to, "automation", reason, None)
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            error!("Lifecycle transition of {} to {:?} failed: {}", id, to, e);
+            if e.downcast_ref::<crate::models::recommendation::TransitionError>()
+                .is_some()
+            {
+                StatusCode::CONFLICT
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })
+}
+
+/// Canaux live, avec la politique de frais courante relevée par la synchronisation.
+async fn live_channels(
+    app_state: &crate::AppState,
+) -> Result<Vec<crate::api::local_lightning_client::LocalChannelInfo>, StatusCode> {
+    let node_state = {
+        let mut client = app_state.lightning_client.lock().await;
+        NodeState::capture(&mut client).await.map_err(|e| {
+            error!("Failed to capture node state: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        })?
+    };
+    let mut channels = node_state.channels;
+
+    app_state
+        .forwarding_store
+        .apply_latest_policies(&mut channels)
+        .await
+        .map_err(|e| {
+            error!("Failed to load fee policy history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(channels)
+}
+
+/// Recommandation persistée, ou à défaut recommandation ML courante portant cet identifiant.
+async fn find_recommendation(
+    app_state: &crate::AppState,
+    recommendation_id: &str,
+    channels: &[crate::api::local_lightning_client::LocalChannelInfo],
+) -> Result<SmartRecommendation, StatusCode> {
+    let stored = app_state
+        .recommendation_store
+        .get(recommendation_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load recommendation: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if let Some(stored) = stored {
+        return Ok(stored.to_smart_recommendation());
+    }
+
+    let engine = app_state.ml_models.engine().await;
+    let signals = recommendation_signals(app_state, channels).await;
+    engine
+        .build_recommendations(&signals.inputs(channels))
+        .into_iter()
+        .find(|r| r.id == recommendation_id)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Modèles de frais, candidats d'ouverture et de fermeture pour le moteur ML local.
+async fn recommendation_signals(
+    app_state: &crate::AppState,
+    channels: &[crate::api::local_lightning_client::LocalChannelInfo],
+) -> RecommendationSignals {
+    let settings = app_state
+        .settings_store
+        .automation_settings()
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Unable to load automation settings, using defaults: {}", e);
+            AutomationSettings::default()
+        });
+    RecommendationSignals::gather(
+        &app_state.lightning_client,
+        &app_state.forwarding_store,
+        &app_state.fee_strategy_store,
+        &app_state.decision_store,
+        channels,
+        &settings,
+        chrono::Utc::now(),
+    )
+    .await
+}
+
 // Simulate recommendation endpoint
 pub async fn simulate_recommendation(
     State(app_state): State<Arc<crate::AppState>>,
@@ -199,17 +409,51 @@ pub async fn simulate_recommendation(
 
     info!("Simulating recommendation: {}", payload.recommendation_id);
 
-    let channels = Vec::new();
+    let channels = live_channels(&app_state).await?;
+    let selected = find_recommendation(&app_state, &payload.recommendation_id, &channels).await?;
+
+    let now = chrono::Utc::now();
+    let history_start = now - chrono::Duration::days(SIMULATION_HISTORY_DAYS as i64);
+    let history = app_state
+        .forwarding_store
+        .events_between(history_start, now)
+        .await
+        .map_err(|e| {
+            error!("Failed to load forwarding history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let (fee_rate, fee_assumption) = match UmbrelIntegrations::new().get_fee_estimates().await {
+        Ok(estimates) => (estimates.half_hour as f64, None),
+        Err(e) => {
+            error!("Fee estimates unavailable, using default rate: {}", e);
+            (
+                DEFAULT_ONCHAIN_FEE_RATE,
+                Some(format!(
+                    "Estimation de frais on-chain indisponible : {} sat/vB supposés",
+                    DEFAULT_ONCHAIN_FEE_RATE
+                )),
+            )
+        }
+    };
 
-    let recommendations = app_state.ml_engine.build_recommendations(&channels);
-    let selected = recommendations
-        .iter()
-        .find(|r| r.id == payload.recommendation_id)
-        .or_else(|| recommendations.first())
-        .cloned()
-        .ok_or(StatusCode::NOT_FOUND)?;
+    let mut context = SimulationContext::new(
+        channels,
+        history,
+        history_start,
+        SIMULATION_HISTORY_DAYS,
+        fee_rate,
+    );
+    if let Some(horizon_days) = payload.horizon_days {
+        context.horizon_days = horizon_days.clamp(1, 365);
+    }
+    if let Some(runs) = payload.runs {
+        context.runs = runs.clamp(1, 5_000);
+    }
 
-    let outcome = app_state.ml_engine.simulate(&selected);
+    let engine = app_state.ml_models.engine().await;
+    let mut outcome = engine.simulate(&selected, &context);
+    outcome.assumptions.extend(fee_assumption);
 
     let simulation = SimulationResponse { outcome };
 
@@ -262,70 +506,191 @@ pub async fn get_optimal_time(
         recommendation_id
     );
 
-    let channels = Vec::new();
+    let channels = live_channels(&app_state).await?;
+    let selected = find_recommendation(&app_state, &recommendation_id, &channels).await?;
 
-    let recommendations = app_state.ml_engine.build_recommendations(&channels);
-    let selected = recommendations
-        .into_iter()
-        .find(|r| r.id == recommendation_id)
-        .unwrap_or_else(|| app_state.ml_engine.build_recommendations(&[]).remove(0));
+    let now = chrono::Utc::now();
+    let store = &app_state.forwarding_store;
+    let internal = |e: anyhow::Error| {
+        error!("Failed to load scheduling history: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let fee_samples = store
+        .fee_estimates_since(now - chrono::Duration::days(SCHEDULING_FEE_DAYS))
+        .await
+        .map_err(internal)?;
+    let events = store
+        .events_between(
+            now - chrono::Duration::days(SCHEDULING_HISTORY_DAYS as i64),
+            now,
+        )
+        .await
+        .map_err(internal)?;
+    let snapshots = store
+        .channel_snapshots_between(now - chrono::Duration::days(SCHEDULING_FEE_DAYS), now)
+        .await
+        .map_err(internal)?;
+    let current_fees = UmbrelIntegrations::new()
+        .get_fee_estimates()
+        .await
+        .map_err(|e| error!("Fee estimates unavailable, using last sample: {}", e))
+        .ok();
+
+    let inputs = SchedulingInputs {
+        now,
+        current_fees,
+        fee_samples,
+        events,
+        history_days: SCHEDULING_HISTORY_DAYS,
+        snapshots,
+        channels,
+    };
+    let engine = app_state.ml_models.engine().await;
+    let window = engine.optimal_window(&selected, &inputs);
+    let scheduling = ExecutionScheduler::default().smart_scheduling(&inputs);
+
+    Ok(Json(OptimalTimeResponse {
+        optimal_time: window.start.to_rfc3339(),
+        window,
+        scheduling,
+    }))
+}
 
-    let window = app_state.ml_engine.optimal_window(&selected);
+/// Réglages d'automatisation enregistrés.
+async fn load_settings(app_state: &crate::AppState) -> Result<AutomationSettings, StatusCode> {
+    app_state
+        .settings_store
+        .automation_settings()
+        .await
+        .map_err(|e| {
+            error!("Failed to load automation settings: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
 
-    Ok(Json(OptimalTimeResponse { window }))
+async fn save_settings(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+) -> Result<(), StatusCode> {
+    app_state
+        .settings_store
+        .save_automation_settings(settings)
+        .await
+        .map_err(|e| {
+            error!("Failed to save automation settings: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
 }
 
-// Update automation mode
+// Update automation mode (tolérance au risque)
 pub async fn update_automation_mode(
+    State(app_state): State<Arc<crate::AppState>>,
     Json(payload): Json<AutomationModeRequest>,
 ) -> Result<StatusCode, StatusCode> {
     info!("Updating automation mode to: {}", payload.mode);
 
-    // Here you would update the automation settings in your database
-    // For now, we'll just log and return success
-
+    let mut settings = load_settings(&app_state).await?;
+    settings.risk_tolerance = match payload.mode.as_str() {
+        "conservative" => RiskTolerance::Conservative,
+        "balanced" => RiskTolerance::Moderate,
+        "aggressive" => RiskTolerance::Aggressive,
+        // Les seuils personnalisés se règlent par PUT /api/automation/settings
+        "custom" if matches!(settings.risk_tolerance, RiskTolerance::Custom(_)) => {
+            settings.risk_tolerance
+        }
+        _ => {
+            error!("Unsupported automation mode: {}", payload.mode);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+    save_settings(&app_state, &settings).await?;
     Ok(StatusCode::OK)
 }
 
 // Update max actions per day
 pub async fn update_max_actions(
+    State(app_state): State<Arc<crate::AppState>>,
     Json(payload): Json<MaxActionsRequest>,
 ) -> Result<StatusCode, StatusCode> {
     info!("Updating max actions to: {}", payload.max_actions);
+    if payload.max_actions == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
-    // Here you would update the automation settings in your database
-
+    let mut settings = load_settings(&app_state).await?;
+    settings.max_daily_actions = payload.max_actions;
+    save_settings(&app_state, &settings).await?;
     Ok(StatusCode::OK)
 }
 
-// Toggle auto-execution
+// Toggle auto-execution ; l'activer active aussi l'automatisation
 pub async fn toggle_auto_execution(
+    State(app_state): State<Arc<crate::AppState>>,
     Json(payload): Json<AutoExecutionToggleRequest>,
 ) -> Result<StatusCode, StatusCode> {
     info!("Toggling auto-execution to: {}", payload.enabled);
 
-    // Here you would update the automation settings in your database
-
+    let mut settings = load_settings(&app_state).await?;
+    settings.auto_execution_enabled = payload.enabled;
+    settings.enabled |= payload.enabled;
+    save_settings(&app_state, &settings).await?;
     Ok(StatusCode::OK)
 }
 
+// Remplacement complet des réglages d'automatisation
+pub async fn update_automation_settings(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(settings): Json<AutomationSettings>,
+) -> Result<Json<AutomationSettings>, StatusCode> {
+    if settings.max_daily_actions == 0 || settings.max_amount_per_action == 0 {
+        error!("Invalid automation settings: zero daily or per-action limit");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    for peer in settings
+        .blacklisted_peers
+        .iter()
+        .chain(&settings.whitelisted_peers)
+    {
+        if let Err(e) = validate_input("pubkey", peer) {
+            error!("Invalid peer in automation settings: {:?}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    if !(0.0..=1.0).contains(&settings.advanced_settings.ml_confidence_threshold) {
+        error!("Invalid automation settings: ML confidence threshold out of range");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    save_settings(&app_state, &settings).await?;
+    info!(
+        "Automation settings updated (enabled: {}, auto-execution: {})",
+        settings.enabled, settings.auto_execution_enabled
+    );
+    Ok(Json(settings))
+}
+
 // Force deep analysis
 pub async fn force_deep_analysis(
     State(app_state): State<Arc<crate::AppState>>,
 ) -> Result<Json<DeepAnalysisResponse>, StatusCode> {
     info!("Initiating force deep analysis");
+    let started = std::time::Instant::now();
 
-    // Simulate deep analysis time
-    tokio::time::sleep(tokio::time::Duration::from_millis(1200)).await;
-
-    let channels = Vec::new();
+    // Comme le rafraîchissement : pas d'analyse sur les canaux fictifs du mode mock
+    if !app_state.lightning_client.lock().await.is_connected() {
+        warn!("Deep analysis refused: Lightning node not connected");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let channels = live_channels(&app_state).await?;
+    let settings = load_settings(&app_state).await?;
+    let signals = recommendation_signals(&app_state, &channels).await;
+    let inputs = signals.inputs(&channels);
 
-    let scorecard = app_state.ml_engine.score_channels(&channels);
-    let insights = app_state.ml_engine.derive_insights(&channels);
-    let recommendations = app_state.ml_engine.build_recommendations(&channels);
-    let automation = app_state
-        .ml_engine
-        .automation_readiness(&AutomationSettings::default(), &channels);
+    let engine = app_state.ml_models.engine().await;
+    let scorecard = engine.score_channels(&inputs);
+    let insights = engine.derive_insights(&channels);
+    let recommendations = engine.build_recommendations(&inputs);
+    let automation = engine.automation_readiness(&settings, &channels);
 
     for rec in &recommendations {
         let payload = serde_json::json!({
@@ -336,18 +701,31 @@ pub async fn force_deep_analysis(
             "description": rec.rationale.first().cloned().unwrap_or_default(),
             "confidence": rec.confidence * 100.0,
             "risk_level": rec.risk_score,
+            "model_version": engine.version,
         });
         app_state.ws_state.broadcast_new_recommendation(payload);
     }
 
+    // Chaque recommandation persistée garde la version du modèle qui l'a produite
+    let stored: Vec<Recommendation> = recommendations
+        .iter()
+        .cloned()
+        .map(|rec| Recommendation::from(rec).with_model_version(engine.version))
+        .collect();
+    let ttl = chrono::Duration::hours(app_state.config.recommendation_ttl_hours);
+    if let Err(e) = app_state.recommendation_store.save_all(&stored, ttl).await {
+        error!("Failed to persist deep analysis recommendations: {}", e);
+    }
+
     let response = DeepAnalysisResponse {
         success: true,
         recommendations_count: recommendations.len() as u32,
-        analysis_time_ms: 1200,
+        analysis_time_ms: started.elapsed().as_millis() as u64,
         insights: insights.into_iter().map(|i| i.detail).collect(),
         scorecard,
         recommendations,
         automation,
+        model_version: engine.version,
     };
 
     Ok(Json(response))
@@ -357,13 +735,12 @@ pub async fn force_deep_analysis(
 pub async fn get_automation_settings(
     State(app_state): State<Arc<crate::AppState>>,
 ) -> Result<Json<AutomationSettingsResponse>, StatusCode> {
-    let settings = AutomationSettings::default();
+    let settings = load_settings(&app_state).await?;
 
     let channels = Vec::new();
 
-    let readiness = app_state
-        .ml_engine
-        .automation_readiness(&settings, &channels);
+    let engine = app_state.ml_models.engine().await;
+    let readiness = engine.automation_readiness(&settings, &channels);
 
     Ok(Json(AutomationSettingsResponse {
         settings,
@@ -371,49 +748,224 @@ pub async fn get_automation_settings(
     }))
 }
 
-// Get node analytics
+// Scores du nœud calculés sur ses canaux, ses forwards et le graphe
 pub async fn get_node_analytics(
     State(app_state): State<Arc<crate::AppState>>,
 ) -> Result<Json<NodeAnalytics>, StatusCode> {
-    let channels = Vec::new();
+    NodeAnalyzer::default()
+        .analyze_live(
+            &app_state.lightning_client,
+            &app_state.forwarding_store,
+            chrono::Utc::now(),
+        )
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to compute node analytics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelAnalyticsQuery {
+    /// Fenêtre du bilan, en jours
+    #[serde(default = "default_pnl_history_days")]
+    pub days: u32,
+    #[serde(default = "default_include_closed")]
+    pub include_closed: bool,
+}
+
+fn default_pnl_history_days() -> u32 {
+    365
+}
+
+fn default_include_closed() -> bool {
+    true
+}
+
+// Bilan net de chaque canal, coûts on-chain et de rééquilibrage déduits
+pub async fn get_channel_analytics(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<ChannelAnalyticsQuery>,
+) -> Result<Json<Vec<ChannelAnalytics>>, StatusCode> {
+    let calculator = ChannelPnlCalculator::new(ChannelPnlConfig {
+        history_days: query.days.clamp(7, 730),
+        include_closed: query.include_closed,
+        ..ChannelPnlConfig::default()
+    });
+    calculator
+        .analyze_live(
+            &app_state.lightning_client,
+            &app_state.forwarding_store,
+            &app_state.peer_reputation_store,
+            chrono::Utc::now(),
+        )
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to compute channel P&L: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
 
-    let scorecard = app_state.ml_engine.score_channels(&channels);
-
-    let analytics = NodeAnalytics {
-        performance_score: (scorecard.confidence * 100.0).round(),
-        roi_current: (scorecard.predicted_roi_30d - 1.8).max(10.0),
-        roi_predicted_30d: scorecard.predicted_roi_30d,
-        efficiency_score: 80.0 + (scorecard.confidence * 10.0),
-        risk_score: (scorecard.risk_index * 100.0).round(),
-        centrality_score: 90.0,
-        liquidity_score: (scorecard.capacity_saturation).min(100.0),
-        reliability_score: 93.0,
-        growth_potential: 76.8,
-        last_calculated: chrono::Utc::now(),
+#[derive(Debug, Deserialize)]
+pub struct StressTestRequest {
+    pub scenarios: Vec<StressScenario>,
+}
+
+// Scénarios prédéfinis : fermeture forcée du premier pair, pic de frais, pairs hors ligne
+pub async fn get_stress_tests(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<Vec<StressTestResult>>, StatusCode> {
+    let tester = StressTester::default();
+    let scenarios = tester.presets();
+    run_stress_tests(&app_state, &tester, scenarios).await
+}
+
+// Scénarios personnalisés, chacun combinant des événements simultanés
+pub async fn run_custom_stress_tests(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(payload): Json<StressTestRequest>,
+) -> Result<Json<Vec<StressTestResult>>, StatusCode> {
+    if payload.scenarios.is_empty() || payload.scenarios.len() > MAX_STRESS_SCENARIOS {
+        error!("Invalid stress test count: {}", payload.scenarios.len());
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    for scenario in &payload.scenarios {
+        if let Err(e) = validate_stress_scenario(scenario) {
+            error!("Invalid stress scenario '{}': {}", scenario.name, e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    run_stress_tests(&app_state, &StressTester::default(), payload.scenarios).await
+}
+
+async fn run_stress_tests(
+    app_state: &crate::AppState,
+    tester: &StressTester,
+    scenarios: Vec<StressScenario>,
+) -> Result<Json<Vec<StressTestResult>>, StatusCode> {
+    // Pas de scénario sur les canaux fictifs du mode mock
+    if !app_state.lightning_client.lock().await.is_connected() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    tester
+        .run_live(
+            &app_state.lightning_client,
+            &app_state.forwarding_store,
+            scenarios,
+            chrono::Utc::now(),
+        )
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to run stress tests: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+fn validate_stress_scenario(scenario: &StressScenario) -> Result<(), String> {
+    if scenario.name.trim().is_empty() || scenario.name.chars().count() > 100 {
+        return Err("name must be 1-100 characters".to_string());
+    }
+    if scenario.events.is_empty() || scenario.events.len() > MAX_STRESS_EVENTS {
+        return Err(format!("expected 1-{} events", MAX_STRESS_EVENTS));
+    }
+    if scenario
+        .probability
+        .is_some_and(|p| !(0.0..=1.0).contains(&p))
+    {
+        return Err("probability must be within 0-1".to_string());
+    }
+
+    let validate_peers = |peers: &PeerSelection| match peers {
+        PeerSelection::TopRevenue { count } if !(1..=50).contains(count) => {
+            Err("top_revenue count must be within 1-50".to_string())
+        }
+        PeerSelection::Pubkeys { pubkeys } if pubkeys.is_empty() || pubkeys.len() > 50 => {
+            Err("expected 1-50 pubkeys".to_string())
+        }
+        PeerSelection::Pubkeys { pubkeys } => pubkeys.iter().try_for_each(|pubkey| {
+            validate_input("pubkey", pubkey).map_err(|e| format!("{:?}", e))
+        }),
+        PeerSelection::TopRevenue { .. } => Ok(()),
     };
+    for event in &scenario.events {
+        match event {
+            StressEvent::ForceClose { peers, .. } => validate_peers(peers)?,
+            StressEvent::PeersOffline { peers, days } => {
+                validate_peers(peers)?;
+                if !(1..=365).contains(days) {
+                    return Err("offline days must be within 1-365".to_string());
+                }
+            }
+            StressEvent::OnchainFeeSpike { fee_rate_sat_vb } => {
+                if !(1.0..=10_000.0).contains(fee_rate_sat_vb) {
+                    return Err("fee rate must be within 1-10000 sat/vB".to_string());
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-    Ok(Json(analytics))
+#[derive(Debug, Deserialize)]
+pub struct PredictiveAnalyticsQuery {
+    /// Historique sur lequel les modèles sont ajustés, en jours
+    #[serde(default = "default_forecast_history_days")]
+    pub days: i64,
 }
 
-// Get competitive analysis
-pub async fn get_competitive_analysis() -> Result<Json<serde_json::Value>, StatusCode> {
-    let analysis = serde_json::json!({
-        "dazno_advantage": 15.3,
-        "ml_accuracy": 94.7,
-        "amboss_accuracy": 87.2,
-        "response_time": 145,
-        "amboss_response_time": 420,
-        "features_comparison": {
-            "dazno": ["AI Predictions", "Auto-execution", "Real-time updates", "Advanced analytics"],
-            "amboss": ["Basic recommendations", "Manual execution", "Delayed updates"]
+fn default_forecast_history_days() -> i64 {
+    60
+}
+
+// Prévisions saisonnières du volume, des frais gagnés et des frais médians du réseau
+pub async fn get_predictive_analytics(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<PredictiveAnalyticsQuery>,
+) -> Result<Json<PredictiveAnalytics>, StatusCode> {
+    let now = chrono::Utc::now();
+    let history_start = now - chrono::Duration::days(query.days.clamp(7, 365));
+    let store = &app_state.forwarding_store;
+
+    let events = store
+        .events_between(history_start, now)
+        .await
+        .map_err(|e| {
+            error!("Failed to load forwarding history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let network_fees = store.network_fees_since(history_start).await.map_err(|e| {
+        error!("Failed to load network fee samples: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    // Sans nœud joignable, les prévisions restent servies sans les ratios de liquidité
+    let channels = live_channels(&app_state).await.unwrap_or_default();
+
+    Ok(Json(Forecaster::default().predictive_analytics(
+        &ForecastInputs {
+            now,
+            history_start,
+            events: &events,
+            network_fees: &network_fees,
+            channels: &channels,
         },
-        "user_satisfaction": {
-            "dazno": 96.2,
-            "amboss": 78.5
-        }
-    });
+    )))
+}
 
-    Ok(Json(analysis))
+// Position du nœud face au réseau : capacité, canaux, centralité et frais par pair
+pub async fn get_competitive_analysis(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<CompetitivePositioning>, StatusCode> {
+    CompetitivePositioning::load_live(&app_state.lightning_client, chrono::Utc::now())
+        .await
+        .map_err(|e| {
+            error!("Failed to compute competitive positioning: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
 }
 
 // Health check endpoint
@@ -0,0 +1,39 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::models::anomaly::{Anomaly, AutomationPause};
+
+#[derive(Debug, Serialize)]
+pub struct AnomaliesResponse {
+    /// Suspension de l'automatisation en cours, le cas échéant
+    pub automation_pause: Option<AutomationPause>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+// Anomalies récentes et état de suspension de l'automatisation
+pub async fn get_anomalies(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<AnomaliesResponse>, StatusCode> {
+    Ok(Json(AnomaliesResponse {
+        automation_pause: app_state.anomaly_monitor.pause().await,
+        anomalies: app_state.anomaly_monitor.recent().await,
+    }))
+}
+
+// Reprise manuelle de l'automatisation après une anomalie critique
+pub async fn resume_automation(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<AutomationPause>, StatusCode> {
+    let pause = app_state
+        .anomaly_monitor
+        .resume()
+        .await
+        .ok_or(StatusCode::NOT_FOUND)?;
+    info!(
+        "Automation resumed after anomaly {} ({})",
+        pause.anomaly_id, pause.reason
+    );
+    Ok(Json(pause))
+}
@@ -0,0 +1,865 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::api::mcp_client::ActionType;
+use crate::handlers::actions::{
+    apply_node_action, capture_live_state, check_automated_action, ActionRefusal,
+};
+use crate::handlers::fee_strategies::validate_assignment;
+use crate::handlers::websocket::{AutomationResult, RealTimeUpdate};
+use crate::middleware::validation::validate_input;
+use crate::models::action_params::ActionParameters;
+use crate::models::automation::{
+    ActionResult, AutomationAction, AutomationActionType, AutomationCondition, AutomationExecution,
+    AutomationExecutionType, AutomationRule, AutomationSettings, ConditionType, ExecutionResults,
+    ExecutionStatus,
+};
+use crate::models::fee_strategy::FeeStrategyAssignment;
+use crate::models::recommendation::RecommendationStatus;
+use crate::utils::rule_engine::{
+    bind_parameters, validate_rule, RuleContext, RuleEngine, RuleEvaluation, RuleFiring,
+    RuleSubject,
+};
+use crate::utils::scheduling::{ExecutionScheduler, SchedulingInputs};
+
+/// Attente entre deux tentatives d'une action en échec
+const RETRY_BACKOFF_SECONDS: u64 = 30;
+/// Historique considéré pour la performance récente et les conditions de marché
+const RULE_HISTORY_DAYS: i64 = 7;
+
+/// État du nœud nécessaire aux règles ; seules les mesures demandées sont relevées.
+async fn capture_context(
+    app_state: &crate::AppState,
+    rules: &[AutomationRule],
+) -> Result<RuleContext> {
+    let now = Utc::now();
+    let needs = |wanted: fn(&ConditionType) -> bool| {
+        rules
+            .iter()
+            .flat_map(|r| &r.conditions)
+            .any(|c| wanted(&c.condition_type))
+    };
+
+    let mut channels = {
+        let mut client = app_state.lightning_client.lock().await;
+        // Pas de règle sur les canaux fictifs du mode mock
+        if !client.is_connected() {
+            return Err(anyhow!("Lightning node not connected"));
+        }
+        client.list_local_channels().await?
+    };
+    app_state
+        .forwarding_store
+        .apply_latest_policies(&mut channels)
+        .await?;
+
+    let mut peer_scores = HashMap::new();
+    if needs(|t| matches!(t, ConditionType::PeerReliability)) {
+        for reputation in app_state.peer_reputation_store.latest_all().await? {
+            peer_scores.insert(reputation.peer_pubkey, reputation.score);
+        }
+    }
+
+    let recommendations = if needs(|t| matches!(t, ConditionType::ROIImpact)) {
+        app_state
+            .recommendation_store
+            .list_fetched_since(
+                now - Duration::days(RULE_HISTORY_DAYS),
+                &[
+                    RecommendationStatus::Pending,
+                    RecommendationStatus::Approved,
+                ],
+            )
+            .await?
+    } else {
+        vec![]
+    };
+
+    let needs_market = needs(|t| matches!(t, ConditionType::MarketCondition));
+    let needs_performance = needs(|t| matches!(t, ConditionType::RecentPerformance));
+    let since = now - Duration::days(RULE_HISTORY_DAYS);
+    let events = if needs_market || needs_performance {
+        app_state
+            .forwarding_store
+            .events_between(since, now)
+            .await?
+    } else {
+        vec![]
+    };
+    let recent_revenue_sat =
+        needs_performance.then(|| events.iter().map(|e| e.fee_msat).sum::<u64>() / 1000);
+
+    let market_condition = if needs_market {
+        let inputs = SchedulingInputs {
+            now,
+            current_fees: None,
+            fee_samples: app_state
+                .forwarding_store
+                .fee_estimates_since(since)
+                .await?,
+            events,
+            history_days: RULE_HISTORY_DAYS as u32,
+            snapshots: app_state
+                .forwarding_store
+                .channel_snapshots_between(since, now)
+                .await?,
+            channels: channels.clone(),
+        };
+        Some(
+            ExecutionScheduler::default()
+                .smart_scheduling(&inputs)
+                .market_condition_analysis
+                .current_conditions,
+        )
+    } else {
+        None
+    };
+
+    Ok(RuleContext {
+        now,
+        channels,
+        peer_scores,
+        recommendations,
+        market_condition,
+        recent_revenue_sat,
+    })
+}
+
+/// Évalue les règles actives et exécute leurs actions, de la plus prioritaire à la
+/// moins prioritaire. Appelée périodiquement par la tâche de fond ; tant que
+/// l'automatisation ou l'exécution automatique est désactivée, seules les règles
+/// sans action sur le nœud sont évaluées. Les déclenchements comportant un délai
+/// se poursuivent en tâche de fond et ne figurent pas dans les exécutions
+/// renvoyées ; ils sont enregistrés en cours dès leur lancement.
+pub async fn run_automation_rules(
+    app_state: &Arc<crate::AppState>,
+) -> Result<Vec<AutomationExecution>> {
+    let mut rules = app_state.automation_rule_store.list().await?;
+    // Mêmes réglages que l'exécution automatique des recommandations
+    let settings = app_state.settings_store.automation_settings().await?;
+    if !settings.enabled || !settings.auto_execution_enabled {
+        rules.retain(|r| !acts_on_node(r));
+    }
+    if !rules.iter().any(|r| r.enabled) {
+        return Ok(vec![]);
+    }
+    if let Some(pause) = app_state.anomaly_monitor.pause().await {
+        info!(
+            "Automation rules skipped: automation paused since {} ({})",
+            pause.paused_at, pause.reason
+        );
+        return Ok(vec![]);
+    }
+
+    let context = capture_context(app_state, &rules).await?;
+    let firings = RuleEngine::default().plan(&rules, &context);
+
+    let today = context
+        .now
+        .date_naive()
+        .and_time(Default::default())
+        .and_utc();
+    let mut executed_today = app_state
+        .automation_rule_store
+        .count_executions_since(today)
+        .await?;
+
+    let mut executions = vec![];
+    for firing in firings {
+        if executed_today >= settings.max_daily_actions as u64 {
+            warn!(
+                "Daily automation limit of {} actions reached, remaining rules deferred",
+                settings.max_daily_actions
+            );
+            break;
+        }
+        // Une action de la passe a pu suspendre l'automatisation
+        if app_state.anomaly_monitor.pause().await.is_some() {
+            break;
+        }
+        executed_today += 1;
+        let execution = begin_execution(app_state, &firing).await;
+        let delayed = firing
+            .rule
+            .actions
+            .iter()
+            .any(|a| a.delay_seconds.unwrap_or(0) > 0);
+        if delayed {
+            let app_state = app_state.clone();
+            let settings = settings.clone();
+            tokio::spawn(async move {
+                execute_firing(&app_state, &firing, &settings, execution).await;
+            });
+        } else {
+            executions.push(execute_firing(app_state, &firing, &settings, execution).await);
+        }
+    }
+    Ok(executions)
+}
+
+/// Vrai si une action de la règle, ou l'une de ses annulations, agit sur le nœud.
+fn acts_on_node(rule: &AutomationRule) -> bool {
+    let mut actions: Vec<_> = rule.actions.iter().collect();
+    while let Some(action) = actions.pop() {
+        if matches!(
+            action.action_type,
+            AutomationActionType::ExecuteRecommendation
+                | AutomationActionType::AdjustFees
+                | AutomationActionType::OpenChannel
+                | AutomationActionType::CloseChannel
+                | AutomationActionType::Rebalance
+        ) {
+            return true;
+        }
+        actions.extend(action.rollback_action.as_deref());
+    }
+    false
+}
+
+/// Enregistre le déclenchement comme en cours, pour qu'il compte dans la limite
+/// quotidienne avant même la fin de ses actions différées.
+async fn begin_execution(app_state: &crate::AppState, firing: &RuleFiring) -> AutomationExecution {
+    let rule = &firing.rule;
+    let execution = AutomationExecution {
+        id: Uuid::new_v4().to_string(),
+        rule_id: rule.id.clone(),
+        recommendation_id: firing
+            .evaluation
+            .subject
+            .recommendation_id()
+            .map(str::to_string),
+        execution_type: AutomationExecutionType::Conditional,
+        status: ExecutionStatus::InProgress,
+        started_at: Utc::now(),
+        completed_at: None,
+        results: ExecutionResults {
+            success: false,
+            actions_taken: vec![],
+            performance_impact: None,
+            cost: 0,
+            time_taken_ms: 0,
+        },
+        error_message: None,
+        rollback_executed: false,
+    };
+    if let Err(e) = app_state
+        .automation_rule_store
+        .record_execution(&execution)
+        .await
+    {
+        error!("Failed to record execution of rule {}: {}", rule.id, e);
+    }
+    execution
+}
+
+/// Exécute les actions d'un déclenchement dans l'ordre, avec délai et nouvelles
+/// tentatives ; en cas d'échec, les actions déjà passées sont annulées si possible.
+async fn execute_firing(
+    app_state: &crate::AppState,
+    firing: &RuleFiring,
+    settings: &AutomationSettings,
+    execution: AutomationExecution,
+) -> AutomationExecution {
+    let rule = &firing.rule;
+    let subject = &firing.evaluation.subject;
+    let started_at = execution.started_at;
+    info!(
+        "Automation rule '{}' triggered on {:?} (score {:.2})",
+        rule.name, subject, firing.evaluation.score
+    );
+    if let Err(e) = app_state
+        .automation_rule_store
+        .mark_triggered(&rule.id, started_at)
+        .await
+    {
+        error!("Failed to mark rule {} as triggered: {}", rule.id, e);
+    }
+
+    let mut actions_taken = vec![];
+    let mut completed: Vec<&AutomationAction> = vec![];
+    let mut error_message = None;
+    let mut paused = false;
+    for action in &rule.actions {
+        if let Some(delay) = action.delay_seconds.filter(|d| *d > 0) {
+            tokio::time::sleep(tokio::time::Duration::from_secs(delay as u64)).await;
+            // L'automatisation a pu être suspendue pendant l'attente
+            if let Some(pause) = app_state.anomaly_monitor.pause().await {
+                error_message = Some(format!(
+                    "{:?} not run: automation paused ({})",
+                    action.action_type, pause.reason
+                ));
+                paused = true;
+                break;
+            }
+        }
+        let (result, attempts) = run_with_retries(app_state, settings, rule, action, subject).await;
+        let success = result.success;
+        actions_taken.push(result);
+        if success {
+            completed.push(action);
+        } else {
+            error_message = Some(format!(
+                "{:?} failed after {} attempt(s)",
+                action.action_type, attempts
+            ));
+            break;
+        }
+    }
+
+    let mut rollback_executed = false;
+    if error_message.is_some() && !paused && settings.advanced_settings.rollback_on_failure {
+        for rollback in completed
+            .iter()
+            .rev()
+            .filter_map(|a| a.rollback_action.as_deref())
+        {
+            let (result, _) = run_with_retries(app_state, settings, rule, rollback, subject).await;
+            rollback_executed = true;
+            actions_taken.push(result);
+        }
+    }
+
+    let success = error_message.is_none();
+    let completed_at = Utc::now();
+    let execution = AutomationExecution {
+        status: if success {
+            ExecutionStatus::Completed
+        } else if rollback_executed {
+            ExecutionStatus::RolledBack
+        } else {
+            ExecutionStatus::Failed
+        },
+        started_at,
+        completed_at: Some(completed_at),
+        results: ExecutionResults {
+            success,
+            actions_taken,
+            performance_impact: None,
+            cost: 0,
+            time_taken_ms: (completed_at - started_at).num_milliseconds().max(0) as u64,
+        },
+        error_message,
+        rollback_executed,
+        ..execution
+    };
+
+    if let Err(e) = app_state
+        .automation_rule_store
+        .record_trigger(&rule.id, started_at, success)
+        .await
+    {
+        error!("Failed to update statistics of rule {}: {}", rule.id, e);
+    }
+    if let Err(e) = app_state
+        .automation_rule_store
+        .record_execution(&execution)
+        .await
+    {
+        error!("Failed to record execution of rule {}: {}", rule.id, e);
+    }
+
+    app_state
+        .ws_state
+        .broadcast_automation_result(AutomationResult {
+            recommendation_id: execution
+                .recommendation_id
+                .clone()
+                .unwrap_or_else(|| rule.id.clone()),
+            success,
+            roi_impact: 0.0,
+            execution_time_ms: execution.results.time_taken_ms,
+            message: match &execution.error_message {
+                Some(message) => format!("Règle « {} » : {}", rule.name, message),
+                None => format!("Règle « {} » exécutée", rule.name),
+            },
+        });
+    execution
+}
+
+/// Échec d'une action de règle. Seuls les échecs survenus avant tout effet sur
+/// le nœud, ou ceux d'une action idempotente, peuvent être retentés.
+struct ActionFailure {
+    error: anyhow::Error,
+    retryable: bool,
+}
+
+impl ActionFailure {
+    fn transient(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            error: error.into(),
+            retryable: true,
+        }
+    }
+
+    fn refused(refusal: ActionRefusal) -> Self {
+        Self {
+            retryable: matches!(refusal, ActionRefusal::Unavailable(_)),
+            error: refusal.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ActionFailure {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            error,
+            retryable: false,
+        }
+    }
+}
+
+/// Exécute une action et renvoie son résultat avec le nombre de tentatives.
+async fn run_with_retries(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+    rule: &AutomationRule,
+    action: &AutomationAction,
+    subject: &RuleSubject,
+) -> (ActionResult, u32) {
+    let parameters = bind_parameters(&action.action_type, &action.parameters, subject);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match perform_action(app_state, settings, rule, &action.action_type, &parameters).await {
+            Ok(details) => {
+                let result = ActionResult {
+                    action: format!("{:?}", action.action_type),
+                    success: true,
+                    details,
+                    timestamp: Utc::now(),
+                };
+                return (result, attempt);
+            }
+            Err(failure) if failure.retryable && attempt <= action.retry_count => {
+                warn!(
+                    "Rule '{}' action {:?} failed (attempt {}): {}",
+                    rule.name, action.action_type, attempt, failure.error
+                );
+                tokio::time::sleep(tokio::time::Duration::from_secs(RETRY_BACKOFF_SECONDS)).await;
+            }
+            Err(failure) => {
+                error!(
+                    "Rule '{}' action {:?} failed: {}",
+                    rule.name, action.action_type, failure.error
+                );
+                let result = ActionResult {
+                    action: format!("{:?}", action.action_type),
+                    success: false,
+                    details: json!({ "error": failure.error.to_string(), "parameters": parameters }),
+                    timestamp: Utc::now(),
+                };
+                return (result, attempt);
+            }
+        }
+    }
+}
+
+fn message(parameters: &Value, rule: &AutomationRule) -> String {
+    parameters
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Règle « {} » déclenchée", rule.name))
+}
+
+/// Une règle ne force jamais la fermeture d'un canal, y compris en exécutant une
+/// recommandation qui le demande.
+fn refuse_forced_close(parameters: &ActionParameters) -> Result<(), ActionFailure> {
+    match parameters {
+        ActionParameters::CloseChannel(close) if close.force => {
+            Err(anyhow!("forced channel close is not run by automation rules").into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Action sur le nœud soumise aux garde-fous de l'exécution automatique. Une
+/// ouverture ou une fermeture en échec n'est jamais retentée : la transaction a
+/// pu être diffusée malgré l'erreur.
+async fn guarded_node_action(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+    parameters: ActionParameters,
+) -> Result<Value, ActionFailure> {
+    refuse_forced_close(&parameters)?;
+    let state = capture_live_state(app_state)
+        .await
+        .map_err(ActionFailure::transient)?;
+    check_automated_action(app_state, settings, &parameters, &state)
+        .await
+        .map_err(ActionFailure::refused)?;
+    let idempotent = matches!(parameters, ActionParameters::AdjustFees(_));
+    apply_node_action(app_state, parameters)
+        .await
+        .map_err(|error| ActionFailure {
+            error,
+            retryable: idempotent,
+        })
+}
+
+async fn perform_action(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+    rule: &AutomationRule,
+    action_type: &AutomationActionType,
+    parameters: &Value,
+) -> Result<Value, ActionFailure> {
+    let typed = |action_type: ActionType| {
+        ActionParameters::parse(action_type, parameters).map_err(|e| anyhow!(e))
+    };
+    match action_type {
+        AutomationActionType::ExecuteRecommendation => {
+            let id = parameters
+                .get("recommendation_id")
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("no recommendation to execute"))?;
+            execute_recommendation(app_state, settings, rule, id).await
+        }
+        AutomationActionType::AdjustFees => {
+            guarded_node_action(app_state, settings, typed(ActionType::AdjustFees)?).await
+        }
+        AutomationActionType::OpenChannel => {
+            guarded_node_action(app_state, settings, typed(ActionType::OpenChannel)?).await
+        }
+        AutomationActionType::CloseChannel => {
+            guarded_node_action(app_state, settings, typed(ActionType::CloseChannel)?).await
+        }
+        AutomationActionType::Rebalance => {
+            guarded_node_action(app_state, settings, typed(ActionType::RebalanceChannel)?).await
+        }
+        AutomationActionType::SendNotification => {
+            let text = message(parameters, rule);
+            app_state.ws_state.broadcast_update(RealTimeUpdate {
+                r#type: "automation_notification".to_string(),
+                payload: json!({ "rule_id": rule.id, "message": text }),
+                timestamp: Utc::now(),
+            });
+            Ok(json!({ "message": text }))
+        }
+        AutomationActionType::UpdateStrategy => {
+            let mut assignment: FeeStrategyAssignment = serde_json::from_value(parameters.clone())
+                .map_err(|e| anyhow!("invalid strategy assignment: {}", e))?;
+            validate_assignment(&assignment).map_err(|e| anyhow!(e))?;
+            assignment.updated_at = Utc::now();
+            app_state.fee_strategy_store.upsert(&assignment).await?;
+            Ok(json!({ "scope": assignment.scope_key() }))
+        }
+        AutomationActionType::PauseAutomation => {
+            let reason = message(parameters, rule);
+            let paused = app_state
+                .anomaly_monitor
+                .suspend(format!("rule:{}", rule.id), reason.clone(), Utc::now())
+                .await;
+            warn!("Automation paused by rule '{}': {}", rule.name, reason);
+            Ok(json!({ "paused": paused, "reason": reason }))
+        }
+        AutomationActionType::LogEvent => {
+            let text = message(parameters, rule);
+            info!("Automation rule '{}': {}", rule.name, text);
+            Ok(json!({ "message": text }))
+        }
+    }
+}
+
+/// Fait passer une recommandation par son cycle de vie jusqu'à l'exécution,
+/// après les garde-fous de l'exécution automatique. Seuls les échecs antérieurs
+/// au passage en `Executing` peuvent être retentés.
+async fn execute_recommendation(
+    app_state: &crate::AppState,
+    settings: &AutomationSettings,
+    rule: &AutomationRule,
+    id: &str,
+) -> Result<Value, ActionFailure> {
+    let store = &app_state.recommendation_store;
+    let recommendation = store
+        .get(id)
+        .await
+        .map_err(ActionFailure::transient)?
+        .ok_or_else(|| anyhow!("recommendation {} not found", id))?;
+    if !recommendation.status.is_actionable() {
+        return Err(anyhow!(
+            "recommendation {} is {}",
+            id,
+            recommendation.status.as_str()
+        )
+        .into());
+    }
+    let parameters =
+        ActionParameters::parse(recommendation.action_type, &recommendation.parameters)
+            .map_err(|e| anyhow!(e))?;
+    refuse_forced_close(&parameters)?;
+    let state = capture_live_state(app_state)
+        .await
+        .map_err(ActionFailure::transient)?;
+    check_automated_action(app_state, settings, &parameters, &state)
+        .await
+        .map_err(ActionFailure::refused)?;
+
+    let actor = format!("automation:rule:{}", rule.id);
+    let reason = Some(format!("Règle « {} »", rule.name));
+    if recommendation.status == RecommendationStatus::Pending {
+        store
+            .transition(
+                id,
+                RecommendationStatus::Approved,
+                &actor,
+                reason.clone(),
+                None,
+            )
+            .await?;
+    }
+    store
+        .transition(id, RecommendationStatus::Executing, &actor, reason, None)
+        .await?;
+
+    match apply_node_action(app_state, parameters).await {
+        Ok(details) => {
+            store
+                .transition(id, RecommendationStatus::Executed, &actor, None, None)
+                .await?;
+            Ok(json!({ "recommendation_id": id, "result": details }))
+        }
+        Err(e) => {
+            store
+                .transition(
+                    id,
+                    RecommendationStatus::Failed,
+                    &actor,
+                    Some(e.to_string()),
+                    None,
+                )
+                .await?;
+            Err(e.into())
+        }
+    }
+}
+
+/// Corps de création et de mise à jour d'une règle ; les statistiques de
+/// déclenchement restent gérées par le moteur.
+#[derive(Debug, Deserialize)]
+pub struct RuleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub conditions: Vec<AutomationCondition>,
+    pub actions: Vec<AutomationAction>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub priority: u32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RuleRequest {
+    fn validate(&self) -> Result<(), StatusCode> {
+        for text in [&self.name, &self.description] {
+            if !text.is_empty() {
+                if let Err(e) = validate_input("message", text) {
+                    error!("Invalid automation rule text: {:?}", e);
+                    return Err(StatusCode::BAD_REQUEST);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn into_rule(self, existing: Option<AutomationRule>) -> AutomationRule {
+        let id = existing
+            .as_ref()
+            .map(|r| r.id.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        AutomationRule {
+            id,
+            name: self.name,
+            description: self.description,
+            conditions: self.conditions,
+            actions: self.actions,
+            enabled: self.enabled,
+            priority: self.priority,
+            created_at: existing.as_ref().map_or_else(Utc::now, |r| r.created_at),
+            last_triggered: existing.as_ref().and_then(|r| r.last_triggered),
+            trigger_count: existing.as_ref().map_or(0, |r| r.trigger_count),
+            success_rate: existing.as_ref().map_or(0.0, |r| r.success_rate),
+        }
+    }
+}
+
+fn valid_rule_id(id: &str) -> Result<(), StatusCode> {
+    validate_input("recommendation_id", id).map_err(|e| {
+        error!("Invalid automation rule id: {:?}", e);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+async fn load_rule(app_state: &crate::AppState, id: &str) -> Result<AutomationRule, StatusCode> {
+    valid_rule_id(id)?;
+    app_state
+        .automation_rule_store
+        .get(id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load automation rule {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn save_rule(
+    app_state: &crate::AppState,
+    rule: AutomationRule,
+) -> Result<Json<AutomationRule>, StatusCode> {
+    if let Err(e) = validate_rule(&rule) {
+        error!("Invalid automation rule: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    app_state
+        .automation_rule_store
+        .save(&rule)
+        .await
+        .map_err(|e| {
+            error!("Failed to save automation rule: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    info!("Automation rule '{}' saved ({})", rule.name, rule.id);
+    Ok(Json(rule))
+}
+
+// Règles d'automatisation, de la plus prioritaire à la moins prioritaire
+pub async fn list_rules(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<Vec<AutomationRule>>, StatusCode> {
+    app_state
+        .automation_rule_store
+        .list()
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to list automation rules: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Création d'une règle
+pub async fn create_rule(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(request): Json<RuleRequest>,
+) -> Result<Json<AutomationRule>, StatusCode> {
+    request.validate()?;
+    save_rule(&app_state, request.into_rule(None)).await
+}
+
+// Détail d'une règle
+pub async fn get_rule(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<AutomationRule>, StatusCode> {
+    load_rule(&app_state, &id).await.map(Json)
+}
+
+// Mise à jour d'une règle ; ses statistiques de déclenchement sont conservées
+pub async fn update_rule(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+    Json(request): Json<RuleRequest>,
+) -> Result<Json<AutomationRule>, StatusCode> {
+    request.validate()?;
+    let existing = load_rule(&app_state, &id).await?;
+    save_rule(&app_state, request.into_rule(Some(existing))).await
+}
+
+// Suppression d'une règle et de son historique d'exécution
+pub async fn delete_rule(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    valid_rule_id(&id)?;
+    let deleted = app_state
+        .automation_rule_store
+        .delete(&id)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete automation rule {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecutionsQuery {
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_limit() -> u32 {
+    50
+}
+
+// Historique des exécutions d'une règle
+pub async fn get_rule_executions(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ExecutionsQuery>,
+) -> Result<Json<Vec<AutomationExecution>>, StatusCode> {
+    valid_rule_id(&id)?;
+    app_state
+        .automation_rule_store
+        .executions(&id, query.limit.clamp(1, 500))
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to load executions of rule {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleEvaluationResponse {
+    pub rule_id: String,
+    pub evaluations: Vec<RuleEvaluation>,
+}
+
+// Évaluation à blanc d'une règle sur l'état courant du nœud, sans exécution
+pub async fn evaluate_rule(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<RuleEvaluationResponse>, StatusCode> {
+    let rule = load_rule(&app_state, &id).await?;
+    if !app_state.lightning_client.lock().await.is_connected() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let context = capture_context(&app_state, std::slice::from_ref(&rule))
+        .await
+        .map_err(|e| {
+            error!("Failed to capture automation context: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let engine = RuleEngine::default();
+    let evaluations = engine
+        .subjects(&rule, &context)
+        .into_iter()
+        .map(|subject| engine.evaluate(&rule, subject, &context))
+        .collect();
+    Ok(Json(RuleEvaluationResponse {
+        rule_id: rule.id,
+        evaluations,
+    }))
+}
@@ -0,0 +1,78 @@
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::utils::backtest::{
+    strategy_by_name, BacktestConfig, BacktestDataset, BacktestReport, BacktestStrategy,
+    Backtester, MLEngineStrategy, TRAINING_LOOKBACK_DAYS,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct BacktestRequest {
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    /// Fenêtre en jours jusqu'à `end` quand `start` est absent
+    #[serde(default = "default_days")]
+    pub days: i64,
+    #[serde(default = "default_strategy")]
+    pub strategy: String,
+    #[serde(default)]
+    pub config: Option<BacktestConfig>,
+}
+
+fn default_days() -> i64 {
+    30
+}
+
+fn default_strategy() -> String {
+    "ml_engine".to_string()
+}
+
+// Rejoue l'historique stocké avec une stratégie et la compare à la référence
+pub async fn run_backtest(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(request): Json<BacktestRequest>,
+) -> Result<Json<BacktestReport>, StatusCode> {
+    // Le moteur ML rejoué est la version active, pas la configuration par défaut
+    let strategy: Box<dyn BacktestStrategy> = match request.strategy.as_str() {
+        "ml_engine" | "ml" => Box::new(MLEngineStrategy::new(app_state.ml_models.engine().await)),
+        name => strategy_by_name(name).ok_or(StatusCode::BAD_REQUEST)?,
+    };
+
+    let end = request.end.unwrap_or_else(Utc::now);
+    let start = request
+        .start
+        .unwrap_or_else(|| end - Duration::days(request.days.clamp(1, 365)));
+    if start >= end {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    info!(
+        "Running backtest '{}' from {} to {}",
+        request.strategy, start, end
+    );
+
+    let dataset = BacktestDataset::load(
+        &app_state.forwarding_store,
+        start,
+        end,
+        Duration::days(TRAINING_LOOKBACK_DAYS),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to load backtest dataset: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let backtester = Backtester::new(request.config.unwrap_or_default());
+    Ok(Json(backtester.run(
+        &dataset,
+        strategy.as_ref(),
+        start,
+        end,
+    )))
+}
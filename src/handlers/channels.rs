@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::middleware::validation::validate_input;
+use crate::storage::forwarding::ChannelHistoryPoint;
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelHistoryQuery {
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    /// Fenêtre en jours jusqu'à `end` quand `start` est absent
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+fn default_days() -> i64 {
+    7
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChannelHistoryResponse {
+    pub channel_id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub points: Vec<ChannelHistoryPoint>,
+}
+
+// Série temporelle des soldes, de la politique et des HTLC d'un canal
+pub async fn get_channel_history(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(channel_id): Path<String>,
+    Query(query): Query<ChannelHistoryQuery>,
+) -> Result<Json<ChannelHistoryResponse>, StatusCode> {
+    if let Err(e) = validate_input("channel_id", &channel_id) {
+        error!("Invalid channel id in path: {:?}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let end = query.end.unwrap_or_else(Utc::now);
+    let start = query
+        .start
+        .unwrap_or_else(|| end - Duration::days(query.days.clamp(1, 730)));
+    if start >= end {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let points = app_state
+        .forwarding_store
+        .channel_history(&channel_id, start, end)
+        .await
+        .map_err(|e| {
+            error!("Failed to load channel history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ChannelHistoryResponse {
+        channel_id,
+        start,
+        end,
+        points,
+    }))
+}
@@ -0,0 +1,141 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::middleware::validation::{validate_input, validate_numeric_input};
+use crate::models::fee_strategy::{AssignmentScope, FeeStrategyAssignment, FeeStrategyKind};
+
+// Affectations de stratégies de frais en vigueur
+pub async fn list_fee_strategies(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<Vec<FeeStrategyAssignment>>, StatusCode> {
+    app_state
+        .fee_strategy_store
+        .list()
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to list fee strategy assignments: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Affecte une chaîne de stratégies à un canal ou à un groupe de pairs
+pub async fn put_fee_strategy(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(mut assignment): Json<FeeStrategyAssignment>,
+) -> Result<Json<FeeStrategyAssignment>, StatusCode> {
+    if let Err(e) = validate_assignment(&assignment) {
+        error!("Invalid fee strategy assignment: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    assignment.updated_at = Utc::now();
+
+    app_state
+        .fee_strategy_store
+        .upsert(&assignment)
+        .await
+        .map_err(|e| {
+            error!("Failed to save fee strategy assignment: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("Fee strategy assigned to {}", assignment.scope_key());
+    Ok(Json(assignment))
+}
+
+// Retire l'affectation d'un canal (`channel`) ou d'un groupe (`group`)
+pub async fn delete_fee_strategy(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path((scope, id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    let field = match scope.as_str() {
+        "channel" => "channel_id",
+        "group" => "group_name",
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    if let Err(e) = validate_input(field, &id) {
+        error!("Invalid fee strategy scope: {:?}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let deleted = app_state
+        .fee_strategy_store
+        .delete(&format!("{}:{}", scope, id))
+        .await
+        .map_err(|e| {
+            error!("Failed to delete fee strategy assignment: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+pub fn validate_assignment(assignment: &FeeStrategyAssignment) -> Result<(), String> {
+    match &assignment.scope {
+        AssignmentScope::Channel { channel_id } => {
+            validate_input("channel_id", channel_id).map_err(|e| format!("{:?}", e))?
+        }
+        AssignmentScope::PeerGroup { name, peers } => {
+            validate_input("group_name", name).map_err(|e| format!("{:?}", e))?;
+            if peers.is_empty() {
+                return Err("peer group without peers".to_string());
+            }
+            for peer in peers {
+                validate_input("pubkey", peer).map_err(|e| format!("{:?}", e))?;
+            }
+        }
+    }
+
+    if assignment.strategies.is_empty() {
+        return Err("no strategy given".to_string());
+    }
+    let fee_rate =
+        |ppm: u32| validate_numeric_input("fee_rate", ppm as f64).map_err(|e| format!("{:?}", e));
+    for strategy in &assignment.strategies {
+        match strategy {
+            FeeStrategyKind::BalanceProportional { min_ppm, max_ppm } => {
+                fee_rate(*min_ppm)?;
+                fee_rate(*max_ppm)?;
+                if min_ppm > max_ppm {
+                    return Err("min_ppm above max_ppm".to_string());
+                }
+            }
+            FeeStrategyKind::CompetitiveMatching { undercut_pct, .. } => {
+                if !(0.0..100.0).contains(undercut_pct) {
+                    return Err("undercut_pct out of range".to_string());
+                }
+            }
+            FeeStrategyKind::DemandStepping {
+                step_ppm,
+                window_days,
+                ..
+            } => {
+                fee_rate(*step_ppm)?;
+                if !(1..=90).contains(window_days) {
+                    return Err("window_days out of range".to_string());
+                }
+            }
+            FeeStrategyKind::StaticBounds {
+                floor_ppm,
+                ceiling_ppm,
+            } => {
+                fee_rate(*floor_ppm)?;
+                fee_rate(*ceiling_ppm)?;
+                if floor_ppm > ceiling_ppm {
+                    return Err("floor_ppm above ceiling_ppm".to_string());
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::models::ledger::Ledger;
+use crate::utils::ledger::{export_csv, load_ledger, LedgerExportFormat};
+
+#[derive(Debug, Deserialize)]
+pub struct LedgerQuery {
+    #[serde(default)]
+    pub start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+    /// Fenêtre en jours jusqu'à `end` quand `start` est absent
+    #[serde(default = "default_days")]
+    pub days: i64,
+    /// `generic`, `koinly` ou `cointracking`
+    #[serde(default = "default_format")]
+    pub format: String,
+}
+
+fn default_days() -> i64 {
+    365
+}
+
+fn default_format() -> String {
+    "generic".to_string()
+}
+
+async fn ledger_for(
+    app_state: &crate::AppState,
+    query: &LedgerQuery,
+) -> Result<Ledger, StatusCode> {
+    let end = query.end.unwrap_or_else(Utc::now);
+    let start = query
+        .start
+        .unwrap_or_else(|| end - Duration::days(query.days.clamp(1, 3_650)));
+    if start >= end {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    load_ledger(
+        &app_state.lightning_client,
+        &app_state.forwarding_store,
+        &app_state.price_store,
+        &app_state.price_source,
+        start,
+        end,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to build ledger: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+// Grand livre en partie double de la période, avec contre-valeur au cours du jour
+pub async fn get_ledger(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<LedgerQuery>,
+) -> Result<Json<Ledger>, StatusCode> {
+    ledger_for(&app_state, &query).await.map(Json)
+}
+
+// Export CSV du grand livre, générique ou pour un outil fiscal
+pub async fn export_ledger(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<LedgerQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let format = LedgerExportFormat::from_name(&query.format).ok_or(StatusCode::BAD_REQUEST)?;
+    let ledger = ledger_for(&app_state, &query).await?;
+    info!(
+        "Exporting {} ledger entries as {:?}",
+        ledger.entries.len(),
+        format
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", format.file_name(&ledger)),
+            ),
+        ],
+        export_csv(&ledger, format),
+    ))
+}
@@ -0,0 +1,63 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::utils::inbound_liquidity::{
+    InboundAnalyzerConfig, InboundLiquidityAnalyzer, InboundLiquidityReport,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct InboundLiquidityQuery {
+    #[serde(default = "default_inbound_days")]
+    pub days: u32,
+}
+
+fn default_inbound_days() -> u32 {
+    30
+}
+
+// Canaux sans entrant, demande perdue et coût de chaque moyen d'acquérir de l'entrant
+pub async fn get_inbound_liquidity(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<InboundLiquidityQuery>,
+) -> Result<Json<InboundLiquidityReport>, StatusCode> {
+    let (channels, wallet) = {
+        let mut client = app_state.lightning_client.lock().await;
+        let channels = client.list_local_channels().await.map_err(|e| {
+            error!("Failed to list channels for inbound analysis: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        })?;
+        let wallet = client.get_local_wallet_balance().await;
+        (channels, wallet)
+    };
+    // Sans solde on-chain connu, les méthodes financées on-chain sont jugées irréalisables
+    let wallet_confirmed_sat = wallet
+        .map(|balance| balance.confirmed_balance)
+        .unwrap_or_else(|e| {
+            warn!("Wallet balance unavailable for inbound analysis: {}", e);
+            0
+        });
+
+    InboundLiquidityAnalyzer::new(InboundAnalyzerConfig {
+        history_days: query.days.clamp(7, 180),
+        ..Default::default()
+    })
+    .analyze_stored(
+        &app_state.forwarding_store,
+        &channels,
+        wallet_confirmed_sat,
+        Utc::now(),
+    )
+    .await
+    .map(Json)
+    .map_err(|e| {
+        error!("Failed to analyze inbound liquidity: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
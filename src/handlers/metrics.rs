@@ -0,0 +1,205 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, warn};
+
+use crate::api::local_lightning_client::LocalChannelInfo;
+use crate::models::recommendation::RecommendationStatus;
+use crate::utils::prometheus::{MetricKind, PrometheusWriter};
+
+/// Délai au-delà duquel le MCP est déclaré injoignable
+const MCP_HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Métriques au format Prometheus : nœud, canaux, forwards, recommandations, HTTP
+pub async fn get_metrics(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let mut writer = PrometheusWriter::new();
+
+    // LND : pas de soldes fictifs du mode mock dans les séries
+    let (node, channels) = {
+        let mut client = app_state.lightning_client.lock().await;
+        let node = client
+            .get_local_node_info()
+            .await
+            .map_err(|e| e.to_string());
+        let channels = if client.is_connected() {
+            client
+                .list_local_channels()
+                .await
+                .map_err(|e| e.to_string())
+        } else {
+            Err("LND unavailable".to_string())
+        };
+        (node.ok().filter(|_| channels.is_ok()), channels)
+    };
+    writer.single(
+        "dazno_lnd_up",
+        MetricKind::Gauge,
+        "Connexion gRPC à LND établie",
+        node.is_some() as u8 as f64,
+    );
+    if let Some(node) = &node {
+        writer.single(
+            "dazno_lnd_synced_to_chain",
+            MetricKind::Gauge,
+            "LND synchronisé avec la chaîne",
+            node.synced_to_chain as u8 as f64,
+        );
+        writer.single(
+            "dazno_lnd_block_height",
+            MetricKind::Gauge,
+            "Hauteur de bloc vue par LND",
+            node.block_height as f64,
+        );
+    }
+
+    let mcp_up = matches!(
+        tokio::time::timeout(MCP_HEALTH_TIMEOUT, app_state.mcp_client.health_check()).await,
+        Ok(Ok(true))
+    );
+    writer.single(
+        "dazno_mcp_up",
+        MetricKind::Gauge,
+        "API MCP joignable",
+        mcp_up as u8 as f64,
+    );
+
+    match &channels {
+        Ok(channels) => {
+            let sum = |balance: fn(&LocalChannelInfo) -> u64| {
+                channels.iter().map(balance).sum::<u64>() as f64
+            };
+            writer.single(
+                "dazno_node_local_balance_sat",
+                MetricKind::Gauge,
+                "Solde local total des canaux",
+                sum(|c| c.local_balance),
+            );
+            writer.single(
+                "dazno_node_remote_balance_sat",
+                MetricKind::Gauge,
+                "Solde distant total des canaux",
+                sum(|c| c.remote_balance),
+            );
+            writer.single(
+                "dazno_node_capacity_sat",
+                MetricKind::Gauge,
+                "Capacité totale des canaux",
+                sum(|c| c.capacity),
+            );
+            writer.family(
+                "dazno_node_channels",
+                MetricKind::Gauge,
+                "Canaux ouverts, par état",
+            );
+            let active = channels.iter().filter(|c| c.active).count();
+            writer.sample("dazno_node_channels", &[("state", "active")], active as f64);
+            writer.sample(
+                "dazno_node_channels",
+                &[("state", "inactive")],
+                (channels.len() - active) as f64,
+            );
+
+            writer.family(
+                "dazno_channel_local_ratio",
+                MetricKind::Gauge,
+                "Part locale de la capacité du canal",
+            );
+            for channel in channels {
+                writer.sample(
+                    "dazno_channel_local_ratio",
+                    &[
+                        ("channel_id", &channel.channel_id),
+                        ("peer_alias", &channel.peer_alias),
+                    ],
+                    channel.local_balance as f64 / channel.capacity.max(1) as f64,
+                );
+            }
+        }
+        Err(e) => warn!("Channel metrics unavailable: {}", e),
+    }
+
+    let totals = app_state
+        .forwarding_store
+        .forwarding_totals()
+        .await
+        .map_err(|e| {
+            error!("Failed to load forwarding totals: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    writer.single(
+        "dazno_forwards_total",
+        MetricKind::Counter,
+        "Forwards réussis enregistrés",
+        totals.forwards as f64,
+    );
+    writer.single(
+        "dazno_forward_fees_earned_msat_total",
+        MetricKind::Counter,
+        "Frais de routage gagnés",
+        totals.fees_msat as f64,
+    );
+    writer.single(
+        "dazno_forwarded_msat_total",
+        MetricKind::Counter,
+        "Volume routé",
+        totals.amt_out_msat as f64,
+    );
+
+    let store = &app_state.recommendation_store;
+    let internal = |e: anyhow::Error| {
+        error!("Failed to load recommendation metrics: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let by_status = store.count_by_status().await.map_err(internal)?;
+    writer.family(
+        "dazno_recommendations",
+        MetricKind::Gauge,
+        "Recommandations par état",
+    );
+    for status in RecommendationStatus::ALL {
+        let count = by_status
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map_or(0, |(_, count)| *count);
+        writer.sample(
+            "dazno_recommendations",
+            &[("state", status.as_str())],
+            count as f64,
+        );
+    }
+
+    // Une exécution se termine par une transition vers « executed » ou « failed »
+    let transitions = store.transition_counts().await.map_err(internal)?;
+    writer.family(
+        "dazno_automation_executions_total",
+        MetricKind::Counter,
+        "Exécutions de recommandations, par issue",
+    );
+    for status in [RecommendationStatus::Executed, RecommendationStatus::Failed] {
+        let count = transitions
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map_or(0, |(_, count)| *count);
+        writer.sample(
+            "dazno_automation_executions_total",
+            &[("status", status.as_str())],
+            count as f64,
+        );
+    }
+
+    app_state.http_metrics.write(&mut writer);
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        writer.finish(),
+    ))
+}
@@ -0,0 +1,221 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::middleware::validation::validate_input;
+use crate::storage::ml_models::ModelVersion;
+use crate::utils::backtest::{
+    BacktestDataset, BacktestReport, Backtester, MLEngineStrategy, TRAINING_LOOKBACK_DAYS,
+};
+use crate::utils::ml_engine::MLEngineConfig;
+use crate::utils::preference_learning::{PreferenceLearner, PreferenceReport};
+
+#[derive(Debug, Deserialize)]
+pub struct PublishModelRequest {
+    pub config: MLEngineConfig,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareModelsQuery {
+    pub a: i64,
+    pub b: i64,
+    /// Fenêtre d'historique rejouée, en jours jusqu'à maintenant
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+fn default_days() -> i64 {
+    30
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreferenceReportQuery {
+    /// Période dont on mesure l'effet sur les préférences, en jours
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelComparison {
+    pub a: BacktestReport,
+    pub b: BacktestReport,
+    /// Gain net de `b` moins celui de `a`, sur les mêmes données
+    pub net_delta_difference_sat: f64,
+}
+
+// Versions enregistrées de la configuration du moteur ML
+pub async fn list_models(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<Vec<ModelVersion>>, StatusCode> {
+    app_state
+        .ml_models
+        .store()
+        .list()
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to list ML model versions: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Enregistre une nouvelle configuration et l'active
+pub async fn publish_model(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(request): Json<PublishModelRequest>,
+) -> Result<Json<ModelVersion>, StatusCode> {
+    if let Err(e) = validate_config(&request.config) {
+        error!("Invalid ML model configuration: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if !request.note.is_empty() {
+        if let Err(e) = validate_input("message", &request.note) {
+            error!("Invalid ML model note: {:?}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let version = app_state
+        .ml_models
+        .publish(request.config, &request.note)
+        .await
+        .map_err(|e| {
+            error!("Failed to publish ML model version: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("ML model version {} published", version.version);
+    Ok(Json(version))
+}
+
+// Active une version enregistrée
+pub async fn activate_model(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(version): Path<i64>,
+) -> Result<Json<ModelVersion>, StatusCode> {
+    app_state
+        .ml_models
+        .activate(version)
+        .await
+        .map_err(|e| {
+            error!("Failed to activate ML model version {}: {}", version, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+// Revient à la version active précédente
+pub async fn rollback_model(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<ModelVersion>, StatusCode> {
+    app_state
+        .ml_models
+        .rollback()
+        .await
+        .map_err(|e| {
+            error!("Failed to roll back ML model: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(Json)
+        .ok_or(StatusCode::CONFLICT)
+}
+
+// Rejoue le même historique avec deux versions et compare leurs résultats
+pub async fn compare_models(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<CompareModelsQuery>,
+) -> Result<Json<ModelComparison>, StatusCode> {
+    let engine = |version: i64| {
+        let app_state = app_state.clone();
+        async move {
+            app_state
+                .ml_models
+                .engine_for(version)
+                .await
+                .map_err(|e| {
+                    error!("Failed to load ML model version {}: {}", version, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .ok_or(StatusCode::NOT_FOUND)
+        }
+    };
+    let engine_a = engine(query.a).await?;
+    let engine_b = engine(query.b).await?;
+
+    let end = Utc::now();
+    let start = end - Duration::days(query.days.clamp(1, 365));
+    let dataset = BacktestDataset::load(
+        &app_state.forwarding_store,
+        start,
+        end,
+        Duration::days(TRAINING_LOOKBACK_DAYS),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to load backtest dataset: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let backtester = Backtester::default();
+    let a = backtester.run(&dataset, &MLEngineStrategy::new(engine_a), start, end);
+    let b = backtester.run(&dataset, &MLEngineStrategy::new(engine_b), start, end);
+
+    Ok(Json(ModelComparison {
+        net_delta_difference_sat: b.net_delta_sat - a.net_delta_sat,
+        a,
+        b,
+    }))
+}
+
+// Évolution des préférences apprises des approbations et refus de l'opérateur
+pub async fn get_preference_report(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<PreferenceReportQuery>,
+) -> Result<Json<PreferenceReport>, StatusCode> {
+    let decisions = app_state.decision_store.list().await.map_err(|e| {
+        error!("Failed to load operator decisions: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let settings = app_state
+        .settings_store
+        .automation_settings()
+        .await
+        .map_err(|e| {
+            error!("Failed to load automation settings: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let since = Utc::now() - Duration::days(query.days.clamp(1, 365));
+    Ok(Json(PreferenceLearner::default().report(
+        &decisions,
+        since,
+        settings.advanced_settings.learning_mode,
+    )))
+}
+
+fn validate_config(config: &MLEngineConfig) -> Result<(), String> {
+    let unit = [
+        ("confidence_floor", config.confidence_floor),
+        ("risk_floor", config.risk_floor),
+        ("min_fee_model_confidence", config.min_fee_model_confidence),
+    ];
+    for (field, value) in unit {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("{} out of range", field));
+        }
+    }
+    if !(1..=20).contains(&config.max_recommendations) {
+        return Err("max_recommendations out of range".to_string());
+    }
+    Ok(())
+}
@@ -1,4 +1,16 @@
 pub mod actions;
 pub mod advanced_api;
+pub mod anomalies;
+pub mod automation_rules;
+pub mod backtest;
+pub mod channels;
 pub mod dashboard;
+pub mod fee_strategies;
+pub mod ledger;
+pub mod liquidity;
+pub mod metrics;
+pub mod ml_models;
+pub mod peers;
+pub mod recommendations;
+pub mod reports;
 pub mod websocket;
@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::middleware::validation::validate_input;
+use crate::models::reputation::PeerReputation;
+use crate::utils::channel_candidates::{ChannelCandidate, ChannelCandidateFinder};
+use crate::utils::peer_reputation::PeerReputationEngine;
+
+#[derive(Debug, Deserialize)]
+pub struct ReputationQuery {
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReputationHistoryQuery {
+    #[serde(default = "default_history_days")]
+    pub days: i64,
+}
+
+fn default_history_days() -> i64 {
+    30
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeerReputationResponse {
+    pub latest: Option<PeerReputation>,
+    pub explanations: Vec<String>,
+    pub history: Vec<PeerReputation>,
+}
+
+// Dernier score de réputation de chaque pair, recalculé à la demande
+pub async fn get_peer_reputations(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<ReputationQuery>,
+) -> Result<Json<Vec<PeerReputation>>, StatusCode> {
+    let reputations = if query.refresh {
+        PeerReputationEngine::default()
+            .refresh(
+                &app_state.lightning_client,
+                &app_state.forwarding_store,
+                &app_state.peer_reputation_store,
+                &[],
+                Utc::now(),
+            )
+            .await
+    } else {
+        app_state.peer_reputation_store.latest_all().await
+    };
+
+    reputations.map(Json).map_err(|e| {
+        error!("Failed to load peer reputations: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+// Pairs candidats à l'ouverture d'un canal, classés sur le graphe du réseau
+pub async fn get_channel_candidates(
+    State(app_state): State<Arc<crate::AppState>>,
+) -> Result<Json<Vec<ChannelCandidate>>, StatusCode> {
+    let channels = {
+        let mut client = app_state.lightning_client.lock().await;
+        client.list_local_channels().await.map_err(|e| {
+            error!("Failed to list channels for candidate search: {}", e);
+            StatusCode::SERVICE_UNAVAILABLE
+        })?
+    };
+
+    let settings = app_state
+        .settings_store
+        .automation_settings()
+        .await
+        .map_err(|e| {
+            error!("Failed to load automation settings: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    ChannelCandidateFinder::default()
+        .find_live(
+            &app_state.lightning_client,
+            &channels,
+            &settings.blacklisted_peers,
+        )
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to rank channel open candidates: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Score courant d'un pair, ses explications et son évolution
+pub async fn get_peer_reputation(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(pubkey): Path<String>,
+    Query(query): Query<ReputationHistoryQuery>,
+) -> Result<Json<PeerReputationResponse>, StatusCode> {
+    if let Err(e) = validate_input("pubkey", &pubkey) {
+        error!("Invalid pubkey in path: {:?}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let store = &app_state.peer_reputation_store;
+    let internal = |e: anyhow::Error| {
+        error!("Failed to load peer reputation: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let latest = store.latest(&pubkey).await.map_err(internal)?;
+    let history = store
+        .history(
+            &pubkey,
+            Utc::now() - Duration::days(query.days.clamp(1, 365)),
+        )
+        .await
+        .map_err(internal)?;
+
+    Ok(Json(PeerReputationResponse {
+        explanations: latest
+            .as_ref()
+            .map(PeerReputation::explanations)
+            .unwrap_or_default(),
+        latest,
+        history,
+    }))
+}
@@ -0,0 +1,317 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tower_sessions::Session;
+use tracing::{error, info, warn};
+
+use crate::middleware::validation::validate_input;
+use crate::models::feedback::OperatorDecision;
+use crate::models::recommendation::{
+    Recommendation, RecommendationStatus, StatusTransition, TransitionError,
+};
+use crate::utils::ml_engine::RecommendationSignals;
+use crate::utils::recommendation_aggregator::{
+    AggregatedRecommendations, RecommendationAggregator, SourceReport,
+};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RecommendationsQuery {
+    /// Filtre optionnel par état, séparé par des virgules (ex. `rejected,expired`)
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// États servis par défaut : ce qui attend encore une décision ou une exécution
+const DEFAULT_STATUSES: [RecommendationStatus; 2] = [
+    RecommendationStatus::Pending,
+    RecommendationStatus::Approved,
+];
+
+fn requested_statuses(filter: Option<&str>) -> Result<Vec<RecommendationStatus>, StatusCode> {
+    match filter {
+        None => Ok(DEFAULT_STATUSES.to_vec()),
+        Some(filter) => filter
+            .split(',')
+            .map(|value| RecommendationStatus::parse(value.trim()).ok_or(StatusCode::BAD_REQUEST))
+            .collect(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransitionRequest {
+    pub status: RecommendationStatus,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Requis pour `Snoozed`
+    #[serde(default)]
+    pub snoozed_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransitionResponse {
+    pub recommendation: Recommendation,
+    pub transition: StatusTransition,
+}
+
+/// Identité enregistrée dans l'historique : l'utilisateur de la session, sinon l'opérateur API
+pub async fn session_actor(session: &Session) -> String {
+    session
+        .get::<String>("username")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "api".to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecommendationsResponse {
+    pub recommendations: Vec<Recommendation>,
+    pub sources: Vec<SourceReport>,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub from_cache: bool,
+}
+
+/// Intervalle entre deux agrégations complètes par la tâche de fond
+pub const REFRESH_INTERVAL_MINUTES: i64 = 10;
+
+// Unified recommendations endpoint (MCP + local ML), servi depuis le store
+pub async fn get_recommendations(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<RecommendationsQuery>,
+) -> Result<Json<RecommendationsResponse>, StatusCode> {
+    let statuses = requested_statuses(query.status.as_deref())?;
+    let now = chrono::Utc::now();
+
+    let last_fetched = app_state
+        .recommendation_store
+        .last_fetched_at()
+        .await
+        .map_err(|e| {
+            error!("Failed to read recommendation store: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let since = now - RecommendationAggregator::default().max_age;
+    let recommendations = app_state
+        .recommendation_store
+        .list_fetched_since(since, &statuses)
+        .await
+        .map_err(|e| {
+            error!("Failed to list stored recommendations: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(RecommendationsResponse {
+        recommendations,
+        sources: vec![],
+        generated_at: last_fetched.unwrap_or(now),
+        from_cache: true,
+    }))
+}
+
+// Nouvelle agrégation immédiate ; refusée tant que le nœud n'est pas connecté
+pub async fn refresh_recommendations(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<RecommendationsQuery>,
+) -> Result<Json<RecommendationsResponse>, StatusCode> {
+    let statuses = requested_statuses(query.status.as_deref())?;
+    if !app_state.lightning_client.lock().await.is_connected() {
+        warn!("Recommendation refresh refused: Lightning node not connected");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    let aggregated = refresh(&app_state).await.map_err(|e| {
+        error!("Failed to refresh recommendations: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    // Relecture : les recommandations déjà traitées gardent leur état et sont filtrées
+    let since = aggregated.generated_at - RecommendationAggregator::default().max_age;
+    let recommendations = app_state
+        .recommendation_store
+        .list_fetched_since(since, &statuses)
+        .await
+        .map_err(|e| {
+            error!("Failed to list stored recommendations: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(RecommendationsResponse {
+        recommendations,
+        sources: aggregated.sources,
+        generated_at: aggregated.generated_at,
+        from_cache: false,
+    }))
+}
+
+/// Agrège les recommandations MCP et locales puis les enregistre. Le mode mock
+/// est refusé : ses canaux fictifs produiraient des recommandations sans objet.
+pub async fn refresh(app_state: &crate::AppState) -> anyhow::Result<AggregatedRecommendations> {
+    info!("Refreshing unified recommendations");
+    let now = chrono::Utc::now();
+
+    let (node_pubkey, mut channels) = {
+        let mut client = app_state.lightning_client.lock().await;
+        if !client.is_connected() {
+            return Err(anyhow::anyhow!("Lightning node not connected"));
+        }
+        let node_pubkey = match client.get_local_node_info().await {
+            Ok(info) => info.pubkey,
+            Err(e) => {
+                warn!("Unable to read node info for MCP lookup: {}", e);
+                String::new()
+            }
+        };
+        (node_pubkey, client.list_local_channels().await?)
+    };
+
+    if let Err(e) = app_state
+        .forwarding_store
+        .apply_latest_policies(&mut channels)
+        .await
+    {
+        warn!("Unable to apply stored fee policies: {}", e);
+    }
+
+    // Mode apprentissage et liste noire tels que réglés par l'opérateur
+    let settings = app_state.settings_store.automation_settings().await?;
+    let signals = RecommendationSignals::gather(
+        &app_state.lightning_client,
+        &app_state.forwarding_store,
+        &app_state.fee_strategy_store,
+        &app_state.decision_store,
+        &channels,
+        &settings,
+        now,
+    )
+    .await;
+
+    let aggregated = RecommendationAggregator::default()
+        .collect(
+            &app_state.mcp_client,
+            &app_state.ml_models.engine().await,
+            &node_pubkey,
+            &signals.inputs(&channels),
+        )
+        .await;
+
+    let ttl = chrono::Duration::hours(app_state.config.recommendation_ttl_hours);
+    app_state
+        .recommendation_store
+        .save_all(&aggregated.recommendations, ttl)
+        .await?;
+    Ok(aggregated)
+}
+
+// Transition manuelle du cycle de vie (approbation, refus, mise en attente...)
+pub async fn transition_recommendation(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+    session: Session,
+    Json(request): Json<TransitionRequest>,
+) -> Result<Json<TransitionResponse>, (StatusCode, String)> {
+    if let Err(e) = validate_input("recommendation_id", &id) {
+        error!("Invalid recommendation_id: {:?}", e);
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Invalid recommendation id".to_string(),
+        ));
+    }
+    if let Some(reason) = &request.reason {
+        if let Err(e) = validate_input("message", reason) {
+            error!("Invalid transition reason: {:?}", e);
+            return Err((StatusCode::BAD_REQUEST, "Invalid reason".to_string()));
+        }
+    }
+
+    // L'exécution passe par /api/actions et le moteur d'exécution, pas par ce point d'entrée
+    if matches!(
+        request.status,
+        RecommendationStatus::Executing
+            | RecommendationStatus::Executed
+            | RecommendationStatus::Failed
+            | RecommendationStatus::Expired
+    ) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("{:?} is set by the system only", request.status),
+        ));
+    }
+
+    let actor = session_actor(&session).await;
+    let result = app_state
+        .recommendation_store
+        .transition(
+            &id,
+            request.status,
+            &actor,
+            request.reason,
+            request.snoozed_until,
+        )
+        .await
+        .map_err(transition_error)?;
+
+    match result {
+        Some((recommendation, transition)) => {
+            info!(
+                "Recommendation {} moved {:?} → {:?} by {}",
+                id, transition.from, transition.to, transition.actor
+            );
+            // Approbations et refus nourrissent l'apprentissage des préférences
+            if let Some(decision) = OperatorDecision::from_transition(&recommendation, &transition)
+            {
+                if let Err(e) = app_state.decision_store.record(&decision).await {
+                    error!("Failed to record operator decision: {}", e);
+                }
+            }
+            Ok(Json(TransitionResponse {
+                recommendation,
+                transition,
+            }))
+        }
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("Recommendation {} not found", id),
+        )),
+    }
+}
+
+// Historique des transitions d'une recommandation
+pub async fn get_recommendation_history(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<StatusTransition>>, StatusCode> {
+    if let Err(e) = validate_input("recommendation_id", &id) {
+        error!("Invalid recommendation_id: {:?}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    app_state
+        .recommendation_store
+        .history(&id)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            error!("Failed to read recommendation history: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Traduit une erreur du store : transition refusée → 409, le reste → 500
+pub fn transition_error(e: anyhow::Error) -> (StatusCode, String) {
+    match e.downcast_ref::<TransitionError>() {
+        Some(TransitionError::NotAllowed { .. }) => (StatusCode::CONFLICT, e.to_string()),
+        Some(TransitionError::MissingSnoozeUntil) => (StatusCode::BAD_REQUEST, e.to_string()),
+        None => {
+            error!("Failed to update recommendation status: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Recommendation store unavailable".to_string(),
+            )
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::middleware::validation::validate_input;
+use crate::models::recommendation::RecommendationStatus;
+use crate::models::report::{PerformanceReport, ReportPeriod, ReportSummary};
+use crate::utils::close_candidates::CloseCandidateFinder;
+use crate::utils::ledger::load_ledger;
+use crate::utils::reports::{
+    build_report, measured_label, render_markdown, report_title, ReportInputs, IMPACT_WINDOW_DAYS,
+};
+
+/// Génère le rapport de la période `[start, end)` à partir des données du nœud.
+pub async fn generate_report(
+    app_state: &crate::AppState,
+    period: ReportPeriod,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<PerformanceReport> {
+    let now = Utc::now();
+    let (channels, rebalances) = {
+        let mut client = app_state.lightning_client.lock().await;
+        // Pas de rapport sur les données fictives du mode mock
+        if !client.is_connected() {
+            return Err(anyhow!("Lightning node not connected"));
+        }
+        (
+            client.list_local_channels().await?,
+            client.rebalance_payments().await?,
+        )
+    };
+
+    let ledger = load_ledger(
+        &app_state.lightning_client,
+        &app_state.forwarding_store,
+        &app_state.price_store,
+        &app_state.price_source,
+        start,
+        end,
+    )
+    .await?;
+    // Les actions du début de période sont mesurées sur la semaine qui précède
+    let events = app_state
+        .forwarding_store
+        .events_between(start - Duration::days(IMPACT_WINDOW_DAYS), now)
+        .await?;
+    let executions = app_state
+        .recommendation_store
+        .transitions_between(
+            start,
+            end,
+            &[RecommendationStatus::Executed, RecommendationStatus::Failed],
+        )
+        .await?;
+    let anomalies = app_state.anomaly_monitor.recent().await;
+    let pause = app_state.anomaly_monitor.pause().await;
+    let close_candidates = CloseCandidateFinder::default()
+        .find_stored(&app_state.forwarding_store, &channels, now)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Unable to detect channel close candidates: {}", e);
+            vec![]
+        });
+
+    Ok(build_report(&ReportInputs {
+        period,
+        start,
+        end,
+        now,
+        ledger: &ledger,
+        events: &events,
+        rebalances: &rebalances,
+        channels: &channels,
+        executions: &executions,
+        anomalies: &anomalies,
+        pause: pause.as_ref(),
+        close_candidates: &close_candidates,
+    }))
+}
+
+/// Génère et archive les rapports des dernières périodes écoulées qui manquent.
+pub async fn generate_due_reports(app_state: &crate::AppState) -> Result<()> {
+    let now = Utc::now();
+    for period in ReportPeriod::ALL {
+        let (start, end) = period.last_complete(now);
+        if app_state
+            .report_store
+            .exists(&period.report_id(start))
+            .await?
+        {
+            continue;
+        }
+        let report = generate_report(app_state, period, start, end).await?;
+        app_state.report_store.save(&report).await?;
+        info!(
+            "Generated {} performance report {}",
+            period.as_str(),
+            report.id
+        );
+    }
+    Ok(())
+}
+
+fn summary_context(summary: &ReportSummary) -> serde_json::Value {
+    json!({
+        "id": summary.id,
+        "label": match summary.period {
+            ReportPeriod::Weekly => format!("Semaine du {}", summary.period_start.format("%Y-%m-%d")),
+            ReportPeriod::Monthly => format!("Mois de {}", summary.period_start.format("%Y-%m")),
+        },
+        "period_start": summary.period_start.format("%Y-%m-%d").to_string(),
+        "period_end": summary.period_end.format("%Y-%m-%d").to_string(),
+        "generated_at": summary.generated_at.format("%Y-%m-%d %H:%M").to_string(),
+        "revenue_sat": summary.revenue_sat,
+        "net_sat": summary.net_sat,
+        "positive": summary.net_sat >= 0,
+    })
+}
+
+fn render_report_html(
+    app_state: &crate::AppState,
+    report: &PerformanceReport,
+) -> Result<String, StatusCode> {
+    let financials = &report.financials;
+    let context = json!({
+        "connection_status": "connected",
+        "label": report_title(report),
+        "generated_at": report.generated_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+        "net_fiat": financials
+            .net_fiat
+            .map(|fiat| format!("{:.2} {}", fiat, financials.fiat_currency)),
+        "net_yield": financials.net_yield_pct.map(|pct| format!("{:.2} %", pct)),
+        "actions": report
+            .actions
+            .iter()
+            .map(|action| json!({
+                "action_type": action.action_type,
+                "description": action.description,
+                "at": action.at.format("%Y-%m-%d %H:%M").to_string(),
+                "status": action.status.as_str(),
+                "executed": action.status == RecommendationStatus::Executed,
+                "measured": measured_label(action),
+            }))
+            .collect::<Vec<_>>(),
+        "report": report,
+    });
+
+    app_state
+        .handlebars
+        .render("report", &context)
+        .map_err(|e| {
+            error!("Erreur de rendu du template report: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+async fn load_report(
+    app_state: &crate::AppState,
+    id: &str,
+) -> Result<PerformanceReport, StatusCode> {
+    if let Err(e) = validate_input("recommendation_id", id) {
+        error!("Invalid report id: {:?}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    app_state
+        .report_store
+        .get(id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load report {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportListQuery {
+    #[serde(default)]
+    pub period: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_limit() -> u32 {
+    52
+}
+
+async fn list_reports(
+    app_state: &crate::AppState,
+    query: &ReportListQuery,
+) -> Result<Vec<ReportSummary>, StatusCode> {
+    let period = match &query.period {
+        Some(period) => Some(ReportPeriod::parse(period).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    app_state
+        .report_store
+        .list(period, query.limit.clamp(1, 500))
+        .await
+        .map_err(|e| {
+            error!("Failed to list reports: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Page d'archive des rapports générés
+pub async fn reports_page(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<ReportListQuery>,
+) -> Result<Html<String>, StatusCode> {
+    let reports = list_reports(&app_state, &query).await?;
+    let context = json!({
+        "connection_status": "connected",
+        "reports": reports.iter().map(summary_context).collect::<Vec<_>>(),
+    });
+
+    app_state
+        .handlebars
+        .render("reports", &context)
+        .map(Html)
+        .map_err(|e| {
+            error!("Erreur de rendu du template reports: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+// Page HTML d'un rapport archivé
+pub async fn report_page(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+) -> Result<Html<String>, StatusCode> {
+    let report = load_report(&app_state, &id).await?;
+    render_report_html(&app_state, &report).map(Html)
+}
+
+// Liste des rapports archivés, filtrable par périodicité
+pub async fn get_reports(
+    State(app_state): State<Arc<crate::AppState>>,
+    Query(query): Query<ReportListQuery>,
+) -> Result<Json<Vec<ReportSummary>>, StatusCode> {
+    list_reports(&app_state, &query).await.map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportFormatQuery {
+    /// `json`, `markdown` ou `html`
+    #[serde(default = "default_format")]
+    pub format: String,
+}
+
+fn default_format() -> String {
+    "json".to_string()
+}
+
+// Rapport archivé en JSON, Markdown ou HTML
+pub async fn get_report(
+    State(app_state): State<Arc<crate::AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReportFormatQuery>,
+) -> Result<Response, StatusCode> {
+    let report = load_report(&app_state, &id).await?;
+    match query.format.to_ascii_lowercase().as_str() {
+        "json" => Ok(Json(report).into_response()),
+        "markdown" | "md" => Ok((
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "text/markdown; charset=utf-8".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{}.md\"", report.id),
+                ),
+            ],
+            render_markdown(&report),
+        )
+            .into_response()),
+        "html" => render_report_html(&app_state, &report).map(|html| Html(html).into_response()),
+        _ => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateReportRequest {
+    pub period: ReportPeriod,
+}
+
+// Régénère le rapport de la dernière période écoulée
+pub async fn generate_report_now(
+    State(app_state): State<Arc<crate::AppState>>,
+    Json(request): Json<GenerateReportRequest>,
+) -> Result<Json<ReportSummary>, StatusCode> {
+    if !app_state.lightning_client.lock().await.is_connected() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let (start, end) = request.period.last_complete(Utc::now());
+    let report = generate_report(&app_state, request.period, start, end)
+        .await
+        .map_err(|e| {
+            error!("Failed to generate report: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    app_state.report_store.save(&report).await.map_err(|e| {
+        error!("Failed to save report {}: {}", report.id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    info!("Regenerated performance report {}", report.id);
+    Ok(Json(ReportSummary::from(&report)))
+}
@@ -76,6 +76,15 @@ impl WebSocketState {
         self.broadcast_update(update);
     }
 
+    pub fn broadcast_anomaly(&self, anomaly: serde_json::Value) {
+        let update = RealTimeUpdate {
+            r#type: "anomaly".to_string(),
+            payload: anomaly,
+            timestamp: chrono::Utc::now(),
+        };
+        self.broadcast_update(update);
+    }
+
     pub fn broadcast_competitive_update(&self, competitive_data: serde_json::Value) {
         let update = RealTimeUpdate {
             r#type: "competitive_update".to_string(),
@@ -190,29 +199,6 @@ pub async fn start_real_time_updates(state: Arc<WebSocketState>) {
         };
 
         state.broadcast_roi_update(roi_update);
-
-        // Occasionally broadcast competitive updates
-        if rand::random::<f64>() > 0.8 {
-            let competitive_data = serde_json::json!([
-                {
-                    "metric": "accuracy",
-                    "dazno_value": "95%",
-                    "competitor_value": "85%"
-                },
-                {
-                    "metric": "speed",
-                    "dazno_value": "150ms",
-                    "competitor_value": "380ms"
-                },
-                {
-                    "metric": "roi_improvement",
-                    "dazno_value": "+18.5%",
-                    "competitor_value": "+12.1%"
-                }
-            ]);
-
-            state.broadcast_competitive_update(competitive_data);
-        }
     }
 }
 
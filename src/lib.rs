@@ -7,8 +7,12 @@ pub mod api;
 pub mod handlers;
 pub mod middleware;
 pub mod models;
+pub mod storage;
 pub mod utils;
 
+#[cfg(test)]
+pub mod test_support;
+
 // Re-export commonly used types for easier access
 pub use api::local_lightning_client::{
     LocalChannelBalance, LocalChannelInfo, LocalChannelParams, LocalLightningClient, LocalNodeInfo,
@@ -18,13 +22,23 @@ pub use api::mcp_client::{
     ActionResult, ActionType, ChannelMetrics, MCPClient, MCPRecommendation, NodeMetrics, Priority,
 };
 pub use models::analytics::NodeAnalytics;
-pub use models::recommendation::Recommendation;
+pub use models::recommendation::{Recommendation, RecommendationSource, RecommendationStatus};
 pub use utils::config::AppConfig;
 pub use utils::ml_engine::MLEngine;
+pub use utils::recommendation_aggregator::RecommendationAggregator;
 
 // AppState structure for handlers
+use api::price_source::PriceSource;
 use handlebars::Handlebars;
 use std::sync::Arc;
+use storage::{
+    automation_rules::AutomationRuleStore, decisions::DecisionStore,
+    fee_strategies::FeeStrategyStore, forwarding::ForwardingStore,
+    peer_reputation::PeerReputationStore, prices::PriceStore, recommendations::RecommendationStore,
+    reports::ReportStore, settings::SettingsStore,
+};
+use utils::anomaly_detection::AnomalyMonitor;
+use utils::model_registry::ModelRegistry;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -32,6 +46,18 @@ pub struct AppState {
     pub lightning_client: Arc<tokio::sync::Mutex<LocalLightningClient>>,
     pub handlebars: Arc<Handlebars<'static>>,
     pub ws_state: Arc<handlers::websocket::WebSocketState>,
-    pub ml_engine: MLEngine,
+    pub ml_models: ModelRegistry,
+    pub recommendation_store: RecommendationStore,
+    pub forwarding_store: ForwardingStore,
+    pub peer_reputation_store: PeerReputationStore,
+    pub fee_strategy_store: FeeStrategyStore,
+    pub decision_store: DecisionStore,
+    pub anomaly_monitor: AnomalyMonitor,
+    pub automation_rule_store: AutomationRuleStore,
+    pub price_store: PriceStore,
+    pub price_source: PriceSource,
+    pub report_store: ReportStore,
+    pub settings_store: SettingsStore,
+    pub http_metrics: middleware::HttpMetrics,
     pub config: AppConfig,
 }
@@ -4,7 +4,7 @@ use axum::{
     extract::State,
     http::StatusCode,
     response::{Html, IntoResponse},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use handlebars::Handlebars;
@@ -18,10 +18,16 @@ mod handlers;
 mod middleware;
 mod models;
 mod routes;
+mod storage;
 mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 use api::local_lightning_client::LocalLightningClient;
 use api::mcp_client::MCPClient;
+use api::price_source::PriceSource;
+use api::umbrel_integrations::UmbrelIntegrations;
 use auth::{
     session::{create_sqlite_session_layer, development_session_config, production_session_config},
     AuthService,
@@ -29,13 +35,24 @@ use auth::{
 use handlers::advanced_api::*;
 use handlers::websocket::{start_real_time_updates, websocket_handler, WebSocketState};
 use middleware::{
-    auth_middleware, create_action_rate_limiter, public_route_middleware,
-    rate_limit_middleware_with_state, RateLimitState,
+    auth_middleware, create_action_rate_limiter, metrics_token_middleware, public_route_middleware,
+    rate_limit_middleware_with_state, track_http_metrics, HttpMetrics, RateLimitState,
 };
+use models::automation::TriggerType;
 use routes::auth as auth_routes;
 use sqlx::SqlitePool;
+use storage::{
+    automation_rules::AutomationRuleStore, decisions::DecisionStore,
+    fee_strategies::FeeStrategyStore, forwarding::ForwardingStore, ml_models::MLModelStore,
+    peer_reputation::PeerReputationStore, prices::PriceStore, recommendations::RecommendationStore,
+    reports::ReportStore, settings::SettingsStore,
+};
+use utils::anomaly_detection::{AnomalyDetectorConfig, AnomalyMonitor, NodeObservation};
+use utils::competitive_positioning::CompetitivePositioning;
 use utils::config::AppConfig;
-use utils::ml_engine::MLEngine;
+use utils::model_registry::ModelRegistry;
+use utils::network_graph::network_fee_sample;
+use utils::peer_reputation::PeerReputationEngine;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -45,7 +62,19 @@ pub struct AppState {
     ws_state: Arc<WebSocketState>,
     rate_limiter: RateLimitState,
     auth_service: AuthService,
-    ml_engine: MLEngine,
+    ml_models: ModelRegistry,
+    recommendation_store: RecommendationStore,
+    forwarding_store: ForwardingStore,
+    peer_reputation_store: PeerReputationStore,
+    fee_strategy_store: FeeStrategyStore,
+    decision_store: DecisionStore,
+    anomaly_monitor: AnomalyMonitor,
+    automation_rule_store: AutomationRuleStore,
+    price_store: PriceStore,
+    price_source: PriceSource,
+    report_store: ReportStore,
+    settings_store: SettingsStore,
+    http_metrics: HttpMetrics,
     config: AppConfig,
 }
 
@@ -68,6 +97,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let auth_service = AuthService::new(db_pool.clone());
     auth_service.create_tables().await?;
 
+    // Initialiser le stockage des recommandations
+    let recommendation_store = RecommendationStore::new(db_pool.clone());
+    recommendation_store.create_tables().await?;
+
+    // Initialiser l'historique de forwarding (modèles de frais)
+    let forwarding_store = ForwardingStore::new(db_pool.clone());
+    forwarding_store.create_tables().await?;
+
+    // Initialiser les scores de réputation des pairs
+    let peer_reputation_store = PeerReputationStore::new(db_pool.clone());
+    peer_reputation_store.create_tables().await?;
+
+    // Initialiser les affectations de stratégies de frais
+    let fee_strategy_store = FeeStrategyStore::new(db_pool.clone());
+    fee_strategy_store.create_tables().await?;
+
+    // Initialiser l'historique des décisions de l'opérateur
+    let decision_store = DecisionStore::new(db_pool.clone());
+    decision_store.create_tables().await?;
+
+    // Initialiser le cache des cours du bitcoin (grand livre)
+    let price_store = PriceStore::new(db_pool.clone());
+    price_store.create_tables().await?;
+
+    // Initialiser les règles d'automatisation et leur historique d'exécution
+    let automation_rule_store = AutomationRuleStore::new(db_pool.clone());
+    automation_rule_store.create_tables().await?;
+
+    // Initialiser l'archive des rapports de performance
+    let report_store = ReportStore::new(db_pool.clone());
+    report_store.create_tables().await?;
+
+    // Initialiser les réglages enregistrés (automatisation)
+    let settings_store = SettingsStore::new(db_pool.clone());
+    settings_store.create_tables().await?;
+
     // Initialiser l'utilisateur par défaut et récupérer le mot de passe
     let default_password = auth_service.initialize_default_user().await?;
     if default_password != "Utilisateur existant" {
@@ -86,6 +151,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     handlebars.register_template_file("recommendations", "templates/recommendations.hbs")?;
     handlebars.register_template_file("history", "templates/history.hbs")?;
     handlebars.register_template_file("settings", "templates/settings.hbs")?;
+    handlebars.register_template_file("reports", "templates/reports.hbs")?;
+    handlebars.register_template_file("report", "templates/report.hbs")?;
     handlebars.register_template_file("superior_dashboard", "templates/superior_dashboard.hbs")?;
     handlebars.register_template_file("login", "templates/login.html")?;
     handlebars.register_template_file("change-password", "templates/change-password.html")?;
@@ -115,7 +182,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let rate_limiter = create_action_rate_limiter();
 
-    let ml_engine = MLEngine::new();
+    // Charger la version active du modèle ML
+    let ml_model_store = MLModelStore::new(db_pool.clone());
+    ml_model_store.create_tables().await?;
+    let ml_models = ModelRegistry::load(ml_model_store).await?;
 
     let app_state = Arc::new(AppState {
         mcp_client,
@@ -124,7 +194,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ws_state: ws_state.clone(),
         rate_limiter: rate_limiter.clone(),
         auth_service,
-        ml_engine,
+        ml_models,
+        recommendation_store,
+        forwarding_store,
+        peer_reputation_store,
+        fee_strategy_store,
+        decision_store,
+        anomaly_monitor: AnomalyMonitor::with_store(
+            AnomalyDetectorConfig::default(),
+            settings_store.clone(),
+        )
+        .await?,
+        automation_rule_store,
+        price_store,
+        price_source: PriceSource::new(),
+        report_store,
+        settings_store,
+        http_metrics: HttpMetrics::new(),
         config: config.clone(),
     });
 
@@ -134,6 +220,233 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         start_real_time_updates(ws_state_clone).await;
     });
 
+    // Expiration des recommandations et fin des mises en attente
+    let store_clone = app_state.recommendation_store.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300));
+        loop {
+            interval.tick().await;
+            match store_clone.expire_due(chrono::Utc::now()).await {
+                Ok(transitions) if !transitions.is_empty() => {
+                    info!(
+                        "{} recommendation(s) changed state on schedule",
+                        transitions.len()
+                    )
+                }
+                Ok(_) => {}
+                Err(e) => error!("Recommendation expiry sweep failed: {}", e),
+            }
+        }
+    });
+
+    // Agrégation périodique des recommandations, uniquement sur un nœud connecté
+    let refresh_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            handlers::recommendations::REFRESH_INTERVAL_MINUTES as u64 * 60,
+        ));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if !refresh_state.lightning_client.lock().await.is_connected() {
+                continue;
+            }
+            if let Err(e) = handlers::recommendations::refresh(&refresh_state).await {
+                error!("Recommendation refresh failed: {}", e);
+            }
+        }
+    });
+
+    // Synchronisation de l'historique de forwarding depuis LND
+    let forwarding_store_clone = app_state.forwarding_store.clone();
+    let lightning_client_clone = app_state.lightning_client.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(900));
+        loop {
+            interval.tick().await;
+            if let Err(e) = forwarding_store_clone.sync(&lightning_client_clone).await {
+                error!("Forwarding history sync failed: {}", e);
+            }
+            // Relevé des frais du mempool pour la planification des actions on-chain
+            match UmbrelIntegrations::new().get_fee_estimates().await {
+                Ok(estimates) => {
+                    if let Err(e) = forwarding_store_clone
+                        .record_fee_estimates(&estimates, chrono::Utc::now())
+                        .await
+                    {
+                        error!("Failed to record fee estimates: {}", e);
+                    }
+                }
+                Err(e) => warn!("Fee estimates unavailable: {}", e),
+            }
+        }
+    });
+
+    // Relevé de l'état des canaux, et sous-échantillonnage horaire de l'historique
+    let snapshot_state = app_state.clone();
+    tokio::spawn(async move {
+        let config = &snapshot_state.config;
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            config.channel_snapshot_interval_secs,
+        ));
+        let mut next_downsample = chrono::Utc::now();
+        loop {
+            interval.tick().await;
+            if let Err(e) = snapshot_state
+                .forwarding_store
+                .snapshot_channels(&snapshot_state.lightning_client)
+                .await
+            {
+                error!("Channel snapshot failed: {}", e);
+            }
+            let now = chrono::Utc::now();
+            if now >= next_downsample {
+                next_downsample = now + chrono::Duration::hours(1);
+                if let Err(e) = snapshot_state
+                    .forwarding_store
+                    .downsample_channel_snapshots(&config.snapshot_retention(), now)
+                    .await
+                {
+                    error!("Channel history downsampling failed: {}", e);
+                }
+            }
+        }
+    });
+
+    // Surveillance des anomalies : chaque minute, comparaison à l'observation précédente
+    let anomaly_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let observation =
+                match NodeObservation::capture(&anomaly_state.lightning_client, chrono::Utc::now())
+                    .await
+                {
+                    Ok(observation) => observation,
+                    Err(e) => {
+                        warn!("Anomaly monitoring observation failed: {}", e);
+                        continue;
+                    }
+                };
+            let notify = match anomaly_state.settings_store.automation_settings().await {
+                Ok(settings) => settings
+                    .notification_preferences
+                    .is_triggered(TriggerType::UnusualActivity),
+                Err(e) => {
+                    warn!("Unable to load notification preferences: {}", e);
+                    true
+                }
+            };
+            for anomaly in anomaly_state.anomaly_monitor.observe(observation).await {
+                warn!("Anomaly detected: {}", anomaly.description);
+                if notify {
+                    anomaly_state
+                        .ws_state
+                        .broadcast_anomaly(serde_json::to_value(&anomaly).unwrap_or_default());
+                }
+            }
+        }
+    });
+
+    // Relevé horaire des frais médians du réseau, base des prévisions du marché des frais,
+    // et diffusion du positionnement concurrentiel calculé sur le même graphe
+    let network_fee_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            let (pubkey, graph) = {
+                let mut client = network_fee_state.lightning_client.lock().await;
+                let pubkey = client
+                    .get_local_node_info()
+                    .await
+                    .map(|info| info.pubkey)
+                    .map_err(|e| e.to_string());
+                (pubkey, client.describe_graph().await)
+            };
+            match graph {
+                Ok(graph) => {
+                    let now = chrono::Utc::now();
+                    if let Some(positioning) = pubkey
+                        .ok()
+                        .and_then(|pubkey| CompetitivePositioning::compute(&graph, &pubkey, now))
+                    {
+                        network_fee_state.ws_state.broadcast_competitive_update(
+                            serde_json::to_value(&positioning).unwrap_or_default(),
+                        );
+                    }
+                    if let Some(sample) = network_fee_sample(&graph, now) {
+                        if let Err(e) = network_fee_state
+                            .forwarding_store
+                            .record_network_fees(&sample)
+                            .await
+                        {
+                            error!("Failed to record network fee sample: {}", e);
+                        }
+                    }
+                }
+                Err(e) => warn!("Network graph unavailable for fee sampling: {}", e),
+            }
+        }
+    });
+
+    // Recalcul horaire de la réputation des pairs
+    let reputation_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = PeerReputationEngine::default()
+                .refresh(
+                    &reputation_state.lightning_client,
+                    &reputation_state.forwarding_store,
+                    &reputation_state.peer_reputation_store,
+                    &[],
+                    chrono::Utc::now(),
+                )
+                .await
+            {
+                error!("Peer reputation refresh failed: {}", e);
+            }
+        }
+    });
+
+    // Évaluation des règles d'automatisation toutes les 5 minutes
+    let rules_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if !rules_state.lightning_client.lock().await.is_connected() {
+                continue;
+            }
+            match handlers::automation_rules::run_automation_rules(&rules_state).await {
+                Ok(executions) if !executions.is_empty() => {
+                    info!("{} automation rule execution(s)", executions.len())
+                }
+                Ok(_) => {}
+                Err(e) => error!("Automation rule evaluation failed: {}", e),
+            }
+        }
+    });
+
+    // Génération horaire des rapports hebdomadaires et mensuels manquants
+    let report_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if !report_state.lightning_client.lock().await.is_connected() {
+                continue;
+            }
+            if let Err(e) = handlers::reports::generate_due_reports(&report_state).await {
+                error!("Performance report generation failed: {}", e);
+            }
+        }
+    });
+
     // Configuration des sessions
     let session_config = match std::env::var("APP_ENV") {
         Ok(value) if value.eq_ignore_ascii_case("production") => production_session_config(),
@@ -149,6 +462,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/logout", get(auth_routes::logout))
         .route_layer(axum::middleware::from_fn(public_route_middleware));
 
+    // /metrics : jeton dédié pour Prometheus s'il est configuré, sinon session habituelle
+    let metrics_route =
+        Router::<Arc<AppState>>::new().route("/metrics", get(handlers::metrics::get_metrics));
+    let (public_metrics, protected_metrics) = match config.metrics_token.clone() {
+        Some(token) => (
+            metrics_route.route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(token),
+                metrics_token_middleware,
+            )),
+            Router::new(),
+        ),
+        None => (Router::new(), metrics_route),
+    };
+
     // Routes protégées (avec authentification et rate limiting)
     let protected_routes = Router::<Arc<AppState>>::new()
         // Main pages
@@ -160,10 +487,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/superior", get(superior_dashboard_handler))
         .route("/recommendations", get(recommendations_page_handler))
         .route("/history", get(history_page_handler))
+        .route("/reports", get(handlers::reports::reports_page))
+        .route("/reports/:id", get(handlers::reports::report_page))
         .route("/settings", get(settings_page_handler))
         // Basic API
-        .route("/api/recommendations", get(get_recommendations_handler))
-        .route("/api/actions", post(execute_action_handler))
+        .route(
+            "/api/recommendations",
+            get(handlers::recommendations::get_recommendations),
+        )
+        .route(
+            "/api/recommendations/refresh",
+            post(handlers::recommendations::refresh_recommendations),
+        )
+        .route("/api/actions", post(handlers::actions::execute_action))
         .route("/api/metrics", get(get_metrics_handler))
         .route("/api/status", get(get_status_handler))
         // Advanced API endpoints - CRITIQUE: Actions financières
@@ -183,6 +519,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/api/recommendations/:id/optimal-time",
             get(get_optimal_time),
         )
+        .route(
+            "/api/recommendations/:id/transition",
+            post(handlers::recommendations::transition_recommendation),
+        )
+        .route(
+            "/api/recommendations/:id/history",
+            get(handlers::recommendations::get_recommendation_history),
+        )
         // Automation endpoints - CRITIQUE: Configuration d'automatisation
         .route("/api/automation/mode", post(update_automation_mode))
         .route("/api/automation/max-actions", post(update_max_actions))
@@ -190,16 +534,108 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/api/automation/auto-execution",
             post(toggle_auto_execution),
         )
-        .route("/api/automation/settings", get(get_automation_settings))
+        .route(
+            "/api/automation/settings",
+            get(get_automation_settings).put(update_automation_settings),
+        )
+        .route(
+            "/api/automation/rules",
+            get(handlers::automation_rules::list_rules)
+                .post(handlers::automation_rules::create_rule),
+        )
+        .route(
+            "/api/automation/rules/:id",
+            get(handlers::automation_rules::get_rule)
+                .put(handlers::automation_rules::update_rule)
+                .delete(handlers::automation_rules::delete_rule),
+        )
+        .route(
+            "/api/automation/rules/:id/executions",
+            get(handlers::automation_rules::get_rule_executions),
+        )
+        .route(
+            "/api/automation/rules/:id/evaluation",
+            get(handlers::automation_rules::evaluate_rule),
+        )
+        .route(
+            "/api/automation/resume",
+            post(handlers::anomalies::resume_automation),
+        )
+        .route("/api/anomalies", get(handlers::anomalies::get_anomalies))
         // Analytics endpoints
         .route("/api/analysis/force-deep", post(force_deep_analysis))
+        .route("/api/analytics/channels", get(get_channel_analytics))
         .route("/api/analytics/node", get(get_node_analytics))
+        .route("/api/analytics/predictive", get(get_predictive_analytics))
+        .route(
+            "/api/analytics/stress-tests",
+            get(get_stress_tests).post(run_custom_stress_tests),
+        )
+        .route("/api/backtest", post(handlers::backtest::run_backtest))
+        .route(
+            "/api/ml/models",
+            get(handlers::ml_models::list_models).post(handlers::ml_models::publish_model),
+        )
+        .route(
+            "/api/ml/models/compare",
+            get(handlers::ml_models::compare_models),
+        )
+        .route(
+            "/api/ml/models/rollback",
+            post(handlers::ml_models::rollback_model),
+        )
+        .route(
+            "/api/ml/preferences",
+            get(handlers::ml_models::get_preference_report),
+        )
+        .route(
+            "/api/ml/models/:version/activate",
+            post(handlers::ml_models::activate_model),
+        )
+        .route(
+            "/api/peers/reputation",
+            get(handlers::peers::get_peer_reputations),
+        )
+        .route(
+            "/api/peers/candidates",
+            get(handlers::peers::get_channel_candidates),
+        )
+        .route(
+            "/api/peers/:pubkey/reputation",
+            get(handlers::peers::get_peer_reputation),
+        )
+        .route(
+            "/api/fees/strategies",
+            get(handlers::fee_strategies::list_fee_strategies)
+                .put(handlers::fee_strategies::put_fee_strategy),
+        )
+        .route(
+            "/api/fees/strategies/:scope/:id",
+            delete(handlers::fee_strategies::delete_fee_strategy),
+        )
+        .route(
+            "/api/liquidity/inbound",
+            get(handlers::liquidity::get_inbound_liquidity),
+        )
+        .route(
+            "/api/channels/:id/history",
+            get(handlers::channels::get_channel_history),
+        )
+        .route("/api/ledger", get(handlers::ledger::get_ledger))
+        .route("/api/ledger/export", get(handlers::ledger::export_ledger))
+        .route("/api/reports", get(handlers::reports::get_reports))
+        .route(
+            "/api/reports/generate",
+            post(handlers::reports::generate_report_now),
+        )
+        .route("/api/reports/:id", get(handlers::reports::get_report))
         .route("/api/competitive-analysis", get(get_competitive_analysis))
         // WebSocket endpoint
         .route("/ws/realtime", get(websocket_handler))
         // Real Lightning node data - CRITIQUE: Données sensibles
         .route("/api/node/info", get(get_node_info_handler))
         .route("/api/node/channels", get(get_channels_handler))
+        .merge(protected_metrics)
         // Middleware d'authentification pour toutes les routes protégées
         .route_layer(axum::middleware::from_fn(auth_middleware))
         // Rate limiting plus strict pour les actions critiques
@@ -211,6 +647,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let app = Router::<Arc<AppState>>::new()
         .merge(public_routes)
         .merge(protected_routes)
+        .merge(public_metrics)
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.http_metrics.clone(),
+            track_http_metrics,
+        ))
         .layer(session_layer)
         .with_state(app_state.clone());
 
@@ -495,14 +936,6 @@ async fn get_status_handler() -> impl IntoResponse {
     }))
 }
 
-async fn get_recommendations_handler() -> impl IntoResponse {
-    Json(json!([]))
-}
-
-async fn execute_action_handler() -> impl IntoResponse {
-    StatusCode::OK
-}
-
 async fn get_node_info_handler() -> Result<Json<serde_json::Value>, StatusCode> {
     info!("📡 Node info requested - will integrate with LND client");
 
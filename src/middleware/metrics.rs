@@ -0,0 +1,184 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use subtle::ConstantTimeEq;
+use tracing::warn;
+
+use crate::utils::prometheus::{MetricKind, PrometheusWriter};
+
+/// Bornes supérieures (secondes) des seaux de l'histogramme de latence
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Default, Clone)]
+struct RouteStats {
+    /// Requêtes par seau, non cumulées
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+    statuses: BTreeMap<u16, u64>,
+}
+
+/// Latences et codes de réponse HTTP par méthode et par route.
+#[derive(Clone, Default)]
+pub struct HttpMetrics {
+    routes: Arc<Mutex<BTreeMap<(String, String), RouteStats>>>,
+}
+
+impl HttpMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(&self, method: &str, route: &str, status: u16, elapsed_secs: f64) {
+        let mut routes = self.routes.lock().unwrap();
+        let stats = routes
+            .entry((method.to_string(), route.to_string()))
+            .or_default();
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|le| elapsed_secs <= *le) {
+            stats.buckets[bucket] += 1;
+        }
+        stats.count += 1;
+        stats.sum_secs += elapsed_secs;
+        *stats.statuses.entry(status).or_default() += 1;
+    }
+
+    pub fn write(&self, writer: &mut PrometheusWriter) {
+        let routes = self.routes.lock().unwrap().clone();
+
+        writer.family(
+            "dazno_http_requests_total",
+            MetricKind::Counter,
+            "Requêtes HTTP traitées, par méthode, route et code de réponse",
+        );
+        for ((method, route), stats) in &routes {
+            for (status, count) in &stats.statuses {
+                writer.sample(
+                    "dazno_http_requests_total",
+                    &[
+                        ("method", method),
+                        ("route", route),
+                        ("status", &status.to_string()),
+                    ],
+                    *count as f64,
+                );
+            }
+        }
+
+        writer.family(
+            "dazno_http_request_duration_seconds",
+            MetricKind::Histogram,
+            "Latence des requêtes HTTP",
+        );
+        for ((method, route), stats) in &routes {
+            let labels = [("method", method.as_str()), ("route", route.as_str())];
+            let mut cumulative = 0;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(stats.buckets) {
+                cumulative += count;
+                writer.sample(
+                    "dazno_http_request_duration_seconds_bucket",
+                    &[labels[0], labels[1], ("le", &le.to_string())],
+                    cumulative as f64,
+                );
+            }
+            writer.sample(
+                "dazno_http_request_duration_seconds_bucket",
+                &[labels[0], labels[1], ("le", "+Inf")],
+                stats.count as f64,
+            );
+            writer.sample(
+                "dazno_http_request_duration_seconds_sum",
+                &labels,
+                stats.sum_secs,
+            );
+            writer.sample(
+                "dazno_http_request_duration_seconds_count",
+                &labels,
+                stats.count as f64,
+            );
+        }
+    }
+}
+
+/// Mesure la latence de chaque requête ; la route est le motif déclaré
+/// (`/api/peers/:pubkey`) pour borner le nombre de séries.
+pub async fn track_http_metrics(
+    State(metrics): State<HttpMetrics>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+    metrics.observe(
+        &method,
+        &route,
+        response.status().as_u16(),
+        started.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+/// Jeton dédié à la collecte Prometheus, distinct des sessions utilisateur
+pub async fn metrics_token_middleware(
+    State(token): State<Arc<String>>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let provided = headers
+        .get("Authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    if !bool::from(provided.as_bytes().ct_eq(token.as_bytes())) {
+        warn!("Rejected metrics scrape with invalid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram_is_cumulative() {
+        let metrics = HttpMetrics::new();
+        metrics.observe("GET", "/api/health", 200, 0.003);
+        metrics.observe("GET", "/api/health", 200, 0.2);
+        metrics.observe("GET", "/api/health", 503, 30.0);
+
+        let mut writer = PrometheusWriter::new();
+        metrics.write(&mut writer);
+        let text = writer.finish();
+
+        for line in [
+            "dazno_http_requests_total{method=\"GET\",route=\"/api/health\",status=\"200\"} 2",
+            "dazno_http_requests_total{method=\"GET\",route=\"/api/health\",status=\"503\"} 1",
+            "dazno_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/health\",le=\"0.005\"} 1",
+            "dazno_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/health\",le=\"0.25\"} 2",
+            "dazno_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/health\",le=\"10\"} 2",
+            "dazno_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/health\",le=\"+Inf\"} 3",
+            "dazno_http_request_duration_seconds_count{method=\"GET\",route=\"/api/health\"} 3",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing line: {}", line);
+        }
+    }
+}
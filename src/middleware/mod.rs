@@ -1,10 +1,12 @@
 pub mod auth;
+pub mod metrics;
 pub mod rate_limiting;
 pub mod validation;
 
 #[allow(unused_imports)]
 pub use auth::generate_auth_token;
 pub use auth::{auth_middleware, public_route_middleware};
+pub use metrics::{metrics_token_middleware, track_http_metrics, HttpMetrics};
 #[allow(unused_imports)]
 pub use rate_limiting::rate_limit_middleware;
 pub use rate_limiting::{
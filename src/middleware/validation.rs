@@ -77,6 +77,17 @@ impl Default for InputValidator {
             },
         );
 
+        // Règles pour les noms de groupes de pairs
+        rules.insert(
+            "group_name".to_string(),
+            ValidationRule {
+                min_length: Some(1),
+                max_length: Some(50),
+                pattern: Some(Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap()),
+                ..Default::default()
+            },
+        );
+
         // Règles pour les montants (satoshis)
         rules.insert(
             "amount".to_string(),
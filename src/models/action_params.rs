@@ -0,0 +1,130 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::api::mcp_client::ActionType;
+
+/// Paramètres d'ouverture de canal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenChannelParams {
+    pub peer_pubkey: String,
+    #[serde(alias = "amount", alias = "local_funding_amount")]
+    pub amount_sat: u64,
+    #[serde(default)]
+    pub push_sat: u64,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub sat_per_vbyte: Option<u64>,
+}
+
+/// Paramètres de fermeture de canal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloseChannelParams {
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub channel_point: Option<String>,
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub sat_per_vbyte: Option<u64>,
+}
+
+/// Paramètres de mise à jour de politique de frais.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdjustFeesParams {
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub channel_point: Option<String>,
+    #[serde(default)]
+    pub base_fee_msat: Option<u64>,
+    #[serde(alias = "fee_rate", alias = "fee_ppm")]
+    pub fee_rate_ppm: u32,
+    #[serde(default)]
+    pub time_lock_delta: Option<u32>,
+}
+
+/// Paramètres de rééquilibrage circulaire entre deux canaux.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RebalanceParams {
+    #[serde(deserialize_with = "required_string_or_number")]
+    pub outgoing_channel_id: String,
+    #[serde(deserialize_with = "required_string_or_number")]
+    pub incoming_channel_id: String,
+    #[serde(alias = "amount")]
+    pub amount_sat: u64,
+    pub max_fee_sat: u64,
+}
+
+/// Paramètres typés d'une recommandation, selon son `ActionType`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action_type", content = "parameters")]
+pub enum ActionParameters {
+    OpenChannel(OpenChannelParams),
+    CloseChannel(CloseChannelParams),
+    AdjustFees(AdjustFeesParams),
+    RebalanceChannel(RebalanceParams),
+}
+
+impl ActionParameters {
+    /// Interprète les paramètres libres d'une recommandation selon son type d'action.
+    pub fn parse(action_type: ActionType, value: &serde_json::Value) -> Result<Self, String> {
+        let parsed = match action_type {
+            ActionType::OpenChannel => {
+                serde_json::from_value(value.clone()).map(ActionParameters::OpenChannel)
+            }
+            ActionType::CloseChannel => {
+                serde_json::from_value(value.clone()).map(ActionParameters::CloseChannel)
+            }
+            ActionType::AdjustFees => {
+                serde_json::from_value(value.clone()).map(ActionParameters::AdjustFees)
+            }
+            ActionType::RebalanceChannel => {
+                serde_json::from_value(value.clone()).map(ActionParameters::RebalanceChannel)
+            }
+        };
+        parsed.map_err(|e| format!("{:?}: {}", action_type, e))
+    }
+
+    pub fn action_type(&self) -> ActionType {
+        match self {
+            ActionParameters::OpenChannel(_) => ActionType::OpenChannel,
+            ActionParameters::CloseChannel(_) => ActionType::CloseChannel,
+            ActionParameters::AdjustFees(_) => ActionType::AdjustFees,
+            ActionParameters::RebalanceChannel(_) => ActionType::RebalanceChannel,
+        }
+    }
+
+    /// Sérialise les seuls paramètres (sans l'étiquette de type).
+    pub fn to_value(&self) -> serde_json::Value {
+        let value = match self {
+            ActionParameters::OpenChannel(p) => serde_json::to_value(p),
+            ActionParameters::CloseChannel(p) => serde_json::to_value(p),
+            ActionParameters::AdjustFees(p) => serde_json::to_value(p),
+            ActionParameters::RebalanceChannel(p) => serde_json::to_value(p),
+        };
+        value.unwrap_or_default()
+    }
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value)),
+        Some(serde_json::Value::Number(value)) => Ok(Some(value.to_string())),
+        Some(other) => Err(serde::de::Error::custom(format!(
+            "expected string or number, got {}",
+            other
+        ))),
+    }
+}
+
+fn required_string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    string_or_number(deserializer)?.ok_or_else(|| serde::de::Error::custom("missing value"))
+}
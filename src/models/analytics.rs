@@ -1,23 +1,33 @@
-use crate::api::local_lightning_client::LocalChannelInfo;
 use serde::{Deserialize, Serialize};
 
+/// Scores du nœud calculés sur la fenêtre d'historique (voir `utils::node_analytics`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeAnalytics {
-    pub performance_score: f64, // 0-100 composite score
-    pub roi_current: f64,       // Current ROI percentage
-    pub roi_predicted_30d: f64, // Predicted 30-day ROI
-    pub efficiency_score: f64,  // Capital efficiency score
-    pub risk_score: f64,        // Risk assessment score
-    pub centrality_score: f64,  // Network position score
-    pub liquidity_score: f64,   // Liquidity management score
-    pub reliability_score: f64, // Node reliability score
-    pub growth_potential: f64,  // Growth opportunity score
+    /// Moyenne des scores d'efficacité, centralité, liquidité, fiabilité et de `100 - risque`
+    pub performance_score: f64,
+    /// Frais gagnés / solde local déployé, annualisé (%)
+    pub roi_current: f64,
+    /// Frais prévus sur 30 jours / solde local déployé, annualisé (%)
+    pub roi_predicted_30d: f64,
+    /// Frais annuels par sat de capacité, rapportés à l'objectif de rendement (0-100)
+    pub efficiency_score: f64,
+    /// Moyenne de la concentration de capacité par pair (Herfindahl) et de la part inactive (0-100)
+    pub risk_score: f64,
+    /// Rang centile du nœud dans le graphe local, degré et capacité (0-100)
+    pub centrality_score: f64,
+    /// `100 × (1 - 2 × écart moyen de la part locale à 50 %)`, pondéré par la capacité
+    pub liquidity_score: f64,
+    /// Moyenne de la disponibilité des pairs et du taux de succès HTLC (0-100)
+    pub reliability_score: f64,
+    /// `50 + 100 × tendance relative des frais sur 30 jours`, borné à 0-100
+    pub growth_potential: f64,
     pub last_calculated: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelAnalytics {
     pub channel_id: String,
+    pub pnl: ChannelPnl,
     pub performance_metrics: ChannelPerformanceMetrics,
     pub profitability_metrics: ChannelProfitabilityMetrics,
     pub risk_metrics: ChannelRiskMetrics,
@@ -25,15 +35,50 @@ pub struct ChannelAnalytics {
     pub last_updated: chrono::DateTime<chrono::Utc>,
 }
 
+/// Compte de résultat d'un canal sur sa période observée. Chaque forward et chaque
+/// rééquilibrage est partagé à parts égales entre ses deux canaux : la somme des
+/// P&L de canaux égale celui du nœud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelPnl {
+    pub peer_pubkey: String,
+    pub peer_alias: String,
+    pub capacity_sat: u64,
+    pub closed: bool,
+    pub period_start: chrono::DateTime<chrono::Utc>,
+    pub period_end: chrono::DateTime<chrono::Utc>,
+    pub days: f64,
+    /// Moitié des frais des forwards sortis par ce canal
+    pub revenue_out_sat: u64,
+    /// Moitié des frais des forwards entrés par ce canal
+    pub revenue_in_sat: u64,
+    /// Frais on-chain de la transaction d'ouverture, si notre portefeuille l'a financée
+    pub open_cost_sat: u64,
+    /// Moitié des frais des rééquilibrages entrés dans ce canal
+    pub rebalance_in_cost_sat: u64,
+    /// Moitié des frais des rééquilibrages sortis de ce canal
+    pub rebalance_out_cost_sat: u64,
+    pub close_cost_sat: u64,
+    /// Vrai si le canal est ouvert : coût d'une fermeture coopérative au taux courant
+    pub close_cost_estimated: bool,
+    /// Revenus moins ouverture, rééquilibrages et fermeture
+    pub net_pnl_sat: i64,
+    /// Solde local intégré sur la période (sat × jours)
+    pub capital_sat_days: f64,
+    /// `net / capital en sat-jours × 365`, en %
+    pub annualized_yield_pct: Option<f64>,
+    /// Jours de revenu net d'exploitation pour couvrir ouverture et fermeture
+    pub payback_days: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelPerformanceMetrics {
-    pub forwarding_success_rate: f64, // % of successful forwards
-    pub average_htlc_size: u64,       // Average HTLC amount
-    pub forwards_per_day: f64,        // Daily forwarding volume
-    pub total_fees_earned: u64,       // Total fees in satoshis
-    pub fees_per_day_avg: f64,        // Average daily fees
-    pub uptime_percentage: f64,       // Channel uptime %
-    pub rebalancing_frequency: f64,   // Rebalances per week
+    pub forwarding_success_rate: Option<f64>, // % of successful forwards, None when failures are not observable
+    pub average_htlc_size: u64,               // Average HTLC amount
+    pub forwards_per_day: f64,                // Daily forwarding volume
+    pub total_fees_earned: u64,               // Total fees in satoshis
+    pub fees_per_day_avg: f64,                // Average daily fees
+    pub uptime_percentage: f64,               // Channel uptime %
+    pub rebalancing_frequency: f64,           // Rebalances per week
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +134,29 @@ pub struct PredictiveAnalytics {
     pub node_performance_forecast: NodePerformanceForecast,
     pub optimal_strategies: Vec<OptimalStrategy>,
     pub risk_scenarios: Vec<RiskScenario>,
+    /// Prévisions journalières sous-jacentes, avec intervalles de confiance
+    #[serde(default)]
+    pub forecasts: Vec<MetricForecast>,
+}
+
+/// Prévision d'une série et son intervalle de confiance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastPoint {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricForecast {
+    pub metric: String,
+    pub unit: String,
+    /// Niveau des intervalles (ex. 0.95)
+    pub confidence_level: f64,
+    /// Pente de la tendance, en unités par jour
+    pub trend_per_day: f64,
+    pub points: Vec<ForecastPoint>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -224,29 +292,86 @@ pub struct RiskScenario {
     pub early_warning_indicators: Vec<String>,
 }
 
-impl NodeAnalytics {
-    pub fn calculate_from_channels(channels: &[LocalChannelInfo]) -> Self {
-        // Simplified calculation - in real implementation this would use ML models
-        let _total_capacity: u64 = channels.iter().map(|c| c.capacity).sum();
-        let active_channels = channels.iter().filter(|c| c.active).count();
-
-        let performance_score = if active_channels > 0 {
-            (active_channels as f64 / channels.len() as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        Self {
-            performance_score,
-            roi_current: 15.5, // Mock value
-            roi_predicted_30d: 18.2,
-            efficiency_score: 78.3,
-            risk_score: 25.1,
-            centrality_score: 82.7,
-            liquidity_score: 91.4,
-            reliability_score: 88.9,
-            growth_potential: 73.6,
-            last_calculated: chrono::Utc::now(),
-        }
-    }
+/// Pairs visés par un événement de stress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum PeerSelection {
+    /// Les `count` pairs qui génèrent le plus de frais de routage sur l'historique
+    TopRevenue {
+        count: usize,
+    },
+    Pubkeys {
+        pubkeys: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseInitiator {
+    /// Le pair publie son commitment : nos fonds sont libres dès confirmation
+    #[default]
+    Remote,
+    /// Nous publions le nôtre : notre solde reste soumis au délai CSV
+    Local,
+}
+
+/// Événement élémentaire d'un scénario ; les événements d'un scénario sont simultanés.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StressEvent {
+    ForceClose {
+        peers: PeerSelection,
+        #[serde(default)]
+        initiator: CloseInitiator,
+    },
+    OnchainFeeSpike {
+        fee_rate_sat_vb: f64,
+    },
+    PeersOffline {
+        peers: PeerSelection,
+        days: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressScenario {
+    pub name: String,
+    pub events: Vec<StressEvent>,
+    /// Probabilité fournie par l'appelant ; à défaut, estimée sur l'historique quand c'est possible
+    #[serde(default)]
+    pub probability: Option<f64>,
+}
+
+/// Conséquences d'un scénario sur les canaux ouverts (voir `utils::stress_testing`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressImpact {
+    /// Taux on-chain retenu : le plus élevé entre le taux courant et celui du scénario
+    pub fee_rate_sat_vb: f64,
+    pub affected_channels: Vec<String>,
+    pub closed_channels: usize,
+    /// Solde local inutilisable pendant le scénario (canaux fermés ou pair absent)
+    pub locked_funds_sat: u64,
+    /// Part de ce solde bloquée par le délai CSV de nos fermetures forcées
+    pub timelocked_balance_sat: u64,
+    pub max_timelock_blocks: u32,
+    /// Frais on-chain des fermetures forcées du scénario
+    pub close_cost_sat: u64,
+    /// Frais de routage perdus, au rythme de l'historique
+    pub lost_routing_revenue_sat: u64,
+    /// Part des frais de routage qui transitent par les canaux touchés
+    pub affected_revenue_share: f64,
+    /// Coût d'une fermeture coopérative de tous les canaux restants
+    pub exit_cost_sat: u64,
+    /// Solde local des canaux restants qui ne couvre plus une fermeture forcée
+    pub uneconomic_balance_sat: u64,
+    pub reachable_nodes_before: usize,
+    pub reachable_nodes_after: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StressTestResult {
+    pub scenario: StressScenario,
+    pub impact: StressImpact,
+    /// `probability` vaut 0 sans estimation possible ; `potential_impact` en sat
+    pub risk: RiskScenario,
 }
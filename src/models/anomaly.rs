@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::local_lightning_client::LocalCloseType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnomalySeverity {
+    Warning,
+    Critical,
+}
+
+/// Comportement inhabituel relevé entre deux observations du nœud.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Liquidité sortante d'un canal vidée d'un coup
+    OutboundDrain {
+        channel_id: String,
+        previous_local_sat: u64,
+        current_local_sat: u64,
+    },
+    /// Rafale de tentatives HTLC échouées sur nos propres paiements sortants
+    /// (les échecs de forward ne sont pas exposés par l'API LND utilisée)
+    FailedPaymentBurst {
+        failures: usize,
+        window_minutes: i64,
+    },
+    /// Plusieurs canaux passés inactifs simultanément
+    MassInactive { channel_ids: Vec<String> },
+    /// Fermeture forcée que nous n'avons pas demandée
+    UnexpectedForceClose {
+        channel_id: String,
+        remote_pubkey: String,
+        close_type: LocalCloseType,
+    },
+    /// Politique de frais modifiée hors de l'application
+    UnexpectedFeeChange {
+        channel_id: String,
+        previous_base_fee_msat: u64,
+        previous_fee_rate_ppm: u32,
+        base_fee_msat: u64,
+        fee_rate_ppm: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub id: String,
+    pub kind: AnomalyKind,
+    pub severity: AnomalySeverity,
+    pub detected_at: DateTime<Utc>,
+    pub description: String,
+}
+
+/// Suspension de l'automatisation déclenchée par une anomalie critique ou une règle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationPause {
+    pub paused_at: DateTime<Utc>,
+    pub anomaly_id: String,
+    pub reason: String,
+}
@@ -47,7 +47,7 @@ pub struct NotificationTrigger {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TriggerType {
     HighImpactAction,     // ROI impact > threshold
     LargeAmountAction,    // Amount > threshold
@@ -299,6 +299,26 @@ pub struct ExecutionWindow {
     pub expected_cost_savings: f64,
 }
 
+impl NotificationPreferences {
+    pub fn is_triggered(&self, trigger_type: TriggerType) -> bool {
+        self.notification_triggers
+            .iter()
+            .any(|t| t.enabled && t.trigger_type == trigger_type)
+    }
+}
+
+impl RiskTolerance {
+    /// Score de réputation (0-100) exigé d'un pair avant une action automatique.
+    pub fn min_peer_reputation(&self) -> f64 {
+        match self {
+            RiskTolerance::Conservative => 70.0,
+            RiskTolerance::Moderate => 55.0,
+            RiskTolerance::Aggressive => 40.0,
+            RiskTolerance::Custom(custom) => custom.min_peer_reliability_score,
+        }
+    }
+}
+
 impl Default for AutomationSettings {
     fn default() -> Self {
         Self {
@@ -335,6 +355,11 @@ impl Default for NotificationPreferences {
                     threshold: 0.0,
                     enabled: true,
                 },
+                NotificationTrigger {
+                    trigger_type: TriggerType::UnusualActivity,
+                    threshold: 0.0,
+                    enabled: true,
+                },
             ],
         }
     }
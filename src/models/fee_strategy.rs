@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Stratégie de frais configurable et ses paramètres.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeStrategyKind {
+    /// ppm d'autant plus élevé que la liquidité sortante se raréfie
+    BalanceProportional { min_ppm: u32, max_ppm: u32 },
+    /// Alignement sur les frais des autres nœuds vers le même pair
+    CompetitiveMatching {
+        /// Décote (%) appliquée à la médiane concurrente
+        undercut_pct: f64,
+        min_competitors: usize,
+    },
+    /// Paliers de ppm selon la demande récente
+    DemandStepping {
+        step_ppm: u32,
+        window_days: u32,
+        /// Forwards sortants par jour au-delà desquels le ppm monte
+        busy_forwards_per_day: f64,
+    },
+    /// Bornes fixes appliquées à la politique proposée
+    StaticBounds { floor_ppm: u32, ceiling_ppm: u32 },
+}
+
+/// Canaux couverts par une affectation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum AssignmentScope {
+    Channel { channel_id: String },
+    PeerGroup { name: String, peers: Vec<String> },
+}
+
+/// Chaîne de stratégies affectée à un canal ou à un groupe de pairs.
+/// Les stratégies s'appliquent dans l'ordre, chacune partant de la proposition précédente.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeStrategyAssignment {
+    pub scope: AssignmentScope,
+    pub strategies: Vec<FeeStrategyKind>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeeStrategyAssignment {
+    /// Clé unique de l'affectation (`channel:<id>` ou `group:<nom>`)
+    pub fn scope_key(&self) -> String {
+        match &self.scope {
+            AssignmentScope::Channel { channel_id } => format!("channel:{}", channel_id),
+            AssignmentScope::PeerGroup { name, .. } => format!("group:{}", name),
+        }
+    }
+
+    /// Affectation applicable : celle du canal prime sur celle d'un groupe de pairs.
+    pub fn resolve<'a>(
+        assignments: &'a [FeeStrategyAssignment],
+        channel_id: &str,
+        peer_pubkey: &str,
+    ) -> Option<&'a FeeStrategyAssignment> {
+        assignments
+            .iter()
+            .find(|a| matches!(&a.scope, AssignmentScope::Channel { channel_id: id } if id == channel_id))
+            .or_else(|| {
+                assignments.iter().find(|a| {
+                    matches!(&a.scope, AssignmentScope::PeerGroup { peers, .. } if peers.iter().any(|p| p == peer_pubkey))
+                })
+            })
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::mcp_client::{ActionType, Priority};
+use crate::models::recommendation::{
+    Recommendation, RecommendationSource, RecommendationStatus, StatusTransition,
+};
+
+/// Approbation ou refus d'une recommandation, avec les caractéristiques présentées à l'opérateur.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorDecision {
+    pub recommendation_id: String,
+    pub approved: bool,
+    pub action_type: ActionType,
+    pub priority: Priority,
+    pub source: RecommendationSource,
+    pub confidence: Option<f64>,
+    pub expected_roi_impact: f64,
+    /// Version du modèle local à l'origine de la recommandation
+    pub model_version: Option<i64>,
+    pub actor: String,
+    pub reason: Option<String>,
+    pub decided_at: DateTime<Utc>,
+}
+
+impl OperatorDecision {
+    /// Décision portée par une transition ; `None` pour les changements d'état automatiques.
+    pub fn from_transition(
+        recommendation: &Recommendation,
+        transition: &StatusTransition,
+    ) -> Option<Self> {
+        let approved = match transition.to {
+            RecommendationStatus::Approved => true,
+            RecommendationStatus::Rejected => false,
+            _ => return None,
+        };
+
+        Some(Self {
+            recommendation_id: recommendation.id.clone(),
+            approved,
+            action_type: recommendation.action_type,
+            priority: recommendation.priority,
+            source: recommendation.source,
+            confidence: recommendation.confidence,
+            expected_roi_impact: recommendation.expected_roi_impact,
+            model_version: recommendation
+                .provenance
+                .iter()
+                .find_map(|p| p.model_version),
+            actor: transition.actor.clone(),
+            reason: transition.reason.clone(),
+            decided_at: transition.at,
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Comptes du grand livre. Les noms suivent la convention `Classe:Compte` des
+/// outils de comptabilité en partie double.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerAccount {
+    /// Soldes locaux des canaux
+    LightningBalance,
+    /// Portefeuille on-chain de LND
+    OnChainWallet,
+    RoutingIncome,
+    PaymentsReceived,
+    PaymentsSent,
+    LightningFees,
+    RebalanceFees,
+    OnChainFees,
+}
+
+impl LedgerAccount {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LightningBalance => "Assets:Lightning",
+            Self::OnChainWallet => "Assets:OnChain",
+            Self::RoutingIncome => "Income:Routing",
+            Self::PaymentsReceived => "Income:Payments",
+            Self::PaymentsSent => "Expenses:Payments",
+            Self::LightningFees => "Expenses:Fees:Lightning",
+            Self::RebalanceFees => "Expenses:Fees:Rebalancing",
+            Self::OnChainFees => "Expenses:Fees:OnChain",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerCategory {
+    RoutingIncome,
+    InvoiceReceived,
+    PaymentSent,
+    PaymentFee,
+    RebalanceFee,
+    /// Fonds du portefeuille engagés dans un canal
+    ChannelOpen,
+    ChannelOpenFee,
+    /// Solde d'un canal rendu au portefeuille
+    ChannelClose,
+    ChannelCloseFee,
+}
+
+impl LedgerCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RoutingIncome => "routing_income",
+            Self::InvoiceReceived => "invoice_received",
+            Self::PaymentSent => "payment_sent",
+            Self::PaymentFee => "payment_fee",
+            Self::RebalanceFee => "rebalance_fee",
+            Self::ChannelOpen => "channel_open",
+            Self::ChannelOpenFee => "channel_open_fee",
+            Self::ChannelClose => "channel_close",
+            Self::ChannelCloseFee => "channel_close_fee",
+        }
+    }
+
+    /// Mouvement entre deux comptes d'actif du nœud, sans effet sur le résultat
+    pub fn is_transfer(self) -> bool {
+        matches!(self, Self::ChannelOpen | Self::ChannelClose)
+    }
+}
+
+/// Écriture en partie double : `amount_msat` débite un compte et crédite l'autre.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Identifiant stable d'un export à l'autre
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub category: LedgerCategory,
+    pub debit: LedgerAccount,
+    pub credit: LedgerAccount,
+    pub amount_msat: u64,
+    /// Contre-valeur au cours du jour de l'écriture, absente si le cours est inconnu
+    pub fiat_value: Option<f64>,
+    /// Empreinte du paiement, identifiant de transaction ou canaux du forward
+    pub reference: String,
+    pub description: String,
+}
+
+/// Solde d'un compte sur la période : débits moins crédits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerAccountBalance {
+    pub account: LedgerAccount,
+    pub name: String,
+    pub balance_msat: i64,
+    pub fiat_balance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ledger {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub fiat_currency: String,
+    pub entries: Vec<LedgerEntry>,
+    /// La somme des soldes est nulle, aux écritures sans cours près pour la contre-valeur
+    pub balances: Vec<LedgerAccountBalance>,
+}
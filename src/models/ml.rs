@@ -8,12 +8,14 @@ use crate::api::{
 /// Résumé chiffré produit par le moteur ML local.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MLScorecard {
-    pub predicted_roi_30d: f64,
-    pub predicted_roi_90d: f64,
-    pub predicted_roi_365d: f64,
+    /// Rendement des frais au rythme de l'historique récent ; `None` sans forwarding
+    pub predicted_roi_30d: Option<f64>,
+    pub predicted_roi_90d: Option<f64>,
+    pub predicted_roi_365d: Option<f64>,
     pub risk_index: f64,
     pub capacity_saturation: f64,
-    pub advantage_vs_amboss: f64,
+    /// Écart de prix face aux routes concurrentes ; `None` sans graphe
+    pub advantage_vs_amboss: Option<f64>,
     pub confidence: f64,
 }
 
@@ -37,6 +39,7 @@ pub struct SmartRecommendation {
     pub risk_score: f64,
     pub rationale: Vec<String>,
     pub target_channels: Vec<String>,
+    pub parameters: serde_json::Value,
 }
 
 /// Recommandation d’exécution automatique après analyse ML.
@@ -59,6 +62,58 @@ pub struct SimulationOutcome {
     pub risk_level: String,
     pub estimated_cost: u64,
     pub timeline: Vec<SimulationStep>,
+    #[serde(default)]
+    pub horizon_days: u32,
+    #[serde(default)]
+    pub runs: usize,
+    /// Revenu de routage projeté sur l'horizon (sat)
+    #[serde(default)]
+    pub revenue_sat: PercentileBand,
+    /// Écart de revenu par rapport au statu quo (sat)
+    #[serde(default)]
+    pub revenue_delta_sat: PercentileBand,
+    /// ROI net annualisé (%)
+    #[serde(default)]
+    pub roi_pct: PercentileBand,
+    #[serde(default)]
+    pub cost: CostEstimate,
+    /// Hypothèses retenues faute de données (historique absent, frais par défaut…)
+    #[serde(default)]
+    pub assumptions: Vec<String>,
+}
+
+/// Bande de percentiles d'une grandeur simulée.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PercentileBand {
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+impl PercentileBand {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let at = |q: f64| sorted[((sorted.len() - 1) as f64 * q).round() as usize];
+        Self {
+            p10: at(0.1),
+            p50: at(0.5),
+            p90: at(0.9),
+        }
+    }
+}
+
+/// Coût d'exécution estimé à partir des frais courants.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostEstimate {
+    pub onchain_sat: u64,
+    pub routing_sat: u64,
+    pub total_sat: u64,
+    pub onchain_fee_rate_sat_vb: f64,
+    pub routing_fee_ppm: u64,
 }
 
 /// Étape chronologique dans une simulation.
@@ -76,6 +131,12 @@ pub struct OptimalWindow {
     pub window: String,
     pub confidence: f64,
     pub reasons: Vec<String>,
+    pub start: chrono::DateTime<chrono::Utc>,
+    pub end: chrono::DateTime<chrono::Utc>,
+    /// Coût attendu dans la fenêtre (frais on-chain ou revenu exposé, en sat)
+    pub expected_cost_sat: u64,
+    /// Économie attendue par rapport à une exécution immédiate (sat)
+    pub expected_saving_sat: u64,
 }
 
 /// Photographie rapide des canaux utilisée par le moteur ML.
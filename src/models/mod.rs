@@ -1,5 +1,12 @@
+pub mod action_params;
 pub mod analytics;
+pub mod anomaly;
 pub mod automation;
+pub mod fee_strategy;
+pub mod feedback;
+pub mod ledger;
 pub mod metrics;
 pub mod ml;
 pub mod recommendation;
+pub mod report;
+pub mod reputation;
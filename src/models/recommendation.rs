@@ -1,4 +1,6 @@
 use crate::api::mcp_client::{ActionType, MCPRecommendation, Priority};
+use crate::models::ml::SmartRecommendation;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,21 +13,150 @@ pub struct Recommendation {
     pub parameters: serde_json::Value,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub status: RecommendationStatus,
+    pub source: RecommendationSource,
+    pub confidence: Option<f64>,
+    pub target_channels: Vec<String>,
+    pub provenance: Vec<RecommendationProvenance>,
+    pub superseded: Vec<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Cycle de vie d'une recommandation.
+///
+/// Pending → Approved → Executing → Executed | Failed ;
+/// Pending | Snoozed | Approved → Rejected | Expired ; Pending ⇄ Snoozed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RecommendationStatus {
     Pending,
     Approved,
-    Rejected,
+    Executing,
     Executed,
     Failed,
+    Rejected,
+    Expired,
+    Snoozed,
+}
+
+impl RecommendationStatus {
+    pub const ALL: [RecommendationStatus; 8] = [
+        RecommendationStatus::Pending,
+        RecommendationStatus::Approved,
+        RecommendationStatus::Executing,
+        RecommendationStatus::Executed,
+        RecommendationStatus::Failed,
+        RecommendationStatus::Rejected,
+        RecommendationStatus::Expired,
+        RecommendationStatus::Snoozed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationStatus::Pending => "pending",
+            RecommendationStatus::Approved => "approved",
+            RecommendationStatus::Executing => "executing",
+            RecommendationStatus::Executed => "executed",
+            RecommendationStatus::Failed => "failed",
+            RecommendationStatus::Rejected => "rejected",
+            RecommendationStatus::Expired => "expired",
+            RecommendationStatus::Snoozed => "snoozed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Aucun retour possible depuis un état terminal.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RecommendationStatus::Executed
+                | RecommendationStatus::Failed
+                | RecommendationStatus::Rejected
+                | RecommendationStatus::Expired
+        )
+    }
+
+    /// États encore présentés à l'opérateur.
+    pub fn is_actionable(&self) -> bool {
+        matches!(
+            self,
+            RecommendationStatus::Pending | RecommendationStatus::Approved
+        )
+    }
+
+    pub fn can_transition_to(&self, to: RecommendationStatus) -> bool {
+        use RecommendationStatus::*;
+        matches!(
+            (self, to),
+            (Pending, Approved | Rejected | Expired | Snoozed)
+                | (Snoozed, Pending | Rejected | Expired)
+                | (Approved, Executing | Rejected | Expired)
+                | (Executing, Executed | Failed)
+        )
+    }
+}
+
+/// Changement d'état horodaté, avec son auteur et sa justification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub recommendation_id: String,
+    pub from: RecommendationStatus,
+    pub to: RecommendationStatus,
+    pub at: DateTime<Utc>,
+    pub actor: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TransitionError {
+    #[error("transition {from:?} → {to:?} interdite")]
+    NotAllowed {
+        from: RecommendationStatus,
+        to: RecommendationStatus,
+    },
+    #[error("une mise en attente exige une date de reprise future")]
+    MissingSnoozeUntil,
+}
+
+/// Origine d'une recommandation avant normalisation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RecommendationSource {
+    Mcp,
+    LocalMl,
+}
+
+/// Trace d'une source ayant proposé (ou corroboré) une recommandation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendationProvenance {
+    pub source: RecommendationSource,
+    pub source_id: String,
+    pub fetched_at: DateTime<Utc>,
+    /// Version du modèle local ayant produit la recommandation
+    #[serde(default)]
+    pub model_version: Option<i64>,
+}
+
+impl RecommendationSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationSource::Mcp => "mcp",
+            RecommendationSource::LocalMl => "local_ml",
+        }
+    }
 }
 
 impl From<MCPRecommendation> for Recommendation {
     fn from(mcp_rec: MCPRecommendation) -> Self {
+        let fetched_at = Utc::now();
+        let target_channels = channels_from_parameters(&mcp_rec.parameters);
         Self {
-            id: mcp_rec.id,
+            id: format!("mcp_{}", mcp_rec.id),
             action_type: mcp_rec.action_type,
             priority: mcp_rec.priority,
             expected_roi_impact: mcp_rec.expected_roi_impact,
@@ -33,10 +164,98 @@ impl From<MCPRecommendation> for Recommendation {
             parameters: mcp_rec.parameters,
             created_at: mcp_rec.created_at,
             status: RecommendationStatus::Pending,
+            source: RecommendationSource::Mcp,
+            confidence: None,
+            target_channels,
+            provenance: vec![RecommendationProvenance {
+                source: RecommendationSource::Mcp,
+                source_id: mcp_rec.id,
+                fetched_at,
+                model_version: None,
+            }],
+            superseded: vec![],
+            expires_at: None,
+            snoozed_until: None,
+        }
+    }
+}
+
+impl From<SmartRecommendation> for Recommendation {
+    fn from(smart_rec: SmartRecommendation) -> Self {
+        let now = Utc::now();
+        Self {
+            id: format!("ml_{}", smart_rec.id),
+            action_type: smart_rec.action_type,
+            priority: smart_rec.priority,
+            expected_roi_impact: smart_rec.expected_roi_impact,
+            description: smart_rec.rationale.join(" · "),
+            parameters: smart_rec.parameters,
+            created_at: now,
+            status: RecommendationStatus::Pending,
+            source: RecommendationSource::LocalMl,
+            confidence: Some(smart_rec.confidence),
+            target_channels: smart_rec.target_channels,
+            provenance: vec![RecommendationProvenance {
+                source: RecommendationSource::LocalMl,
+                source_id: smart_rec.id,
+                fetched_at: now,
+                model_version: None,
+            }],
+            superseded: vec![],
+            expires_at: None,
+            snoozed_until: None,
         }
     }
 }
 
+impl Recommendation {
+    /// Attribue la recommandation à la version du modèle local qui l'a produite.
+    pub fn with_model_version(mut self, version: Option<i64>) -> Self {
+        for provenance in &mut self.provenance {
+            if provenance.source == RecommendationSource::LocalMl {
+                provenance.model_version = version;
+            }
+        }
+        self
+    }
+
+    /// Vue ML d'une recommandation persistée, pour la simulation et la planification.
+    pub fn to_smart_recommendation(&self) -> SmartRecommendation {
+        SmartRecommendation {
+            id: self.id.clone(),
+            action_type: self.action_type,
+            priority: self.priority,
+            expected_roi_impact: self.expected_roi_impact,
+            confidence: self.confidence.unwrap_or(0.5),
+            risk_score: 1.0 - self.confidence.unwrap_or(0.5),
+            rationale: vec![self.description.clone()],
+            target_channels: self.target_channels.clone(),
+            parameters: self.parameters.clone(),
+        }
+    }
+}
+
+/// Extrait les canaux visés depuis les paramètres libres d'une recommandation MCP.
+fn channels_from_parameters(parameters: &serde_json::Value) -> Vec<String> {
+    let mut channels = vec![];
+    for key in ["channel_id", "chan_id", "channel_point"] {
+        match parameters.get(key) {
+            Some(serde_json::Value::String(value)) => channels.push(value.clone()),
+            Some(serde_json::Value::Number(value)) => channels.push(value.to_string()),
+            _ => {}
+        }
+    }
+    if let Some(list) = parameters.get("channels").and_then(|v| v.as_array()) {
+        channels.extend(list.iter().filter_map(|v| match v {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }));
+    }
+    channels.dedup();
+    channels
+}
+
 impl Recommendation {
     pub fn priority_class(&self) -> &'static str {
         match self.priority {
@@ -54,4 +273,171 @@ impl Recommendation {
             ActionType::RebalanceChannel => "Rebalance Channel",
         }
     }
+
+    /// Date de la plus récente récupération auprès d'une source.
+    pub fn fetched_at(&self) -> DateTime<Utc> {
+        self.provenance
+            .iter()
+            .map(|p| p.fetched_at)
+            .max()
+            .unwrap_or(self.created_at)
+    }
+
+    /// Âge de la recommandation par rapport à sa création.
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        now - self.created_at
+    }
+
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        self.age(now) > max_age
+    }
+
+    /// Applique une transition de cycle de vie et retourne la trace à persister.
+    pub fn transition(
+        &mut self,
+        to: RecommendationStatus,
+        actor: &str,
+        reason: Option<String>,
+        snoozed_until: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<StatusTransition, TransitionError> {
+        if !self.status.can_transition_to(to) {
+            return Err(TransitionError::NotAllowed {
+                from: self.status,
+                to,
+            });
+        }
+
+        if to == RecommendationStatus::Snoozed {
+            match snoozed_until {
+                Some(until) if until > now => self.snoozed_until = Some(until),
+                _ => return Err(TransitionError::MissingSnoozeUntil),
+            }
+        } else {
+            self.snoozed_until = None;
+        }
+
+        let from = self.status;
+        self.status = to;
+
+        Ok(StatusTransition {
+            recommendation_id: self.id.clone(),
+            from,
+            to,
+            at: now,
+            actor: actor.to_string(),
+            reason,
+        })
+    }
+
+    /// Transition automatique due à l'horloge (expiration ou fin de mise en attente).
+    pub fn due_transition(&self, now: DateTime<Utc>) -> Option<RecommendationStatus> {
+        if self.status == RecommendationStatus::Snoozed
+            && self
+                .snoozed_until
+                .map(|until| until <= now)
+                .unwrap_or(false)
+        {
+            return Some(RecommendationStatus::Pending);
+        }
+        let expired = self.expires_at.map(|at| at <= now).unwrap_or(false);
+        if expired && self.status.can_transition_to(RecommendationStatus::Expired) {
+            return Some(RecommendationStatus::Expired);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pending() -> Recommendation {
+        Recommendation::from(SmartRecommendation {
+            id: "fees".to_string(),
+            action_type: ActionType::AdjustFees,
+            priority: Priority::Medium,
+            expected_roi_impact: 1.0,
+            confidence: 0.8,
+            risk_score: 0.1,
+            rationale: vec![],
+            target_channels: vec!["123".to_string()],
+            parameters: json!({}),
+        })
+    }
+
+    #[test]
+    fn test_full_execution_path() {
+        let mut rec = pending();
+        let now = Utc::now();
+        for to in [
+            RecommendationStatus::Approved,
+            RecommendationStatus::Executing,
+            RecommendationStatus::Executed,
+        ] {
+            rec.transition(to, "alice", None, None, now).unwrap();
+        }
+        assert_eq!(rec.status, RecommendationStatus::Executed);
+        assert!(rec.status.is_terminal());
+    }
+
+    #[test]
+    fn test_terminal_states_are_final() {
+        let mut rec = pending();
+        let now = Utc::now();
+        let trace = rec
+            .transition(
+                RecommendationStatus::Rejected,
+                "alice",
+                Some("trop risqué".to_string()),
+                None,
+                now,
+            )
+            .unwrap();
+        assert_eq!(trace.from, RecommendationStatus::Pending);
+        assert_eq!(trace.reason.as_deref(), Some("trop risqué"));
+
+        let err = rec
+            .transition(RecommendationStatus::Approved, "alice", None, None, now)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            TransitionError::NotAllowed {
+                from: RecommendationStatus::Rejected,
+                to: RecommendationStatus::Approved,
+            }
+        );
+    }
+
+    #[test]
+    fn test_snooze_then_wake_and_expire() {
+        let mut rec = pending();
+        let now = Utc::now();
+        assert_eq!(
+            rec.transition(RecommendationStatus::Snoozed, "bob", None, None, now),
+            Err(TransitionError::MissingSnoozeUntil)
+        );
+
+        rec.transition(
+            RecommendationStatus::Snoozed,
+            "bob",
+            None,
+            Some(now + Duration::hours(1)),
+            now,
+        )
+        .unwrap();
+        assert_eq!(rec.due_transition(now), None);
+        assert_eq!(
+            rec.due_transition(now + Duration::hours(2)),
+            Some(RecommendationStatus::Pending)
+        );
+
+        rec.expires_at = Some(now + Duration::minutes(30));
+        rec.snoozed_until = Some(now + Duration::hours(1));
+        assert_eq!(
+            rec.due_transition(now + Duration::minutes(45)),
+            Some(RecommendationStatus::Expired)
+        );
+    }
 }
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{anomaly::AnomalySeverity, recommendation::RecommendationStatus};
+
+/// Périodicité d'un rapport ; les périodes sont alignées sur le calendrier UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriod {
+    /// Du lundi 00:00 au lundi suivant
+    Weekly,
+    /// Du 1er du mois 00:00 au 1er du mois suivant
+    Monthly,
+}
+
+impl ReportPeriod {
+    pub const ALL: [ReportPeriod; 2] = [ReportPeriod::Weekly, ReportPeriod::Monthly];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|period| period.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Bornes `[début, fin)` de la période qui contient `at`
+    pub fn bounds(self, at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let day = at.date_naive();
+        let start = match self {
+            Self::Weekly => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            Self::Monthly => NaiveDate::from_ymd_opt(day.year(), day.month(), 1).unwrap_or(day),
+        };
+        let end = match self {
+            Self::Weekly => start + Duration::days(7),
+            Self::Monthly => start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(start),
+        };
+        let midnight = |date: NaiveDate| Utc.from_utc_datetime(&date.and_time(Default::default()));
+        (midnight(start), midnight(end))
+    }
+
+    /// Dernière période entièrement écoulée à `now`
+    pub fn last_complete(self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let (current_start, _) = self.bounds(now);
+        self.bounds(current_start - Duration::seconds(1))
+    }
+
+    /// Identifiant stable : `weekly-2026-10-12`, `monthly-2026-09`
+    pub fn report_id(self, start: DateTime<Utc>) -> String {
+        match self {
+            Self::Weekly => format!("weekly-{}", start.format("%Y-%m-%d")),
+            Self::Monthly => format!("monthly-{}", start.format("%Y-%m")),
+        }
+    }
+}
+
+/// Résultat du nœud sur la période, d'après le grand livre.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportFinancials {
+    pub forwards: u64,
+    pub routed_sat: u64,
+    pub revenue_sat: u64,
+    pub rebalance_cost_sat: u64,
+    /// Frais on-chain des ouvertures et fermetures de canaux
+    pub onchain_cost_sat: u64,
+    pub costs_sat: u64,
+    pub net_sat: i64,
+    /// Solde local des canaux ouverts à la génération du rapport
+    pub local_balance_sat: u64,
+    /// Résultat net rapporté au solde local, annualisé (%)
+    pub net_yield_pct: Option<f64>,
+    pub fiat_currency: String,
+    /// Contre-valeur du résultat net, absente si un cours manque
+    pub net_fiat: Option<f64>,
+}
+
+/// Contribution d'un canal au résultat de la période. Forwards et rééquilibrages
+/// sont partagés à parts égales entre leurs deux canaux.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportChannel {
+    pub channel_id: String,
+    pub peer_alias: String,
+    pub forwards: u64,
+    pub revenue_sat: u64,
+    pub rebalance_cost_sat: u64,
+    pub net_sat: i64,
+    pub local_balance_sat: u64,
+    pub open: bool,
+}
+
+/// Recommandation exécutée (ou en échec) pendant la période et son effet mesuré.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportAction {
+    pub recommendation_id: String,
+    pub action_type: String,
+    pub description: String,
+    pub status: RecommendationStatus,
+    pub at: DateTime<Utc>,
+    pub expected_roi_impact: f64,
+    pub target_channels: Vec<String>,
+    /// Frais de routage quotidiens des canaux visés avant l'exécution
+    pub fees_per_day_before_sat: f64,
+    /// Même mesure après l'exécution ; absente tant que moins d'un jour est observé
+    pub fees_per_day_after_sat: Option<f64>,
+    pub measured_change_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportRisk {
+    pub severity: AnomalySeverity,
+    pub title: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub id: String,
+    pub period: ReportPeriod,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub financials: ReportFinancials,
+    pub best_channels: Vec<ReportChannel>,
+    pub worst_channels: Vec<ReportChannel>,
+    pub actions: Vec<ReportAction>,
+    pub risks: Vec<ReportRisk>,
+}
+
+/// Entrée de l'archive des rapports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub id: String,
+    pub period: ReportPeriod,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub revenue_sat: u64,
+    pub net_sat: i64,
+}
+
+impl From<&PerformanceReport> for ReportSummary {
+    fn from(report: &PerformanceReport) -> Self {
+        Self {
+            id: report.id.clone(),
+            period: report.period,
+            period_start: report.period_start,
+            period_end: report.period_end,
+            generated_at: report.generated_at,
+            revenue_sat: report.financials.revenue_sat,
+            net_sat: report.financials.net_sat,
+        }
+    }
+}
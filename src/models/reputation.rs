@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Critère élémentaire du score de réputation d'un pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationComponent {
+    pub name: String,
+    /// Note du critère, 0-100
+    pub score: f64,
+    pub weight: f64,
+    /// Faux si aucune donnée n'a permis d'évaluer le critère (exclu du score)
+    pub available: bool,
+    pub explanation: String,
+}
+
+/// Score de réputation d'un pair (0-100) et son détail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerReputation {
+    pub peer_pubkey: String,
+    pub alias: String,
+    pub score: f64,
+    /// Part (0-1) des poids couverts par des données observées
+    pub confidence: f64,
+    pub components: Vec<ReputationComponent>,
+    pub computed_at: DateTime<Utc>,
+}
+
+impl PeerReputation {
+    /// Explications des critères évalués, du plus pénalisant au plus favorable.
+    pub fn explanations(&self) -> Vec<String> {
+        let mut components: Vec<&ReputationComponent> =
+            self.components.iter().filter(|c| c.available).collect();
+        components.sort_by(|a, b| a.score.total_cmp(&b.score));
+        components
+            .into_iter()
+            .map(|c| format!("{} ({:.0}/100): {}", c.name, c.score, c.explanation))
+            .collect()
+    }
+}
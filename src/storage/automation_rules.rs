@@ -0,0 +1,290 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::automation::{AutomationExecution, AutomationRule};
+use crate::storage::timestamp;
+
+/// Stockage SQLite des règles d'automatisation et de leurs exécutions
+#[derive(Clone)]
+pub struct AutomationRuleStore {
+    db: SqlitePool,
+}
+
+impl AutomationRuleStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée les tables des règles et des exécutions
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS automation_rules (
+                id TEXT PRIMARY KEY,
+                priority INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                payload TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS automation_executions (
+                id TEXT PRIMARY KEY,
+                rule_id TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                payload TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_automation_executions_rule ON automation_executions (rule_id, started_at)",
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Tables des règles d'automatisation créées");
+        Ok(())
+    }
+
+    /// Crée ou remplace une règle
+    pub async fn save(&self, rule: &AutomationRule) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO automation_rules (id, priority, created_at, payload) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(&rule.id)
+        .bind(rule.priority as i64)
+        .bind(timestamp(rule.created_at))
+        .bind(serde_json::to_string(rule)?)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<AutomationRule>> {
+        let payload: Option<String> =
+            sqlx::query_scalar("SELECT payload FROM automation_rules WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()?)
+    }
+
+    /// Règles de la plus prioritaire à la moins prioritaire
+    pub async fn list(&self) -> Result<Vec<AutomationRule>> {
+        let rows = sqlx::query(
+            "SELECT payload FROM automation_rules ORDER BY priority DESC, created_at ASC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+
+    /// Supprime une règle et l'historique de ses exécutions
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("DELETE FROM automation_executions WHERE rule_id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = sqlx::query("DELETE FROM automation_rules WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(deleted > 0)
+    }
+
+    /// Met à jour last_triggered, trigger_count et success_rate sans écraser une
+    /// modification concurrente de la règle
+    /// Marque le début d'un déclenchement, pour que le délai minimal entre deux
+    /// déclenchements coure pendant l'exécution de ses actions.
+    pub async fn mark_triggered(&self, id: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            "UPDATE automation_rules SET payload = json_set(payload, '$.last_triggered', ?2) WHERE id = ?1",
+        )
+        .bind(id)
+        .bind(timestamp(at))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_trigger(&self, id: &str, at: DateTime<Utc>, success: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE automation_rules SET payload = json_set(payload,
+                '$.last_triggered', ?2,
+                '$.trigger_count', json_extract(payload, '$.trigger_count') + 1,
+                '$.success_rate',
+                    (json_extract(payload, '$.success_rate') * json_extract(payload, '$.trigger_count') + ?3)
+                    / (json_extract(payload, '$.trigger_count') + 1.0))
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id)
+        .bind(timestamp(at))
+        .bind(if success { 1.0 } else { 0.0 })
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_execution(&self, execution: &AutomationExecution) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO automation_executions (id, rule_id, started_at, payload) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(&execution.id)
+        .bind(&execution.rule_id)
+        .bind(timestamp(execution.started_at))
+        .bind(serde_json::to_string(execution)?)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Exécutions d'une règle, les plus récentes en premier
+    pub async fn executions(&self, rule_id: &str, limit: u32) -> Result<Vec<AutomationExecution>> {
+        let rows = sqlx::query(
+            "SELECT payload FROM automation_executions WHERE rule_id = ?1 ORDER BY started_at DESC LIMIT ?2",
+        )
+        .bind(rule_id)
+        .bind(limit as i64)
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+
+    /// Nombre d'exécutions, toutes règles confondues, depuis `since`
+    pub async fn count_executions_since(&self, since: DateTime<Utc>) -> Result<u64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM automation_executions WHERE started_at >= ?1")
+                .bind(timestamp(since))
+                .fetch_one(&self.db)
+                .await?;
+        Ok(count as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::automation::{
+        AutomationAction, AutomationActionType, AutomationCondition, AutomationExecutionType,
+        ComparisonOperator, ConditionType, ExecutionResults, ExecutionStatus,
+    };
+    use chrono::TimeZone;
+
+    fn rule(id: &str, priority: u32) -> AutomationRule {
+        AutomationRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            conditions: vec![AutomationCondition {
+                condition_type: ConditionType::TimeOfDay,
+                operator: ComparisonOperator::Between,
+                value: serde_json::json!([22, 6]),
+                weight: 1.0,
+            }],
+            actions: vec![AutomationAction {
+                action_type: AutomationActionType::LogEvent,
+                parameters: serde_json::json!({ "message": "nuit" }),
+                delay_seconds: None,
+                retry_count: 0,
+                rollback_action: None,
+            }],
+            enabled: true,
+            priority,
+            created_at: Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap(),
+            last_triggered: None,
+            trigger_count: 0,
+            success_rate: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rules_ordered_and_trigger_stats() {
+        let store = AutomationRuleStore::new(SqlitePool::connect("sqlite::memory:").await.unwrap());
+        store.create_tables().await.unwrap();
+        store.save(&rule("low", 1)).await.unwrap();
+        store.save(&rule("high", 9)).await.unwrap();
+
+        let ids: Vec<String> = store
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["high", "low"]);
+
+        let at = Utc.with_ymd_and_hms(2026, 10, 19, 23, 0, 0).unwrap();
+        for success in [true, true, false, true] {
+            store.record_trigger("high", at, success).await.unwrap();
+        }
+        let high = store.get("high").await.unwrap().unwrap();
+        assert_eq!(high.trigger_count, 4);
+        assert_eq!(high.last_triggered, Some(at));
+        assert!((high.success_rate - 0.75).abs() < 1e-9);
+
+        assert!(store.delete("low").await.unwrap());
+        assert!(!store.delete("low").await.unwrap());
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_progress_execution_counted_once() {
+        let store = AutomationRuleStore::new(SqlitePool::connect("sqlite::memory:").await.unwrap());
+        store.create_tables().await.unwrap();
+        let started_at = Utc.with_ymd_and_hms(2026, 10, 19, 23, 0, 0).unwrap();
+        let mut execution = AutomationExecution {
+            id: "exec".to_string(),
+            rule_id: "high".to_string(),
+            recommendation_id: None,
+            execution_type: AutomationExecutionType::Conditional,
+            status: ExecutionStatus::InProgress,
+            started_at,
+            completed_at: None,
+            results: ExecutionResults {
+                success: false,
+                actions_taken: vec![],
+                performance_impact: None,
+                cost: 0,
+                time_taken_ms: 0,
+            },
+            error_message: None,
+            rollback_executed: false,
+        };
+        store.record_execution(&execution).await.unwrap();
+        let day = started_at
+            .date_naive()
+            .and_time(Default::default())
+            .and_utc();
+        // Une exécution différée compte dès son lancement
+        assert_eq!(store.count_executions_since(day).await.unwrap(), 1);
+
+        execution.status = ExecutionStatus::Completed;
+        execution.completed_at = Some(started_at);
+        store.record_execution(&execution).await.unwrap();
+        assert_eq!(store.count_executions_since(day).await.unwrap(), 1);
+        let stored = store.executions("high", 10).await.unwrap();
+        assert!(matches!(stored[0].status, ExecutionStatus::Completed));
+    }
+}
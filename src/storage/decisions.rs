@@ -0,0 +1,70 @@
+use anyhow::Result;
+use chrono::SecondsFormat;
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::feedback::OperatorDecision;
+
+/// Persistance SQLite des décisions de l'opérateur sur les recommandations
+#[derive(Clone)]
+pub struct DecisionStore {
+    db: SqlitePool,
+}
+
+impl DecisionStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée la table des décisions
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS operator_decisions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recommendation_id TEXT NOT NULL,
+                approved INTEGER NOT NULL,
+                payload TEXT NOT NULL,
+                decided_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des décisions opérateur créée");
+        Ok(())
+    }
+
+    pub async fn record(&self, decision: &OperatorDecision) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO operator_decisions (recommendation_id, approved, payload, decided_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(&decision.recommendation_id)
+        .bind(decision.approved)
+        .bind(serde_json::to_string(decision)?)
+        .bind(
+            decision
+                .decided_at
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Toutes les décisions, de la plus ancienne à la plus récente
+    pub async fn list(&self) -> Result<Vec<OperatorDecision>> {
+        let rows =
+            sqlx::query("SELECT payload FROM operator_decisions ORDER BY decided_at ASC, id ASC")
+                .fetch_all(&self.db)
+                .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+}
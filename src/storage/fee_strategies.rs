@@ -0,0 +1,77 @@
+use anyhow::Result;
+use chrono::SecondsFormat;
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::fee_strategy::FeeStrategyAssignment;
+
+/// Persistance SQLite des affectations de stratégies de frais
+#[derive(Clone)]
+pub struct FeeStrategyStore {
+    db: SqlitePool,
+}
+
+impl FeeStrategyStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée la table des affectations
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS fee_strategy_assignments (
+                scope_key TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des stratégies de frais créée");
+        Ok(())
+    }
+
+    /// Crée ou remplace l'affectation de même portée
+    pub async fn upsert(&self, assignment: &FeeStrategyAssignment) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO fee_strategy_assignments (scope_key, payload, updated_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+        )
+        .bind(assignment.scope_key())
+        .bind(serde_json::to_string(assignment)?)
+        .bind(
+            assignment
+                .updated_at
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Supprime une affectation ; renvoie faux si elle n'existait pas
+    pub async fn delete(&self, scope_key: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM fee_strategy_assignments WHERE scope_key = ?1")
+            .bind(scope_key)
+            .execute(&self.db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Toutes les affectations, par portée
+    pub async fn list(&self) -> Result<Vec<FeeStrategyAssignment>> {
+        let rows =
+            sqlx::query("SELECT payload FROM fee_strategy_assignments ORDER BY scope_key ASC")
+                .fetch_all(&self.db)
+                .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+}
@@ -0,0 +1,962 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, DurationRound, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::collections::BTreeMap;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::api::{
+    local_lightning_client::{
+        LocalChannelFeePolicy, LocalChannelInfo, LocalForwardingEvent, LocalLightningClient,
+    },
+    umbrel_integrations::FeeEstimates,
+};
+use crate::storage::timestamp;
+use crate::utils::config::SnapshotRetention;
+
+/// Profondeur de l'historique importé lors de la première synchronisation
+const INITIAL_SYNC_DAYS: i64 = 90;
+
+/// Politique de frais observée à un instant donné.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeePolicyObservation {
+    pub channel_id: String,
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u32,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Résolution d'un point de l'historique des canaux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotResolution {
+    /// Relevé brut, à l'intervalle d'échantillonnage
+    Minute,
+    Hour,
+    Day,
+}
+
+impl SnapshotResolution {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+
+    fn from_str(value: &str) -> Self {
+        match value {
+            "hour" => Self::Hour,
+            "day" => Self::Day,
+            _ => Self::Minute,
+        }
+    }
+}
+
+/// Point de l'historique d'un canal. Pour un agrégat, les soldes de `channel`
+/// sont des moyennes, la politique de frais est la dernière observée et
+/// `pending_htlcs` le maximum de la période.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelHistoryPoint {
+    pub taken_at: DateTime<Utc>,
+    pub resolution: SnapshotResolution,
+    /// Relevés bruts représentés
+    pub samples: u32,
+    pub local_balance_min: u64,
+    pub local_balance_max: u64,
+    /// Relevés où le canal était actif
+    pub active_samples: u32,
+    pub channel: LocalChannelInfo,
+}
+
+impl ChannelHistoryPoint {
+    /// Relevé brut de l'état d'un canal
+    pub fn sample(taken_at: DateTime<Utc>, channel: LocalChannelInfo) -> Self {
+        Self {
+            taken_at,
+            resolution: SnapshotResolution::Minute,
+            samples: 1,
+            local_balance_min: channel.local_balance,
+            local_balance_max: channel.local_balance,
+            active_samples: channel.active as u32,
+            channel,
+        }
+    }
+}
+
+/// Relevés actifs et relevés représentés par `points`, agrégats compris : un point
+/// horaire ou journalier pèse autant que les relevés bruts qu'il remplace.
+pub fn uptime_samples<'a>(points: impl IntoIterator<Item = &'a ChannelHistoryPoint>) -> (u64, u64) {
+    points.into_iter().fold((0, 0), |(active, total), point| {
+        (
+            active + point.active_samples.min(point.samples) as u64,
+            total + point.samples as u64,
+        )
+    })
+}
+
+/// Bilan d'un passage de sous-échantillonnage.
+#[derive(Debug, Clone, Default)]
+pub struct DownsampleReport {
+    pub hourly_points: u64,
+    pub daily_points: u64,
+    pub expired_points: u64,
+}
+
+/// Estimation de frais on-chain relevée à un instant donné (sat/vB).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEstimateSample {
+    pub observed_at: DateTime<Utc>,
+    pub fastest: u32,
+    pub half_hour: u32,
+    pub hour: u32,
+    pub economy: u32,
+}
+
+/// Frais médians annoncés sur le graphe public à un instant donné.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkFeeSample {
+    pub observed_at: DateTime<Utc>,
+    pub median_fee_rate_ppm: u32,
+    pub median_base_fee_msat: u64,
+    /// Politiques actives prises en compte
+    pub policies: u32,
+}
+
+/// Cumul des forwards enregistrés.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForwardingTotals {
+    pub forwards: u64,
+    pub fees_msat: u64,
+    pub amt_out_msat: u64,
+}
+
+/// Bilan d'une synchronisation avec LND.
+#[derive(Debug, Clone, Default)]
+pub struct ForwardingSyncReport {
+    pub events_inserted: u64,
+    pub policy_changes: u64,
+}
+
+/// Persistance SQLite de l'historique de forwarding et des changements de frais
+#[derive(Clone)]
+pub struct ForwardingStore {
+    db: SqlitePool,
+}
+
+impl ForwardingStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée les tables nécessaires à l'historique de forwarding
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS forwarding_events (
+                timestamp_ns INTEGER NOT NULL,
+                chan_id_in TEXT NOT NULL,
+                chan_id_out TEXT NOT NULL,
+                amt_in_msat INTEGER NOT NULL,
+                amt_out_msat INTEGER NOT NULL,
+                fee_msat INTEGER NOT NULL,
+                PRIMARY KEY (timestamp_ns, chan_id_in, chan_id_out, amt_out_msat)
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_forwarding_events_out ON forwarding_events(chan_id_out, timestamp_ns)",
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS fee_policy_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id TEXT NOT NULL,
+                base_fee_msat INTEGER NOT NULL,
+                fee_rate_ppm INTEGER NOT NULL,
+                observed_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS channel_snapshots (
+                taken_at TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                PRIMARY KEY (taken_at, channel_id)
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        // Bases créées avant le sous-échantillonnage : les relevés existants sont bruts
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('channel_snapshots')")
+                .fetch_all(&self.db)
+                .await?;
+        for (column, definition) in [
+            ("resolution", "TEXT NOT NULL DEFAULT 'minute'"),
+            ("samples", "INTEGER NOT NULL DEFAULT 1"),
+            ("local_balance_min", "INTEGER"),
+            ("local_balance_max", "INTEGER"),
+            ("active_samples", "INTEGER"),
+        ] {
+            if !columns.iter().any(|c| c == column) {
+                sqlx::query(&format!(
+                    "ALTER TABLE channel_snapshots ADD COLUMN {} {}",
+                    column, definition
+                ))
+                .execute(&self.db)
+                .await?;
+            }
+        }
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_channel_snapshots_channel ON channel_snapshots(channel_id, taken_at)",
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS onchain_fee_estimates (
+                observed_at TEXT PRIMARY KEY,
+                fastest INTEGER NOT NULL,
+                half_hour INTEGER NOT NULL,
+                hour INTEGER NOT NULL,
+                economy INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS network_fee_samples (
+                observed_at TEXT PRIMARY KEY,
+                median_fee_rate_ppm INTEGER NOT NULL,
+                median_base_fee_msat INTEGER NOT NULL,
+                policies INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        // Table jamais alimentée : tonic_lnd n'expose pas les événements HTLC du routeur
+        sqlx::query("DROP TABLE IF EXISTS forward_failures")
+            .execute(&self.db)
+            .await?;
+
+        info!("Tables de l'historique de forwarding créées");
+        Ok(())
+    }
+
+    /// Enregistre des forwards ; les doublons (resynchronisation) sont ignorés
+    pub async fn record_events(&self, events: &[LocalForwardingEvent]) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let mut inserted = 0;
+
+        for event in events {
+            let result = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO forwarding_events (timestamp_ns, chan_id_in, chan_id_out, amt_in_msat, amt_out_msat, fee_msat)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(event.timestamp_ns as i64)
+            .bind(&event.chan_id_in)
+            .bind(&event.chan_id_out)
+            .bind(event.amt_in_msat as i64)
+            .bind(event.amt_out_msat as i64)
+            .bind(event.fee_msat as i64)
+            .execute(&mut *tx)
+            .await?;
+            inserted += result.rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Historise les politiques de frais qui diffèrent de la dernière observation connue
+    pub async fn record_fee_policies(
+        &self,
+        policies: &[LocalChannelFeePolicy],
+        observed_at: DateTime<Utc>,
+    ) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let mut changes = 0;
+
+        for policy in policies {
+            let last = sqlx::query(
+                "SELECT base_fee_msat, fee_rate_ppm FROM fee_policy_history WHERE channel_id = ?1 ORDER BY id DESC LIMIT 1",
+            )
+            .bind(&policy.channel_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let unchanged = last
+                .map(|row| {
+                    row.get::<i64, _>("base_fee_msat") as u64 == policy.base_fee_msat
+                        && row.get::<i64, _>("fee_rate_ppm") as u32 == policy.fee_rate_ppm
+                })
+                .unwrap_or(false);
+            if unchanged {
+                continue;
+            }
+
+            sqlx::query(
+                "INSERT INTO fee_policy_history (channel_id, base_fee_msat, fee_rate_ppm, observed_at) VALUES (?1, ?2, ?3, ?4)",
+            )
+            .bind(&policy.channel_id)
+            .bind(policy.base_fee_msat as i64)
+            .bind(policy.fee_rate_ppm as i64)
+            .bind(timestamp(observed_at))
+            .execute(&mut *tx)
+            .await?;
+            changes += 1;
+        }
+
+        tx.commit().await?;
+        Ok(changes)
+    }
+
+    /// Horodatage du forward le plus récent déjà importé
+    pub async fn latest_event_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let latest: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(timestamp_ns) FROM forwarding_events")
+                .fetch_one(&self.db)
+                .await?;
+        Ok(latest.map(|ns| Utc.timestamp_nanos(ns)))
+    }
+
+    /// Forwards sortant par `channel_id` depuis `since` : ce sont eux qui rapportent des frais
+    pub async fn outgoing_events(
+        &self,
+        channel_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<LocalForwardingEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT timestamp_ns, chan_id_in, chan_id_out, amt_in_msat, amt_out_msat, fee_msat
+            FROM forwarding_events WHERE chan_id_out = ?1 AND timestamp_ns >= ?2
+            ORDER BY timestamp_ns ASC
+            "#,
+        )
+        .bind(channel_id)
+        .bind(since.timestamp_nanos_opt().unwrap_or(0))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.iter().map(row_to_event).collect())
+    }
+
+    /// Tous les forwards dans `[start, end)`, dans l'ordre chronologique
+    pub async fn events_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<LocalForwardingEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT timestamp_ns, chan_id_in, chan_id_out, amt_in_msat, amt_out_msat, fee_msat
+            FROM forwarding_events WHERE timestamp_ns >= ?1 AND timestamp_ns < ?2
+            ORDER BY timestamp_ns ASC, chan_id_out ASC, amt_out_msat ASC
+            "#,
+        )
+        .bind(start.timestamp_nanos_opt().unwrap_or(0))
+        .bind(end.timestamp_nanos_opt().unwrap_or(i64::MAX))
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.iter().map(row_to_event).collect())
+    }
+
+    /// Forwards et frais cumulés de tout l'historique enregistré
+    pub async fn forwarding_totals(&self) -> Result<ForwardingTotals> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS forwards, COALESCE(SUM(fee_msat), 0) AS fees_msat, COALESCE(SUM(amt_out_msat), 0) AS amt_out_msat FROM forwarding_events",
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(ForwardingTotals {
+            forwards: row.get::<i64, _>("forwards") as u64,
+            fees_msat: row.get::<i64, _>("fees_msat") as u64,
+            amt_out_msat: row.get::<i64, _>("amt_out_msat") as u64,
+        })
+    }
+
+    /// Changements de politique de frais d'un canal, du plus ancien au plus récent
+    pub async fn fee_history(&self, channel_id: &str) -> Result<Vec<FeePolicyObservation>> {
+        let rows = sqlx::query(
+            "SELECT channel_id, base_fee_msat, fee_rate_ppm, observed_at FROM fee_policy_history WHERE channel_id = ?1 ORDER BY id ASC",
+        )
+        .bind(channel_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter().map(row_to_policy).collect()
+    }
+
+    /// Historique des politiques de frais de tous les canaux, regroupé par canal
+    pub async fn fee_history_by_channel(
+        &self,
+    ) -> Result<BTreeMap<String, Vec<FeePolicyObservation>>> {
+        let rows = sqlx::query(
+            "SELECT channel_id, base_fee_msat, fee_rate_ppm, observed_at FROM fee_policy_history ORDER BY id ASC",
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut history: BTreeMap<String, Vec<FeePolicyObservation>> = BTreeMap::new();
+        for row in &rows {
+            let policy = row_to_policy(row)?;
+            history
+                .entry(policy.channel_id.clone())
+                .or_default()
+                .push(policy);
+        }
+        Ok(history)
+    }
+
+    /// Reporte sur les canaux la dernière politique de frais connue
+    pub async fn apply_latest_policies(&self, channels: &mut [LocalChannelInfo]) -> Result<()> {
+        let policies = self.fee_history_by_channel().await?;
+        for channel in channels {
+            if let Some(policy) = policies.get(&channel.channel_id).and_then(|h| h.last()) {
+                channel.base_fee_msat = policy.base_fee_msat;
+                channel.fee_rate_milli_msat = policy.fee_rate_ppm as u64;
+            }
+        }
+        Ok(())
+    }
+
+    /// Photographie l'état des canaux (balances, politique) à l'instant `taken_at`
+    pub async fn record_channel_snapshots(
+        &self,
+        channels: &[LocalChannelInfo],
+        taken_at: DateTime<Utc>,
+    ) -> Result<u64> {
+        let points: Vec<ChannelHistoryPoint> = channels
+            .iter()
+            .map(|channel| ChannelHistoryPoint::sample(taken_at, channel.clone()))
+            .collect();
+
+        let mut tx = self.db.begin().await?;
+        for point in &points {
+            insert_history_point(&mut tx, point).await?;
+        }
+        tx.commit().await?;
+        Ok(points.len() as u64)
+    }
+
+    /// Relève l'état courant des canaux auprès de LND
+    pub async fn snapshot_channels(&self, client: &Mutex<LocalLightningClient>) -> Result<u64> {
+        let channels = {
+            let mut client = client.lock().await;
+            // Les canaux fictifs du mode mock ne doivent pas polluer l'historique
+            if !client.is_connected() {
+                return Ok(0);
+            }
+            client.list_local_channels().await?
+        };
+        self.record_channel_snapshots(&channels, Utc::now()).await
+    }
+
+    /// Historique d'un canal dans `[start, end)`, toutes résolutions confondues
+    pub async fn channel_history(
+        &self,
+        channel_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ChannelHistoryPoint>> {
+        let rows = sqlx::query(
+            "SELECT * FROM channel_snapshots WHERE channel_id = ?1 AND taken_at >= ?2 AND taken_at < ?3 ORDER BY taken_at ASC",
+        )
+        .bind(channel_id)
+        .bind(timestamp(start))
+        .bind(timestamp(end))
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter().map(row_to_history_point).collect()
+    }
+
+    /// Agrège les relevés bruts anciens par heure, les heures anciennes par jour,
+    /// et supprime les jours au-delà de la conservation. Seules les périodes
+    /// entièrement révolues sont agrégées.
+    pub async fn downsample_channel_snapshots(
+        &self,
+        retention: &SnapshotRetention,
+        now: DateTime<Utc>,
+    ) -> Result<DownsampleReport> {
+        let hour_cutoff = (now - retention.minute).duration_trunc(Duration::hours(1))?;
+        let day_cutoff = (now - retention.hour).duration_trunc(Duration::days(1))?;
+
+        let report = DownsampleReport {
+            hourly_points: self
+                .aggregate_snapshots(SnapshotResolution::Minute, hour_cutoff)
+                .await?,
+            daily_points: self
+                .aggregate_snapshots(SnapshotResolution::Hour, day_cutoff)
+                .await?,
+            expired_points: sqlx::query(
+                "DELETE FROM channel_snapshots WHERE resolution = ?1 AND taken_at < ?2",
+            )
+            .bind(SnapshotResolution::Day.as_str())
+            .bind(timestamp(now - retention.day))
+            .execute(&self.db)
+            .await?
+            .rows_affected(),
+        };
+
+        info!(
+            "Channel history downsampled: {} hourly points, {} daily points, {} expired",
+            report.hourly_points, report.daily_points, report.expired_points
+        );
+        Ok(report)
+    }
+
+    /// Remplace les points de `resolution` antérieurs à `cutoff` par leurs agrégats
+    /// à la résolution suivante
+    async fn aggregate_snapshots(
+        &self,
+        resolution: SnapshotResolution,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64> {
+        let (target, bucket) = match resolution {
+            SnapshotResolution::Minute => (SnapshotResolution::Hour, Duration::hours(1)),
+            SnapshotResolution::Hour => (SnapshotResolution::Day, Duration::days(1)),
+            SnapshotResolution::Day => return Ok(0),
+        };
+        let cutoff = timestamp(cutoff);
+
+        let mut tx = self.db.begin().await?;
+        let rows = sqlx::query(
+            "SELECT * FROM channel_snapshots WHERE resolution = ?1 AND taken_at < ?2 ORDER BY taken_at ASC",
+        )
+        .bind(resolution.as_str())
+        .bind(&cutoff)
+        .fetch_all(&mut *tx)
+        .await?;
+        let points = rows
+            .iter()
+            .map(row_to_history_point)
+            .collect::<Result<Vec<_>>>()?;
+        let aggregates = aggregate_history(&points, target, bucket)?;
+
+        sqlx::query("DELETE FROM channel_snapshots WHERE resolution = ?1 AND taken_at < ?2")
+            .bind(resolution.as_str())
+            .bind(&cutoff)
+            .execute(&mut *tx)
+            .await?;
+        for aggregate in &aggregates {
+            insert_history_point(&mut tx, aggregate).await?;
+        }
+        tx.commit().await?;
+        Ok(aggregates.len() as u64)
+    }
+
+    /// Points de l'historique de tous les canaux dans `[start, end)`, dans l'ordre
+    /// chronologique ; les agrégats portent le nombre de relevés qu'ils représentent.
+    pub async fn channel_snapshots_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ChannelHistoryPoint>> {
+        let rows = sqlx::query(
+            "SELECT * FROM channel_snapshots WHERE taken_at >= ?1 AND taken_at < ?2 ORDER BY taken_at ASC, channel_id ASC",
+        )
+        .bind(timestamp(start))
+        .bind(timestamp(end))
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter().map(row_to_history_point).collect()
+    }
+
+    /// Enregistre un relevé des estimations de frais du mempool
+    pub async fn record_fee_estimates(
+        &self,
+        estimates: &FeeEstimates,
+        observed_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO onchain_fee_estimates (observed_at, fastest, half_hour, hour, economy)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(timestamp(observed_at))
+        .bind(estimates.fastest as i64)
+        .bind(estimates.half_hour as i64)
+        .bind(estimates.hour as i64)
+        .bind(estimates.economy as i64)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Relevés de frais on-chain depuis `since`, du plus ancien au plus récent
+    pub async fn fee_estimates_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FeeEstimateSample>> {
+        let rows = sqlx::query(
+            "SELECT observed_at, fastest, half_hour, hour, economy FROM onchain_fee_estimates WHERE observed_at >= ?1 ORDER BY observed_at ASC",
+        )
+        .bind(timestamp(since))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut samples = Vec::with_capacity(rows.len());
+        for row in rows {
+            let observed_at: String = row.get("observed_at");
+            samples.push(FeeEstimateSample {
+                observed_at: DateTime::parse_from_rfc3339(&observed_at)?.with_timezone(&Utc),
+                fastest: row.get::<i64, _>("fastest") as u32,
+                half_hour: row.get::<i64, _>("half_hour") as u32,
+                hour: row.get::<i64, _>("hour") as u32,
+                economy: row.get::<i64, _>("economy") as u32,
+            });
+        }
+        Ok(samples)
+    }
+
+    /// Enregistre un relevé des frais médians du réseau
+    pub async fn record_network_fees(&self, sample: &NetworkFeeSample) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO network_fee_samples (observed_at, median_fee_rate_ppm, median_base_fee_msat, policies)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(timestamp(sample.observed_at))
+        .bind(sample.median_fee_rate_ppm as i64)
+        .bind(sample.median_base_fee_msat as i64)
+        .bind(sample.policies as i64)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Relevés des frais médians du réseau depuis `since`, du plus ancien au plus récent
+    pub async fn network_fees_since(&self, since: DateTime<Utc>) -> Result<Vec<NetworkFeeSample>> {
+        let rows = sqlx::query(
+            "SELECT observed_at, median_fee_rate_ppm, median_base_fee_msat, policies FROM network_fee_samples WHERE observed_at >= ?1 ORDER BY observed_at ASC",
+        )
+        .bind(timestamp(since))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut samples = Vec::with_capacity(rows.len());
+        for row in rows {
+            let observed_at: String = row.get("observed_at");
+            samples.push(NetworkFeeSample {
+                observed_at: DateTime::parse_from_rfc3339(&observed_at)?.with_timezone(&Utc),
+                median_fee_rate_ppm: row.get::<i64, _>("median_fee_rate_ppm") as u32,
+                median_base_fee_msat: row.get::<i64, _>("median_base_fee_msat") as u64,
+                policies: row.get::<i64, _>("policies") as u32,
+            });
+        }
+        Ok(samples)
+    }
+
+    /// Importe les nouveaux forwards et la politique de frais courante depuis LND
+    pub async fn sync(&self, client: &Mutex<LocalLightningClient>) -> Result<ForwardingSyncReport> {
+        let now = Utc::now();
+        let start = self
+            .latest_event_at()
+            .await?
+            .unwrap_or_else(|| now - chrono::Duration::days(INITIAL_SYNC_DAYS));
+
+        let (events, policies) = {
+            let mut client = client.lock().await;
+            let events = client
+                .forwarding_history(start.timestamp().max(0) as u64, now.timestamp() as u64)
+                .await?;
+            let policies = client.fee_report().await?;
+            (events, policies)
+        };
+
+        let report = ForwardingSyncReport {
+            events_inserted: self.record_events(&events).await?,
+            policy_changes: self.record_fee_policies(&policies, now).await?,
+        };
+
+        info!(
+            "Forwarding history synced: {} new events, {} fee policy changes",
+            report.events_inserted, report.policy_changes
+        );
+        Ok(report)
+    }
+}
+
+fn row_to_event(row: &SqliteRow) -> LocalForwardingEvent {
+    LocalForwardingEvent {
+        timestamp_ns: row.get::<i64, _>("timestamp_ns") as u64,
+        chan_id_in: row.get("chan_id_in"),
+        chan_id_out: row.get("chan_id_out"),
+        amt_in_msat: row.get::<i64, _>("amt_in_msat") as u64,
+        amt_out_msat: row.get::<i64, _>("amt_out_msat") as u64,
+        fee_msat: row.get::<i64, _>("fee_msat") as u64,
+    }
+}
+
+async fn insert_history_point(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    point: &ChannelHistoryPoint,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT OR REPLACE INTO channel_snapshots
+            (taken_at, channel_id, payload, resolution, samples, local_balance_min, local_balance_max, active_samples)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+    )
+    .bind(timestamp(point.taken_at))
+    .bind(&point.channel.channel_id)
+    .bind(serde_json::to_string(&point.channel)?)
+    .bind(point.resolution.as_str())
+    .bind(point.samples as i64)
+    .bind(point.local_balance_min as i64)
+    .bind(point.local_balance_max as i64)
+    .bind(point.active_samples as i64)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+fn row_to_history_point(row: &SqliteRow) -> Result<ChannelHistoryPoint> {
+    let taken_at: String = row.get("taken_at");
+    let channel: LocalChannelInfo = serde_json::from_str(&row.get::<String, _>("payload"))?;
+    // Relevés antérieurs au sous-échantillonnage : extrema et activité tirés du relevé
+    Ok(ChannelHistoryPoint {
+        taken_at: DateTime::parse_from_rfc3339(&taken_at)?.with_timezone(&Utc),
+        resolution: SnapshotResolution::from_str(&row.get::<String, _>("resolution")),
+        samples: row.get::<i64, _>("samples").max(1) as u32,
+        local_balance_min: row
+            .get::<Option<i64>, _>("local_balance_min")
+            .map_or(channel.local_balance, |v| v as u64),
+        local_balance_max: row
+            .get::<Option<i64>, _>("local_balance_max")
+            .map_or(channel.local_balance, |v| v as u64),
+        active_samples: row
+            .get::<Option<i64>, _>("active_samples")
+            .map_or(channel.active as u32, |v| v as u32),
+        channel,
+    })
+}
+
+/// Regroupe les points par canal et par période `bucket` : soldes moyens pondérés
+/// par le nombre de relevés, extrema, dernière politique observée et pic de HTLC.
+fn aggregate_history(
+    points: &[ChannelHistoryPoint],
+    resolution: SnapshotResolution,
+    bucket: Duration,
+) -> Result<Vec<ChannelHistoryPoint>> {
+    let mut groups: BTreeMap<(String, DateTime<Utc>), Vec<&ChannelHistoryPoint>> = BTreeMap::new();
+    for point in points {
+        let start = point.taken_at.duration_trunc(bucket)?;
+        groups
+            .entry((point.channel.channel_id.clone(), start))
+            .or_default()
+            .push(point);
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|((_, taken_at), group)| {
+            let samples: u32 = group.iter().map(|p| p.samples).sum();
+            let weighted_mean = |balance: fn(&LocalChannelInfo) -> u64| {
+                (group
+                    .iter()
+                    .map(|p| balance(&p.channel) as u128 * p.samples as u128)
+                    .sum::<u128>()
+                    / samples.max(1) as u128) as u64
+            };
+            let active_samples = group.iter().map(|p| p.active_samples).sum::<u32>();
+            // Points triés chronologiquement : le dernier porte la politique la plus récente
+            let mut channel = group[group.len() - 1].channel.clone();
+            channel.local_balance = weighted_mean(|c| c.local_balance);
+            channel.remote_balance = weighted_mean(|c| c.remote_balance);
+            channel.pending_htlcs = group
+                .iter()
+                .map(|p| p.channel.pending_htlcs)
+                .max()
+                .unwrap_or(0);
+            channel.active = active_samples * 2 >= samples;
+
+            ChannelHistoryPoint {
+                taken_at,
+                resolution,
+                samples,
+                local_balance_min: group.iter().map(|p| p.local_balance_min).min().unwrap_or(0),
+                local_balance_max: group.iter().map(|p| p.local_balance_max).max().unwrap_or(0),
+                active_samples,
+                channel,
+            }
+        })
+        .collect())
+}
+
+fn row_to_policy(row: &SqliteRow) -> Result<FeePolicyObservation> {
+    let observed_at: String = row.get("observed_at");
+    Ok(FeePolicyObservation {
+        channel_id: row.get("channel_id"),
+        base_fee_msat: row.get::<i64, _>("base_fee_msat") as u64,
+        fee_rate_ppm: row.get::<i64, _>("fee_rate_ppm") as u32,
+        observed_at: DateTime::parse_from_rfc3339(&observed_at)?.with_timezone(&Utc),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn store() -> ForwardingStore {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = ForwardingStore::new(db);
+        store.create_tables().await.unwrap();
+        store
+    }
+
+    fn channel(local: u64, active: bool, pending_htlcs: u32) -> LocalChannelInfo {
+        LocalChannelInfo {
+            channel_point: "tx:0".to_string(),
+            peer_pubkey: "peer".to_string(),
+            active,
+            base_fee_msat: 1_000,
+            fee_rate_milli_msat: 100,
+            pending_htlcs,
+            ..test_support::channel("chan", 1_000, local)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_old_snapshots_downsampled_to_hours() {
+        let store = store().await;
+        let now = Utc.with_ymd_and_hms(2024, 6, 10, 12, 30, 0).unwrap();
+        let hour = Utc.with_ymd_and_hms(2024, 6, 7, 9, 0, 0).unwrap();
+
+        for (minute, local, active, htlcs) in
+            [(5, 100, true, 1), (25, 200, false, 4), (45, 600, true, 0)]
+        {
+            store
+                .record_channel_snapshots(
+                    &[channel(local, active, htlcs)],
+                    hour + Duration::minutes(minute),
+                )
+                .await
+                .unwrap();
+        }
+        let mut latest = channel(500, true, 0);
+        latest.fee_rate_milli_msat = 250;
+        store
+            .record_channel_snapshots(&[latest], now - Duration::minutes(1))
+            .await
+            .unwrap();
+
+        let report = store
+            .downsample_channel_snapshots(&SnapshotRetention::default(), now)
+            .await
+            .unwrap();
+        assert_eq!(report.hourly_points, 1);
+        assert_eq!(report.daily_points, 0);
+
+        let history = store
+            .channel_history("chan", now - Duration::days(7), now)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        let aggregate = &history[0];
+        assert_eq!(aggregate.taken_at, hour);
+        assert_eq!(aggregate.resolution, SnapshotResolution::Hour);
+        assert_eq!(aggregate.samples, 3);
+        assert_eq!(aggregate.channel.local_balance, 300);
+        assert_eq!(aggregate.channel.remote_balance, 700);
+        assert_eq!(
+            (aggregate.local_balance_min, aggregate.local_balance_max),
+            (100, 600)
+        );
+        assert_eq!(aggregate.active_samples, 2);
+        assert!(aggregate.channel.active);
+        assert_eq!(aggregate.channel.pending_htlcs, 4);
+        assert_eq!(history[1].resolution, SnapshotResolution::Minute);
+        assert_eq!(history[1].channel.fee_rate_milli_msat, 250);
+
+        // Les analyses voient l'agrégat avec le poids des relevés qu'il remplace
+        let snapshots = store
+            .channel_snapshots_between(now - Duration::days(7), now)
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].resolution, SnapshotResolution::Hour);
+        assert_eq!(uptime_samples(&snapshots), (3, 4));
+    }
+
+    #[tokio::test]
+    async fn test_hours_rolled_into_days_and_expired() {
+        let store = store().await;
+        let now = Utc.with_ymd_and_hms(2024, 6, 10, 12, 30, 0).unwrap();
+        let day = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+
+        for (at, local) in [
+            (day + Duration::hours(3), 200),
+            (day + Duration::hours(15) + Duration::minutes(10), 400),
+            (day + Duration::hours(15) + Duration::minutes(40), 600),
+            // Au-delà de la conservation des points journaliers
+            (now - Duration::days(800), 900),
+        ] {
+            store
+                .record_channel_snapshots(&[channel(local, true, 0)], at)
+                .await
+                .unwrap();
+        }
+
+        let report = store
+            .downsample_channel_snapshots(&SnapshotRetention::default(), now)
+            .await
+            .unwrap();
+        assert_eq!(report.hourly_points, 3);
+        assert_eq!(report.daily_points, 2);
+        assert_eq!(report.expired_points, 1);
+
+        let history = store
+            .channel_history("chan", now - Duration::days(1_000), now)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].taken_at, day);
+        assert_eq!(history[0].resolution, SnapshotResolution::Day);
+        assert_eq!(history[0].samples, 3);
+        // Moyenne pondérée : (200 + 500 × 2) / 3
+        assert_eq!(history[0].channel.local_balance, 400);
+        assert_eq!(
+            (history[0].local_balance_min, history[0].local_balance_max),
+            (200, 600)
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use tracing::info;
+
+use crate::storage::timestamp;
+use crate::utils::ml_engine::MLEngineConfig;
+
+/// Version enregistrée de la configuration du moteur ML.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelVersion {
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub note: String,
+    pub config: MLEngineConfig,
+    pub active: bool,
+}
+
+/// Persistance SQLite des versions de configuration du moteur ML et de leurs activations
+#[derive(Clone)]
+pub struct MLModelStore {
+    db: SqlitePool,
+}
+
+fn row_to_version(row: &SqliteRow) -> Result<ModelVersion> {
+    let created_at: String = row.get("created_at");
+    Ok(ModelVersion {
+        version: row.get("version"),
+        created_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+        note: row.get("note"),
+        config: serde_json::from_str(&row.get::<String, _>("config"))?,
+        active: row.get::<i64, _>("active") != 0,
+    })
+}
+
+impl MLModelStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée les tables des versions et de l'historique des activations
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ml_model_versions (
+                version INTEGER PRIMARY KEY AUTOINCREMENT,
+                created_at TEXT NOT NULL,
+                note TEXT NOT NULL,
+                config TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS ml_model_activations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                version INTEGER NOT NULL,
+                activated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Tables des versions du modèle ML créées");
+        Ok(())
+    }
+
+    /// Enregistre une nouvelle version, inactive
+    pub async fn create(
+        &self,
+        config: &MLEngineConfig,
+        note: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<ModelVersion> {
+        let result = sqlx::query(
+            "INSERT INTO ml_model_versions (created_at, note, config, active) VALUES (?1, ?2, ?3, 0)",
+        )
+        .bind(timestamp(created_at))
+        .bind(note)
+        .bind(serde_json::to_string(config)?)
+        .execute(&self.db)
+        .await?;
+
+        Ok(ModelVersion {
+            version: result.last_insert_rowid(),
+            created_at,
+            note: note.to_string(),
+            config: config.clone(),
+            active: false,
+        })
+    }
+
+    pub async fn get(&self, version: i64) -> Result<Option<ModelVersion>> {
+        let row = sqlx::query("SELECT * FROM ml_model_versions WHERE version = ?1")
+            .bind(version)
+            .fetch_optional(&self.db)
+            .await?;
+        row.as_ref().map(row_to_version).transpose()
+    }
+
+    /// Toutes les versions, de la plus récente à la plus ancienne
+    pub async fn list(&self) -> Result<Vec<ModelVersion>> {
+        let rows = sqlx::query("SELECT * FROM ml_model_versions ORDER BY version DESC")
+            .fetch_all(&self.db)
+            .await?;
+        rows.iter().map(row_to_version).collect()
+    }
+
+    pub async fn active(&self) -> Result<Option<ModelVersion>> {
+        let row = sqlx::query("SELECT * FROM ml_model_versions WHERE active = 1 LIMIT 1")
+            .fetch_optional(&self.db)
+            .await?;
+        row.as_ref().map(row_to_version).transpose()
+    }
+
+    /// Active une version (une seule à la fois) ; renvoie faux si elle n'existe pas
+    pub async fn activate(&self, version: i64, at: DateTime<Utc>) -> Result<bool> {
+        let mut tx = self.db.begin().await?;
+
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT version FROM ml_model_versions WHERE version = ?1")
+                .bind(version)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE ml_model_versions SET active = (version = ?1)")
+            .bind(version)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO ml_model_activations (version, activated_at) VALUES (?1, ?2)")
+            .bind(version)
+            .bind(timestamp(at))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Version active avant l'activation courante, cible d'un retour arrière
+    pub async fn previously_active(&self) -> Result<Option<i64>> {
+        let versions: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM ml_model_activations ORDER BY id DESC LIMIT 2")
+                .fetch_all(&self.db)
+                .await?;
+        Ok(versions.get(1).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ml_engine::MLEngine;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_versions_activate_and_roll_back() {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = MLModelStore::new(db);
+        store.create_tables().await.unwrap();
+        let now = Utc::now();
+
+        let mut config = MLEngine::new().config;
+        let v1 = store.create(&config, "initiale", now).await.unwrap();
+        config.max_recommendations = 5;
+        let v2 = store.create(&config, "plus large", now).await.unwrap();
+        assert!(store.active().await.unwrap().is_none());
+
+        assert!(store.activate(v1.version, now).await.unwrap());
+        assert!(store.activate(v2.version, now).await.unwrap());
+        assert!(!store.activate(99, now).await.unwrap());
+
+        let active = store.active().await.unwrap().unwrap();
+        assert_eq!(active.version, v2.version);
+        assert_eq!(active.config.max_recommendations, 5);
+        assert_eq!(store.previously_active().await.unwrap(), Some(v1.version));
+        assert_eq!(store.list().await.unwrap().len(), 2);
+    }
+}
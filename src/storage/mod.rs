@@ -0,0 +1,17 @@
+pub mod automation_rules;
+pub mod decisions;
+pub mod fee_strategies;
+pub mod forwarding;
+pub mod ml_models;
+pub mod peer_reputation;
+pub mod prices;
+pub mod recommendations;
+pub mod reports;
+pub mod settings;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Horodatage RFC 3339 à la milliseconde, comparable en texte dans SQLite
+pub fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
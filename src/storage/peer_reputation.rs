@@ -0,0 +1,283 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::reputation::PeerReputation;
+use crate::storage::timestamp;
+
+/// Politique de frais annoncée par un pair sur un canal partagé avec nous.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerPolicyObservation {
+    pub peer_pubkey: String,
+    pub channel_id: String,
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u64,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Persistance SQLite des scores de réputation des pairs et de leurs politiques de frais
+#[derive(Clone)]
+pub struct PeerReputationStore {
+    db: SqlitePool,
+}
+
+impl PeerReputationStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée les tables des scores et de l'historique des politiques des pairs
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS peer_reputation (
+                peer_pubkey TEXT NOT NULL,
+                computed_at TEXT NOT NULL,
+                score REAL NOT NULL,
+                confidence REAL NOT NULL,
+                payload TEXT NOT NULL,
+                PRIMARY KEY (peer_pubkey, computed_at)
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS peer_policy_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                peer_pubkey TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                base_fee_msat INTEGER NOT NULL,
+                fee_rate_ppm INTEGER NOT NULL,
+                observed_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Tables de réputation des pairs créées");
+        Ok(())
+    }
+
+    /// Enregistre un score calculé
+    pub async fn record(&self, reputation: &PeerReputation) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO peer_reputation (peer_pubkey, computed_at, score, confidence, payload)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(&reputation.peer_pubkey)
+        .bind(timestamp(reputation.computed_at))
+        .bind(reputation.score)
+        .bind(reputation.confidence)
+        .bind(serde_json::to_string(reputation)?)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Dernier score connu d'un pair
+    pub async fn latest(&self, peer_pubkey: &str) -> Result<Option<PeerReputation>> {
+        let row = sqlx::query(
+            "SELECT payload FROM peer_reputation WHERE peer_pubkey = ?1 ORDER BY computed_at DESC LIMIT 1",
+        )
+        .bind(peer_pubkey)
+        .fetch_optional(&self.db)
+        .await?;
+
+        row.map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .transpose()
+    }
+
+    /// Dernier score de chaque pair, du moins bien noté au mieux noté
+    pub async fn latest_all(&self) -> Result<Vec<PeerReputation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT r.payload FROM peer_reputation r
+            JOIN (SELECT peer_pubkey, MAX(computed_at) AS computed_at FROM peer_reputation GROUP BY peer_pubkey) latest
+            ON r.peer_pubkey = latest.peer_pubkey AND r.computed_at = latest.computed_at
+            ORDER BY r.score ASC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+
+    /// Évolution du score d'un pair depuis `since`
+    pub async fn history(
+        &self,
+        peer_pubkey: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PeerReputation>> {
+        let rows = sqlx::query(
+            "SELECT payload FROM peer_reputation WHERE peer_pubkey = ?1 AND computed_at >= ?2 ORDER BY computed_at ASC",
+        )
+        .bind(peer_pubkey)
+        .bind(timestamp(since))
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .collect()
+    }
+
+    /// Enregistre les politiques des pairs ; seules les modifications sont conservées
+    pub async fn record_peer_policies(&self, policies: &[PeerPolicyObservation]) -> Result<u64> {
+        let mut tx = self.db.begin().await?;
+        let mut changes = 0;
+
+        for policy in policies {
+            let last = sqlx::query(
+                "SELECT base_fee_msat, fee_rate_ppm FROM peer_policy_history WHERE channel_id = ?1 AND peer_pubkey = ?2 ORDER BY id DESC LIMIT 1",
+            )
+            .bind(&policy.channel_id)
+            .bind(&policy.peer_pubkey)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let unchanged = last.is_some_and(|row| {
+                row.get::<i64, _>("base_fee_msat") as u64 == policy.base_fee_msat
+                    && row.get::<i64, _>("fee_rate_ppm") as u64 == policy.fee_rate_ppm
+            });
+            if unchanged {
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO peer_policy_history (peer_pubkey, channel_id, base_fee_msat, fee_rate_ppm, observed_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+            )
+            .bind(&policy.peer_pubkey)
+            .bind(&policy.channel_id)
+            .bind(policy.base_fee_msat as i64)
+            .bind(policy.fee_rate_ppm as i64)
+            .bind(timestamp(policy.observed_at))
+            .execute(&mut *tx)
+            .await?;
+            changes += 1;
+        }
+
+        tx.commit().await?;
+        Ok(changes)
+    }
+
+    /// Politiques observées pour un pair depuis `since`, dans l'ordre chronologique
+    pub async fn peer_policies_since(
+        &self,
+        peer_pubkey: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PeerPolicyObservation>> {
+        let rows = sqlx::query(
+            "SELECT peer_pubkey, channel_id, base_fee_msat, fee_rate_ppm, observed_at FROM peer_policy_history WHERE peer_pubkey = ?1 AND observed_at >= ?2 ORDER BY id ASC",
+        )
+        .bind(peer_pubkey)
+        .bind(timestamp(since))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut policies = Vec::with_capacity(rows.len());
+        for row in rows {
+            let observed_at: String = row.get("observed_at");
+            policies.push(PeerPolicyObservation {
+                peer_pubkey: row.get("peer_pubkey"),
+                channel_id: row.get("channel_id"),
+                base_fee_msat: row.get::<i64, _>("base_fee_msat") as u64,
+                fee_rate_ppm: row.get::<i64, _>("fee_rate_ppm") as u64,
+                observed_at: DateTime::parse_from_rfc3339(&observed_at)?.with_timezone(&Utc),
+            });
+        }
+        Ok(policies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn store() -> PeerReputationStore {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = PeerReputationStore::new(db);
+        store.create_tables().await.unwrap();
+        store
+    }
+
+    fn reputation(peer: &str, score: f64, computed_at: DateTime<Utc>) -> PeerReputation {
+        PeerReputation {
+            peer_pubkey: peer.to_string(),
+            alias: String::new(),
+            score,
+            confidence: 1.0,
+            components: vec![],
+            computed_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latest_score_per_peer() {
+        let store = store().await;
+        let now = Utc::now();
+        store
+            .record(&reputation("a", 40.0, now - chrono::Duration::hours(2)))
+            .await
+            .unwrap();
+        store.record(&reputation("a", 80.0, now)).await.unwrap();
+        store.record(&reputation("b", 60.0, now)).await.unwrap();
+
+        assert_eq!(store.latest("a").await.unwrap().unwrap().score, 80.0);
+        let all = store.latest_all().await.unwrap();
+        assert_eq!(
+            all.iter().map(|r| r.score).collect::<Vec<_>>(),
+            vec![60.0, 80.0]
+        );
+        assert_eq!(
+            store
+                .history("a", now - chrono::Duration::days(1))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_peer_policies_only_record_changes() {
+        let store = store().await;
+        let now = Utc::now();
+        let policy = |ppm| PeerPolicyObservation {
+            peer_pubkey: "a".to_string(),
+            channel_id: "1".to_string(),
+            base_fee_msat: 1000,
+            fee_rate_ppm: ppm,
+            observed_at: now,
+        };
+
+        assert_eq!(store.record_peer_policies(&[policy(100)]).await.unwrap(), 1);
+        assert_eq!(store.record_peer_policies(&[policy(100)]).await.unwrap(), 0);
+        assert_eq!(store.record_peer_policies(&[policy(250)]).await.unwrap(), 1);
+        assert_eq!(
+            store
+                .peer_policies_since("a", now - chrono::Duration::days(1))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
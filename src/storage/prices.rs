@@ -0,0 +1,72 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Cache SQLite des cours journaliers du bitcoin : un cours passé ne change plus.
+#[derive(Clone)]
+pub struct PriceStore {
+    db: SqlitePool,
+}
+
+impl PriceStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée la table des cours
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS btc_daily_prices (
+                currency TEXT NOT NULL,
+                day TEXT NOT NULL,
+                price REAL NOT NULL,
+                PRIMARY KEY (currency, day)
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des cours du bitcoin créée");
+        Ok(())
+    }
+
+    pub async fn record(&self, currency: &str, day: NaiveDate, price: f64) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO btc_daily_prices (currency, day, price) VALUES (?1, ?2, ?3)",
+        )
+        .bind(currency)
+        .bind(day.to_string())
+        .bind(price)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Cours connus entre `start` et `end` inclus
+    pub async fn prices_between(
+        &self,
+        currency: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<HashMap<NaiveDate, f64>> {
+        let rows = sqlx::query(
+            "SELECT day, price FROM btc_daily_prices WHERE currency = ?1 AND day >= ?2 AND day <= ?3",
+        )
+        .bind(currency)
+        .bind(start.to_string())
+        .bind(end.to_string())
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let day = row.get::<String, _>("day").parse::<NaiveDate>()?;
+                Ok((day, row.get::<f64, _>("price")))
+            })
+            .collect()
+    }
+}
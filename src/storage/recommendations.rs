@@ -0,0 +1,702 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use tracing::info;
+
+use crate::models::recommendation::{Recommendation, RecommendationStatus, StatusTransition};
+use crate::storage::timestamp;
+
+/// Acteur enregistré pour les transitions déclenchées par l'application elle-même
+pub const SYSTEM_ACTOR: &str = "system";
+/// Délai après une décision (refus, exécution, échec) avant qu'une source puisse
+/// reproposer la même recommandation
+pub const DECISION_COOLDOWN_DAYS: i64 = 7;
+
+/// Persistance SQLite des recommandations agrégées et de leur cycle de vie
+#[derive(Clone)]
+pub struct RecommendationStore {
+    db: SqlitePool,
+}
+
+fn parse_timestamp(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Reconstruit une recommandation : le payload fige le contenu, les colonnes portent l'état.
+fn row_to_recommendation(row: &SqliteRow) -> Result<Recommendation> {
+    let mut recommendation: Recommendation =
+        serde_json::from_str(&row.get::<String, _>("payload"))?;
+    let status: String = row.get("status");
+    recommendation.status = RecommendationStatus::parse(&status)
+        .ok_or_else(|| anyhow!("Statut de recommandation inconnu: {}", status))?;
+    recommendation.expires_at = parse_timestamp(row.get("expires_at"));
+    recommendation.snoozed_until = parse_timestamp(row.get("snoozed_until"));
+    Ok(recommendation)
+}
+
+impl RecommendationStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée les tables nécessaires au stockage des recommandations
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS recommendations (
+                id TEXT PRIMARY KEY,
+                action_type TEXT NOT NULL,
+                priority TEXT NOT NULL,
+                source TEXT NOT NULL,
+                expected_roi_impact REAL NOT NULL,
+                payload TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                fetched_at TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                expires_at TEXT,
+                snoozed_until TEXT,
+                updated_at TEXT
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        // Bases créées avant l'introduction du cycle de vie
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('recommendations')")
+                .fetch_all(&self.db)
+                .await?;
+        for (column, definition) in [
+            ("status", "TEXT NOT NULL DEFAULT 'pending'"),
+            ("expires_at", "TEXT"),
+            ("snoozed_until", "TEXT"),
+            ("updated_at", "TEXT"),
+        ] {
+            if !columns.iter().any(|c| c == column) {
+                sqlx::query(&format!(
+                    "ALTER TABLE recommendations ADD COLUMN {} {}",
+                    column, definition
+                ))
+                .execute(&self.db)
+                .await?;
+            }
+        }
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS recommendation_transitions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recommendation_id TEXT NOT NULL,
+                from_status TEXT NOT NULL,
+                to_status TEXT NOT NULL,
+                at TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                reason TEXT
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_recommendation_transitions_rec ON recommendation_transitions(recommendation_id)",
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des recommandations créée");
+        Ok(())
+    }
+
+    /// Enregistre (ou rafraîchit) une liste de recommandations normalisées.
+    ///
+    /// Une recommandation déjà connue conserve son état : une suggestion refusée ou
+    /// exécutée ne redevient pas `Pending` parce qu'une source la propose à nouveau
+    /// dans la foulée. Seules les recommandations en attente de décision voient leur
+    /// contenu rafraîchi, et celles en `Pending` leur échéance repoussée. Les recommandations expirées sont rouvertes aussitôt, les autres
+    /// états terminaux une fois passé `DECISION_COOLDOWN_DAYS` après la décision.
+    pub async fn save_all(&self, recommendations: &[Recommendation], ttl: Duration) -> Result<()> {
+        self.save_all_at(recommendations, ttl, Utc::now()).await
+    }
+
+    pub async fn save_all_at(
+        &self,
+        recommendations: &[Recommendation],
+        ttl: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+
+        for recommendation in recommendations {
+            let existing = sqlx::query(
+                "SELECT status, COALESCE(updated_at, created_at) AS decided_at FROM recommendations WHERE id = ?1",
+            )
+            .bind(&recommendation.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let existing = existing.map(|row| {
+                (
+                    RecommendationStatus::parse(&row.get::<String, _>("status")),
+                    parse_timestamp(row.get("decided_at")),
+                )
+            });
+
+            let payload = serde_json::to_string(recommendation)?;
+
+            let reopen_reason = match &existing {
+                Some((Some(RecommendationStatus::Expired), _)) => {
+                    Some("Proposée à nouveau par une source")
+                }
+                Some((Some(status), Some(decided_at)))
+                    if status.is_terminal()
+                        && now - *decided_at >= Duration::days(DECISION_COOLDOWN_DAYS) =>
+                {
+                    Some("Proposée à nouveau après le délai de réexamen")
+                }
+                _ => None,
+            };
+
+            match (existing, reopen_reason) {
+                (None, _) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO recommendations (id, action_type, priority, source, expected_roi_impact, payload, created_at, fetched_at, status, expires_at, updated_at)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                        "#,
+                    )
+                    .bind(&recommendation.id)
+                    .bind(format!("{:?}", recommendation.action_type))
+                    .bind(format!("{:?}", recommendation.priority))
+                    .bind(recommendation.source.as_str())
+                    .bind(recommendation.expected_roi_impact)
+                    .bind(payload)
+                    .bind(timestamp(recommendation.created_at))
+                    .bind(timestamp(recommendation.fetched_at()))
+                    .bind(RecommendationStatus::Pending.as_str())
+                    .bind(timestamp(now + ttl))
+                    .bind(timestamp(now))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                (Some((Some(from), _)), Some(reason)) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE recommendations SET priority = ?2, expected_roi_impact = ?3, payload = ?4,
+                            created_at = ?5, fetched_at = ?6, status = ?7, expires_at = ?8, snoozed_until = NULL, updated_at = ?9
+                        WHERE id = ?1
+                        "#,
+                    )
+                    .bind(&recommendation.id)
+                    .bind(format!("{:?}", recommendation.priority))
+                    .bind(recommendation.expected_roi_impact)
+                    .bind(payload)
+                    .bind(timestamp(recommendation.created_at))
+                    .bind(timestamp(recommendation.fetched_at()))
+                    .bind(RecommendationStatus::Pending.as_str())
+                    .bind(timestamp(now + ttl))
+                    .bind(timestamp(now))
+                    .execute(&mut *tx)
+                    .await?;
+
+                    insert_transition(
+                        &mut tx,
+                        &StatusTransition {
+                            recommendation_id: recommendation.id.clone(),
+                            from,
+                            to: RecommendationStatus::Pending,
+                            at: now,
+                            actor: SYSTEM_ACTOR.to_string(),
+                            reason: Some(reason.to_string()),
+                        },
+                    )
+                    .await?;
+                }
+                // Encore en attente de décision : contenu rafraîchi, échéance repoussée
+                (Some((Some(status @ RecommendationStatus::Pending), _)), None)
+                | (Some((Some(status @ RecommendationStatus::Snoozed), _)), None) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE recommendations SET priority = ?2, expected_roi_impact = ?3, payload = ?4, fetched_at = ?5,
+                            expires_at = CASE WHEN ?6 THEN ?7 ELSE expires_at END
+                        WHERE id = ?1
+                        "#,
+                    )
+                    .bind(&recommendation.id)
+                    .bind(format!("{:?}", recommendation.priority))
+                    .bind(recommendation.expected_roi_impact)
+                    .bind(payload)
+                    .bind(timestamp(recommendation.fetched_at()))
+                    .bind(status == RecommendationStatus::Pending)
+                    .bind(timestamp(now + ttl))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                // Décidée, en cours ou exécutée : seul le dernier relevé est noté,
+                // les paramètres approuvés et l'historique restent inchangés
+                _ => {
+                    sqlx::query("UPDATE recommendations SET fetched_at = ?2 WHERE id = ?1")
+                        .bind(&recommendation.id)
+                        .bind(timestamp(recommendation.fetched_at()))
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Liste les recommandations rafraîchies depuis `since` dont l'état figure dans
+    /// `statuses`, les plus récentes en premier
+    pub async fn list_fetched_since(
+        &self,
+        since: DateTime<Utc>,
+        statuses: &[RecommendationStatus],
+    ) -> Result<Vec<Recommendation>> {
+        let rows = sqlx::query(
+            "SELECT payload, status, expires_at, snoozed_until FROM recommendations WHERE fetched_at >= ?1 ORDER BY fetched_at DESC, expected_roi_impact DESC",
+        )
+        .bind(timestamp(since))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut recommendations = Vec::with_capacity(rows.len());
+        for row in rows {
+            let recommendation = row_to_recommendation(&row)?;
+            if statuses.contains(&recommendation.status) {
+                recommendations.push(recommendation);
+            }
+        }
+        Ok(recommendations)
+    }
+
+    /// Nombre de recommandations dans chaque état
+    pub async fn count_by_status(&self) -> Result<Vec<(RecommendationStatus, u64)>> {
+        let rows =
+            sqlx::query("SELECT status, COUNT(*) AS count FROM recommendations GROUP BY status")
+                .fetch_all(&self.db)
+                .await?;
+        status_counts(&rows, "status")
+    }
+
+    /// Nombre de transitions vers chaque état depuis la création de la base
+    pub async fn transition_counts(&self) -> Result<Vec<(RecommendationStatus, u64)>> {
+        let rows = sqlx::query(
+            "SELECT to_status, COUNT(*) AS count FROM recommendation_transitions GROUP BY to_status",
+        )
+        .fetch_all(&self.db)
+        .await?;
+        status_counts(&rows, "to_status")
+    }
+
+    /// Récupère une recommandation par identifiant
+    pub async fn get(&self, id: &str) -> Result<Option<Recommendation>> {
+        let row = sqlx::query(
+            "SELECT payload, status, expires_at, snoozed_until FROM recommendations WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        row.as_ref().map(row_to_recommendation).transpose()
+    }
+
+    /// Applique une transition validée par la machine à états et l'historise.
+    ///
+    /// Retourne `Ok(None)` si la recommandation n'existe pas ; une transition
+    /// interdite est remontée comme erreur `TransitionError`.
+    pub async fn transition(
+        &self,
+        id: &str,
+        to: RecommendationStatus,
+        actor: &str,
+        reason: Option<String>,
+        snoozed_until: Option<DateTime<Utc>>,
+    ) -> Result<Option<(Recommendation, StatusTransition)>> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query(
+            "SELECT payload, status, expires_at, snoozed_until FROM recommendations WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let mut recommendation = match row.as_ref().map(row_to_recommendation).transpose()? {
+            Some(recommendation) => recommendation,
+            None => return Ok(None),
+        };
+
+        let trace = recommendation.transition(to, actor, reason, snoozed_until, Utc::now())?;
+        update_status(&mut tx, &recommendation, trace.at).await?;
+        insert_transition(&mut tx, &trace).await?;
+
+        tx.commit().await?;
+        Ok(Some((recommendation, trace)))
+    }
+
+    /// Applique les transitions dues à l'horloge : expiration après TTL et fin de
+    /// mise en attente. Retourne les transitions effectuées.
+    pub async fn expire_due(&self, now: DateTime<Utc>) -> Result<Vec<StatusTransition>> {
+        let mut tx = self.db.begin().await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT payload, status, expires_at, snoozed_until FROM recommendations
+            WHERE status IN ('pending', 'approved', 'snoozed')
+              AND ((expires_at IS NOT NULL AND expires_at <= ?1)
+                OR (snoozed_until IS NOT NULL AND snoozed_until <= ?1))
+            "#,
+        )
+        .bind(timestamp(now))
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut transitions = vec![];
+        for row in rows {
+            let mut recommendation = row_to_recommendation(&row)?;
+            let Some(to) = recommendation.due_transition(now) else {
+                continue;
+            };
+            let reason = match to {
+                RecommendationStatus::Expired => "Durée de validité dépassée",
+                _ => "Fin de la mise en attente",
+            };
+            let trace =
+                recommendation.transition(to, SYSTEM_ACTOR, Some(reason.to_string()), None, now)?;
+            update_status(&mut tx, &recommendation, now).await?;
+            insert_transition(&mut tx, &trace).await?;
+            transitions.push(trace);
+        }
+
+        tx.commit().await?;
+        Ok(transitions)
+    }
+
+    /// Historique complet des transitions d'une recommandation, du plus ancien au plus récent
+    pub async fn history(&self, id: &str) -> Result<Vec<StatusTransition>> {
+        let rows = sqlx::query(
+            "SELECT recommendation_id, from_status, to_status, at, actor, reason FROM recommendation_transitions WHERE recommendation_id = ?1 ORDER BY id ASC",
+        )
+        .bind(id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut history = Vec::with_capacity(rows.len());
+        for row in rows {
+            let status = |column: &str| {
+                let value: String = row.get(column);
+                RecommendationStatus::parse(&value)
+                    .ok_or_else(|| anyhow!("Statut de recommandation inconnu: {}", value))
+            };
+            history.push(StatusTransition {
+                recommendation_id: row.get("recommendation_id"),
+                from: status("from_status")?,
+                to: status("to_status")?,
+                at: parse_timestamp(row.get("at")).unwrap_or_default(),
+                actor: row.get("actor"),
+                reason: row.get("reason"),
+            });
+        }
+        Ok(history)
+    }
+
+    /// Transitions vers l'un des `statuses` survenues dans `[start, end)`, avec leur
+    /// recommandation, de la plus ancienne à la plus récente
+    pub async fn transitions_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        statuses: &[RecommendationStatus],
+    ) -> Result<Vec<(Recommendation, StatusTransition)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.recommendation_id, t.from_status, t.to_status, t.at, t.actor, t.reason,
+                   r.payload, r.status, r.expires_at, r.snoozed_until
+            FROM recommendation_transitions t
+            JOIN recommendations r ON r.id = t.recommendation_id
+            WHERE t.at >= ?1 AND t.at < ?2
+            ORDER BY t.at ASC, t.id ASC
+            "#,
+        )
+        .bind(timestamp(start))
+        .bind(timestamp(end))
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut transitions = vec![];
+        for row in rows {
+            let status = |column: &str| {
+                let value: String = row.get(column);
+                RecommendationStatus::parse(&value)
+                    .ok_or_else(|| anyhow!("Statut de recommandation inconnu: {}", value))
+            };
+            let to = status("to_status")?;
+            if !statuses.contains(&to) {
+                continue;
+            }
+            let transition = StatusTransition {
+                recommendation_id: row.get("recommendation_id"),
+                from: status("from_status")?,
+                to,
+                at: parse_timestamp(row.get("at")).unwrap_or_default(),
+                actor: row.get("actor"),
+                reason: row.get("reason"),
+            };
+            transitions.push((row_to_recommendation(&row)?, transition));
+        }
+        Ok(transitions)
+    }
+
+    /// Date du dernier rafraîchissement, toutes sources confondues
+    pub async fn last_fetched_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let last: Option<String> =
+            sqlx::query_scalar("SELECT MAX(fetched_at) FROM recommendations")
+                .fetch_one(&self.db)
+                .await?;
+
+        Ok(parse_timestamp(last))
+    }
+}
+
+async fn update_status(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    recommendation: &Recommendation,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE recommendations SET status = ?2, snoozed_until = ?3, updated_at = ?4 WHERE id = ?1",
+    )
+    .bind(&recommendation.id)
+    .bind(recommendation.status.as_str())
+    .bind(recommendation.snoozed_until.map(timestamp))
+    .bind(timestamp(at))
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+async fn insert_transition(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    transition: &StatusTransition,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO recommendation_transitions (recommendation_id, from_status, to_status, at, actor, reason)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+    )
+    .bind(&transition.recommendation_id)
+    .bind(transition.from.as_str())
+    .bind(transition.to.as_str())
+    .bind(timestamp(transition.at))
+    .bind(&transition.actor)
+    .bind(&transition.reason)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+fn status_counts(rows: &[SqliteRow], column: &str) -> Result<Vec<(RecommendationStatus, u64)>> {
+    rows.iter()
+        .map(|row| {
+            let status: String = row.get(column);
+            let status = RecommendationStatus::parse(&status)
+                .ok_or_else(|| anyhow!("Statut de recommandation inconnu: {}", status))?;
+            Ok((status, row.get::<i64, _>("count") as u64))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::{ActionType, Priority};
+    use crate::models::ml::SmartRecommendation;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn store() -> RecommendationStore {
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = RecommendationStore::new(db);
+        store.create_tables().await.unwrap();
+        store
+    }
+
+    fn recommendation(id: &str) -> Recommendation {
+        Recommendation::from(SmartRecommendation {
+            id: id.to_string(),
+            action_type: ActionType::AdjustFees,
+            priority: Priority::High,
+            expected_roi_impact: 2.0,
+            confidence: 0.9,
+            risk_score: 0.1,
+            rationale: vec![],
+            target_channels: vec!["123".to_string()],
+            parameters: serde_json::json!({}),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rejected_recommendation_is_not_reproposed() {
+        let store = store().await;
+        let since = Utc::now() - Duration::hours(1);
+        store
+            .save_all(&[recommendation("fees")], Duration::hours(24))
+            .await
+            .unwrap();
+        store
+            .transition(
+                "ml_fees",
+                RecommendationStatus::Rejected,
+                "alice",
+                Some("pair instable".to_string()),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        // La même suggestion revient de la source
+        store
+            .save_all(&[recommendation("fees")], Duration::hours(24))
+            .await
+            .unwrap();
+
+        let actionable = store
+            .list_fetched_since(since, &[RecommendationStatus::Pending])
+            .await
+            .unwrap();
+        assert!(actionable.is_empty());
+
+        let history = store.history("ml_fees").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].actor, "alice");
+        assert_eq!(history[0].to, RecommendationStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_recommendation_returns_after_cooldown() {
+        let store = store().await;
+        store
+            .save_all(&[recommendation("fees")], Duration::hours(24))
+            .await
+            .unwrap();
+        store
+            .transition(
+                "ml_fees",
+                RecommendationStatus::Rejected,
+                "alice",
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        let later = Utc::now() + Duration::days(DECISION_COOLDOWN_DAYS + 1);
+        store
+            .save_all_at(&[recommendation("fees")], Duration::hours(24), later)
+            .await
+            .unwrap();
+
+        let reopened = store.get("ml_fees").await.unwrap().unwrap();
+        assert_eq!(reopened.status, RecommendationStatus::Pending);
+        assert!(reopened.expires_at.unwrap() > later);
+        let history = store.history("ml_fees").await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].from, RecommendationStatus::Rejected);
+        assert_eq!(history[1].actor, SYSTEM_ACTOR);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_decided_content_and_extends_pending() {
+        let store = store().await;
+        store
+            .save_all(
+                &[recommendation("fees"), recommendation("close")],
+                Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        store
+            .transition(
+                "ml_fees",
+                RecommendationStatus::Approved,
+                "alice",
+                None,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        let later = Utc::now() + Duration::minutes(50);
+        let refreshed: Vec<_> = ["fees", "close"]
+            .into_iter()
+            .map(|id| {
+                let mut changed = recommendation(id);
+                changed.expected_roi_impact = 9.0;
+                changed
+            })
+            .collect();
+        store
+            .save_all_at(&refreshed, Duration::hours(1), later)
+            .await
+            .unwrap();
+
+        // Les paramètres approuvés ne changent plus avant l'exécution
+        let approved = store.get("ml_fees").await.unwrap().unwrap();
+        assert_eq!(approved.status, RecommendationStatus::Approved);
+        assert_eq!(approved.expected_roi_impact, 2.0);
+
+        let pending = store.get("ml_close").await.unwrap().unwrap();
+        assert_eq!(pending.expected_roi_impact, 9.0);
+        assert!(pending.expires_at.unwrap() > later);
+        // Toujours proposée : pas d'expiration à l'échéance initiale
+        let expired = store
+            .expire_due(Utc::now() + Duration::minutes(90))
+            .await
+            .unwrap();
+        assert!(expired.iter().all(|t| t.recommendation_id != "ml_close"));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_sweep_and_reopening() {
+        let store = store().await;
+        store
+            .save_all(&[recommendation("fees")], Duration::hours(1))
+            .await
+            .unwrap();
+
+        assert!(store.expire_due(Utc::now()).await.unwrap().is_empty());
+        let swept = store
+            .expire_due(Utc::now() + Duration::hours(2))
+            .await
+            .unwrap();
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].actor, SYSTEM_ACTOR);
+        assert_eq!(
+            store.get("ml_fees").await.unwrap().unwrap().status,
+            RecommendationStatus::Expired
+        );
+
+        // Une recommandation expirée sans décision peut être proposée à nouveau
+        store
+            .save_all(&[recommendation("fees")], Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("ml_fees").await.unwrap().unwrap().status,
+            RecommendationStatus::Pending
+        );
+        assert_eq!(store.history("ml_fees").await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::Result;
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::report::{PerformanceReport, ReportPeriod, ReportSummary};
+use crate::storage::timestamp;
+
+/// Archive SQLite des rapports de performance générés
+#[derive(Clone)]
+pub struct ReportStore {
+    db: SqlitePool,
+}
+
+impl ReportStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée la table des rapports
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS performance_reports (
+                id TEXT PRIMARY KEY,
+                period TEXT NOT NULL,
+                period_start TEXT NOT NULL,
+                period_end TEXT NOT NULL,
+                generated_at TEXT NOT NULL,
+                payload TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_performance_reports_period ON performance_reports (period, period_start)",
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des rapports de performance créée");
+        Ok(())
+    }
+
+    /// Enregistre un rapport ; une nouvelle génération remplace la précédente
+    pub async fn save(&self, report: &PerformanceReport) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO performance_reports (id, period, period_start, period_end, generated_at, payload)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&report.id)
+        .bind(report.period.as_str())
+        .bind(timestamp(report.period_start))
+        .bind(timestamp(report.period_end))
+        .bind(timestamp(report.generated_at))
+        .bind(serde_json::to_string(report)?)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<PerformanceReport>> {
+        let payload: Option<String> =
+            sqlx::query_scalar("SELECT payload FROM performance_reports WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&self.db)
+                .await?;
+        Ok(payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()?)
+    }
+
+    pub async fn exists(&self, id: &str) -> Result<bool> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM performance_reports WHERE id = ?1")
+                .bind(id)
+                .fetch_one(&self.db)
+                .await?;
+        Ok(count > 0)
+    }
+
+    /// Rapports archivés, les plus récents en premier
+    pub async fn list(
+        &self,
+        period: Option<ReportPeriod>,
+        limit: u32,
+    ) -> Result<Vec<ReportSummary>> {
+        let rows = sqlx::query(
+            "SELECT payload FROM performance_reports WHERE ?1 IS NULL OR period = ?1 ORDER BY period_start DESC, period ASC LIMIT ?2",
+        )
+        .bind(period.map(ReportPeriod::as_str))
+        .bind(limit as i64)
+        .fetch_all(&self.db)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let report: PerformanceReport =
+                    serde_json::from_str(&row.get::<String, _>("payload"))?;
+                Ok(ReportSummary::from(&report))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::report::ReportFinancials;
+    use chrono::{DateTime, Utc};
+
+    fn report(period: ReportPeriod, start: DateTime<Utc>, net_sat: i64) -> PerformanceReport {
+        let (period_start, period_end) = period.bounds(start);
+        PerformanceReport {
+            id: period.report_id(period_start),
+            period,
+            period_start,
+            period_end,
+            generated_at: period_end,
+            financials: ReportFinancials {
+                forwards: 0,
+                routed_sat: 0,
+                revenue_sat: net_sat.max(0) as u64,
+                rebalance_cost_sat: 0,
+                onchain_cost_sat: 0,
+                costs_sat: 0,
+                net_sat,
+                local_balance_sat: 0,
+                net_yield_pct: None,
+                fiat_currency: "EUR".to_string(),
+                net_fiat: None,
+            },
+            best_channels: vec![],
+            worst_channels: vec![],
+            actions: vec![],
+            risks: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_lists_latest_first_and_replaces() {
+        let store = ReportStore::new(SqlitePool::connect("sqlite::memory:").await.unwrap());
+        store.create_tables().await.unwrap();
+
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        store
+            .save(&report(
+                ReportPeriod::Weekly,
+                at("2026-10-07T12:00:00Z"),
+                100,
+            ))
+            .await
+            .unwrap();
+        store
+            .save(&report(
+                ReportPeriod::Weekly,
+                at("2026-10-14T12:00:00Z"),
+                200,
+            ))
+            .await
+            .unwrap();
+        store
+            .save(&report(
+                ReportPeriod::Monthly,
+                at("2026-09-14T12:00:00Z"),
+                900,
+            ))
+            .await
+            .unwrap();
+        // Régénération de la même semaine
+        store
+            .save(&report(
+                ReportPeriod::Weekly,
+                at("2026-10-15T12:00:00Z"),
+                250,
+            ))
+            .await
+            .unwrap();
+
+        let weekly = store.list(Some(ReportPeriod::Weekly), 10).await.unwrap();
+        let ids: Vec<&str> = weekly.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["weekly-2026-10-12", "weekly-2026-10-05"]);
+        assert_eq!(weekly[0].net_sat, 250);
+
+        assert_eq!(store.list(None, 10).await.unwrap().len(), 3);
+        assert!(store.exists("monthly-2026-09").await.unwrap());
+        assert_eq!(
+            store
+                .get("monthly-2026-09")
+                .await
+                .unwrap()
+                .unwrap()
+                .financials
+                .net_sat,
+            900
+        );
+    }
+}
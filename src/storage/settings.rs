@@ -0,0 +1,113 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{Row, SqlitePool};
+use tracing::info;
+
+use crate::models::{anomaly::AutomationPause, automation::AutomationSettings};
+use crate::storage::timestamp;
+
+const AUTOMATION_SETTINGS_KEY: &str = "automation_settings";
+const AUTOMATION_PAUSE_KEY: &str = "automation_pause";
+
+/// Persistance SQLite des réglages modifiables depuis l'interface
+#[derive(Clone)]
+pub struct SettingsStore {
+    db: SqlitePool,
+}
+
+impl SettingsStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Crée la table des réglages
+    pub async fn create_tables(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.db)
+        .await?;
+
+        info!("Table des réglages créée");
+        Ok(())
+    }
+
+    /// Réglages d'automatisation enregistrés, sinon les réglages par défaut
+    pub async fn automation_settings(&self) -> Result<AutomationSettings> {
+        Ok(self.get(AUTOMATION_SETTINGS_KEY).await?.unwrap_or_default())
+    }
+
+    pub async fn save_automation_settings(&self, settings: &AutomationSettings) -> Result<()> {
+        self.put(AUTOMATION_SETTINGS_KEY, settings).await
+    }
+
+    /// Suspension de l'automatisation en vigueur, conservée d'un redémarrage à l'autre
+    pub async fn automation_pause(&self) -> Result<Option<AutomationPause>> {
+        self.get(AUTOMATION_PAUSE_KEY).await
+    }
+
+    pub async fn save_automation_pause(&self, pause: Option<&AutomationPause>) -> Result<()> {
+        match pause {
+            Some(pause) => self.put(AUTOMATION_PAUSE_KEY, pause).await,
+            None => {
+                sqlx::query("DELETE FROM app_settings WHERE key = ?1")
+                    .bind(AUTOMATION_PAUSE_KEY)
+                    .execute(&self.db)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let row = sqlx::query("SELECT payload FROM app_settings WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(&self.db)
+            .await?;
+        row.map(|row| Ok(serde_json::from_str(&row.get::<String, _>("payload"))?))
+            .transpose()
+    }
+
+    async fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO app_settings (key, payload, updated_at) VALUES (?1, ?2, ?3)",
+        )
+        .bind(key)
+        .bind(serde_json::to_string(value)?)
+        .bind(timestamp(Utc::now()))
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_automation_settings_default_until_saved() {
+        let store = SettingsStore::new(SqlitePool::connect("sqlite::memory:").await.unwrap());
+        store.create_tables().await.unwrap();
+
+        let mut settings = store.automation_settings().await.unwrap();
+        assert!(!settings.enabled);
+        assert!(!settings.auto_execution_enabled);
+
+        settings.enabled = true;
+        settings.auto_execution_enabled = true;
+        settings.max_daily_actions = 3;
+        store.save_automation_settings(&settings).await.unwrap();
+
+        let stored = store.automation_settings().await.unwrap();
+        assert!(stored.enabled && stored.auto_execution_enabled);
+        assert_eq!(stored.max_daily_actions, 3);
+    }
+}
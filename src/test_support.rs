@@ -0,0 +1,26 @@
+//! Données de test partagées par les modules d'analyse.
+
+use crate::api::local_lightning_client::LocalChannelInfo;
+
+/// Canal actif sans frais ni HTLC ; les tests surchargent les champs qui les
+/// intéressent avec `..channel(id, capacity, local)`.
+pub fn channel(id: &str, capacity: u64, local: u64) -> LocalChannelInfo {
+    LocalChannelInfo {
+        channel_id: id.to_string(),
+        channel_point: format!("{}:0", id),
+        peer_pubkey: format!("peer_{}", id),
+        peer_alias: String::new(),
+        capacity,
+        local_balance: local,
+        remote_balance: capacity - local,
+        active: true,
+        private: false,
+        fee_per_kw: 0,
+        base_fee_msat: 0,
+        fee_rate_milli_msat: 0,
+        commit_fee: 0,
+        pending_htlcs: 0,
+        total_satoshis_sent: 0,
+        total_satoshis_received: 0,
+    }
+}
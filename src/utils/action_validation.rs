@@ -0,0 +1,495 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalLightningClient, LocalWalletBalance},
+    middleware::validation::validate_input,
+    models::action_params::{
+        ActionParameters, AdjustFeesParams, CloseChannelParams, OpenChannelParams, RebalanceParams,
+    },
+};
+
+/// Raison pour laquelle une recommandation ne peut pas être exécutée telle quelle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Error)]
+pub enum ParameterViolation {
+    #[error("paramètres invalides: {0}")]
+    Malformed(String),
+    #[error("clé publique invalide: {0}")]
+    InvalidPubkey(String),
+    #[error("canal inconnu: {0}")]
+    UnknownChannel(String),
+    #[error("canal inactif: {0}")]
+    InactiveChannel(String),
+    #[error("montant {requested} sats supérieur au disponible ({available} sats)")]
+    InsufficientFunds { requested: u64, available: u64 },
+    #[error("montant {amount} sats hors bornes [{min}, {max}]")]
+    AmountOutOfBounds { amount: u64, min: u64, max: u64 },
+    #[error("frais {field}={value} hors bornes [{min}, {max}]")]
+    FeeOutOfBounds {
+        field: String,
+        value: u64,
+        min: u64,
+        max: u64,
+    },
+    #[error("{0}")]
+    Inconsistent(String),
+}
+
+/// Bornes acceptées pour les politiques et montants.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionBounds {
+    pub min_fee_rate_ppm: u64,
+    pub max_fee_rate_ppm: u64,
+    pub max_base_fee_msat: u64,
+    pub min_time_lock_delta: u32,
+    pub max_time_lock_delta: u32,
+    pub min_channel_size: u64,
+    pub max_channel_size: u64,
+    pub max_sat_per_vbyte: u64,
+    /// Réserve on-chain conservée pour les fermetures forcées et le CPFP
+    pub onchain_reserve: u64,
+}
+
+impl Default for ActionBounds {
+    fn default() -> Self {
+        Self {
+            min_fee_rate_ppm: 0,
+            max_fee_rate_ppm: 10_000,
+            max_base_fee_msat: 10_000,
+            min_time_lock_delta: 18,
+            max_time_lock_delta: 2_016,
+            min_channel_size: 20_000,
+            max_channel_size: 100_000_000,
+            max_sat_per_vbyte: 500,
+            onchain_reserve: 50_000,
+        }
+    }
+}
+
+/// État du nœud au moment de la validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeState {
+    pub channels: Vec<LocalChannelInfo>,
+    pub wallet: LocalWalletBalance,
+}
+
+impl NodeState {
+    /// Capture l'état courant depuis LND (ou le mode mock).
+    pub async fn capture(client: &mut LocalLightningClient) -> Result<Self> {
+        let channels = client.list_local_channels().await?;
+        let wallet = client.get_local_wallet_balance().await?;
+        Ok(Self { channels, wallet })
+    }
+
+    pub fn find_channel(&self, id: &str) -> Option<&LocalChannelInfo> {
+        self.channels
+            .iter()
+            .find(|c| c.channel_id == id || c.channel_point == id)
+    }
+
+    /// Liquidité sortante réellement utilisable : balance locale moins la réserve de canal (1%).
+    pub fn spendable_outbound(channel: &LocalChannelInfo) -> u64 {
+        let reserve = channel.capacity / 100;
+        channel.local_balance.saturating_sub(reserve)
+    }
+}
+
+/// Valide les paramètres typés d'une recommandation contre l'état du nœud.
+#[derive(Debug, Clone, Default)]
+pub struct ActionValidator {
+    pub bounds: ActionBounds,
+}
+
+impl ActionValidator {
+    pub fn new(bounds: ActionBounds) -> Self {
+        Self { bounds }
+    }
+
+    /// Interprète puis valide ; renvoie les paramètres typés ou toutes les violations.
+    pub fn validate(
+        &self,
+        action_type: crate::api::mcp_client::ActionType,
+        parameters: &serde_json::Value,
+        state: &NodeState,
+    ) -> Result<ActionParameters, Vec<ParameterViolation>> {
+        let typed = ActionParameters::parse(action_type, parameters)
+            .map_err(|e| vec![ParameterViolation::Malformed(e)])?;
+
+        let violations = self.check(&typed, state);
+        if violations.is_empty() {
+            Ok(typed)
+        } else {
+            Err(violations)
+        }
+    }
+
+    pub fn check(
+        &self,
+        parameters: &ActionParameters,
+        state: &NodeState,
+    ) -> Vec<ParameterViolation> {
+        match parameters {
+            ActionParameters::OpenChannel(p) => self.check_open(p, state),
+            ActionParameters::CloseChannel(p) => self.check_close(p, state),
+            ActionParameters::AdjustFees(p) => self.check_fees(p, state),
+            ActionParameters::RebalanceChannel(p) => self.check_rebalance(p, state),
+        }
+    }
+
+    fn check_open(&self, params: &OpenChannelParams, state: &NodeState) -> Vec<ParameterViolation> {
+        let mut violations = vec![];
+
+        if validate_input("pubkey", &params.peer_pubkey).is_err() {
+            violations.push(ParameterViolation::InvalidPubkey(
+                params.peer_pubkey.clone(),
+            ));
+        }
+
+        if params.amount_sat < self.bounds.min_channel_size
+            || params.amount_sat > self.bounds.max_channel_size
+        {
+            violations.push(ParameterViolation::AmountOutOfBounds {
+                amount: params.amount_sat,
+                min: self.bounds.min_channel_size,
+                max: self.bounds.max_channel_size,
+            });
+        }
+
+        let spendable = state
+            .wallet
+            .confirmed_balance
+            .saturating_sub(self.bounds.onchain_reserve);
+        if params.amount_sat > spendable {
+            violations.push(ParameterViolation::InsufficientFunds {
+                requested: params.amount_sat,
+                available: spendable,
+            });
+        }
+
+        if params.push_sat >= params.amount_sat {
+            violations.push(ParameterViolation::Inconsistent(
+                "push_sat doit rester inférieur au montant du canal".to_string(),
+            ));
+        }
+
+        if let Some(sat_per_vbyte) = params.sat_per_vbyte {
+            self.check_onchain_fee(sat_per_vbyte, &mut violations);
+        }
+
+        violations
+    }
+
+    fn check_close(
+        &self,
+        params: &CloseChannelParams,
+        state: &NodeState,
+    ) -> Vec<ParameterViolation> {
+        let mut violations = vec![];
+
+        match self.resolve_channel(
+            params.channel_id.as_deref(),
+            params.channel_point.as_deref(),
+            state,
+        ) {
+            Ok(channel) => {
+                if !channel.active && !params.force {
+                    violations.push(ParameterViolation::InactiveChannel(
+                        channel.channel_id.clone(),
+                    ));
+                }
+            }
+            Err(violation) => violations.push(violation),
+        }
+
+        if let Some(sat_per_vbyte) = params.sat_per_vbyte {
+            self.check_onchain_fee(sat_per_vbyte, &mut violations);
+        }
+
+        violations
+    }
+
+    fn check_fees(&self, params: &AdjustFeesParams, state: &NodeState) -> Vec<ParameterViolation> {
+        let mut violations = vec![];
+
+        if let Err(violation) = self.resolve_channel(
+            params.channel_id.as_deref(),
+            params.channel_point.as_deref(),
+            state,
+        ) {
+            violations.push(violation);
+        }
+
+        let fee_rate = params.fee_rate_ppm as u64;
+        if fee_rate < self.bounds.min_fee_rate_ppm || fee_rate > self.bounds.max_fee_rate_ppm {
+            violations.push(ParameterViolation::FeeOutOfBounds {
+                field: "fee_rate_ppm".to_string(),
+                value: fee_rate,
+                min: self.bounds.min_fee_rate_ppm,
+                max: self.bounds.max_fee_rate_ppm,
+            });
+        }
+
+        if let Some(base_fee) = params.base_fee_msat {
+            if base_fee > self.bounds.max_base_fee_msat {
+                violations.push(ParameterViolation::FeeOutOfBounds {
+                    field: "base_fee_msat".to_string(),
+                    value: base_fee,
+                    min: 0,
+                    max: self.bounds.max_base_fee_msat,
+                });
+            }
+        }
+
+        if let Some(delta) = params.time_lock_delta {
+            if delta < self.bounds.min_time_lock_delta || delta > self.bounds.max_time_lock_delta {
+                violations.push(ParameterViolation::FeeOutOfBounds {
+                    field: "time_lock_delta".to_string(),
+                    value: delta as u64,
+                    min: self.bounds.min_time_lock_delta as u64,
+                    max: self.bounds.max_time_lock_delta as u64,
+                });
+            }
+        }
+
+        violations
+    }
+
+    fn check_rebalance(
+        &self,
+        params: &RebalanceParams,
+        state: &NodeState,
+    ) -> Vec<ParameterViolation> {
+        let mut violations = vec![];
+
+        if params.outgoing_channel_id == params.incoming_channel_id {
+            violations.push(ParameterViolation::Inconsistent(
+                "les canaux sortant et entrant doivent être distincts".to_string(),
+            ));
+        }
+
+        match self.resolve_channel(Some(&params.outgoing_channel_id), None, state) {
+            Ok(outgoing) => {
+                if !outgoing.active {
+                    violations.push(ParameterViolation::InactiveChannel(
+                        outgoing.channel_id.clone(),
+                    ));
+                }
+                let available = NodeState::spendable_outbound(outgoing);
+                if params.amount_sat > available {
+                    violations.push(ParameterViolation::InsufficientFunds {
+                        requested: params.amount_sat,
+                        available,
+                    });
+                }
+            }
+            Err(violation) => violations.push(violation),
+        }
+
+        match self.resolve_channel(Some(&params.incoming_channel_id), None, state) {
+            Ok(incoming) => {
+                if !incoming.active {
+                    violations.push(ParameterViolation::InactiveChannel(
+                        incoming.channel_id.clone(),
+                    ));
+                }
+                if params.amount_sat > incoming.remote_balance {
+                    violations.push(ParameterViolation::InsufficientFunds {
+                        requested: params.amount_sat,
+                        available: incoming.remote_balance,
+                    });
+                }
+            }
+            Err(violation) => violations.push(violation),
+        }
+
+        if params.max_fee_sat > params.amount_sat / 100 {
+            violations.push(ParameterViolation::FeeOutOfBounds {
+                field: "max_fee_sat".to_string(),
+                value: params.max_fee_sat,
+                min: 0,
+                max: params.amount_sat / 100,
+            });
+        }
+
+        violations
+    }
+
+    fn check_onchain_fee(&self, sat_per_vbyte: u64, violations: &mut Vec<ParameterViolation>) {
+        if sat_per_vbyte == 0 || sat_per_vbyte > self.bounds.max_sat_per_vbyte {
+            violations.push(ParameterViolation::FeeOutOfBounds {
+                field: "sat_per_vbyte".to_string(),
+                value: sat_per_vbyte,
+                min: 1,
+                max: self.bounds.max_sat_per_vbyte,
+            });
+        }
+    }
+
+    fn resolve_channel<'a>(
+        &self,
+        channel_id: Option<&str>,
+        channel_point: Option<&str>,
+        state: &'a NodeState,
+    ) -> Result<&'a LocalChannelInfo, ParameterViolation> {
+        let reference = channel_id.or(channel_point).ok_or_else(|| {
+            ParameterViolation::Malformed("channel_id ou channel_point requis".to_string())
+        })?;
+
+        let channel = state
+            .find_channel(reference)
+            .ok_or_else(|| ParameterViolation::UnknownChannel(reference.to_string()))?;
+
+        // Si les deux identifiants sont fournis, ils doivent désigner le même canal
+        if let (Some(_), Some(point)) = (channel_id, channel_point) {
+            if channel.channel_point != point {
+                return Err(ParameterViolation::Inconsistent(format!(
+                    "channel_point {} ne correspond pas au canal {}",
+                    point, channel.channel_id
+                )));
+            }
+        }
+
+        Ok(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::ActionType;
+    use crate::test_support;
+    use serde_json::json;
+
+    fn channel(id: &str, local: u64, remote: u64) -> LocalChannelInfo {
+        LocalChannelInfo {
+            channel_point: format!("txid{}:0", id),
+            peer_pubkey: "03fedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210"
+                .to_string(),
+            peer_alias: "peer".to_string(),
+            fee_per_kw: 2500,
+            base_fee_msat: 1000,
+            fee_rate_milli_msat: 100,
+            commit_fee: 5000,
+            ..test_support::channel(id, local + remote, local)
+        }
+    }
+
+    fn state() -> NodeState {
+        NodeState {
+            channels: vec![
+                channel("111", 800_000, 200_000),
+                channel("222", 100_000, 900_000),
+            ],
+            wallet: LocalWalletBalance {
+                total_balance: 1_000_000,
+                confirmed_balance: 1_000_000,
+                unconfirmed_balance: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_mcp_fee_parameters_are_accepted() {
+        let validator = ActionValidator::default();
+        let params = validator
+            .validate(
+                ActionType::AdjustFees,
+                &json!({"channel_id": 111, "fee_rate": 500}),
+                &state(),
+            )
+            .unwrap();
+
+        match params {
+            ActionParameters::AdjustFees(p) => {
+                assert_eq!(p.channel_id.as_deref(), Some("111"));
+                assert_eq!(p.fee_rate_ppm, 500);
+            }
+            other => panic!("unexpected parameters: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_channel_and_fee_bounds() {
+        let validator = ActionValidator::default();
+        let violations = validator
+            .validate(
+                ActionType::AdjustFees,
+                &json!({"channel_id": "999", "fee_rate_ppm": 50_000}),
+                &state(),
+            )
+            .unwrap_err();
+
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[0],
+            ParameterViolation::UnknownChannel(_)
+        ));
+        assert!(matches!(
+            violations[1],
+            ParameterViolation::FeeOutOfBounds { .. }
+        ));
+    }
+
+    #[test]
+    fn test_open_amount_above_spendable() {
+        let validator = ActionValidator::default();
+        let violations = validator
+            .validate(
+                ActionType::OpenChannel,
+                &json!({
+                    "peer_pubkey": "02a1b2c3d4e5f6789abcdef123456789abcdef123456789abcdef123456789abcd",
+                    "amount_sat": 2_000_000
+                }),
+                &state(),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            violations,
+            vec![ParameterViolation::InsufficientFunds {
+                requested: 2_000_000,
+                available: 950_000
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rebalance_checks_both_sides() {
+        let validator = ActionValidator::default();
+        let ok = validator.validate(
+            ActionType::RebalanceChannel,
+            &json!({
+                "outgoing_channel_id": "111",
+                "incoming_channel_id": "222",
+                "amount_sat": 300_000,
+                "max_fee_sat": 300
+            }),
+            &state(),
+        );
+        assert!(ok.is_ok());
+
+        let violations = validator
+            .validate(
+                ActionType::RebalanceChannel,
+                &json!({
+                    "outgoing_channel_id": "222",
+                    "incoming_channel_id": "111",
+                    "amount_sat": 300_000,
+                    "max_fee_sat": 300
+                }),
+                &state(),
+            )
+            .unwrap_err();
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn test_missing_parameters_are_malformed() {
+        let validator = ActionValidator::default();
+        let violations = validator
+            .validate(ActionType::OpenChannel, &json!({}), &state())
+            .unwrap_err();
+        assert!(matches!(violations[0], ParameterViolation::Malformed(_)));
+    }
+}
@@ -0,0 +1,697 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    api::local_lightning_client::{
+        LocalChannelFeePolicy, LocalChannelInfo, LocalCloseType, LocalClosedChannel,
+        LocalHtlcAttempt, LocalLightningClient,
+    },
+    models::anomaly::{Anomaly, AnomalyKind, AnomalySeverity, AutomationPause},
+    storage::settings::SettingsStore,
+};
+
+/// Nombre d'anomalies conservées en mémoire pour l'API
+const RECENT_ANOMALIES: usize = 200;
+
+/// Seuils de détection des anomalies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyDetectorConfig {
+    /// Part de la capacité perdue en local entre deux observations
+    pub drain_capacity_fraction: f64,
+    pub min_drain_sat: u64,
+    /// Au-delà, la vidange est critique
+    pub critical_drain_capacity_fraction: f64,
+    /// Rafale d'échecs sur nos paiements sortants (`ListPayments`) : les HTLC routés
+    /// pour des tiers n'apparaissent pas, faute d'abonnement aux événements du routeur
+    pub payment_failure_window_minutes: i64,
+    pub payment_failure_burst: usize,
+    /// Canaux devenus inactifs ensemble, en nombre et en part des canaux actifs
+    pub mass_inactive_count: usize,
+    pub mass_inactive_fraction: f64,
+    /// Délai pendant lequel une mise à jour de frais de l'application explique un changement observé
+    pub expectation_ttl_minutes: i64,
+    /// Borne de conservation d'une fermeture annoncée qui n'apparaît pas parmi les
+    /// canaux fermés (fermeture forcée peu rémunérée, longue à confirmer)
+    pub close_expectation_ttl_hours: i64,
+    /// Suspend l'automatisation à la première anomalie critique
+    pub pause_on_critical: bool,
+}
+
+impl Default for AnomalyDetectorConfig {
+    fn default() -> Self {
+        Self {
+            drain_capacity_fraction: 0.3,
+            min_drain_sat: 200_000,
+            critical_drain_capacity_fraction: 0.6,
+            payment_failure_window_minutes: 10,
+            payment_failure_burst: 20,
+            mass_inactive_count: 3,
+            mass_inactive_fraction: 0.25,
+            expectation_ttl_minutes: 60,
+            close_expectation_ttl_hours: 24 * 14,
+            pause_on_critical: true,
+        }
+    }
+}
+
+/// État du nœud relevé à un instant donné.
+#[derive(Debug, Clone)]
+pub struct NodeObservation {
+    pub at: DateTime<Utc>,
+    pub channels: Vec<LocalChannelInfo>,
+    pub policies: Vec<LocalChannelFeePolicy>,
+    pub closed: Vec<LocalClosedChannel>,
+    pub htlc_attempts: Vec<LocalHtlcAttempt>,
+}
+
+impl NodeObservation {
+    pub async fn capture(client: &Mutex<LocalLightningClient>, at: DateTime<Utc>) -> Result<Self> {
+        let mut client = client.lock().await;
+        Ok(Self {
+            at,
+            channels: client.list_local_channels().await?,
+            policies: client.fee_report().await?,
+            closed: client.closed_channels().await?,
+            htlc_attempts: client.htlc_attempts().await?,
+        })
+    }
+}
+
+/// Compare chaque observation à la précédente ; les actions de l'application
+/// annoncées au préalable ne sont pas signalées.
+#[derive(Debug, Clone, Default)]
+pub struct AnomalyDetector {
+    pub config: AnomalyDetectorConfig,
+    previous: Option<NodeObservation>,
+    expected_policies: HashMap<String, (u32, Option<u64>, DateTime<Utc>)>,
+    expected_closes: HashMap<String, DateTime<Utc>>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyDetectorConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Annonce une mise à jour de frais décidée par l'application.
+    pub fn expect_policy(
+        &mut self,
+        channel_id: &str,
+        fee_rate_ppm: u32,
+        base_fee_msat: Option<u64>,
+        at: DateTime<Utc>,
+    ) {
+        self.expected_policies
+            .insert(channel_id.to_string(), (fee_rate_ppm, base_fee_msat, at));
+    }
+
+    /// Annonce une fermeture décidée par l'application, attendue jusqu'à ce que le
+    /// canal figure parmi les canaux fermés.
+    pub fn expect_close(&mut self, channel_id: &str, at: DateTime<Utc>) {
+        self.expected_closes.insert(channel_id.to_string(), at);
+    }
+
+    /// Retire une annonce après l'échec de l'action correspondante.
+    pub fn withdraw_policy(&mut self, channel_id: &str) {
+        self.expected_policies.remove(channel_id);
+    }
+
+    pub fn withdraw_close(&mut self, channel_id: &str) {
+        self.expected_closes.remove(channel_id);
+    }
+
+    /// La première observation sert de référence et ne produit aucune anomalie.
+    pub fn detect(&mut self, observation: NodeObservation) -> Vec<Anomaly> {
+        let ttl = Duration::minutes(self.config.expectation_ttl_minutes);
+        self.expected_policies
+            .retain(|_, (_, _, at)| observation.at - *at <= ttl);
+        let close_ttl = Duration::hours(self.config.close_expectation_ttl_hours);
+        self.expected_closes
+            .retain(|_, at| observation.at - *at <= close_ttl);
+
+        // Sans canaux, LND était sans doute injoignable : l'observation ne sert pas de référence
+        let previous = match self.previous.take() {
+            Some(previous) if !previous.channels.is_empty() => previous,
+            _ => {
+                self.previous = Some(observation);
+                return vec![];
+            }
+        };
+
+        let mut found = vec![];
+        found.extend(self.drains(&previous, &observation));
+        found.extend(self.payment_failure_burst(&previous, &observation));
+        found.extend(self.mass_inactive(&previous, &observation));
+        found.extend(self.force_closes(&previous, &observation));
+        found.extend(self.fee_changes(&previous, &observation));
+
+        // Fermetures annoncées désormais confirmées
+        for closed in &observation.closed {
+            self.expected_closes.remove(&closed.channel_id);
+        }
+
+        let at = observation.at;
+        self.previous = Some(observation);
+        found
+            .into_iter()
+            .map(|(kind, severity, description)| Anomaly {
+                id: Uuid::new_v4().to_string(),
+                kind,
+                severity,
+                detected_at: at,
+                description,
+            })
+            .collect()
+    }
+
+    fn drains(
+        &self,
+        previous: &NodeObservation,
+        current: &NodeObservation,
+    ) -> Vec<(AnomalyKind, AnomalySeverity, String)> {
+        current
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                let before = previous
+                    .channels
+                    .iter()
+                    .find(|c| c.channel_id == channel.channel_id)?;
+                let drained = before.local_balance.saturating_sub(channel.local_balance);
+                let capacity = channel.capacity.max(1) as f64;
+                let threshold = (self.config.drain_capacity_fraction * capacity)
+                    .max(self.config.min_drain_sat as f64);
+                if (drained as f64) < threshold {
+                    return None;
+                }
+                let severity =
+                    if drained as f64 >= self.config.critical_drain_capacity_fraction * capacity {
+                        AnomalySeverity::Critical
+                    } else {
+                        AnomalySeverity::Warning
+                    };
+                Some((
+                    AnomalyKind::OutboundDrain {
+                        channel_id: channel.channel_id.clone(),
+                        previous_local_sat: before.local_balance,
+                        current_local_sat: channel.local_balance,
+                    },
+                    severity,
+                    format!(
+                        "Canal {} : {} sats de liquidité sortante perdus ({:.0}% de la capacité)",
+                        channel.channel_id,
+                        drained,
+                        drained as f64 / capacity * 100.0
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    /// Échecs des tentatives HTLC de nos paiements sortants depuis l'observation précédente.
+    fn payment_failure_burst(
+        &self,
+        previous: &NodeObservation,
+        current: &NodeObservation,
+    ) -> Option<(AnomalyKind, AnomalySeverity, String)> {
+        let window = Duration::minutes(self.config.payment_failure_window_minutes);
+        // Seuls les échecs postérieurs à l'observation précédente : une rafale n'est signalée qu'une fois
+        let since = previous.at.max(current.at - window);
+        let since_ns = since.timestamp_nanos_opt().unwrap_or_default().max(0) as u64;
+        let failures = current
+            .htlc_attempts
+            .iter()
+            .filter(|a| !a.succeeded && a.attempt_time_ns > since_ns)
+            .count();
+        if failures < self.config.payment_failure_burst {
+            return None;
+        }
+        let severity = if failures >= self.config.payment_failure_burst * 3 {
+            AnomalySeverity::Critical
+        } else {
+            AnomalySeverity::Warning
+        };
+        Some((
+            AnomalyKind::FailedPaymentBurst {
+                failures,
+                window_minutes: self.config.payment_failure_window_minutes,
+            },
+            severity,
+            format!(
+                "{} tentatives de paiement sortant échouées en moins de {} minutes",
+                failures, self.config.payment_failure_window_minutes
+            ),
+        ))
+    }
+
+    fn mass_inactive(
+        &self,
+        previous: &NodeObservation,
+        current: &NodeObservation,
+    ) -> Option<(AnomalyKind, AnomalySeverity, String)> {
+        let was_active: HashSet<&str> = previous
+            .channels
+            .iter()
+            .filter(|c| c.active)
+            .map(|c| c.channel_id.as_str())
+            .collect();
+        let went_inactive: Vec<String> = current
+            .channels
+            .iter()
+            .filter(|c| !c.active && was_active.contains(c.channel_id.as_str()))
+            .map(|c| c.channel_id.clone())
+            .collect();
+
+        let fraction = went_inactive.len() as f64 / was_active.len().max(1) as f64;
+        if went_inactive.len() < self.config.mass_inactive_count
+            || fraction < self.config.mass_inactive_fraction
+        {
+            return None;
+        }
+        let description = format!(
+            "{} canaux sur {} devenus inactifs simultanément",
+            went_inactive.len(),
+            was_active.len()
+        );
+        Some((
+            AnomalyKind::MassInactive {
+                channel_ids: went_inactive,
+            },
+            AnomalySeverity::Critical,
+            description,
+        ))
+    }
+
+    fn force_closes(
+        &self,
+        previous: &NodeObservation,
+        current: &NodeObservation,
+    ) -> Vec<(AnomalyKind, AnomalySeverity, String)> {
+        let known: HashSet<&str> = previous
+            .closed
+            .iter()
+            .map(|c| c.channel_id.as_str())
+            .collect();
+        current
+            .closed
+            .iter()
+            .filter(|c| c.close_type.is_force() && !known.contains(c.channel_id.as_str()))
+            .filter(|c| !self.expected_closes.contains_key(&c.channel_id))
+            .map(|closed| {
+                let severity = match closed.close_type {
+                    LocalCloseType::RemoteForce => AnomalySeverity::Warning,
+                    _ => AnomalySeverity::Critical,
+                };
+                (
+                    AnomalyKind::UnexpectedForceClose {
+                        channel_id: closed.channel_id.clone(),
+                        remote_pubkey: closed.remote_pubkey.clone(),
+                        close_type: closed.close_type,
+                    },
+                    severity,
+                    format!(
+                        "Fermeture forcée inattendue du canal {} ({:?}) avec {}",
+                        closed.channel_id, closed.close_type, closed.remote_pubkey
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn fee_changes(
+        &self,
+        previous: &NodeObservation,
+        current: &NodeObservation,
+    ) -> Vec<(AnomalyKind, AnomalySeverity, String)> {
+        current
+            .policies
+            .iter()
+            .filter_map(|policy| {
+                let before = previous
+                    .policies
+                    .iter()
+                    .find(|p| p.channel_id == policy.channel_id)?;
+                if before.fee_rate_ppm == policy.fee_rate_ppm
+                    && before.base_fee_msat == policy.base_fee_msat
+                {
+                    return None;
+                }
+                let expected = self
+                    .expected_policies
+                    .get(&policy.channel_id)
+                    .is_some_and(|(ppm, base, _)| {
+                        *ppm == policy.fee_rate_ppm
+                            && base.is_none_or(|base| base == policy.base_fee_msat)
+                    });
+                if expected {
+                    return None;
+                }
+                Some((
+                    AnomalyKind::UnexpectedFeeChange {
+                        channel_id: policy.channel_id.clone(),
+                        previous_base_fee_msat: before.base_fee_msat,
+                        previous_fee_rate_ppm: before.fee_rate_ppm,
+                        base_fee_msat: policy.base_fee_msat,
+                        fee_rate_ppm: policy.fee_rate_ppm,
+                    },
+                    AnomalySeverity::Warning,
+                    format!(
+                        "Frais du canal {} modifiés hors de l'application : {} ppm / {} msat → {} ppm / {} msat",
+                        policy.channel_id,
+                        before.fee_rate_ppm,
+                        before.base_fee_msat,
+                        policy.fee_rate_ppm,
+                        policy.base_fee_msat
+                    ),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct MonitorState {
+    detector: AnomalyDetector,
+    recent: VecDeque<Anomaly>,
+    pause: Option<AutomationPause>,
+}
+
+/// Détecteur partagé entre la tâche de surveillance et les handlers.
+#[derive(Clone, Default)]
+pub struct AnomalyMonitor {
+    state: Arc<Mutex<MonitorState>>,
+    /// Persistance de la suspension : un redémarrage ne relance pas l'automatisation
+    store: Option<SettingsStore>,
+}
+
+impl AnomalyMonitor {
+    pub fn new(config: AnomalyDetectorConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MonitorState {
+                detector: AnomalyDetector::new(config),
+                ..Default::default()
+            })),
+            store: None,
+        }
+    }
+
+    /// Moniteur dont la suspension est enregistrée et reprise depuis `store`.
+    pub async fn with_store(config: AnomalyDetectorConfig, store: SettingsStore) -> Result<Self> {
+        let pause = store.automation_pause().await?;
+        let monitor = Self {
+            store: Some(store),
+            ..Self::new(config)
+        };
+        monitor.state.lock().await.pause = pause;
+        Ok(monitor)
+    }
+
+    async fn persist_pause(&self, pause: Option<&AutomationPause>) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.save_automation_pause(pause).await {
+                warn!("Unable to persist automation pause: {}", e);
+            }
+        }
+    }
+
+    /// Analyse une observation ; une anomalie critique suspend l'automatisation si configuré.
+    pub async fn observe(&self, observation: NodeObservation) -> Vec<Anomaly> {
+        let mut state = self.state.lock().await;
+        let anomalies = state.detector.detect(observation);
+
+        if state.detector.config.pause_on_critical && state.pause.is_none() {
+            if let Some(critical) = anomalies
+                .iter()
+                .find(|a| a.severity == AnomalySeverity::Critical)
+            {
+                state.pause = Some(AutomationPause {
+                    paused_at: critical.detected_at,
+                    anomaly_id: critical.id.clone(),
+                    reason: critical.description.clone(),
+                });
+                self.persist_pause(state.pause.as_ref()).await;
+            }
+        }
+
+        for anomaly in &anomalies {
+            if state.recent.len() == RECENT_ANOMALIES {
+                state.recent.pop_front();
+            }
+            state.recent.push_back(anomaly.clone());
+        }
+        anomalies
+    }
+
+    /// Anomalies récentes, de la plus récente à la plus ancienne
+    pub async fn recent(&self) -> Vec<Anomaly> {
+        self.state
+            .lock()
+            .await
+            .recent
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    pub async fn pause(&self) -> Option<AutomationPause> {
+        self.state.lock().await.pause.clone()
+    }
+
+    /// Suspend l'automatisation hors anomalie (règle d'automatisation par exemple) ;
+    /// une suspension en cours est conservée.
+    pub async fn suspend(&self, source_id: String, reason: String, at: DateTime<Utc>) -> bool {
+        let mut state = self.state.lock().await;
+        if state.pause.is_some() {
+            return false;
+        }
+        state.pause = Some(AutomationPause {
+            paused_at: at,
+            anomaly_id: source_id,
+            reason,
+        });
+        self.persist_pause(state.pause.as_ref()).await;
+        true
+    }
+
+    /// Lève la suspension ; renvoie celle qui était en vigueur.
+    pub async fn resume(&self) -> Option<AutomationPause> {
+        let mut state = self.state.lock().await;
+        let pause = state.pause.take();
+        if pause.is_some() {
+            self.persist_pause(None).await;
+        }
+        pause
+    }
+
+    pub async fn expect_policy(
+        &self,
+        channel_id: &str,
+        fee_rate_ppm: u32,
+        base_fee_msat: Option<u64>,
+        at: DateTime<Utc>,
+    ) {
+        self.state
+            .lock()
+            .await
+            .detector
+            .expect_policy(channel_id, fee_rate_ppm, base_fee_msat, at);
+    }
+
+    pub async fn expect_close(&self, channel_id: &str, at: DateTime<Utc>) {
+        self.state
+            .lock()
+            .await
+            .detector
+            .expect_close(channel_id, at);
+    }
+
+    pub async fn withdraw_policy(&self, channel_id: &str) {
+        self.state.lock().await.detector.withdraw_policy(channel_id);
+    }
+
+    pub async fn withdraw_close(&self, channel_id: &str) {
+        self.state.lock().await.detector.withdraw_close(channel_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn channel(id: &str, local: u64, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            active,
+            base_fee_msat: 1000,
+            fee_rate_milli_msat: 100,
+            ..test_support::channel(id, 1_000_000, local)
+        }
+    }
+
+    fn policy(id: &str, ppm: u32) -> LocalChannelFeePolicy {
+        LocalChannelFeePolicy {
+            channel_id: id.to_string(),
+            channel_point: format!("{}:0", id),
+            base_fee_msat: 1000,
+            fee_rate_ppm: ppm,
+        }
+    }
+
+    fn observation(at: DateTime<Utc>, channels: Vec<LocalChannelInfo>) -> NodeObservation {
+        NodeObservation {
+            at,
+            policies: channels
+                .iter()
+                .map(|c| policy(&c.channel_id, 100))
+                .collect(),
+            channels,
+            closed: vec![],
+            htlc_attempts: vec![],
+        }
+    }
+
+    #[test]
+    fn test_detects_drain_mass_inactive_and_force_close() {
+        let mut detector = AnomalyDetector::default();
+        let baseline = observation(
+            start(),
+            (1..=4)
+                .map(|i| channel(&i.to_string(), 800_000, true))
+                .collect(),
+        );
+        assert!(detector.detect(baseline).is_empty());
+
+        let mut next = observation(
+            start() + Duration::minutes(1),
+            vec![
+                channel("1", 100_000, true),
+                channel("2", 800_000, false),
+                channel("3", 800_000, false),
+                channel("4", 800_000, false),
+            ],
+        );
+        next.closed.push(LocalClosedChannel {
+            channel_id: "9".to_string(),
+            remote_pubkey: "peer_9".to_string(),
+            capacity: 500_000,
+            close_height: 800_000,
+            close_type: LocalCloseType::RemoteForce,
+            channel_point: "9:0".to_string(),
+            closing_tx_hash: String::new(),
+        });
+        let anomalies = detector.detect(next);
+
+        assert_eq!(anomalies.len(), 3);
+        assert!(matches!(
+            &anomalies[0].kind,
+            AnomalyKind::OutboundDrain { channel_id, .. } if channel_id == "1"
+        ));
+        assert_eq!(anomalies[0].severity, AnomalySeverity::Critical);
+        assert!(matches!(
+            &anomalies[1].kind,
+            AnomalyKind::MassInactive { channel_ids } if channel_ids.len() == 3
+        ));
+        assert!(matches!(
+            anomalies[2].kind,
+            AnomalyKind::UnexpectedForceClose { .. }
+        ));
+    }
+
+    #[test]
+    fn test_fee_changes_we_announced_are_not_flagged() {
+        let mut detector = AnomalyDetector::default();
+        let channels = vec![channel("1", 500_000, true), channel("2", 500_000, true)];
+        detector.detect(observation(start(), channels.clone()));
+
+        let at = start() + Duration::minutes(1);
+        detector.expect_policy("1", 250, None, at);
+        let mut next = observation(at, channels);
+        next.policies = vec![policy("1", 250), policy("2", 400)];
+        let anomalies = detector.detect(next);
+
+        assert_eq!(anomalies.len(), 1);
+        assert!(matches!(
+            &anomalies[0].kind,
+            AnomalyKind::UnexpectedFeeChange { channel_id, fee_rate_ppm: 400, .. } if channel_id == "2"
+        ));
+    }
+
+    #[test]
+    fn test_announced_force_close_kept_until_confirmed() {
+        let mut detector = AnomalyDetector::default();
+        let channels = vec![channel("1", 500_000, true), channel("2", 500_000, true)];
+        detector.detect(observation(start(), channels.clone()));
+        detector.expect_close("1", start());
+
+        // Confirmation bien après le délai des mises à jour de frais
+        let confirmed = start() + Duration::hours(6);
+        let mut next = observation(confirmed, vec![channels[1].clone()]);
+        next.closed.push(LocalClosedChannel {
+            channel_id: "1".to_string(),
+            remote_pubkey: "peer_1".to_string(),
+            capacity: 1_000_000,
+            close_height: 800_000,
+            close_type: LocalCloseType::LocalForce,
+            channel_point: "1:0".to_string(),
+            closing_tx_hash: String::new(),
+        });
+        assert!(detector.detect(next).is_empty());
+        assert!(detector.expected_closes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_critical_anomaly_pauses_automation() {
+        let monitor = AnomalyMonitor::new(AnomalyDetectorConfig::default());
+        monitor
+            .observe(observation(start(), vec![channel("1", 900_000, true)]))
+            .await;
+        monitor
+            .observe(observation(
+                start() + Duration::minutes(1),
+                vec![channel("1", 50_000, true)],
+            ))
+            .await;
+
+        let pause = monitor.pause().await.unwrap();
+        assert_eq!(monitor.recent().await[0].id, pause.anomaly_id);
+        assert!(monitor.resume().await.is_some());
+        assert!(monitor.pause().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pause_survives_restart() {
+        let store = SettingsStore::new(sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap());
+        store.create_tables().await.unwrap();
+
+        let monitor = AnomalyMonitor::with_store(AnomalyDetectorConfig::default(), store.clone())
+            .await
+            .unwrap();
+        assert!(
+            monitor
+                .suspend("rule_1".to_string(), "Règle".to_string(), start())
+                .await
+        );
+
+        // Nouveau moniteur sur le même stockage, comme après un redémarrage
+        let restarted = AnomalyMonitor::with_store(AnomalyDetectorConfig::default(), store.clone())
+            .await
+            .unwrap();
+        assert_eq!(restarted.pause().await.unwrap().anomaly_id, "rule_1");
+        restarted.resume().await;
+        assert!(store.automation_pause().await.unwrap().is_none());
+    }
+}
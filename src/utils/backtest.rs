@@ -0,0 +1,589 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    api::{
+        local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+        mcp_client::ActionType,
+    },
+    models::{action_params::ActionParameters, ml::SmartRecommendation},
+    storage::forwarding::{
+        ChannelHistoryPoint, FeePolicyObservation, ForwardingStore, SnapshotResolution,
+    },
+    utils::{
+        fee_elasticity::FeeElasticityEstimator,
+        ml_engine::{MLEngine, RecommendationInputs},
+    },
+};
+
+/// Historique supplémentaire chargé avant la fenêtre pour entraîner les modèles
+pub const TRAINING_LOOKBACK_DAYS: i64 = 60;
+
+/// Données historiques rejouées par le backtest.
+#[derive(Debug, Clone, Default)]
+pub struct BacktestDataset {
+    pub snapshots: Vec<ChannelHistoryPoint>,
+    /// Forwards triés chronologiquement, historique d'entraînement inclus
+    pub events: Vec<LocalForwardingEvent>,
+    pub policies: BTreeMap<String, Vec<FeePolicyObservation>>,
+}
+
+impl BacktestDataset {
+    /// Charge `[start, end)` plus `lookback` d'historique pour entraîner les modèles.
+    pub async fn load(
+        store: &ForwardingStore,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        lookback: Duration,
+    ) -> Result<Self> {
+        Ok(Self {
+            snapshots: store
+                .channel_snapshots_between(start - lookback, end)
+                .await?,
+            events: store.events_between(start - lookback, end).await?,
+            policies: store.fee_history_by_channel().await?,
+        })
+    }
+
+    /// Politique en vigueur sur un canal à l'instant `at`.
+    fn policy_at(&self, channel_id: &str, at: DateTime<Utc>) -> Option<&FeePolicyObservation> {
+        let policies = self.policies.get(channel_id)?;
+        policies
+            .iter()
+            .rev()
+            .find(|p| p.observed_at <= at)
+            .or_else(|| policies.first())
+    }
+
+    /// Dernier état connu de chaque canal à l'instant `at`. Un agrégat horaire ou
+    /// journalier n'est connu qu'à la fin de sa période : pas de lecture anticipée.
+    fn channels_at(&self, at: DateTime<Utc>) -> Vec<LocalChannelInfo> {
+        let known_at = |s: &ChannelHistoryPoint| match s.resolution {
+            SnapshotResolution::Minute => s.taken_at,
+            SnapshotResolution::Hour => s.taken_at + Duration::hours(1),
+            SnapshotResolution::Day => s.taken_at + Duration::days(1),
+        };
+        let mut latest: BTreeMap<&str, &LocalChannelInfo> = BTreeMap::new();
+        for snapshot in self.snapshots.iter().filter(|s| known_at(s) <= at) {
+            latest.insert(&snapshot.channel.channel_id, &snapshot.channel);
+        }
+        latest.into_values().cloned().collect()
+    }
+
+    fn events_before(&self, at: DateTime<Utc>) -> &[LocalForwardingEvent] {
+        let at_ns = at.timestamp_nanos_opt().unwrap_or(i64::MAX) as u64;
+        let end = self.events.partition_point(|e| e.timestamp_ns < at_ns);
+        &self.events[..end]
+    }
+
+    fn events_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> &[LocalForwardingEvent] {
+        let start_ns = start.timestamp_nanos_opt().unwrap_or(0) as u64;
+        let end_ns = end.timestamp_nanos_opt().unwrap_or(i64::MAX) as u64;
+        let from = self.events.partition_point(|e| e.timestamp_ns < start_ns);
+        let to = self.events.partition_point(|e| e.timestamp_ns < end_ns);
+        &self.events[from..to]
+    }
+}
+
+/// Ce qu'une stratégie peut voir à un pas donné : rien de postérieur à `at`.
+pub struct BacktestContext<'a> {
+    pub at: DateTime<Utc>,
+    pub channels: &'a [LocalChannelInfo],
+    pub past_events: &'a [LocalForwardingEvent],
+    pub policies: BTreeMap<String, Vec<FeePolicyObservation>>,
+}
+
+/// Stratégie rejouable : produit des recommandations au même format que le moteur ML.
+pub trait BacktestStrategy: Send + Sync {
+    fn name(&self) -> String;
+    fn decide(&self, context: &BacktestContext) -> Vec<SmartRecommendation>;
+}
+
+/// Référence : ne rien changer.
+pub struct DoNothingStrategy;
+
+impl BacktestStrategy for DoNothingStrategy {
+    fn name(&self) -> String {
+        "do_nothing".to_string()
+    }
+
+    fn decide(&self, _context: &BacktestContext) -> Vec<SmartRecommendation> {
+        vec![]
+    }
+}
+
+/// Le moteur ML local, avec des modèles de frais entraînés sur le seul passé.
+pub struct MLEngineStrategy {
+    pub engine: MLEngine,
+    pub estimator: FeeElasticityEstimator,
+}
+
+impl MLEngineStrategy {
+    pub fn new(engine: MLEngine) -> Self {
+        Self {
+            engine,
+            estimator: FeeElasticityEstimator::default(),
+        }
+    }
+}
+
+impl BacktestStrategy for MLEngineStrategy {
+    fn name(&self) -> String {
+        match self.engine.version {
+            Some(version) => format!("ml_engine_v{}", version),
+            None => "ml_engine".to_string(),
+        }
+    }
+
+    fn decide(&self, context: &BacktestContext) -> Vec<SmartRecommendation> {
+        let models: Vec<_> = context
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                let policies = context.policies.get(&channel.channel_id)?;
+                let events: Vec<LocalForwardingEvent> = context
+                    .past_events
+                    .iter()
+                    .filter(|e| e.chan_id_out == channel.channel_id)
+                    .cloned()
+                    .collect();
+                let observations = self.estimator.observations(&events, policies, context.at);
+                self.estimator.fit(
+                    &channel.channel_id,
+                    &observations,
+                    policies.last()?,
+                    context.at,
+                )
+            })
+            .collect();
+
+        self.engine.build_recommendations(&RecommendationInputs {
+            fee_models: &models,
+            ..RecommendationInputs::new(context.channels)
+        })
+    }
+}
+
+/// Hypothèses de simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub step_hours: i64,
+    /// Élasticité appliquée quand aucun modèle n'a pu être ajusté avant le changement de frais
+    pub default_elasticity: f64,
+    pub max_actions_per_step: usize,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            step_hours: 24,
+            default_elasticity: -0.8,
+            max_actions_per_step: 3,
+        }
+    }
+}
+
+/// Revenus et coûts cumulés d'un scénario.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestTotals {
+    pub revenue_sat: f64,
+    pub costs_sat: f64,
+    pub net_sat: f64,
+    pub forwards: f64,
+    pub roi_annualized_pct: f64,
+}
+
+/// Action appliquée pendant la simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedAction {
+    pub at: DateTime<Utc>,
+    pub recommendation_id: String,
+    pub action_type: ActionType,
+    pub detail: String,
+    pub cost_sat: f64,
+}
+
+/// Résultat d'un backtest : scénario simulé contre la référence historique.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub strategy: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub steps: usize,
+    pub channels: usize,
+    pub capacity_sat: u64,
+    pub baseline: BacktestTotals,
+    pub simulated: BacktestTotals,
+    pub net_delta_sat: f64,
+    pub actions: Vec<SimulatedAction>,
+    pub skipped_actions: usize,
+    pub assumptions: Vec<String>,
+}
+
+/// Frais simulés en vigueur sur un canal.
+#[derive(Debug, Clone, Copy)]
+struct FeeOverride {
+    ppm: u32,
+    elasticity: f64,
+}
+
+/// Rejoue l'historique pas à pas. Entièrement déterministe : aucune horloge ni aléa.
+#[derive(Debug, Clone, Default)]
+pub struct Backtester {
+    pub config: BacktestConfig,
+}
+
+impl Backtester {
+    pub fn new(config: BacktestConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn run(
+        &self,
+        dataset: &BacktestDataset,
+        strategy: &dyn BacktestStrategy,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> BacktestReport {
+        let step = Duration::hours(self.config.step_hours.max(1));
+        let estimator = FeeElasticityEstimator::default();
+
+        let mut overrides: BTreeMap<String, FeeOverride> = BTreeMap::new();
+        let mut balance_offsets: BTreeMap<String, i64> = BTreeMap::new();
+        let mut baseline = BacktestTotals::default();
+        let mut simulated = BacktestTotals::default();
+        let mut actions = vec![];
+        let mut skipped_actions = 0;
+        let mut steps = 0;
+        let mut capacity_sat = 0;
+        let mut channel_count = 0;
+
+        let mut at = start;
+        while at < end {
+            let next = (at + step).min(end);
+            steps += 1;
+
+            // État vu par la stratégie : dernier snapshot, frais et balances simulés
+            let mut channels = dataset.channels_at(at);
+            for channel in &mut channels {
+                if let Some(fee) = overrides.get(&channel.channel_id) {
+                    channel.fee_rate_milli_msat = fee.ppm as u64;
+                } else if let Some(policy) = dataset.policy_at(&channel.channel_id, at) {
+                    channel.fee_rate_milli_msat = policy.fee_rate_ppm as u64;
+                    channel.base_fee_msat = policy.base_fee_msat;
+                }
+                let offset = balance_offsets
+                    .get(&channel.channel_id)
+                    .copied()
+                    .unwrap_or(0);
+                let local =
+                    (channel.local_balance as i64 + offset).clamp(0, channel.capacity as i64);
+                channel.local_balance = local as u64;
+                channel.remote_balance = channel.capacity.saturating_sub(local as u64);
+            }
+            capacity_sat = capacity_sat.max(channels.iter().map(|c| c.capacity).sum());
+            channel_count = channel_count.max(channels.len());
+
+            let policies: BTreeMap<String, Vec<FeePolicyObservation>> = dataset
+                .policies
+                .iter()
+                .map(|(channel_id, history)| {
+                    let past: Vec<_> = history
+                        .iter()
+                        .filter(|p| p.observed_at <= at)
+                        .cloned()
+                        .collect();
+                    (channel_id.clone(), past)
+                })
+                .filter(|(_, history)| !history.is_empty())
+                .collect();
+
+            let context = BacktestContext {
+                at,
+                channels: &channels,
+                past_events: dataset.events_before(at),
+                policies,
+            };
+
+            let mut decisions = strategy.decide(&context);
+            decisions.truncate(self.config.max_actions_per_step);
+
+            for recommendation in decisions {
+                match ActionParameters::parse(
+                    recommendation.action_type,
+                    &recommendation.parameters,
+                ) {
+                    Ok(ActionParameters::AdjustFees(params)) => {
+                        let Some(channel_id) = params.channel_id.clone() else {
+                            skipped_actions += 1;
+                            continue;
+                        };
+                        if !channels.iter().any(|c| c.channel_id == channel_id) {
+                            skipped_actions += 1;
+                            continue;
+                        }
+                        let elasticity = context
+                            .policies
+                            .get(&channel_id)
+                            .and_then(|history| {
+                                let events: Vec<_> = context
+                                    .past_events
+                                    .iter()
+                                    .filter(|e| e.chan_id_out == channel_id)
+                                    .cloned()
+                                    .collect();
+                                let observations = estimator.observations(&events, history, at);
+                                estimator.fit(&channel_id, &observations, history.last()?, at)
+                            })
+                            .map(|model| model.elasticity)
+                            .unwrap_or(self.config.default_elasticity);
+
+                        overrides.insert(
+                            channel_id.clone(),
+                            FeeOverride {
+                                ppm: params.fee_rate_ppm,
+                                elasticity,
+                            },
+                        );
+                        actions.push(SimulatedAction {
+                            at,
+                            recommendation_id: recommendation.id,
+                            action_type: ActionType::AdjustFees,
+                            detail: format!(
+                                "{} → {} ppm (élasticité {:.2})",
+                                channel_id, params.fee_rate_ppm, elasticity
+                            ),
+                            cost_sat: 0.0,
+                        });
+                    }
+                    Ok(ActionParameters::RebalanceChannel(params)) if params.amount_sat > 0 => {
+                        let amount = params.amount_sat as i64;
+                        *balance_offsets
+                            .entry(params.outgoing_channel_id.clone())
+                            .or_default() -= amount;
+                        *balance_offsets
+                            .entry(params.incoming_channel_id.clone())
+                            .or_default() += amount;
+                        // Hypothèse pessimiste : le plafond de frais est entièrement consommé
+                        let cost = params.max_fee_sat as f64;
+                        simulated.costs_sat += cost;
+                        actions.push(SimulatedAction {
+                            at,
+                            recommendation_id: recommendation.id,
+                            action_type: ActionType::RebalanceChannel,
+                            detail: format!(
+                                "{} sat de {} vers {}",
+                                params.amount_sat,
+                                params.outgoing_channel_id,
+                                params.incoming_channel_id
+                            ),
+                            cost_sat: cost,
+                        });
+                    }
+                    // Ouvertures/fermetures : pas de trafic contrefactuel observable
+                    _ => skipped_actions += 1,
+                }
+            }
+
+            for event in dataset.events_between(at, next) {
+                let fee_sat = event.fee_msat as f64 / 1000.0;
+                baseline.revenue_sat += fee_sat;
+                baseline.forwards += 1.0;
+
+                let Some(fee) = overrides.get(&event.chan_id_out) else {
+                    simulated.revenue_sat += fee_sat;
+                    simulated.forwards += 1.0;
+                    continue;
+                };
+
+                let event_at = chrono::TimeZone::timestamp_nanos(&Utc, event.timestamp_ns as i64);
+                let (historical_ppm, base_fee_msat) = dataset
+                    .policy_at(&event.chan_id_out, event_at)
+                    .map(|p| (p.fee_rate_ppm, p.base_fee_msat))
+                    .unwrap_or((fee.ppm, 0));
+
+                // Volume attendu au ppm simulé selon l'élasticité
+                let factor =
+                    (fee.ppm.max(1) as f64 / historical_ppm.max(1) as f64).powf(fee.elasticity);
+                let fee_msat =
+                    base_fee_msat as f64 + event.amt_out_msat as f64 * fee.ppm as f64 / 1_000_000.0;
+                simulated.revenue_sat += factor * fee_msat / 1000.0;
+                simulated.forwards += factor;
+            }
+
+            at = next;
+        }
+
+        let days = (end - start).num_seconds().max(1) as f64 / 86_400.0;
+        for totals in [&mut baseline, &mut simulated] {
+            totals.net_sat = totals.revenue_sat - totals.costs_sat;
+            totals.roi_annualized_pct = if capacity_sat == 0 {
+                0.0
+            } else {
+                totals.net_sat / capacity_sat as f64 * (365.0 / days) * 100.0
+            };
+        }
+
+        BacktestReport {
+            strategy: strategy.name(),
+            start,
+            end,
+            steps,
+            channels: channel_count,
+            capacity_sat,
+            net_delta_sat: simulated.net_sat - baseline.net_sat,
+            baseline,
+            simulated,
+            actions,
+            skipped_actions,
+            assumptions: vec![
+                "La référence est l'historique réel (aucune action)".to_string(),
+                format!(
+                    "Volume routé ∝ ppm^ε ; ε = {:.2} sans modèle ajusté",
+                    self.config.default_elasticity
+                ),
+                "Rééquilibrages : coût = plafond de frais, gain de trafic non modélisé".to_string(),
+                "Ouvertures et fermetures de canaux non simulées".to_string(),
+            ],
+        }
+    }
+}
+
+/// Construit une stratégie à partir de son nom (API et CLI).
+pub fn strategy_by_name(name: &str) -> Option<Box<dyn BacktestStrategy>> {
+    match name {
+        "ml_engine" | "ml" => Some(Box::new(MLEngineStrategy::new(MLEngine::new()))),
+        "do_nothing" | "baseline" => Some(Box::new(DoNothingStrategy)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::Priority;
+    use crate::test_support;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+    }
+
+    fn channel(id: &str) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_pubkey: String::new(),
+            fee_rate_milli_msat: 100,
+            ..test_support::channel(id, 1_000_000, 500_000)
+        }
+    }
+
+    /// 10 jours, un forward de 100 000 sat par jour à 100 ppm sur le canal "1".
+    fn dataset() -> BacktestDataset {
+        let events = (0..10)
+            .map(|day| LocalForwardingEvent {
+                timestamp_ns: (start() + Duration::days(day) + Duration::hours(12))
+                    .timestamp_nanos_opt()
+                    .unwrap() as u64,
+                chan_id_in: "2".to_string(),
+                chan_id_out: "1".to_string(),
+                amt_in_msat: 100_010_000,
+                amt_out_msat: 100_000_000,
+                fee_msat: 10_000,
+            })
+            .collect();
+        let mut policies = BTreeMap::new();
+        policies.insert(
+            "1".to_string(),
+            vec![FeePolicyObservation {
+                channel_id: "1".to_string(),
+                base_fee_msat: 0,
+                fee_rate_ppm: 100,
+                observed_at: start() - Duration::days(1),
+            }],
+        );
+        BacktestDataset {
+            snapshots: vec![ChannelHistoryPoint::sample(
+                start() - Duration::hours(1),
+                channel("1"),
+            )],
+            events,
+            policies,
+        }
+    }
+
+    /// Double les frais du canal "1" dès le premier pas.
+    struct DoubleFees;
+
+    impl BacktestStrategy for DoubleFees {
+        fn name(&self) -> String {
+            "double_fees".to_string()
+        }
+
+        fn decide(&self, context: &BacktestContext) -> Vec<SmartRecommendation> {
+            if context.at != start() {
+                return vec![];
+            }
+            vec![SmartRecommendation {
+                id: "double".to_string(),
+                action_type: ActionType::AdjustFees,
+                priority: Priority::High,
+                expected_roi_impact: 0.0,
+                confidence: 1.0,
+                risk_score: 0.0,
+                rationale: vec![],
+                target_channels: vec!["1".to_string()],
+                parameters: json!({ "channel_id": "1", "fee_rate_ppm": 200 }),
+            }]
+        }
+    }
+
+    #[test]
+    fn test_do_nothing_matches_history() {
+        let report = Backtester::default().run(
+            &dataset(),
+            &DoNothingStrategy,
+            start(),
+            start() + Duration::days(10),
+        );
+        assert_eq!(report.steps, 10);
+        assert_eq!(report.baseline.revenue_sat, 100.0);
+        assert_eq!(report.simulated.revenue_sat, 100.0);
+        assert_eq!(report.net_delta_sat, 0.0);
+    }
+
+    #[test]
+    fn test_fee_change_applies_elasticity() {
+        let backtester = Backtester::new(BacktestConfig {
+            default_elasticity: -0.5,
+            ..BacktestConfig::default()
+        });
+        let report = backtester.run(
+            &dataset(),
+            &DoubleFees,
+            start(),
+            start() + Duration::days(10),
+        );
+
+        // Revenu ×2 au ppm, volume ×2^-0.5
+        let expected = 100.0 * 2.0 * 2f64.powf(-0.5);
+        assert!((report.simulated.revenue_sat - expected).abs() < 1e-6);
+        assert_eq!(report.actions.len(), 1);
+        assert!(report.net_delta_sat > 0.0);
+    }
+
+    #[test]
+    fn test_runs_are_deterministic() {
+        let data = dataset();
+        let strategy = strategy_by_name("ml_engine").unwrap();
+        let end = start() + Duration::days(10);
+        let first = Backtester::default().run(&data, strategy.as_ref(), start(), end);
+        let second = Backtester::default().run(&data, strategy.as_ref(), start(), end);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+    }
+}
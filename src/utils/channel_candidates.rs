@@ -0,0 +1,449 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::Mutex;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalLightningClient, LocalNetworkGraph},
+    utils::network_graph::{percentile_rank, GraphAnalysis},
+};
+
+/// Distance attribuée aux nœuds injoignables
+const UNREACHABLE_HOPS: u32 = 10;
+
+/// Paramètres de la recherche de pairs.
+#[derive(Debug, Clone)]
+pub struct CandidateFinderConfig {
+    pub max_candidates: usize,
+    /// Nœuds les mieux placés évalués par parcours complet du graphe
+    pub pool_size: usize,
+    /// Nombre de grandes destinations (par capacité) pour la réduction de chemins
+    pub destinations: usize,
+    pub min_peer_channels: usize,
+    pub min_channel_sat: u64,
+    pub max_channel_sat: u64,
+}
+
+impl Default for CandidateFinderConfig {
+    fn default() -> Self {
+        Self {
+            max_candidates: 5,
+            pool_size: 100,
+            destinations: 25,
+            min_peer_channels: 10,
+            min_channel_sat: 1_000_000,
+            max_channel_sat: 10_000_000,
+        }
+    }
+}
+
+/// Pair candidat à l'ouverture d'un canal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelCandidate {
+    pub pubkey: String,
+    pub alias: String,
+    /// Score de classement, 0-100
+    pub score: f64,
+    pub suggested_size_sat: u64,
+    /// Réduction moyenne de distance (sauts) vers l'ensemble du graphe
+    pub centrality_gain: f64,
+    /// Réduction moyenne de distance (sauts) vers les grandes destinations
+    pub avg_hop_reduction: f64,
+    pub destinations_improved: usize,
+    pub median_fee_ppm: u64,
+    /// 100 = frais parmi les plus bas du réseau
+    pub fee_competitiveness: f64,
+    pub capacity_sat: u64,
+    pub channels: usize,
+    pub rationale: Vec<String>,
+}
+
+/// Graphe indexé non orienté, canaux désactivés des deux côtés exclus.
+struct IndexedGraph {
+    index: HashMap<String, usize>,
+    pubkeys: Vec<String>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl IndexedGraph {
+    fn new(graph: &LocalNetworkGraph) -> Self {
+        let mut index = HashMap::new();
+        let mut pubkeys = vec![];
+        let mut id = |pubkey: &str, index: &mut HashMap<String, usize>| {
+            *index.entry(pubkey.to_string()).or_insert_with(|| {
+                pubkeys.push(pubkey.to_string());
+                pubkeys.len() - 1
+            })
+        };
+        let mut edges = vec![];
+        for edge in &graph.edges {
+            let disabled = |p: &Option<_>| {
+                p.as_ref().is_some_and(
+                    |p: &crate::api::local_lightning_client::LocalRoutingPolicy| p.disabled,
+                )
+            };
+            if disabled(&edge.node1_policy) && disabled(&edge.node2_policy) {
+                continue;
+            }
+            let a = id(&edge.node1_pub, &mut index);
+            let b = id(&edge.node2_pub, &mut index);
+            edges.push((a, b));
+        }
+        for node in &graph.nodes {
+            id(&node.pubkey, &mut index);
+        }
+
+        let mut adjacency = vec![vec![]; pubkeys.len()];
+        for (a, b) in edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+        Self {
+            index,
+            pubkeys,
+            adjacency,
+        }
+    }
+
+    /// Distances en sauts depuis un ensemble de sources (distance initiale fournie).
+    fn bfs(&self, sources: &[(usize, u32)]) -> Vec<u32> {
+        let mut distance = vec![UNREACHABLE_HOPS; self.pubkeys.len()];
+        let mut queue = VecDeque::new();
+        for (source, d) in sources {
+            if *d < distance[*source] {
+                distance[*source] = *d;
+                queue.push_back(*source);
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            let next = distance[node] + 1;
+            if next >= UNREACHABLE_HOPS {
+                continue;
+            }
+            for neighbour in &self.adjacency[node] {
+                if next < distance[*neighbour] {
+                    distance[*neighbour] = next;
+                    queue.push_back(*neighbour);
+                }
+            }
+        }
+        distance
+    }
+}
+
+/// Recherche de pairs : gain de centralité, raccourcis vers les grandes destinations,
+/// frais compétitifs et capacité, hors pairs existants et liste noire.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelCandidateFinder {
+    pub config: CandidateFinderConfig,
+}
+
+impl ChannelCandidateFinder {
+    pub fn new(config: CandidateFinderConfig) -> Self {
+        Self { config }
+    }
+
+    /// Recherche sur le graphe courant de LND (vide en mode mock).
+    pub async fn find_live(
+        &self,
+        client: &Mutex<LocalLightningClient>,
+        channels: &[LocalChannelInfo],
+        blacklisted_peers: &[String],
+    ) -> Result<Vec<ChannelCandidate>> {
+        let (own_pubkey, graph) = {
+            let mut client = client.lock().await;
+            let own_pubkey = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .pubkey;
+            (own_pubkey, client.describe_graph().await?)
+        };
+        Ok(self.find(&graph, &own_pubkey, channels, blacklisted_peers))
+    }
+
+    pub fn find(
+        &self,
+        graph: &LocalNetworkGraph,
+        own_pubkey: &str,
+        channels: &[LocalChannelInfo],
+        blacklisted_peers: &[String],
+    ) -> Vec<ChannelCandidate> {
+        let indexed = IndexedGraph::new(graph);
+        let analysis = GraphAnalysis::new(graph);
+        if indexed.pubkeys.is_empty() {
+            return vec![];
+        }
+
+        let excluded: HashSet<&str> = channels
+            .iter()
+            .map(|c| c.peer_pubkey.as_str())
+            .chain(blacklisted_peers.iter().map(String::as_str))
+            .chain(std::iter::once(own_pubkey))
+            .collect();
+
+        // Distances actuelles : notre nœud (éventuellement privé) et ses pairs directs
+        let mut sources: Vec<(usize, u32)> = channels
+            .iter()
+            .filter_map(|c| indexed.index.get(&c.peer_pubkey).map(|i| (*i, 1)))
+            .collect();
+        if let Some(own) = indexed.index.get(own_pubkey) {
+            sources.push((*own, 0));
+        }
+        let current = indexed.bfs(&sources);
+
+        let fees = median_fees(graph);
+        let mut all_fees: Vec<u64> = fees.values().copied().collect();
+        all_fees.sort_unstable();
+
+        let mut by_capacity: Vec<usize> = (0..indexed.pubkeys.len())
+            .filter(|i| {
+                analysis
+                    .node(&indexed.pubkeys[*i])
+                    .is_some_and(|n| n.channels >= self.config.min_peer_channels)
+            })
+            .collect();
+        by_capacity.sort_by_key(|i| {
+            std::cmp::Reverse(
+                analysis
+                    .node(&indexed.pubkeys[*i])
+                    .map_or(0, |n| n.capacity_sat),
+            )
+        });
+        let destinations: Vec<usize> = by_capacity
+            .iter()
+            .copied()
+            .filter(|i| indexed.pubkeys[*i] != own_pubkey)
+            .take(self.config.destinations)
+            .collect();
+        let pool: Vec<usize> = by_capacity
+            .iter()
+            .copied()
+            .filter(|i| !excluded.contains(indexed.pubkeys[*i].as_str()))
+            .take(self.config.pool_size)
+            .collect();
+
+        let node_count = indexed.pubkeys.len() as f64;
+        let mut scored: Vec<(ChannelCandidate, f64, f64)> = pool
+            .iter()
+            .filter_map(|candidate| {
+                let pubkey = &indexed.pubkeys[*candidate];
+                let stats = analysis.node(pubkey)?;
+                let from_candidate = indexed.bfs(&[(*candidate, 0)]);
+
+                let gain: u64 = current
+                    .iter()
+                    .zip(&from_candidate)
+                    .map(|(now, via)| now.saturating_sub(via + 1) as u64)
+                    .sum();
+                let reductions: Vec<u32> = destinations
+                    .iter()
+                    .filter(|d| *d != candidate)
+                    .map(|d| current[*d].saturating_sub(from_candidate[*d] + 1))
+                    .collect();
+                let avg_hop_reduction =
+                    reductions.iter().sum::<u32>() as f64 / reductions.len().max(1) as f64;
+                let destinations_improved = reductions.iter().filter(|r| **r > 0).count();
+
+                let median_fee_ppm = fees.get(pubkey).copied().unwrap_or(0);
+                let fee_competitiveness = 100.0 - percentile_rank(&all_fees, &median_fee_ppm);
+
+                let typical_channel = stats.capacity_sat / stats.channels.max(1) as u64;
+                let suggested_size_sat = round_size(
+                    typical_channel.clamp(self.config.min_channel_sat, self.config.max_channel_sat),
+                );
+
+                let centrality_gain = gain as f64 / node_count;
+                let mut rationale = vec![format!(
+                    "Rapproche le nœud de {:.2} saut en moyenne de l'ensemble du graphe",
+                    centrality_gain
+                )];
+                if destinations_improved > 0 {
+                    rationale.push(format!(
+                        "Raccourcit la route vers {} des {} plus grandes destinations ({:.2} saut en moyenne)",
+                        destinations_improved,
+                        destinations.len(),
+                        avg_hop_reduction
+                    ));
+                }
+                rationale.push(format!(
+                    "Frais médians {} ppm (compétitivité {:.0}/100)",
+                    median_fee_ppm, fee_competitiveness
+                ));
+                rationale.push(format!(
+                    "{} canaux, {} sat de capacité (centile {:.0})",
+                    stats.channels, stats.capacity_sat, stats.capacity_percentile
+                ));
+
+                Some((
+                    ChannelCandidate {
+                        pubkey: pubkey.clone(),
+                        alias: stats.alias.clone(),
+                        score: 0.0,
+                        suggested_size_sat,
+                        centrality_gain,
+                        avg_hop_reduction,
+                        destinations_improved,
+                        median_fee_ppm,
+                        fee_competitiveness,
+                        capacity_sat: stats.capacity_sat,
+                        channels: stats.channels,
+                        rationale,
+                    },
+                    centrality_gain,
+                    stats.capacity_percentile,
+                ))
+            })
+            .collect();
+
+        // Gains normalisés par le meilleur candidat évalué
+        let max_gain = scored.iter().map(|(_, g, _)| *g).fold(0.0, f64::max);
+        let max_reduction = scored
+            .iter()
+            .map(|(c, _, _)| c.avg_hop_reduction)
+            .fold(0.0, f64::max);
+        for (candidate, gain, capacity_percentile) in &mut scored {
+            let normalized = |value: f64, max: f64| if max > 0.0 { value / max } else { 0.0 };
+            candidate.score = 35.0 * normalized(*gain, max_gain)
+                + 25.0 * normalized(candidate.avg_hop_reduction, max_reduction)
+                + 0.2 * candidate.fee_competitiveness
+                + 0.2 * *capacity_percentile;
+        }
+
+        let mut candidates: Vec<ChannelCandidate> = scored.into_iter().map(|(c, _, _)| c).collect();
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.pubkey.cmp(&b.pubkey)));
+        candidates.truncate(self.config.max_candidates);
+        candidates
+    }
+}
+
+/// Frais médians (ppm) annoncés par chaque nœud sur ses canaux actifs.
+fn median_fees(graph: &LocalNetworkGraph) -> HashMap<String, u64> {
+    let mut fees: HashMap<String, Vec<u64>> = HashMap::new();
+    for edge in &graph.edges {
+        for (pubkey, policy) in [
+            (&edge.node1_pub, &edge.node1_policy),
+            (&edge.node2_pub, &edge.node2_policy),
+        ] {
+            if let Some(policy) = policy.as_ref().filter(|p| !p.disabled) {
+                fees.entry(pubkey.clone())
+                    .or_default()
+                    .push(policy.fee_rate_ppm);
+            }
+        }
+    }
+    fees.into_iter()
+        .map(|(pubkey, mut rates)| {
+            rates.sort_unstable();
+            (pubkey, rates[rates.len() / 2])
+        })
+        .collect()
+}
+
+fn round_size(sat: u64) -> u64 {
+    (sat + 50_000) / 100_000 * 100_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::local_lightning_client::{LocalGraphEdge, LocalGraphNode, LocalRoutingPolicy};
+    use crate::test_support;
+
+    fn policy(ppm: u64) -> Option<LocalRoutingPolicy> {
+        Some(LocalRoutingPolicy {
+            base_fee_msat: 1000,
+            fee_rate_ppm: ppm,
+            time_lock_delta: 40,
+            disabled: false,
+            last_update: 0,
+        })
+    }
+
+    fn edge(id: usize, a: &str, b: &str, ppm: u64) -> LocalGraphEdge {
+        LocalGraphEdge {
+            channel_id: id.to_string(),
+            node1_pub: a.to_string(),
+            node2_pub: b.to_string(),
+            capacity: 5_000_000,
+            node1_policy: policy(ppm),
+            node2_policy: policy(ppm),
+        }
+    }
+
+    fn our_channel(peer: &str) -> LocalChannelInfo {
+        LocalChannelInfo {
+            channel_point: "p:0".to_string(),
+            peer_pubkey: peer.to_string(),
+            ..test_support::channel("1", 1_000_000, 500_000)
+        }
+    }
+
+    /// Nous sommes au bout d'une chaîne (us - a - b) ; « hub » relie trois grappes.
+    fn graph() -> LocalNetworkGraph {
+        let mut edges = vec![edge(0, "us", "a", 100), edge(1, "a", "b", 100)];
+        let mut n = 2;
+        edges.push(edge(n, "b", "hub", 50));
+        n += 1;
+        for cluster in ["x", "y", "z"] {
+            edges.push(edge(n, "hub", &format!("{}0", cluster), 50));
+            n += 1;
+            for i in 1..4 {
+                edges.push(edge(
+                    n,
+                    &format!("{}0", cluster),
+                    &format!("{}{}", cluster, i),
+                    500,
+                ));
+                n += 1;
+            }
+        }
+        let mut pubkeys: Vec<String> = edges
+            .iter()
+            .flat_map(|e| [e.node1_pub.clone(), e.node2_pub.clone()])
+            .collect();
+        pubkeys.sort();
+        pubkeys.dedup();
+        LocalNetworkGraph {
+            nodes: pubkeys
+                .into_iter()
+                .map(|pubkey| LocalGraphNode {
+                    alias: pubkey.to_uppercase(),
+                    pubkey,
+                    last_update: 0,
+                    features: vec![],
+                })
+                .collect(),
+            edges,
+        }
+    }
+
+    fn finder() -> ChannelCandidateFinder {
+        ChannelCandidateFinder::new(CandidateFinderConfig {
+            min_peer_channels: 1,
+            ..CandidateFinderConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_hub_ranks_first_and_existing_peers_are_excluded() {
+        let candidates = finder().find(&graph(), "us", &[our_channel("a")], &[]);
+
+        assert_eq!(candidates[0].pubkey, "hub");
+        assert!(candidates[0].centrality_gain > 0.0);
+        assert!(candidates[0].destinations_improved > 0);
+        assert!(candidates
+            .iter()
+            .all(|c| c.pubkey != "a" && c.pubkey != "us"));
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(candidates[0].suggested_size_sat % 100_000, 0);
+        assert!(candidates[0].suggested_size_sat >= 1_000_000);
+    }
+
+    #[test]
+    fn test_blacklisted_peers_are_excluded() {
+        let candidates = finder().find(&graph(), "us", &[our_channel("a")], &["hub".to_string()]);
+        assert!(!candidates.is_empty());
+        assert!(candidates.iter().all(|c| c.pubkey != "hub"));
+    }
+}
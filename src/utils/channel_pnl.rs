@@ -0,0 +1,689 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::{
+    api::local_lightning_client::{
+        LocalChannelInfo, LocalClosedChannel, LocalForwardingEvent, LocalLightningClient,
+        LocalRebalance,
+    },
+    models::{
+        analytics::{
+            ChannelAnalytics, ChannelOptimization, ChannelPerformanceMetrics, ChannelPnl,
+            ChannelProfitabilityMetrics, ChannelRiskMetrics, OptimizationPriority,
+            OptimizationType,
+        },
+        reputation::PeerReputation,
+    },
+    storage::{
+        forwarding::{uptime_samples, ChannelHistoryPoint, ForwardingStore},
+        peer_reputation::PeerReputationStore,
+    },
+    utils::{
+        network_graph::GraphAnalysis,
+        onchain::{COOP_CLOSE_VBYTES, DEFAULT_ONCHAIN_FEE_RATE},
+    },
+};
+
+const MINUTES_PER_BLOCK: i64 = 10;
+
+/// Fenêtre du bilan et hypothèses de valorisation.
+#[derive(Debug, Clone)]
+pub struct ChannelPnlConfig {
+    pub history_days: u32,
+    pub include_closed: bool,
+    /// Rendement annuel (%) d'un placement sans risque du capital immobilisé
+    pub opportunity_yield_pct: f64,
+    /// Part de capacité chez un même pair au-delà de laquelle la diversification est suggérée
+    pub concentration_threshold: f64,
+}
+
+impl Default for ChannelPnlConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 365,
+            include_closed: true,
+            opportunity_yield_pct: 1.0,
+            concentration_threshold: 0.3,
+        }
+    }
+}
+
+/// Données observées pour le bilan des canaux.
+pub struct ChannelPnlInputs<'a> {
+    pub now: DateTime<Utc>,
+    pub block_height: u32,
+    pub channels: &'a [LocalChannelInfo],
+    pub closed: &'a [LocalClosedChannel],
+    pub events: &'a [LocalForwardingEvent],
+    pub snapshots: &'a [ChannelHistoryPoint],
+    pub rebalances: &'a [LocalRebalance],
+    /// Frais on-chain payés par notre portefeuille, par transaction
+    pub wallet_fees: &'a HashMap<String, u64>,
+    pub onchain_fee_rate: f64,
+    pub reputations: &'a [PeerReputation],
+    pub graph: &'a GraphAnalysis,
+}
+
+/// Canal ouvert ou fermé soumis au bilan.
+struct Subject<'a> {
+    channel_id: &'a str,
+    channel_point: &'a str,
+    peer_pubkey: &'a str,
+    peer_alias: &'a str,
+    capacity: u64,
+    local_balance: u64,
+    active: bool,
+    closed: Option<&'a LocalClosedChannel>,
+}
+
+/// P&L net de chaque canal : revenus de routage moins coûts on-chain et de rééquilibrage.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelPnlCalculator {
+    pub config: ChannelPnlConfig,
+}
+
+impl ChannelPnlCalculator {
+    pub fn new(config: ChannelPnlConfig) -> Self {
+        Self { config }
+    }
+
+    /// Bilan à partir de LND et de l'historique persisté.
+    pub async fn analyze_live(
+        &self,
+        client: &Mutex<LocalLightningClient>,
+        forwarding_store: &ForwardingStore,
+        reputation_store: &PeerReputationStore,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ChannelAnalytics>> {
+        let (block_height, channels, closed, rebalances, wallet_fees, graph) = {
+            let mut client = client.lock().await;
+            let block_height = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .block_height;
+            (
+                block_height,
+                client.list_local_channels().await?,
+                client.closed_channels().await?,
+                client.rebalance_payments().await?,
+                client.wallet_transaction_fees().await?,
+                client.describe_graph().await?,
+            )
+        };
+
+        let since = now - Duration::days(self.config.history_days as i64);
+        let events = forwarding_store.events_between(since, now).await?;
+        let snapshots = forwarding_store
+            .channel_snapshots_between(since, now)
+            .await?;
+        let onchain_fee_rate = forwarding_store
+            .fee_estimates_since(now - Duration::days(1))
+            .await?
+            .last()
+            .map_or(DEFAULT_ONCHAIN_FEE_RATE, |sample| sample.half_hour as f64);
+        let reputations = reputation_store.latest_all().await?;
+
+        Ok(self.analyze(&ChannelPnlInputs {
+            now,
+            block_height,
+            channels: &channels,
+            closed: &closed,
+            events: &events,
+            snapshots: &snapshots,
+            rebalances: &rebalances,
+            wallet_fees: &wallet_fees,
+            onchain_fee_rate,
+            reputations: &reputations,
+            graph: &GraphAnalysis::new(&graph),
+        }))
+    }
+
+    /// Bilans triés du meilleur au pire rendement net.
+    pub fn analyze(&self, inputs: &ChannelPnlInputs) -> Vec<ChannelAnalytics> {
+        let open = inputs.channels.iter().map(|c| Subject {
+            channel_id: &c.channel_id,
+            channel_point: &c.channel_point,
+            peer_pubkey: &c.peer_pubkey,
+            peer_alias: &c.peer_alias,
+            capacity: c.capacity,
+            local_balance: c.local_balance,
+            active: c.active,
+            closed: None,
+        });
+        let closed = inputs
+            .closed
+            .iter()
+            .filter(|c| self.config.include_closed && c.close_height > 0)
+            .map(|c| Subject {
+                channel_id: &c.channel_id,
+                channel_point: &c.channel_point,
+                peer_pubkey: &c.remote_pubkey,
+                peer_alias: "",
+                capacity: c.capacity,
+                local_balance: 0,
+                active: false,
+                closed: Some(c),
+            });
+
+        let node_capacity: u64 = inputs.channels.iter().map(|c| c.capacity).sum();
+        let mut peer_capacity: HashMap<&str, u64> = HashMap::new();
+        for channel in inputs.channels {
+            *peer_capacity.entry(&channel.peer_pubkey).or_default() += channel.capacity;
+        }
+
+        let mut analytics: Vec<ChannelAnalytics> = open
+            .chain(closed)
+            .filter_map(|subject| {
+                let pnl = self.pnl(&subject, inputs)?;
+                let concentration = if subject.closed.is_some() || node_capacity == 0 {
+                    0.0
+                } else {
+                    peer_capacity.get(subject.peer_pubkey).copied().unwrap_or(0) as f64
+                        / node_capacity as f64
+                };
+                Some(self.channel_analytics(&subject, pnl, concentration, inputs))
+            })
+            .collect();
+        analytics.sort_by_key(|a| std::cmp::Reverse(a.pnl.net_pnl_sat));
+        analytics
+    }
+
+    fn block_time(&self, height: u32, inputs: &ChannelPnlInputs) -> DateTime<Utc> {
+        let blocks = inputs.block_height.saturating_sub(height) as i64;
+        inputs.now - Duration::minutes(blocks * MINUTES_PER_BLOCK)
+    }
+
+    /// `None` si la période du canal ne recoupe pas la fenêtre analysée.
+    fn pnl(&self, subject: &Subject, inputs: &ChannelPnlInputs) -> Option<ChannelPnl> {
+        let id = subject.channel_id;
+        let history_start = inputs.now - Duration::days(self.config.history_days as i64);
+        // Hauteur d'ouverture encodée dans l'identifiant court du canal
+        let opened_at = id
+            .parse::<u64>()
+            .ok()
+            .map(|scid| (scid >> 40) as u32)
+            .filter(|height| *height > 0)
+            .map(|height| self.block_time(height, inputs));
+        let start = opened_at.unwrap_or(history_start).max(history_start);
+        let end = subject
+            .closed
+            .map_or(inputs.now, |c| self.block_time(c.close_height, inputs));
+        if end <= start {
+            return None;
+        }
+        let days = (end - start).num_seconds() as f64 / 86_400.0;
+        let (start_ns, end_ns) = (
+            start.timestamp_nanos_opt().unwrap_or(0) as u64,
+            end.timestamp_nanos_opt().unwrap_or(i64::MAX) as u64,
+        );
+        let in_period = |ns: u64| ns >= start_ns && ns < end_ns;
+
+        let fees_msat = |matches: &dyn Fn(&LocalForwardingEvent) -> bool| {
+            inputs
+                .events
+                .iter()
+                .filter(|e| in_period(e.timestamp_ns) && matches(e))
+                .map(|e| e.fee_msat)
+                .sum::<u64>()
+        };
+        let revenue_out_sat = fees_msat(&|e| e.chan_id_out == id) / 2000;
+        let revenue_in_sat = fees_msat(&|e| e.chan_id_in == id) / 2000;
+
+        let rebalance_msat = |matches: &dyn Fn(&LocalRebalance) -> bool| {
+            inputs
+                .rebalances
+                .iter()
+                .filter(|r| in_period(r.settled_at_ns) && matches(r))
+                .map(|r| r.fee_msat)
+                .sum::<u64>()
+        };
+        let rebalance_in_cost_sat = rebalance_msat(&|r| r.incoming_channel_id == id) / 2000;
+        let rebalance_out_cost_sat = rebalance_msat(&|r| r.outgoing_channel_id == id) / 2000;
+
+        // Ouverture antérieure à la fenêtre : ses revenus passés manquent, son coût aussi
+        let funding_txid = subject.channel_point.split(':').next().unwrap_or_default();
+        let open_cost_sat = if opened_at.is_some_and(|at| at >= history_start) {
+            inputs.wallet_fees.get(funding_txid).copied().unwrap_or(0)
+        } else {
+            0
+        };
+        let (close_cost_sat, close_cost_estimated) = match subject.closed {
+            Some(closed) => (
+                inputs
+                    .wallet_fees
+                    .get(&closed.closing_tx_hash)
+                    .copied()
+                    .unwrap_or(0),
+                false,
+            ),
+            None => (
+                (COOP_CLOSE_VBYTES as f64 * inputs.onchain_fee_rate).round() as u64,
+                true,
+            ),
+        };
+
+        let revenue_sat = revenue_out_sat + revenue_in_sat;
+        let rebalance_cost_sat = rebalance_in_cost_sat + rebalance_out_cost_sat;
+        let net_pnl_sat = revenue_sat as i64
+            - rebalance_cost_sat as i64
+            - open_cost_sat as i64
+            - close_cost_sat as i64;
+
+        let capital_sat_days = self.capital_sat_days(subject, inputs, start, end);
+        let annualized_yield_pct =
+            (capital_sat_days > 0.0).then(|| net_pnl_sat as f64 / capital_sat_days * 365.0 * 100.0);
+
+        let one_time_sat = open_cost_sat + close_cost_sat;
+        let operating_per_day = (revenue_sat as f64 - rebalance_cost_sat as f64) / days;
+        let payback_days = if one_time_sat == 0 {
+            Some(0.0)
+        } else {
+            (operating_per_day > 0.0).then(|| one_time_sat as f64 / operating_per_day)
+        };
+
+        Some(ChannelPnl {
+            peer_pubkey: subject.peer_pubkey.to_string(),
+            peer_alias: subject.peer_alias.to_string(),
+            capacity_sat: subject.capacity,
+            closed: subject.closed.is_some(),
+            period_start: start,
+            period_end: end,
+            days,
+            revenue_out_sat,
+            revenue_in_sat,
+            open_cost_sat,
+            rebalance_in_cost_sat,
+            rebalance_out_cost_sat,
+            close_cost_sat,
+            close_cost_estimated,
+            net_pnl_sat,
+            capital_sat_days,
+            annualized_yield_pct,
+            payback_days,
+        })
+    }
+
+    /// Solde local intégré sur `[start, end)`, constant entre deux instantanés ; sans
+    /// instantané, le solde courant d'un canal ouvert vaut pour toute la période.
+    fn capital_sat_days(
+        &self,
+        subject: &Subject,
+        inputs: &ChannelPnlInputs,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> f64 {
+        let days_between =
+            |a: DateTime<Utc>, b: DateTime<Utc>| (b - a).num_seconds().max(0) as f64 / 86_400.0;
+        let mut snapshots: Vec<&ChannelHistoryPoint> = inputs
+            .snapshots
+            .iter()
+            .filter(|s| s.channel.channel_id == subject.channel_id)
+            .filter(|s| s.taken_at >= start && s.taken_at < end)
+            .collect();
+        snapshots.sort_by_key(|s| s.taken_at);
+
+        let Some(first) = snapshots.first() else {
+            return if subject.closed.is_none() {
+                subject.local_balance as f64 * days_between(start, end)
+            } else {
+                0.0
+            };
+        };
+        let mut total = first.channel.local_balance as f64 * days_between(start, first.taken_at);
+        for (i, snapshot) in snapshots.iter().enumerate() {
+            let until = snapshots.get(i + 1).map_or(end, |next| next.taken_at);
+            total += snapshot.channel.local_balance as f64 * days_between(snapshot.taken_at, until);
+        }
+        total
+    }
+
+    fn channel_analytics(
+        &self,
+        subject: &Subject,
+        pnl: ChannelPnl,
+        concentration: f64,
+        inputs: &ChannelPnlInputs,
+    ) -> ChannelAnalytics {
+        let id = subject.channel_id;
+        let in_period = |ns: u64| {
+            ns >= pnl.period_start.timestamp_nanos_opt().unwrap_or(0) as u64
+                && ns < pnl.period_end.timestamp_nanos_opt().unwrap_or(i64::MAX) as u64
+        };
+        let days = pnl.days.max(1.0 / 24.0);
+
+        let outgoing: Vec<&LocalForwardingEvent> = inputs
+            .events
+            .iter()
+            .filter(|e| e.chan_id_out == id && in_period(e.timestamp_ns))
+            .collect();
+        let incoming = inputs
+            .events
+            .iter()
+            .filter(|e| e.chan_id_in == id && in_period(e.timestamp_ns))
+            .count();
+        let rebalances = inputs
+            .rebalances
+            .iter()
+            .filter(|r| {
+                (r.incoming_channel_id == id || r.outgoing_channel_id == id)
+                    && in_period(r.settled_at_ns)
+            })
+            .count();
+        let volume_out_sat = outgoing.iter().map(|e| e.amt_out_msat).sum::<u64>() / 1000;
+
+        let snapshots: Vec<&ChannelHistoryPoint> = inputs
+            .snapshots
+            .iter()
+            .filter(|s| s.channel.channel_id == id)
+            .collect();
+        let uptime_percentage = if snapshots.is_empty() {
+            if subject.active {
+                100.0
+            } else {
+                0.0
+            }
+        } else {
+            // Agrégats pondérés par les relevés qu'ils représentent
+            let (active, samples) = uptime_samples(snapshots.iter().copied());
+            active as f64 / samples.max(1) as f64 * 100.0
+        };
+
+        let revenue_sat = pnl.revenue_out_sat + pnl.revenue_in_sat;
+        let rebalance_cost_sat = pnl.rebalance_in_cost_sat + pnl.rebalance_out_cost_sat;
+        let average_capital = pnl.capital_sat_days / days;
+
+        let performance_metrics = ChannelPerformanceMetrics {
+            // Les forwards refusés ne sont pas exposés par l'API LND utilisée (pas d'événements HTLC)
+            forwarding_success_rate: None,
+            average_htlc_size: if outgoing.is_empty() {
+                0
+            } else {
+                volume_out_sat / outgoing.len() as u64
+            },
+            forwards_per_day: (outgoing.len() + incoming) as f64 / days,
+            total_fees_earned: revenue_sat,
+            fees_per_day_avg: revenue_sat as f64 / days,
+            uptime_percentage,
+            rebalancing_frequency: rebalances as f64 / days * 7.0,
+        };
+
+        // Part des revenus conservée après rééquilibrage : des frais trop bas la font chuter
+        let fee_optimization_score = if revenue_sat + rebalance_cost_sat == 0 {
+            0.0
+        } else {
+            revenue_sat as f64 / (revenue_sat + rebalance_cost_sat) as f64 * 100.0
+        };
+        let profitability_metrics = ChannelProfitabilityMetrics {
+            roi_annualized: pnl.annualized_yield_pct.unwrap_or(0.0),
+            profit_per_sat: pnl.net_pnl_sat as f64 / subject.capacity.max(1) as f64,
+            fee_optimization_score,
+            // Rotation annualisée du capital local
+            capital_efficiency: if average_capital > 0.0 {
+                volume_out_sat as f64 / average_capital * 365.0 / days
+            } else {
+                0.0
+            },
+            opportunity_cost: pnl.capital_sat_days * self.config.opportunity_yield_pct
+                / 100.0
+                / 365.0,
+            // Non sérialisable en JSON : un canal qui ne se rembourse pas donne `null`
+            break_even_days: pnl.payback_days.unwrap_or(f64::INFINITY),
+        };
+
+        let peer_closes: Vec<&LocalClosedChannel> = inputs
+            .closed
+            .iter()
+            .filter(|c| c.remote_pubkey == subject.peer_pubkey)
+            .collect();
+        let local_ratio = subject.local_balance as f64 / subject.capacity.max(1) as f64;
+        let risk_metrics = ChannelRiskMetrics {
+            peer_reliability_score: inputs
+                .reputations
+                .iter()
+                .find(|r| r.peer_pubkey == subject.peer_pubkey)
+                .map_or(50.0, |r| r.score),
+            liquidity_risk: if subject.closed.is_some() {
+                0.0
+            } else {
+                2.0 * (local_ratio - 0.5).abs()
+            },
+            force_close_probability: if peer_closes.is_empty() {
+                0.0
+            } else {
+                peer_closes
+                    .iter()
+                    .filter(|c| c.close_type.is_force())
+                    .count() as f64
+                    / peer_closes.len() as f64
+            },
+            // Un pair très connecté offre des routes concurrentes aux nôtres
+            routing_competition: inputs
+                .graph
+                .node(subject.peer_pubkey)
+                .map_or(0.0, |n| n.degree_percentile / 100.0),
+            concentration_risk: concentration,
+        };
+
+        let mut optimization_suggestions = vec![];
+        if subject.closed.is_none() {
+            if rebalance_cost_sat > revenue_sat {
+                optimization_suggestions.push(ChannelOptimization {
+                    optimization_type: OptimizationType::FeeAdjustment,
+                    description: format!(
+                        "Les rééquilibrages ({} sat) coûtent plus que le canal ne rapporte ({} sat) : relever les frais sortants",
+                        rebalance_cost_sat, revenue_sat
+                    ),
+                    expected_improvement: 100.0 - fee_optimization_score,
+                    implementation_cost: 0,
+                    confidence_level: 0.6,
+                    priority: OptimizationPriority::High,
+                });
+            }
+            if risk_metrics.liquidity_risk >= 0.8 {
+                optimization_suggestions.push(ChannelOptimization {
+                    optimization_type: OptimizationType::Rebalancing,
+                    description: format!(
+                        "Solde local à {:.0}% de la capacité : le canal ne route plus que dans un sens",
+                        local_ratio * 100.0
+                    ),
+                    expected_improvement: risk_metrics.liquidity_risk * 100.0 - 50.0,
+                    implementation_cost: 0,
+                    confidence_level: 0.5,
+                    priority: OptimizationPriority::Medium,
+                });
+            }
+            if concentration >= self.config.concentration_threshold {
+                optimization_suggestions.push(ChannelOptimization {
+                    optimization_type: OptimizationType::PeerDiversification,
+                    description: format!(
+                        "{:.0}% de la capacité du nœud chez ce pair",
+                        concentration * 100.0
+                    ),
+                    expected_improvement: (concentration - self.config.concentration_threshold)
+                        * 100.0,
+                    implementation_cost: 0,
+                    confidence_level: 0.5,
+                    priority: OptimizationPriority::Low,
+                });
+            }
+        }
+
+        ChannelAnalytics {
+            channel_id: id.to_string(),
+            pnl,
+            performance_metrics,
+            profitability_metrics,
+            risk_metrics,
+            optimization_suggestions,
+            last_updated: inputs.now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::local_lightning_client::LocalCloseType;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    const BLOCK_HEIGHT: u32 = 850_000;
+
+    /// Identifiant court d'un canal ouvert `days` jours avant la hauteur courante
+    fn scid(days: u32, index: u64) -> String {
+        let height = (BLOCK_HEIGHT - days * 144) as u64;
+        ((height << 40) | index).to_string()
+    }
+
+    fn channel(id: &str, peer: &str, local: u64) -> LocalChannelInfo {
+        LocalChannelInfo {
+            channel_point: format!("tx_{}:0", id),
+            peer_pubkey: peer.to_string(),
+            ..test_support::channel(id, 2_000_000, local)
+        }
+    }
+
+    fn forward(
+        at: DateTime<Utc>,
+        chan_in: &str,
+        chan_out: &str,
+        fee_sat: u64,
+    ) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+            chan_id_in: chan_in.to_string(),
+            chan_id_out: chan_out.to_string(),
+            amt_in_msat: 500_000_000 + fee_sat * 1000,
+            amt_out_msat: 500_000_000,
+            fee_msat: fee_sat * 1000,
+        }
+    }
+
+    fn inputs<'a>(
+        now: DateTime<Utc>,
+        channels: &'a [LocalChannelInfo],
+        closed: &'a [LocalClosedChannel],
+        events: &'a [LocalForwardingEvent],
+        rebalances: &'a [LocalRebalance],
+        wallet_fees: &'a HashMap<String, u64>,
+        graph: &'a GraphAnalysis,
+    ) -> ChannelPnlInputs<'a> {
+        ChannelPnlInputs {
+            now,
+            block_height: BLOCK_HEIGHT,
+            channels,
+            closed,
+            events,
+            snapshots: &[],
+            rebalances,
+            wallet_fees,
+            onchain_fee_rate: 10.0,
+            reputations: &[],
+            graph,
+        }
+    }
+
+    #[test]
+    fn test_net_pnl_subtracts_all_costs() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let (a, b) = (scid(100, 1), scid(100, 2));
+        let channels = [
+            channel(&a, "peer_a", 1_000_000),
+            channel(&b, "peer_b", 1_000_000),
+        ];
+        // 50 forwards a → b à 100 sat, et un rééquilibrage b → a à 600 sat
+        let events: Vec<_> = (1..=50)
+            .map(|d| forward(now - Duration::days(d), &a, &b, 100))
+            .collect();
+        let rebalances = [LocalRebalance {
+            outgoing_channel_id: b.clone(),
+            incoming_channel_id: a.clone(),
+            amount_msat: 1_000_000_000,
+            fee_msat: 600_000,
+            settled_at_ns: (now - Duration::days(10)).timestamp_nanos_opt().unwrap() as u64,
+        }];
+        let wallet_fees = HashMap::from([(format!("tx_{}", a), 1_500)]);
+        let graph = GraphAnalysis::default();
+
+        let analytics = ChannelPnlCalculator::default().analyze(&inputs(
+            now,
+            &channels,
+            &[],
+            &events,
+            &rebalances,
+            &wallet_fees,
+            &graph,
+        ));
+
+        let by_id = |id: &str| analytics.iter().find(|c| c.channel_id == id).unwrap();
+        let (pnl_a, pnl_b) = (&by_id(&a).pnl, &by_id(&b).pnl);
+        assert_eq!(pnl_a.revenue_in_sat, 2_500);
+        assert_eq!(pnl_b.revenue_out_sat, 2_500);
+        assert_eq!(pnl_a.rebalance_in_cost_sat, 300);
+        assert_eq!(pnl_b.rebalance_out_cost_sat, 300);
+        assert_eq!(pnl_a.open_cost_sat, 1_500);
+        assert_eq!(pnl_b.open_cost_sat, 0);
+        assert!(pnl_a.close_cost_estimated);
+        assert_eq!(pnl_a.close_cost_sat, 1_700);
+        assert_eq!(pnl_a.net_pnl_sat, 2_500 - 300 - 1_500 - 1_700);
+        assert_eq!(pnl_b.net_pnl_sat, 2_500 - 300 - 1_700);
+        // Le canal b, sans coût d'ouverture, passe devant
+        assert_eq!(analytics[0].channel_id, b);
+
+        // 1 M sat locaux pendant 100 jours
+        assert!((pnl_a.capital_sat_days - 100_000_000.0).abs() < 1.0);
+        let expected_yield = -1_000.0 / 100_000_000.0 * 365.0 * 100.0;
+        assert!((pnl_a.annualized_yield_pct.unwrap() - expected_yield).abs() < 1e-6);
+        // 2 200 sat nets d'exploitation en 100 jours pour couvrir 3 200 sat
+        assert!((pnl_a.payback_days.unwrap() - 3_200.0 / 22.0).abs() < 1e-6);
+        assert_eq!(by_id(&a).performance_metrics.forwards_per_day, 0.5);
+    }
+
+    #[test]
+    fn test_closed_channel_uses_actual_close_cost() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let id = scid(60, 3);
+        let closed = [LocalClosedChannel {
+            channel_id: id.clone(),
+            remote_pubkey: "peer_c".to_string(),
+            capacity: 2_000_000,
+            // Fermé 20 jours avant la hauteur courante
+            close_height: BLOCK_HEIGHT - 20 * 144,
+            close_type: LocalCloseType::RemoteForce,
+            channel_point: format!("tx_{}:0", id),
+            closing_tx_hash: "close_tx".to_string(),
+        }];
+        let events = vec![
+            forward(now - Duration::days(30), "x", &id, 400),
+            // Après la fermeture : hors période
+            forward(now - Duration::days(5), "x", &id, 400),
+        ];
+        let wallet_fees = HashMap::from([("close_tx".to_string(), 2_000)]);
+        let graph = GraphAnalysis::default();
+
+        let analytics = ChannelPnlCalculator::default().analyze(&inputs(
+            now,
+            &[],
+            &closed,
+            &events,
+            &[],
+            &wallet_fees,
+            &graph,
+        ));
+
+        assert_eq!(analytics.len(), 1);
+        let channel = &analytics[0];
+        assert!(channel.pnl.closed);
+        assert!(!channel.pnl.close_cost_estimated);
+        assert!((channel.pnl.days - 40.0).abs() < 1e-6);
+        assert_eq!(channel.pnl.revenue_out_sat, 200);
+        assert_eq!(channel.pnl.net_pnl_sat, 200 - 2_000);
+        assert_eq!(channel.pnl.annualized_yield_pct, None);
+        assert_eq!(channel.pnl.payback_days, Some(2_000.0 / 5.0));
+        assert_eq!(channel.risk_metrics.force_close_probability, 1.0);
+        assert!(channel.optimization_suggestions.is_empty());
+    }
+}
@@ -0,0 +1,442 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+    storage::forwarding::{uptime_samples, ChannelHistoryPoint, ForwardingStore},
+    utils::onchain::{
+        BLOCKS_PER_DAY, COOP_CLOSE_VBYTES, DEFAULT_ONCHAIN_FEE_RATE, FORCE_CLOSE_VBYTES,
+        OPEN_CHANNEL_VBYTES,
+    },
+};
+
+/// Seuils de détection et hypothèses du bilan de fermeture.
+#[derive(Debug, Clone)]
+pub struct CloseFinderConfig {
+    /// Profondeur de l'historique de forwarding et des instantanés analysés
+    pub history_days: u32,
+    pub idle_days: u32,
+    pub min_uptime: f64,
+    pub min_uptime_snapshots: usize,
+    /// Part locale au-delà de laquelle la liquidité est considérée bloquée
+    pub stuck_local_ratio: f64,
+    pub stuck_days: u32,
+    /// Délai CSV supposé pour récupérer nos fonds après une fermeture forcée
+    pub force_close_csv_blocks: u32,
+    /// Horizon sur lequel le capital libéré est valorisé
+    pub horizon_days: u32,
+    /// Rendement annuel (%) du capital redéployé quand le nœud n'a pas d'historique
+    pub default_yield_pct: f64,
+}
+
+impl Default for CloseFinderConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 60,
+            idle_days: 30,
+            min_uptime: 0.6,
+            min_uptime_snapshots: 12,
+            stuck_local_ratio: 0.9,
+            stuck_days: 14,
+            force_close_csv_blocks: 144,
+            horizon_days: 90,
+            default_yield_pct: 1.0,
+        }
+    }
+}
+
+/// Motif de fermeture détecté.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloseReason {
+    /// Aucun forward depuis `days` jours
+    Idle { days: i64 },
+    /// Pair joignable sur une fraction `uptime` des relevés
+    OfflinePeer { uptime: f64 },
+    /// Liquidité locale immobilisée au-dessus de `local_ratio`
+    StuckLiquidity { local_ratio: f64 },
+    /// Revenu inférieur au coût d'ouverture estimé
+    NegativePnl { pnl_sat: i64 },
+}
+
+impl CloseReason {
+    pub fn describe(&self) -> String {
+        match self {
+            CloseReason::Idle { days } => format!("Aucun forward depuis {} jours", days),
+            CloseReason::OfflinePeer { uptime } => {
+                format!("Pair en ligne sur {:.0}% des relevés", uptime * 100.0)
+            }
+            CloseReason::StuckLiquidity { local_ratio } => format!(
+                "{:.0}% de la capacité bloquée côté local sans flux sortant",
+                local_ratio * 100.0
+            ),
+            CloseReason::NegativePnl { pnl_sat } => {
+                format!("P&L du canal négatif : {} sat", pnl_sat)
+            }
+        }
+    }
+}
+
+/// Coûts d'une fermeture coopérative et d'une fermeture forcée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseCost {
+    pub onchain_fee_rate_sat_vb: f64,
+    pub cooperative_sat: u64,
+    pub force_sat: u64,
+    pub force_timelock_blocks: u32,
+    /// Rendement perdu pendant le verrouillage CSV d'une fermeture forcée
+    pub force_timelock_opportunity_sat: u64,
+}
+
+/// Canal candidat à la fermeture et son bilan coût / bénéfice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseCandidate {
+    pub channel_id: String,
+    pub channel_point: String,
+    pub peer_pubkey: String,
+    pub peer_alias: String,
+    pub reasons: Vec<CloseReason>,
+    pub local_balance_sat: u64,
+    pub observed_days: i64,
+    pub revenue_sat: u64,
+    pub lifetime_pnl_sat: i64,
+    pub uptime: Option<f64>,
+    pub close_cost: CloseCost,
+    /// Revenu espéré du canal sur l'horizon s'il reste ouvert
+    pub expected_revenue_sat: u64,
+    /// Revenu espéré du capital libéré, redéployé au rendement moyen du nœud
+    pub reallocation_value_sat: u64,
+    pub reopen_cost_sat: u64,
+    pub net_benefit_sat: i64,
+    pub horizon_days: u32,
+    pub recommended: bool,
+    pub rationale: Vec<String>,
+}
+
+/// Données observées pour l'analyse des fermetures.
+pub struct CloseInputs<'a> {
+    pub now: DateTime<Utc>,
+    pub channels: &'a [LocalChannelInfo],
+    pub events: &'a [LocalForwardingEvent],
+    pub snapshots: &'a [ChannelHistoryPoint],
+    pub onchain_fee_rate: f64,
+}
+
+/// Détection des canaux à fermer : inactivité, pair absent, liquidité bloquée, P&L négatif.
+#[derive(Debug, Clone, Default)]
+pub struct CloseCandidateFinder {
+    pub config: CloseFinderConfig,
+}
+
+impl CloseCandidateFinder {
+    pub fn new(config: CloseFinderConfig) -> Self {
+        Self { config }
+    }
+
+    /// Analyse à partir de l'historique persisté, du plus avantageux au moins avantageux.
+    pub async fn find_stored(
+        &self,
+        forwarding_store: &ForwardingStore,
+        channels: &[LocalChannelInfo],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<CloseCandidate>> {
+        let since = now - Duration::days(self.config.history_days as i64);
+        let events = forwarding_store.events_between(since, now).await?;
+        let snapshots = forwarding_store
+            .channel_snapshots_between(since, now)
+            .await?;
+        let onchain_fee_rate = forwarding_store
+            .fee_estimates_since(now - Duration::days(1))
+            .await?
+            .last()
+            .map_or(DEFAULT_ONCHAIN_FEE_RATE, |sample| sample.half_hour as f64);
+
+        Ok(self.find(&CloseInputs {
+            now,
+            channels,
+            events: &events,
+            snapshots: &snapshots,
+            onchain_fee_rate,
+        }))
+    }
+
+    pub fn find(&self, inputs: &CloseInputs) -> Vec<CloseCandidate> {
+        let config = &self.config;
+        let history_start = inputs.now - Duration::days(config.history_days as i64);
+        let fee_rate = inputs.onchain_fee_rate;
+        let onchain = |vbytes: u64| (vbytes as f64 * fee_rate).round() as u64;
+
+        // Rendement annuel moyen du capital local, sur l'historique observé
+        let node_revenue_sat: u64 = inputs.events.iter().map(|e| e.fee_msat).sum::<u64>() / 1000;
+        let node_local_sat: u64 = inputs.channels.iter().map(|c| c.local_balance).sum();
+        let node_yield = if node_revenue_sat > 0 && node_local_sat > 0 {
+            node_revenue_sat as f64 / node_local_sat as f64 * 365.0 / config.history_days as f64
+        } else {
+            config.default_yield_pct / 100.0
+        };
+        let horizon_years = config.horizon_days as f64 / 365.0;
+
+        let mut candidates: Vec<CloseCandidate> = inputs
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                let id = &channel.channel_id;
+                let snapshots: Vec<&ChannelHistoryPoint> = inputs
+                    .snapshots
+                    .iter()
+                    .filter(|s| &s.channel.channel_id == id)
+                    .collect();
+                let observed_since = snapshots
+                    .first()
+                    .map_or(inputs.now, |s| s.taken_at)
+                    .max(history_start);
+                let observed_days = (inputs.now - observed_since).num_days();
+
+                let events: Vec<&LocalForwardingEvent> = inputs
+                    .events
+                    .iter()
+                    .filter(|e| &e.chan_id_in == id || &e.chan_id_out == id)
+                    .collect();
+                let last_forward = events.iter().map(|e| e.timestamp_ns).max().map(|ns| Utc.timestamp_nanos(ns as i64));
+                let revenue_sat = events
+                    .iter()
+                    .filter(|e| &e.chan_id_out == id)
+                    .map(|e| e.fee_msat)
+                    .sum::<u64>()
+                    / 1000;
+
+                let mut reasons = vec![];
+
+                let idle_days = (inputs.now - last_forward.unwrap_or(observed_since)).num_days();
+                if observed_days >= config.idle_days as i64 && idle_days >= config.idle_days as i64
+                {
+                    reasons.push(CloseReason::Idle { days: idle_days });
+                }
+
+                // Agrégats horaires et journaliers pondérés par les relevés qu'ils représentent
+                let (active_samples, samples) = uptime_samples(snapshots.iter().copied());
+                let uptime = (samples >= config.min_uptime_snapshots as u64)
+                    .then(|| active_samples as f64 / samples as f64);
+                if let Some(uptime) = uptime.filter(|u| *u < config.min_uptime) {
+                    reasons.push(CloseReason::OfflinePeer { uptime });
+                }
+
+                let stuck_since = inputs.now - Duration::days(config.stuck_days as i64);
+                let recent: Vec<&&ChannelHistoryPoint> = snapshots
+                    .iter()
+                    .filter(|s| s.taken_at >= stuck_since)
+                    .collect();
+                let local_ratio =
+                    |c: &LocalChannelInfo| c.local_balance as f64 / c.capacity.max(1) as f64;
+                let outgoing_recent_sat = events
+                    .iter()
+                    .filter(|e| {
+                        &e.chan_id_out == id
+                            && e.timestamp_ns
+                                >= stuck_since.timestamp_nanos_opt().unwrap_or(0) as u64
+                    })
+                    .map(|e| e.amt_out_msat)
+                    .sum::<u64>()
+                    / 1000;
+                // Le solde local minimal de chaque point : bloqué même au plus bas de la période
+                let min_local_ratio = |s: &ChannelHistoryPoint| {
+                    s.local_balance_min as f64 / s.channel.capacity.max(1) as f64
+                };
+                if observed_days >= config.stuck_days as i64
+                    && recent.iter().map(|s| s.samples as u64).sum::<u64>() >= 2
+                    && recent
+                        .iter()
+                        .all(|s| min_local_ratio(s) >= config.stuck_local_ratio)
+                    && outgoing_recent_sat * 20 < channel.local_balance
+                {
+                    reasons.push(CloseReason::StuckLiquidity {
+                        local_ratio: local_ratio(channel),
+                    });
+                }
+
+                // Initiateur supposé local : l'ouverture est valorisée au taux courant
+                let open_cost_sat = onchain(OPEN_CHANNEL_VBYTES);
+                let lifetime_pnl_sat = revenue_sat as i64 - open_cost_sat as i64;
+                if observed_days >= config.idle_days as i64 && lifetime_pnl_sat < 0 {
+                    reasons.push(CloseReason::NegativePnl {
+                        pnl_sat: lifetime_pnl_sat,
+                    });
+                }
+
+                if reasons.is_empty() {
+                    return None;
+                }
+
+                let local = channel.local_balance as f64;
+                let timelock_days = config.force_close_csv_blocks as f64 / BLOCKS_PER_DAY as f64;
+                let close_cost = CloseCost {
+                    onchain_fee_rate_sat_vb: fee_rate,
+                    cooperative_sat: onchain(COOP_CLOSE_VBYTES),
+                    force_sat: onchain(FORCE_CLOSE_VBYTES),
+                    force_timelock_blocks: config.force_close_csv_blocks,
+                    force_timelock_opportunity_sat: (local * node_yield * timelock_days / 365.0)
+                        .round() as u64,
+                };
+
+                let expected_revenue_sat = (revenue_sat as f64 * config.horizon_days as f64
+                    / observed_days.max(1) as f64)
+                    .round() as u64;
+                let reallocation_value_sat = (local * node_yield * horizon_years).round() as u64;
+                let reopen_cost_sat = open_cost_sat;
+                let net_benefit_sat = reallocation_value_sat as i64
+                    - expected_revenue_sat as i64
+                    - close_cost.cooperative_sat as i64
+                    - reopen_cost_sat as i64;
+                let recommended = net_benefit_sat > 0;
+
+                let mut rationale: Vec<String> = reasons.iter().map(CloseReason::describe).collect();
+                rationale.push(format!(
+                    "{} sat libérés rapporteraient ~{} sat sur {} jours au rendement moyen du nœud ({:.2}%/an), contre ~{} sat en restant ouvert",
+                    channel.local_balance,
+                    reallocation_value_sat,
+                    config.horizon_days,
+                    node_yield * 100.0,
+                    expected_revenue_sat
+                ));
+                rationale.push(format!(
+                    "Fermeture coopérative ~{} sat et réouverture ~{} sat à {:.0} sat/vB : bénéfice net {} sat",
+                    close_cost.cooperative_sat, reopen_cost_sat, fee_rate, net_benefit_sat
+                ));
+                if !channel.active {
+                    rationale.push(format!(
+                        "Pair hors ligne : la fermeture coopérative attend son retour ; une fermeture forcée coûterait ~{} sat et bloquerait les fonds {} blocs",
+                        close_cost.force_sat + close_cost.force_timelock_opportunity_sat,
+                        config.force_close_csv_blocks
+                    ));
+                }
+
+                Some(CloseCandidate {
+                    channel_id: id.clone(),
+                    channel_point: channel.channel_point.clone(),
+                    peer_pubkey: channel.peer_pubkey.clone(),
+                    peer_alias: channel.peer_alias.clone(),
+                    reasons,
+                    local_balance_sat: channel.local_balance,
+                    observed_days,
+                    revenue_sat,
+                    lifetime_pnl_sat,
+                    uptime,
+                    close_cost,
+                    expected_revenue_sat,
+                    reallocation_value_sat,
+                    reopen_cost_sat,
+                    net_benefit_sat,
+                    horizon_days: config.horizon_days,
+                    recommended,
+                    rationale,
+                })
+            })
+            .collect();
+
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.net_benefit_sat));
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn channel(id: &str, local: u64, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            active,
+            ..test_support::channel(id, 10_000_000, local)
+        }
+    }
+
+    fn snapshots(
+        channel: &LocalChannelInfo,
+        now: DateTime<Utc>,
+        days: i64,
+        active: impl Fn(i64) -> bool,
+    ) -> Vec<ChannelHistoryPoint> {
+        (0..days)
+            .rev()
+            .map(|d| {
+                let mut channel = channel.clone();
+                channel.active = active(d);
+                ChannelHistoryPoint::sample(now - Duration::days(d) - Duration::hours(1), channel)
+            })
+            .collect()
+    }
+
+    fn forward(out: &str, at: DateTime<Utc>, fee_msat: u64) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+            chan_id_in: "x".to_string(),
+            chan_id_out: out.to_string(),
+            amt_in_msat: 1_000_000_000 + fee_msat,
+            amt_out_msat: 1_000_000_000,
+            fee_msat,
+        }
+    }
+
+    #[test]
+    fn test_idle_stuck_channel_is_recommended_for_closing() {
+        let now = Utc::now();
+        let idle = channel("idle", 9_500_000, true);
+        let busy = channel("busy", 5_000_000, true);
+        let mut history = snapshots(&idle, now, 50, |_| true);
+        history.extend(snapshots(&busy, now, 50, |_| true));
+        let events: Vec<_> = (1..50)
+            .map(|d| forward("busy", now - Duration::days(d), 20_000_000))
+            .collect();
+
+        let candidates = CloseCandidateFinder::default().find(&CloseInputs {
+            now,
+            channels: &[idle, busy],
+            events: &events,
+            snapshots: &history,
+            onchain_fee_rate: 5.0,
+        });
+
+        assert_eq!(candidates.len(), 1);
+        let candidate = &candidates[0];
+        assert_eq!(candidate.channel_id, "idle");
+        assert!(candidate
+            .reasons
+            .iter()
+            .any(|r| matches!(r, CloseReason::Idle { .. })));
+        assert!(candidate
+            .reasons
+            .iter()
+            .any(|r| matches!(r, CloseReason::StuckLiquidity { .. })));
+        assert!(candidate.recommended);
+        assert!(candidate.reallocation_value_sat > candidate.close_cost.cooperative_sat);
+    }
+
+    #[test]
+    fn test_offline_peer_costs_favour_cooperative_close() {
+        let now = Utc::now();
+        let offline = channel("offline", 3_000_000, false);
+        let history = snapshots(&offline, now, 20, |d| d % 4 == 0);
+        let events = vec![forward("offline", now - Duration::days(2), 1_000)];
+
+        let candidates = CloseCandidateFinder::default().find(&CloseInputs {
+            now,
+            channels: &[offline],
+            events: &events,
+            snapshots: &history,
+            onchain_fee_rate: 20.0,
+        });
+
+        let candidate = &candidates[0];
+        assert!(matches!(
+            candidate.reasons[..],
+            [CloseReason::OfflinePeer { uptime }] if uptime < 0.6
+        ));
+        let cost = &candidate.close_cost;
+        assert!(cost.force_sat + cost.force_timelock_opportunity_sat > cost.cooperative_sat);
+        assert!(candidate
+            .rationale
+            .iter()
+            .any(|r| r.contains("fermeture forcée")));
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::api::local_lightning_client::{
+    LocalLightningClient, LocalNetworkGraph, LocalRoutingPolicy,
+};
+use crate::utils::network_graph::{percentile_rank, GraphAnalysis};
+
+/// Notre tarif vers un pair, comparé à celui des autres nœuds qui y mènent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerFeePosition {
+    pub channel_id: String,
+    pub peer_pubkey: String,
+    pub peer_alias: String,
+    pub our_fee_rate_ppm: u64,
+    pub our_base_fee_msat: u64,
+    /// Rang centile (0-100) de notre taux parmi toutes les politiques actives du réseau
+    pub network_fee_percentile: f64,
+    /// Canaux actifs d'autres nœuds vers ce pair
+    pub competitors: usize,
+    pub competitor_p25_ppm: Option<u64>,
+    pub competitor_median_ppm: Option<u64>,
+    pub competitor_p75_ppm: Option<u64>,
+    /// Rang centile de notre taux parmi les concurrents : 0 = le moins cher
+    pub competitor_fee_percentile: Option<f64>,
+    /// Part de notre canal dans la capacité totale menant à ce pair
+    pub capacity_share: f64,
+}
+
+/// Position du nœud dans le graphe public, en rangs centiles (0-100).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetitivePositioning {
+    pub computed_at: DateTime<Utc>,
+    /// Nœuds du graphe ayant au moins un canal
+    pub network_nodes: usize,
+    pub capacity_sat: u64,
+    pub capacity_percentile: f64,
+    pub channels: usize,
+    pub channels_percentile: f64,
+    pub centrality_percentile: f64,
+    /// Taux médian de nos politiques publiques
+    pub median_fee_rate_ppm: Option<u64>,
+    /// Rang de notre taux médian parmi les taux médians des autres nœuds
+    pub median_fee_percentile: Option<f64>,
+    pub peers: Vec<PeerFeePosition>,
+}
+
+/// Politique active fixée par `from` sur un canal vers `to`.
+struct DirectedPolicy<'a> {
+    channel_id: &'a str,
+    from: &'a str,
+    to: &'a str,
+    capacity: u64,
+    policy: &'a LocalRoutingPolicy,
+}
+
+fn directed_policies(graph: &LocalNetworkGraph) -> Vec<DirectedPolicy<'_>> {
+    graph
+        .edges
+        .iter()
+        .flat_map(|edge| {
+            [
+                (&edge.node1_pub, &edge.node2_pub, &edge.node1_policy),
+                (&edge.node2_pub, &edge.node1_pub, &edge.node2_policy),
+            ]
+            .into_iter()
+            .filter_map(move |(from, to, policy)| {
+                let policy = policy.as_ref().filter(|p| !p.disabled)?;
+                Some(DirectedPolicy {
+                    channel_id: &edge.channel_id,
+                    from,
+                    to,
+                    capacity: edge.capacity,
+                    policy,
+                })
+            })
+        })
+        .collect()
+}
+
+/// Quantile `q` (0-1) d'une liste triée, au rang le plus proche
+fn quantile(sorted: &[u64], q: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let index = ((sorted.len() - 1) as f64 * q).round() as usize;
+    Some(sorted[index])
+}
+
+fn median(values: &mut [u64]) -> Option<u64> {
+    values.sort_unstable();
+    quantile(values, 0.5)
+}
+
+impl CompetitivePositioning {
+    pub async fn load_live(
+        client: &Mutex<LocalLightningClient>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        let (pubkey, graph) = {
+            let mut client = client.lock().await;
+            let pubkey = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .pubkey;
+            (pubkey, client.describe_graph().await?)
+        };
+        Ok(Self::compute(&graph, &pubkey, now))
+    }
+
+    /// `None` si notre nœud n'apparaît pas dans le graphe (aucun canal public).
+    pub fn compute(
+        graph: &LocalNetworkGraph,
+        own_pubkey: &str,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let analysis = GraphAnalysis::new(graph);
+        let own = analysis.node(own_pubkey).filter(|node| node.channels > 0)?;
+
+        let policies = directed_policies(graph);
+        let mut network_ppm: Vec<u64> = policies.iter().map(|p| p.policy.fee_rate_ppm).collect();
+        network_ppm.sort_unstable();
+
+        // Taux médian de chaque nœud, pour situer le nôtre parmi les opérateurs
+        let mut ppm_by_node: HashMap<&str, Vec<u64>> = HashMap::new();
+        for policy in &policies {
+            ppm_by_node
+                .entry(policy.from)
+                .or_default()
+                .push(policy.policy.fee_rate_ppm);
+        }
+        let mut own_ppm = ppm_by_node.remove(own_pubkey).unwrap_or_default();
+        let median_fee_rate_ppm = median(&mut own_ppm);
+        let mut node_medians: Vec<u64> = ppm_by_node
+            .values_mut()
+            .filter_map(|ppm| median(ppm))
+            .collect();
+        node_medians.sort_unstable();
+
+        // Capacité totale menant à chaque pair, toutes origines confondues
+        let mut inbound_capacity: HashMap<&str, u64> = HashMap::new();
+        for edge in &graph.edges {
+            *inbound_capacity.entry(&edge.node1_pub).or_default() += edge.capacity;
+            *inbound_capacity.entry(&edge.node2_pub).or_default() += edge.capacity;
+        }
+
+        let mut peers: Vec<PeerFeePosition> = policies
+            .iter()
+            .filter(|p| p.from == own_pubkey)
+            .map(|ours| {
+                let mut competitor_ppm: Vec<u64> = policies
+                    .iter()
+                    .filter(|p| p.to == ours.to && p.from != own_pubkey)
+                    .map(|p| p.policy.fee_rate_ppm)
+                    .collect();
+                competitor_ppm.sort_unstable();
+                let our_ppm = ours.policy.fee_rate_ppm;
+
+                PeerFeePosition {
+                    channel_id: ours.channel_id.to_string(),
+                    peer_pubkey: ours.to.to_string(),
+                    peer_alias: analysis
+                        .node(ours.to)
+                        .map(|node| node.alias.clone())
+                        .unwrap_or_default(),
+                    our_fee_rate_ppm: our_ppm,
+                    our_base_fee_msat: ours.policy.base_fee_msat,
+                    network_fee_percentile: percentile_rank(&network_ppm, &our_ppm),
+                    competitors: competitor_ppm.len(),
+                    competitor_p25_ppm: quantile(&competitor_ppm, 0.25),
+                    competitor_median_ppm: quantile(&competitor_ppm, 0.5),
+                    competitor_p75_ppm: quantile(&competitor_ppm, 0.75),
+                    competitor_fee_percentile: (!competitor_ppm.is_empty())
+                        .then(|| percentile_rank(&competitor_ppm, &our_ppm)),
+                    capacity_share: ours.capacity as f64
+                        / inbound_capacity.get(ours.to).copied().unwrap_or(0).max(1) as f64,
+                }
+            })
+            .collect();
+        // Les pairs où nous sommes les plus chers d'abord
+        peers.sort_by(|a, b| {
+            b.competitor_fee_percentile
+                .unwrap_or(0.0)
+                .total_cmp(&a.competitor_fee_percentile.unwrap_or(0.0))
+        });
+
+        Some(Self {
+            computed_at: now,
+            network_nodes: analysis.connected_nodes(),
+            capacity_sat: own.capacity_sat,
+            capacity_percentile: own.capacity_percentile,
+            channels: own.channels,
+            channels_percentile: own.degree_percentile,
+            centrality_percentile: analysis.centrality(own_pubkey).unwrap_or(0.0),
+            median_fee_percentile: median_fee_rate_ppm
+                .map(|ppm| percentile_rank(&node_medians, &ppm)),
+            median_fee_rate_ppm,
+            peers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::local_lightning_client::{LocalGraphEdge, LocalGraphNode};
+
+    fn node(pubkey: &str) -> LocalGraphNode {
+        LocalGraphNode {
+            pubkey: pubkey.to_string(),
+            alias: pubkey.to_uppercase(),
+            last_update: 0,
+            features: vec![],
+        }
+    }
+
+    fn policy(fee_rate_ppm: u64) -> Option<LocalRoutingPolicy> {
+        Some(LocalRoutingPolicy {
+            base_fee_msat: 1_000,
+            fee_rate_ppm,
+            time_lock_delta: 40,
+            disabled: false,
+            last_update: 0,
+        })
+    }
+
+    /// Canal `a`-`b` : `a_ppm` est le tarif de `a` vers `b`, `b_ppm` l'inverse
+    fn edge(id: &str, a: &str, b: &str, capacity: u64, a_ppm: u64, b_ppm: u64) -> LocalGraphEdge {
+        LocalGraphEdge {
+            channel_id: id.to_string(),
+            node1_pub: a.to_string(),
+            node2_pub: b.to_string(),
+            capacity,
+            node1_policy: policy(a_ppm),
+            node2_policy: policy(b_ppm),
+        }
+    }
+
+    fn graph() -> LocalNetworkGraph {
+        LocalNetworkGraph {
+            nodes: ["us", "hub", "x", "y", "z"].map(node).to_vec(),
+            edges: vec![
+                edge("1", "us", "hub", 2_000_000, 800, 100),
+                edge("2", "x", "hub", 4_000_000, 200, 100),
+                edge("3", "hub", "y", 3_000_000, 100, 400),
+                edge("4", "z", "hub", 1_000_000, 600, 100),
+                edge("5", "us", "x", 1_000_000, 50, 300),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_peer_fee_compared_with_competitors() {
+        let now = Utc::now();
+        let report = CompetitivePositioning::compute(&graph(), "us", now).unwrap();
+
+        assert_eq!(report.channels, 2);
+        assert_eq!(report.capacity_sat, 3_000_000);
+        assert_eq!(report.network_nodes, 5);
+        assert_eq!(report.median_fee_rate_ppm, Some(800));
+
+        // Vers hub, x (200), y (400) et z (600) nous concurrencent ; nous sommes les plus chers
+        let hub = &report.peers[0];
+        assert_eq!(hub.peer_pubkey, "hub");
+        assert_eq!(hub.peer_alias, "HUB");
+        assert_eq!(hub.competitors, 3);
+        assert_eq!(hub.competitor_median_ppm, Some(400));
+        assert_eq!(hub.competitor_fee_percentile, Some(100.0));
+        // 2 M sur les 10 M menant à hub
+        assert!((hub.capacity_share - 0.2).abs() < 1e-9);
+
+        // Vers x, seul hub concurrence (100 ppm) et nous sommes moins chers
+        let x = &report.peers[1];
+        assert_eq!(x.peer_pubkey, "x");
+        assert_eq!(x.competitors, 1);
+        assert_eq!(x.competitor_fee_percentile, Some(0.0));
+    }
+
+    #[test]
+    fn test_unknown_node_has_no_positioning() {
+        assert!(CompetitivePositioning::compute(&graph(), "missing", Utc::now()).is_none());
+        assert!(
+            CompetitivePositioning::compute(&LocalNetworkGraph::default(), "us", Utc::now())
+                .is_none()
+        );
+    }
+}
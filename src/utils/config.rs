@@ -1,6 +1,26 @@
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
 use std::env;
 
+/// Durée de conservation de chaque résolution avant agrégation à la suivante ;
+/// les points journaliers sont supprimés au-delà de `day`.
+#[derive(Debug, Clone)]
+pub struct SnapshotRetention {
+    pub minute: Duration,
+    pub hour: Duration,
+    pub day: Duration,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self {
+            minute: Duration::days(2),
+            hour: Duration::days(30),
+            day: Duration::days(730),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub mcp_api_url: String,
@@ -10,6 +30,16 @@ pub struct AppConfig {
     pub lnd_macaroon_path: String,
     pub lnd_tls_cert_path: String,
     pub server_port: u16,
+    /// Durée de validité d'une recommandation avant expiration automatique
+    pub recommendation_ttl_hours: i64,
+    /// Intervalle entre deux relevés de l'état des canaux
+    pub channel_snapshot_interval_secs: u64,
+    /// Conservation des relevés bruts, puis des agrégats horaires et journaliers
+    pub snapshot_minute_retention_days: i64,
+    pub snapshot_hour_retention_days: i64,
+    pub snapshot_day_retention_days: i64,
+    /// Jeton Bearer de la collecte Prometheus ; sans jeton, `/metrics` exige une session
+    pub metrics_token: Option<String>,
 }
 
 impl Default for AppConfig {
@@ -22,6 +52,12 @@ impl Default for AppConfig {
             lnd_macaroon_path: "/lnd/data/chain/bitcoin/mainnet/admin.macaroon".to_string(),
             lnd_tls_cert_path: "/lnd/tls.cert".to_string(),
             server_port: 3000,
+            recommendation_ttl_hours: 24,
+            channel_snapshot_interval_secs: 60,
+            snapshot_minute_retention_days: 2,
+            snapshot_hour_retention_days: 30,
+            snapshot_day_retention_days: 730,
+            metrics_token: None,
         }
     }
 }
@@ -46,6 +82,32 @@ impl AppConfig {
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .unwrap_or(3000),
+            recommendation_ttl_hours: positive_env("RECOMMENDATION_TTL_HOURS").unwrap_or(24),
+            channel_snapshot_interval_secs: positive_env("CHANNEL_SNAPSHOT_INTERVAL_SECS")
+                .unwrap_or(60),
+            snapshot_minute_retention_days: positive_env("SNAPSHOT_MINUTE_RETENTION_DAYS")
+                .unwrap_or(2),
+            snapshot_hour_retention_days: positive_env("SNAPSHOT_HOUR_RETENTION_DAYS")
+                .unwrap_or(30),
+            snapshot_day_retention_days: positive_env("SNAPSHOT_DAY_RETENTION_DAYS").unwrap_or(730),
+            metrics_token: env::var("METRICS_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        }
+    }
+
+    pub fn snapshot_retention(&self) -> SnapshotRetention {
+        SnapshotRetention {
+            minute: Duration::days(self.snapshot_minute_retention_days),
+            hour: Duration::days(self.snapshot_hour_retention_days),
+            day: Duration::days(self.snapshot_day_retention_days),
         }
     }
 }
+
+fn positive_env<T: std::str::FromStr + PartialOrd + Default>(name: &str) -> Option<T> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| *value > T::default())
+}
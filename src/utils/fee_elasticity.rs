@@ -0,0 +1,377 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+    storage::forwarding::{FeePolicyObservation, ForwardingStore},
+};
+
+/// Agrégat journalier d'un canal sous une politique de frais donnée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeObservation {
+    pub day: DateTime<Utc>,
+    pub fee_rate_ppm: u32,
+    pub volume_sat: u64,
+    pub revenue_msat: u64,
+    pub forwards: u32,
+}
+
+/// Modèle d'élasticité à élasticité constante : `volume = A · ppm^ε`.
+///
+/// `ε` est estimé par régression linéaire de `ln(volume + 1)` sur `ln(ppm)`
+/// à partir des journées observées à des niveaux de frais différents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeElasticityModel {
+    pub channel_id: String,
+    pub current_ppm: u32,
+    pub base_fee_msat: u64,
+    pub elasticity: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+    pub observed_days: usize,
+    pub fee_levels: usize,
+    pub max_observed_volume_sat: f64,
+    pub fitted_at: DateTime<Utc>,
+}
+
+/// Proposition de frais issue du modèle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeProposal {
+    pub channel_id: String,
+    pub current_ppm: u32,
+    pub target_ppm: u32,
+    pub current_daily_volume_sat: f64,
+    pub target_daily_volume_sat: f64,
+    pub current_daily_revenue_sat: f64,
+    pub target_daily_revenue_sat: f64,
+    pub daily_revenue_delta_sat: f64,
+    pub confidence: f64,
+}
+
+/// Paramètres d'entraînement et de recherche du ppm optimal.
+#[derive(Debug, Clone)]
+pub struct FeeElasticityEstimator {
+    pub window_days: i64,
+    pub min_observed_days: usize,
+    pub min_fee_levels: usize,
+    /// Variation maximale proposée en une fois (0.5 → entre ×0.5 et ×1.5)
+    pub max_step: f64,
+    pub min_ppm: u32,
+    pub max_ppm: u32,
+    /// Gain minimal, en fraction du revenu actuel, pour proposer un changement
+    pub min_relative_gain: f64,
+}
+
+impl Default for FeeElasticityEstimator {
+    fn default() -> Self {
+        Self {
+            window_days: 60,
+            min_observed_days: 7,
+            min_fee_levels: 2,
+            max_step: 0.5,
+            min_ppm: 1,
+            max_ppm: 10_000,
+            min_relative_gain: 0.05,
+        }
+    }
+}
+
+impl FeeElasticityModel {
+    pub fn predicted_daily_volume_sat(&self, ppm: u32) -> f64 {
+        let ln_ppm = (ppm.max(1) as f64).ln();
+        let volume = (self.intercept + self.elasticity * ln_ppm).exp() - 1.0;
+        // Pas d'extrapolation au-delà de ce que le canal a déjà routé
+        volume.clamp(0.0, self.max_observed_volume_sat * 1.5)
+    }
+
+    /// Revenu proportionnel attendu ; la part fixe (base fee) est négligée.
+    pub fn predicted_daily_revenue_sat(&self, ppm: u32) -> f64 {
+        self.predicted_daily_volume_sat(ppm) * ppm as f64 / 1_000_000.0
+    }
+
+    /// Confiance : qualité d'ajustement pondérée par la couverture de la fenêtre.
+    pub fn confidence(&self, window_days: i64) -> f64 {
+        let coverage = (self.observed_days as f64 / window_days.max(1) as f64).min(1.0);
+        (self.r_squared.sqrt() * coverage.sqrt()).clamp(0.0, 0.95)
+    }
+}
+
+impl FeeElasticityEstimator {
+    /// Reconstitue les journées (volume sortant, revenu) et le ppm en vigueur à mi-journée.
+    /// Les journées antérieures à la première politique connue sont ignorées.
+    pub fn observations(
+        &self,
+        events: &[LocalForwardingEvent],
+        policies: &[FeePolicyObservation],
+        now: DateTime<Utc>,
+    ) -> Vec<FeeObservation> {
+        let Some(first_policy) = policies.first() else {
+            return vec![];
+        };
+        let Ok(today) = now.duration_trunc(Duration::days(1)) else {
+            return vec![];
+        };
+
+        let mut observations = vec![];
+        for offset in (1..=self.window_days).rev() {
+            let day = today - Duration::days(offset);
+            let midday = day + Duration::hours(12);
+            if midday < first_policy.observed_at {
+                continue;
+            }
+            let Some(policy) = policies.iter().rev().find(|p| p.observed_at <= midday) else {
+                continue;
+            };
+
+            let day_start = day.timestamp_nanos_opt().unwrap_or(0) as u64;
+            let day_end = (day + Duration::days(1)).timestamp_nanos_opt().unwrap_or(0) as u64;
+            let day_events = events
+                .iter()
+                .filter(|e| e.timestamp_ns >= day_start && e.timestamp_ns < day_end);
+
+            let mut observation = FeeObservation {
+                day,
+                fee_rate_ppm: policy.fee_rate_ppm,
+                volume_sat: 0,
+                revenue_msat: 0,
+                forwards: 0,
+            };
+            for event in day_events {
+                observation.volume_sat += event.amt_out_msat / 1000;
+                observation.revenue_msat += event.fee_msat;
+                observation.forwards += 1;
+            }
+            observations.push(observation);
+        }
+        observations
+    }
+
+    /// Ajuste le modèle ; `None` si l'historique ne couvre pas assez de jours ou de niveaux de frais.
+    pub fn fit(
+        &self,
+        channel_id: &str,
+        observations: &[FeeObservation],
+        current: &FeePolicyObservation,
+        now: DateTime<Utc>,
+    ) -> Option<FeeElasticityModel> {
+        let mut levels: Vec<u32> = observations.iter().map(|o| o.fee_rate_ppm).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        if observations.len() < self.min_observed_days || levels.len() < self.min_fee_levels {
+            return None;
+        }
+
+        let points: Vec<(f64, f64)> = observations
+            .iter()
+            .map(|o| {
+                (
+                    (o.fee_rate_ppm.max(1) as f64).ln(),
+                    (o.volume_sat as f64 + 1.0).ln(),
+                )
+            })
+            .collect();
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let syy: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+        if sxx <= f64::EPSILON {
+            return None;
+        }
+
+        let slope = sxy / sxx;
+        let r_squared = if syy <= f64::EPSILON || slope > 0.0 {
+            0.0
+        } else {
+            (sxy * sxy / (sxx * syy)).clamp(0.0, 1.0)
+        };
+        // Une pente positive (le volume monterait avec les frais) relève du bruit :
+        // on la ramène à une demande insensible au prix
+        let elasticity = slope.clamp(-5.0, 0.0);
+        let intercept = mean_y - elasticity * mean_x;
+
+        Some(FeeElasticityModel {
+            channel_id: channel_id.to_string(),
+            current_ppm: current.fee_rate_ppm,
+            base_fee_msat: current.base_fee_msat,
+            elasticity,
+            intercept,
+            r_squared,
+            observed_days: observations.len(),
+            fee_levels: levels.len(),
+            max_observed_volume_sat: observations
+                .iter()
+                .map(|o| o.volume_sat as f64)
+                .fold(0.0, f64::max),
+            fitted_at: now,
+        })
+    }
+
+    /// Cherche le ppm maximisant le revenu prédit dans la limite d'un pas de `max_step`.
+    pub fn propose(&self, model: &FeeElasticityModel) -> Option<FeeProposal> {
+        let current = model.current_ppm.max(self.min_ppm);
+        let low = ((current as f64 * (1.0 - self.max_step)).round() as u32).max(self.min_ppm);
+        let high = ((current as f64 * (1.0 + self.max_step)).round() as u32)
+            .max(current + 1)
+            .min(self.max_ppm);
+
+        let current_revenue = model.predicted_daily_revenue_sat(model.current_ppm);
+        let (target_ppm, target_revenue) = (low..=high)
+            .step_by(((high - low) / 50).max(1) as usize)
+            .chain(std::iter::once(high))
+            .map(|ppm| (ppm, model.predicted_daily_revenue_sat(ppm)))
+            .fold((model.current_ppm, current_revenue), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        let delta = target_revenue - current_revenue;
+        let threshold = (current_revenue * self.min_relative_gain).max(1.0);
+        if target_ppm == model.current_ppm || delta < threshold {
+            return None;
+        }
+
+        Some(FeeProposal {
+            channel_id: model.channel_id.clone(),
+            current_ppm: model.current_ppm,
+            target_ppm,
+            current_daily_volume_sat: model.predicted_daily_volume_sat(model.current_ppm),
+            target_daily_volume_sat: model.predicted_daily_volume_sat(target_ppm),
+            current_daily_revenue_sat: current_revenue,
+            target_daily_revenue_sat: target_revenue,
+            daily_revenue_delta_sat: delta,
+            confidence: model.confidence(self.window_days),
+        })
+    }
+
+    /// Entraîne un modèle par canal à partir de l'historique persisté.
+    /// Les canaux sans historique suffisant sont simplement absents du résultat.
+    pub async fn fit_channels(
+        &self,
+        store: &ForwardingStore,
+        channels: &[LocalChannelInfo],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<FeeElasticityModel>> {
+        let since = now - Duration::days(self.window_days + 1);
+        let mut models = vec![];
+
+        for channel in channels {
+            let policies = store.fee_history(&channel.channel_id).await?;
+            let Some(current) = policies.last() else {
+                continue;
+            };
+            let events = store.outgoing_events(&channel.channel_id, since).await?;
+            let observations = self.observations(&events, &policies, now);
+            match self.fit(&channel.channel_id, &observations, current, now) {
+                Some(model) => models.push(model),
+                None => warn!(
+                    "Not enough forwarding history to fit fee elasticity for channel {}",
+                    channel.channel_id
+                ),
+            }
+        }
+
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(ppm: u32, at: DateTime<Utc>) -> FeePolicyObservation {
+        FeePolicyObservation {
+            channel_id: "1".to_string(),
+            base_fee_msat: 1000,
+            fee_rate_ppm: ppm,
+            observed_at: at,
+        }
+    }
+
+    /// Historique synthétique : volume journalier = 1e10 · ppm^ε
+    fn history(
+        elasticity: f64,
+        now: DateTime<Utc>,
+    ) -> (Vec<LocalForwardingEvent>, Vec<FeePolicyObservation>) {
+        let today = now.duration_trunc(Duration::days(1)).unwrap();
+        let start = today - Duration::days(30);
+        let policies = vec![
+            policy(100, start),
+            policy(200, start + Duration::days(10)),
+            policy(400, start + Duration::days(20)),
+        ];
+
+        let mut events = vec![];
+        for day in 0..30 {
+            let ppm = [100.0, 200.0, 400.0][day / 10];
+            let volume_sat = 1e10 * f64::powf(ppm, elasticity);
+            let at = start + Duration::days(day as i64) + Duration::hours(14);
+            events.push(LocalForwardingEvent {
+                timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+                chan_id_in: "2".to_string(),
+                chan_id_out: "1".to_string(),
+                amt_in_msat: 0,
+                amt_out_msat: (volume_sat * 1000.0) as u64,
+                fee_msat: (volume_sat * ppm / 1000.0) as u64,
+            });
+        }
+        (events, policies)
+    }
+
+    fn fitted(elasticity: f64) -> FeeElasticityModel {
+        let now = Utc::now();
+        let estimator = FeeElasticityEstimator::default();
+        let (events, policies) = history(elasticity, now);
+        let observations = estimator.observations(&events, &policies, now);
+        estimator
+            .fit("1", &observations, policies.last().unwrap(), now)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_recovers_elasticity_from_history() {
+        let model = fitted(-1.5);
+        assert!((model.elasticity + 1.5).abs() < 0.01);
+        assert!(model.r_squared > 0.99);
+        assert_eq!(model.fee_levels, 3);
+        assert_eq!(model.current_ppm, 400);
+    }
+
+    #[test]
+    fn test_elastic_demand_lowers_fees() {
+        let estimator = FeeElasticityEstimator::default();
+        let proposal = estimator.propose(&fitted(-1.5)).unwrap();
+        assert!(proposal.target_ppm < 400);
+        assert!(proposal.daily_revenue_delta_sat > 0.0);
+    }
+
+    #[test]
+    fn test_inelastic_demand_raises_fees() {
+        let estimator = FeeElasticityEstimator::default();
+        let proposal = estimator.propose(&fitted(-0.3)).unwrap();
+        assert_eq!(proposal.target_ppm, 600);
+        assert!(proposal.target_daily_revenue_sat > proposal.current_daily_revenue_sat);
+    }
+
+    #[test]
+    fn test_single_fee_level_is_not_fitted() {
+        let now = Utc::now();
+        let estimator = FeeElasticityEstimator::default();
+        let (events, mut policies) = history(-1.0, now);
+        policies.truncate(1);
+        let observations = estimator.observations(&events, &policies, now);
+        assert!(estimator
+            .fit("1", &observations, &policies[0], now)
+            .is_none());
+    }
+}
@@ -0,0 +1,442 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent, LocalNetworkGraph},
+    models::fee_strategy::FeeStrategyKind,
+};
+
+/// Politique de frais cible d'un canal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyTarget {
+    pub base_fee_msat: u64,
+    pub fee_rate_ppm: u32,
+}
+
+/// Ce qu'une stratégie observe d'un canal.
+pub struct FeeStrategyContext<'a> {
+    pub now: DateTime<Utc>,
+    pub channel: &'a LocalChannelInfo,
+    /// Forwards du canal, entrants et sortants
+    pub history: &'a [LocalForwardingEvent],
+    /// ppm facturés par les autres nœuds sur leurs canaux vers le même pair
+    pub competitor_ppm: &'a [u32],
+    /// Point de départ : politique en vigueur ou proposition de la stratégie précédente
+    pub proposed: PolicyTarget,
+}
+
+/// Politique retenue par une stratégie et sa justification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyDecision {
+    pub policy: PolicyTarget,
+    pub rationale: Vec<String>,
+}
+
+/// Politique de frais enfichable.
+pub trait FeeStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `None` : pas d'avis (données insuffisantes ou statu quo), la proposition reste inchangée.
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision>;
+}
+
+/// Plus la part locale baisse, plus la liquidité sortante restante est facturée cher.
+pub struct BalanceProportional {
+    pub min_ppm: u32,
+    pub max_ppm: u32,
+}
+
+impl FeeStrategy for BalanceProportional {
+    fn name(&self) -> &'static str {
+        "balance_proportional"
+    }
+
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision> {
+        let channel = ctx.channel;
+        let local_ratio = channel.local_balance as f64 / channel.capacity.max(1) as f64;
+        let span = self.max_ppm.saturating_sub(self.min_ppm) as f64;
+        let ppm = self.min_ppm + (span * (1.0 - local_ratio)).round() as u32;
+
+        Some(StrategyDecision {
+            policy: PolicyTarget {
+                fee_rate_ppm: ppm,
+                ..ctx.proposed
+            },
+            rationale: vec![format!(
+                "Part locale {:.0}% : {} ppm sur l'échelle {}-{}",
+                local_ratio * 100.0,
+                ppm,
+                self.min_ppm,
+                self.max_ppm
+            )],
+        })
+    }
+}
+
+/// S'aligne sous la médiane des frais concurrents vers le même pair.
+pub struct CompetitiveMatching {
+    pub undercut_pct: f64,
+    pub min_competitors: usize,
+}
+
+impl FeeStrategy for CompetitiveMatching {
+    fn name(&self) -> &'static str {
+        "competitive_matching"
+    }
+
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision> {
+        if ctx.competitor_ppm.len() < self.min_competitors.max(1) {
+            return None;
+        }
+        let mut fees = ctx.competitor_ppm.to_vec();
+        fees.sort_unstable();
+        let median = fees[fees.len() / 2];
+        let ppm = (median as f64 * (1.0 - self.undercut_pct / 100.0))
+            .round()
+            .max(0.0) as u32;
+
+        Some(StrategyDecision {
+            policy: PolicyTarget {
+                fee_rate_ppm: ppm,
+                ..ctx.proposed
+            },
+            rationale: vec![format!(
+                "Médiane de {} canaux concurrents vers ce pair : {} ppm, décote {:.0}%",
+                fees.len(),
+                median,
+                self.undercut_pct
+            )],
+        })
+    }
+}
+
+/// Monte d'un palier quand le canal est demandé, descend quand il ne route plus rien.
+pub struct DemandStepping {
+    pub step_ppm: u32,
+    pub window_days: u32,
+    pub busy_forwards_per_day: f64,
+}
+
+impl FeeStrategy for DemandStepping {
+    fn name(&self) -> &'static str {
+        "demand_stepping"
+    }
+
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision> {
+        let since = (ctx.now - Duration::days(self.window_days as i64))
+            .timestamp_nanos_opt()
+            .unwrap_or(0) as u64;
+        let forwards = ctx
+            .history
+            .iter()
+            .filter(|e| e.chan_id_out == ctx.channel.channel_id && e.timestamp_ns >= since)
+            .count();
+        let per_day = forwards as f64 / self.window_days.max(1) as f64;
+        let current = ctx.proposed.fee_rate_ppm;
+
+        let (ppm, reason) = if per_day >= self.busy_forwards_per_day {
+            (current + self.step_ppm, "demande soutenue")
+        } else if forwards == 0 && current > 0 {
+            (current.saturating_sub(self.step_ppm), "aucune demande")
+        } else {
+            return None;
+        };
+
+        Some(StrategyDecision {
+            policy: PolicyTarget {
+                fee_rate_ppm: ppm,
+                ..ctx.proposed
+            },
+            rationale: vec![format!(
+                "{:.1} forwards sortants/jour sur {} jours ({}) : {} → {} ppm",
+                per_day, self.window_days, reason, current, ppm
+            )],
+        })
+    }
+}
+
+/// Plancher et plafond fixes appliqués à la proposition courante.
+pub struct StaticBounds {
+    pub floor_ppm: u32,
+    pub ceiling_ppm: u32,
+}
+
+impl FeeStrategy for StaticBounds {
+    fn name(&self) -> &'static str {
+        "static_bounds"
+    }
+
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision> {
+        let current = ctx.proposed.fee_rate_ppm;
+        let ppm = current.clamp(self.floor_ppm, self.ceiling_ppm.max(self.floor_ppm));
+        let rationale = if ppm != current {
+            vec![format!(
+                "{} ppm ramené dans les bornes {}-{}",
+                current, self.floor_ppm, self.ceiling_ppm
+            )]
+        } else {
+            vec![]
+        };
+
+        Some(StrategyDecision {
+            policy: PolicyTarget {
+                fee_rate_ppm: ppm,
+                ..ctx.proposed
+            },
+            rationale,
+        })
+    }
+}
+
+/// Applique des stratégies successives, chacune partant de la proposition précédente.
+pub struct FeeStrategyChain {
+    strategies: Vec<Box<dyn FeeStrategy>>,
+}
+
+impl FeeStrategyChain {
+    pub fn new(strategies: Vec<Box<dyn FeeStrategy>>) -> Self {
+        Self { strategies }
+    }
+
+    pub fn from_kinds(kinds: &[FeeStrategyKind]) -> Self {
+        Self::new(kinds.iter().map(build_strategy).collect())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|s| s.name()).collect()
+    }
+}
+
+impl FeeStrategy for FeeStrategyChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    fn decide(&self, ctx: &FeeStrategyContext) -> Option<StrategyDecision> {
+        let mut decision: Option<StrategyDecision> = None;
+        for strategy in &self.strategies {
+            let step_ctx = FeeStrategyContext {
+                proposed: decision.as_ref().map_or(ctx.proposed, |d| d.policy),
+                ..*ctx
+            };
+            if let Some(step) = strategy.decide(&step_ctx) {
+                let mut rationale = decision.map(|d| d.rationale).unwrap_or_default();
+                rationale.extend(
+                    step.rationale
+                        .into_iter()
+                        .map(|r| format!("[{}] {}", strategy.name(), r)),
+                );
+                decision = Some(StrategyDecision {
+                    policy: step.policy,
+                    rationale,
+                });
+            }
+        }
+        decision
+    }
+}
+
+/// Instancie une stratégie à partir de sa configuration.
+pub fn build_strategy(kind: &FeeStrategyKind) -> Box<dyn FeeStrategy> {
+    match kind {
+        FeeStrategyKind::BalanceProportional { min_ppm, max_ppm } => {
+            Box::new(BalanceProportional {
+                min_ppm: *min_ppm,
+                max_ppm: *max_ppm,
+            })
+        }
+        FeeStrategyKind::CompetitiveMatching {
+            undercut_pct,
+            min_competitors,
+        } => Box::new(CompetitiveMatching {
+            undercut_pct: *undercut_pct,
+            min_competitors: *min_competitors,
+        }),
+        FeeStrategyKind::DemandStepping {
+            step_ppm,
+            window_days,
+            busy_forwards_per_day,
+        } => Box::new(DemandStepping {
+            step_ppm: *step_ppm,
+            window_days: *window_days,
+            busy_forwards_per_day: *busy_forwards_per_day,
+        }),
+        FeeStrategyKind::StaticBounds {
+            floor_ppm,
+            ceiling_ppm,
+        } => Box::new(StaticBounds {
+            floor_ppm: *floor_ppm,
+            ceiling_ppm: *ceiling_ppm,
+        }),
+    }
+}
+
+/// ppm facturés par les autres nœuds pour router vers `peer_pubkey` (nos canaux exclus).
+pub fn competitor_ppm(
+    graph: &LocalNetworkGraph,
+    peer_pubkey: &str,
+    own_channel_ids: &HashSet<&str>,
+) -> Vec<u32> {
+    graph
+        .edges
+        .iter()
+        .filter(|edge| !own_channel_ids.contains(edge.channel_id.as_str()))
+        .filter_map(|edge| {
+            // La politique du nœud d'en face s'applique au forward vers le pair
+            let policy = if edge.node1_pub == peer_pubkey {
+                edge.node2_policy.as_ref()
+            } else if edge.node2_pub == peer_pubkey {
+                edge.node1_policy.as_ref()
+            } else {
+                None
+            }?;
+            (!policy.disabled).then_some(policy.fee_rate_ppm as u32)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fee_strategy::{AssignmentScope, FeeStrategyAssignment};
+    use crate::test_support;
+
+    fn channel(local: u64) -> LocalChannelInfo {
+        LocalChannelInfo {
+            channel_point: "p:0".to_string(),
+            peer_pubkey: "peer".to_string(),
+            base_fee_msat: 1000,
+            fee_rate_milli_msat: 100,
+            ..test_support::channel("1", 1_000_000, local)
+        }
+    }
+
+    fn context<'a>(
+        channel: &'a LocalChannelInfo,
+        history: &'a [LocalForwardingEvent],
+        competitor_ppm: &'a [u32],
+        now: DateTime<Utc>,
+    ) -> FeeStrategyContext<'a> {
+        FeeStrategyContext {
+            now,
+            channel,
+            history,
+            competitor_ppm,
+            proposed: PolicyTarget {
+                base_fee_msat: channel.base_fee_msat,
+                fee_rate_ppm: channel.fee_rate_milli_msat as u32,
+            },
+        }
+    }
+
+    #[test]
+    fn test_balance_proportional_prices_scarce_outbound() {
+        let strategy = BalanceProportional {
+            min_ppm: 100,
+            max_ppm: 1100,
+        };
+        let now = Utc::now();
+        let drained = channel(100_000);
+        let full = channel(900_000);
+
+        let ppm = |c: &LocalChannelInfo| {
+            strategy
+                .decide(&context(c, &[], &[], now))
+                .unwrap()
+                .policy
+                .fee_rate_ppm
+        };
+        assert_eq!(ppm(&drained), 1000);
+        assert_eq!(ppm(&full), 200);
+    }
+
+    #[test]
+    fn test_chain_matches_competitors_then_applies_bounds() {
+        let chain = FeeStrategyChain::from_kinds(&[
+            FeeStrategyKind::CompetitiveMatching {
+                undercut_pct: 10.0,
+                min_competitors: 3,
+            },
+            FeeStrategyKind::StaticBounds {
+                floor_ppm: 50,
+                ceiling_ppm: 400,
+            },
+        ]);
+        let now = Utc::now();
+        let channel = channel(500_000);
+
+        let decision = chain
+            .decide(&context(&channel, &[], &[200, 1000, 800, 900], now))
+            .unwrap();
+        // Médiane 900, décote 10 % → 810, plafonné à 400
+        assert_eq!(decision.policy.fee_rate_ppm, 400);
+        assert_eq!(decision.policy.base_fee_msat, 1000);
+        assert_eq!(decision.rationale.len(), 2);
+
+        // Trop peu de concurrents : seules les bornes s'appliquent
+        let decision = chain.decide(&context(&channel, &[], &[10], now)).unwrap();
+        assert_eq!(decision.policy.fee_rate_ppm, 100);
+    }
+
+    #[test]
+    fn test_demand_stepping_and_channel_assignment_precedence() {
+        let now = Utc::now();
+        let channel = channel(500_000);
+        let strategy = DemandStepping {
+            step_ppm: 25,
+            window_days: 7,
+            busy_forwards_per_day: 2.0,
+        };
+        let busy: Vec<LocalForwardingEvent> = (0..20)
+            .map(|i| LocalForwardingEvent {
+                timestamp_ns: (now - Duration::hours(i * 6))
+                    .timestamp_nanos_opt()
+                    .unwrap() as u64,
+                chan_id_in: "2".to_string(),
+                chan_id_out: "1".to_string(),
+                amt_in_msat: 1_000_000,
+                amt_out_msat: 999_000,
+                fee_msat: 1_000,
+            })
+            .collect();
+
+        let up = strategy
+            .decide(&context(&channel, &busy, &[], now))
+            .unwrap();
+        assert_eq!(up.policy.fee_rate_ppm, 125);
+        let down = strategy.decide(&context(&channel, &[], &[], now)).unwrap();
+        assert_eq!(down.policy.fee_rate_ppm, 75);
+        assert!(strategy
+            .decide(&context(&channel, &busy[..3], &[], now))
+            .is_none());
+
+        let group = FeeStrategyAssignment {
+            scope: AssignmentScope::PeerGroup {
+                name: "sinks".to_string(),
+                peers: vec!["peer".to_string()],
+            },
+            strategies: vec![],
+            updated_at: now,
+        };
+        let own = FeeStrategyAssignment {
+            scope: AssignmentScope::Channel {
+                channel_id: "1".to_string(),
+            },
+            ..group.clone()
+        };
+        let assignments = [group, own];
+        assert_eq!(
+            FeeStrategyAssignment::resolve(&assignments, "1", "peer")
+                .unwrap()
+                .scope_key(),
+            "channel:1"
+        );
+        assert_eq!(
+            FeeStrategyAssignment::resolve(&assignments, "9", "peer")
+                .unwrap()
+                .scope_key(),
+            "group:sinks"
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+    storage::forwarding::{ChannelHistoryPoint, ForwardingStore},
+    utils::onchain::{DEFAULT_ONCHAIN_FEE_RATE, OPEN_CHANNEL_VBYTES},
+};
+
+/// Frais médians du réseau supposés sans relevé du graphe (ppm)
+const DEFAULT_NETWORK_FEE_RATE_PPM: u32 = 200;
+/// Rendement des forwards supposé quand le nœud n'a encore rien routé (ppm)
+const DEFAULT_ROUTING_FEE_RATE_PPM: f64 = 300.0;
+
+/// Seuils de détection et hypothèses de coût de chaque méthode.
+#[derive(Debug, Clone)]
+pub struct InboundAnalyzerConfig {
+    pub history_days: u32,
+    /// Part distante sous laquelle un relevé compte comme épuisé
+    pub exhausted_remote_ratio: f64,
+    /// Part des relevés épuisés au-delà de laquelle l'épuisement est chronique
+    pub chronic_share: f64,
+    pub min_snapshots: usize,
+    /// Part distante visée après acquisition
+    pub target_remote_ratio: f64,
+    /// Horizon sur lequel la demande retrouvée est valorisée
+    pub horizon_days: u32,
+    pub loop_out_swap_fee_ppm: u64,
+    /// HTLC on-chain et balayage d'un Loop Out
+    pub loop_out_onchain_vbytes: u64,
+    pub loop_out_min_sat: u64,
+    /// Prime d'un bail de canal, par période de bail
+    pub lease_premium_ppm: u64,
+    pub lease_execution_fee_ppm: u64,
+    pub lease_duration_days: u32,
+    /// Canaux d'une route circulaire, y compris les nôtres aux deux extrémités
+    pub rebalance_route_hops: u32,
+}
+
+impl Default for InboundAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 30,
+            exhausted_remote_ratio: 0.1,
+            chronic_share: 0.7,
+            min_snapshots: 6,
+            target_remote_ratio: 0.5,
+            horizon_days: 90,
+            loop_out_swap_fee_ppm: 2_500,
+            loop_out_onchain_vbytes: 250,
+            loop_out_min_sat: 250_000,
+            lease_premium_ppm: 10_000,
+            lease_execution_fee_ppm: 1_000,
+            lease_duration_days: 14,
+            rebalance_route_hops: 3,
+        }
+    }
+}
+
+/// Moyen d'obtenir de la liquidité entrante.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InboundMethod {
+    /// Swap off-chain vers on-chain : le solde local part chez le pair
+    LoopOut,
+    /// Bail de canal entrant acheté sur un marché de liquidité
+    ChannelLease,
+    /// Paiement circulaire de nos canaux épuisés vers ceux qui ont de l'entrant en trop
+    CircularRebalance,
+    /// Ouverture d'un canal en cédant une partie de la capacité au pair
+    PushSatOpen,
+}
+
+/// Canal dont le côté distant est durablement épuisé.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundShortage {
+    pub channel_id: String,
+    pub peer_pubkey: String,
+    pub peer_alias: String,
+    pub capacity_sat: u64,
+    pub remote_balance_sat: u64,
+    pub remote_ratio: f64,
+    /// Part des relevés de la période où le côté distant était épuisé
+    pub exhausted_share: f64,
+    /// Relevés représentés, agrégats horaires et journaliers compris
+    pub snapshots: usize,
+    /// Volume arrivant par ce canal (forwards réussis) quand il avait de l'entrant
+    pub arriving_sat_per_day: u64,
+    /// Volume estimé perdu faute d'entrant
+    pub turned_away_sat_per_day: u64,
+    pub recommended_inbound_sat: u64,
+}
+
+/// Pénurie agrégée par pair, tous canaux confondus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerInboundShortage {
+    pub peer_pubkey: String,
+    pub peer_alias: String,
+    pub channels: usize,
+    pub capacity_sat: u64,
+    pub remote_balance_sat: u64,
+    pub turned_away_sat_per_day: u64,
+    pub recommended_inbound_sat: u64,
+}
+
+/// Coût d'une méthode pour le montant recommandé.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundMethodQuote {
+    pub method: InboundMethod,
+    pub amount_sat: u64,
+    pub cost_sat: u64,
+    pub cost_ppm: u64,
+    pub feasible: bool,
+    /// Jours de frais retrouvés nécessaires pour couvrir le coût
+    pub payback_days: Option<f64>,
+    pub notes: Vec<String>,
+}
+
+/// Bilan du besoin en liquidité entrante du nœud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundLiquidityReport {
+    pub generated_at: DateTime<Utc>,
+    pub history_days: u32,
+    pub shortages: Vec<InboundShortage>,
+    pub peers: Vec<PeerInboundShortage>,
+    pub turned_away_sat_per_day: u64,
+    pub recommended_inbound_sat: u64,
+    /// Frais de routage espérés sur l'horizon si la demande perdue était servie
+    pub expected_revenue_sat: u64,
+    pub horizon_days: u32,
+    pub quotes: Vec<InboundMethodQuote>,
+    pub recommended_method: Option<InboundMethod>,
+    pub rationale: Vec<String>,
+}
+
+/// Données observées pour l'analyse de la liquidité entrante.
+pub struct InboundInputs<'a> {
+    pub now: DateTime<Utc>,
+    pub channels: &'a [LocalChannelInfo],
+    pub snapshots: &'a [ChannelHistoryPoint],
+    pub events: &'a [LocalForwardingEvent],
+    pub onchain_fee_rate: f64,
+    pub network_fee_rate_ppm: u32,
+    pub wallet_confirmed_sat: u64,
+}
+
+/// Détection des canaux sans entrant et comparaison des moyens d'en acquérir.
+#[derive(Debug, Clone, Default)]
+pub struct InboundLiquidityAnalyzer {
+    pub config: InboundAnalyzerConfig,
+}
+
+impl InboundLiquidityAnalyzer {
+    pub fn new(config: InboundAnalyzerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Analyse à partir de l'historique persisté et des derniers relevés de frais.
+    pub async fn analyze_stored(
+        &self,
+        forwarding_store: &ForwardingStore,
+        channels: &[LocalChannelInfo],
+        wallet_confirmed_sat: u64,
+        now: DateTime<Utc>,
+    ) -> Result<InboundLiquidityReport> {
+        let since = now - Duration::days(self.config.history_days as i64);
+        let snapshots = forwarding_store
+            .channel_snapshots_between(since, now)
+            .await?;
+        let events = forwarding_store.events_between(since, now).await?;
+        let onchain_fee_rate = forwarding_store
+            .fee_estimates_since(now - Duration::days(1))
+            .await?
+            .last()
+            .map_or(DEFAULT_ONCHAIN_FEE_RATE, |sample| sample.half_hour as f64);
+        let network_fee_rate_ppm = forwarding_store
+            .network_fees_since(now - Duration::days(1))
+            .await?
+            .last()
+            .map_or(DEFAULT_NETWORK_FEE_RATE_PPM, |sample| {
+                sample.median_fee_rate_ppm
+            });
+
+        Ok(self.analyze(&InboundInputs {
+            now,
+            channels,
+            snapshots: &snapshots,
+            events: &events,
+            onchain_fee_rate,
+            network_fee_rate_ppm,
+            wallet_confirmed_sat,
+        }))
+    }
+
+    pub fn analyze(&self, inputs: &InboundInputs) -> InboundLiquidityReport {
+        let config = &self.config;
+        let history_days = config.history_days.max(1) as f64;
+        let remote_ratio =
+            |c: &LocalChannelInfo| c.remote_balance as f64 / c.capacity.max(1) as f64;
+
+        let mut shortages: Vec<InboundShortage> = inputs
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                let id = &channel.channel_id;
+                let snapshots: Vec<&ChannelHistoryPoint> = inputs
+                    .snapshots
+                    .iter()
+                    .filter(|s| &s.channel.channel_id == id)
+                    .collect();
+                // Chaque point pèse les relevés qu'il représente : un agrégat journalier
+                // compte pour la journée entière, pas pour un relevé
+                let samples: u64 = snapshots.iter().map(|s| s.samples as u64).sum();
+                if samples < config.min_snapshots as u64
+                    || remote_ratio(channel) > config.exhausted_remote_ratio
+                {
+                    return None;
+                }
+                let exhausted_share = snapshots
+                    .iter()
+                    .filter(|s| remote_ratio(&s.channel) <= config.exhausted_remote_ratio)
+                    .map(|s| s.samples as u64)
+                    .sum::<u64>() as f64
+                    / samples as f64;
+                if exhausted_share < config.chronic_share {
+                    return None;
+                }
+
+                // La demande qui arrive par ce canal consomme son entrant, mesurée quand il
+                // en restait ; les forwards refusés ne sont pas observables
+                let arriving_sat = inputs
+                    .events
+                    .iter()
+                    .filter(|e| &e.chan_id_in == id)
+                    .map(|e| e.amt_in_msat)
+                    .sum::<u64>()
+                    / 1000;
+                let available_days = (history_days * (1.0 - exhausted_share)).max(1.0);
+                let arriving_per_day = arriving_sat as f64 / available_days;
+
+                let target_remote = (channel.capacity as f64 * config.target_remote_ratio) as u64;
+                Some(InboundShortage {
+                    channel_id: id.clone(),
+                    peer_pubkey: channel.peer_pubkey.clone(),
+                    peer_alias: channel.peer_alias.clone(),
+                    capacity_sat: channel.capacity,
+                    remote_balance_sat: channel.remote_balance,
+                    remote_ratio: remote_ratio(channel),
+                    exhausted_share,
+                    snapshots: samples as usize,
+                    arriving_sat_per_day: arriving_per_day.round() as u64,
+                    turned_away_sat_per_day: (arriving_per_day * exhausted_share).round() as u64,
+                    recommended_inbound_sat: target_remote.saturating_sub(channel.remote_balance),
+                })
+            })
+            .collect();
+        shortages.sort_by(|a, b| {
+            b.turned_away_sat_per_day
+                .cmp(&a.turned_away_sat_per_day)
+                .then(b.recommended_inbound_sat.cmp(&a.recommended_inbound_sat))
+        });
+
+        let mut by_peer: BTreeMap<&str, PeerInboundShortage> = BTreeMap::new();
+        for shortage in &shortages {
+            let peer =
+                by_peer
+                    .entry(&shortage.peer_pubkey)
+                    .or_insert_with(|| PeerInboundShortage {
+                        peer_pubkey: shortage.peer_pubkey.clone(),
+                        peer_alias: shortage.peer_alias.clone(),
+                        channels: 0,
+                        capacity_sat: 0,
+                        remote_balance_sat: 0,
+                        turned_away_sat_per_day: 0,
+                        recommended_inbound_sat: 0,
+                    });
+            peer.channels += 1;
+            peer.capacity_sat += shortage.capacity_sat;
+            peer.remote_balance_sat += shortage.remote_balance_sat;
+            peer.turned_away_sat_per_day += shortage.turned_away_sat_per_day;
+            peer.recommended_inbound_sat += shortage.recommended_inbound_sat;
+        }
+        let mut peers: Vec<PeerInboundShortage> = by_peer.into_values().collect();
+        peers.sort_by_key(|p| std::cmp::Reverse(p.turned_away_sat_per_day));
+
+        let turned_away_sat_per_day: u64 =
+            shortages.iter().map(|s| s.turned_away_sat_per_day).sum();
+        let recommended_inbound_sat: u64 =
+            shortages.iter().map(|s| s.recommended_inbound_sat).sum();
+
+        // Rendement effectif des forwards du nœud, appliqué à la demande retrouvée
+        let (fees_msat, volume_msat) = inputs.events.iter().fold((0u64, 0u64), |(f, v), e| {
+            (f + e.fee_msat, v + e.amt_out_msat)
+        });
+        let routing_fee_ppm = if volume_msat > 0 {
+            fees_msat as f64 / volume_msat as f64 * 1_000_000.0
+        } else {
+            DEFAULT_ROUTING_FEE_RATE_PPM
+        };
+        let revenue_per_day = turned_away_sat_per_day as f64 * routing_fee_ppm / 1_000_000.0;
+        let expected_revenue_sat = (revenue_per_day * config.horizon_days as f64).round() as u64;
+
+        let quotes = if recommended_inbound_sat > 0 {
+            self.quotes(inputs, &shortages, recommended_inbound_sat, revenue_per_day)
+        } else {
+            vec![]
+        };
+        let recommended_method = quotes
+            .iter()
+            .filter(|q| q.feasible)
+            .min_by_key(|q| q.cost_sat)
+            .map(|q| q.method);
+
+        let mut rationale = vec![];
+        if shortages.is_empty() {
+            rationale.push("Aucun canal dont le côté distant est durablement épuisé".to_string());
+        } else {
+            rationale.push(format!(
+                "{} canal(aux) sans entrant sur au moins {:.0}% des relevés, chez {} pair(s)",
+                shortages.len(),
+                config.chronic_share * 100.0,
+                peers.len()
+            ));
+            rationale.push(format!(
+                "~{} sat/jour de forwards perdus faute d'entrant, soit ~{} sat de frais sur {} jours à {:.0} ppm",
+                turned_away_sat_per_day, expected_revenue_sat, config.horizon_days, routing_fee_ppm
+            ));
+            rationale.push(
+                "Forwards refusés non disponibles (pas d'événements HTLC via l'API LND) : \
+                 demande perdue extrapolée des seuls forwards réussis"
+                    .to_string(),
+            );
+        }
+        if let Some(best) =
+            recommended_method.and_then(|method| quotes.iter().find(|q| q.method == method))
+        {
+            rationale.push(format!(
+                "Méthode la moins chère : {:?}, {} sat pour {} sat d'entrant ({} ppm)",
+                best.method, best.cost_sat, best.amount_sat, best.cost_ppm
+            ));
+        } else if recommended_inbound_sat > 0 {
+            rationale.push("Aucune méthode réalisable avec les fonds disponibles".to_string());
+        }
+
+        InboundLiquidityReport {
+            generated_at: inputs.now,
+            history_days: config.history_days,
+            shortages,
+            peers,
+            turned_away_sat_per_day,
+            recommended_inbound_sat,
+            expected_revenue_sat,
+            horizon_days: config.horizon_days,
+            quotes,
+            recommended_method,
+            rationale,
+        }
+    }
+
+    fn quotes(
+        &self,
+        inputs: &InboundInputs,
+        shortages: &[InboundShortage],
+        amount: u64,
+        revenue_per_day: f64,
+    ) -> Vec<InboundMethodQuote> {
+        let config = &self.config;
+        let onchain = |vbytes: u64| (vbytes as f64 * inputs.onchain_fee_rate).round() as u64;
+        let ppm = |fee_ppm: u64| amount * fee_ppm / 1_000_000;
+
+        // Le solde local des canaux épuisés est ce qu'un Loop Out ou une route circulaire déplace
+        let movable_local_sat: u64 = inputs
+            .channels
+            .iter()
+            .filter(|c| shortages.iter().any(|s| s.channel_id == c.channel_id))
+            .map(|c| c.local_balance)
+            .sum();
+        // Une route circulaire se termine dans un canal qui a de l'entrant en trop
+        let absorbable_sat: u64 = inputs
+            .channels
+            .iter()
+            .filter(|c| c.active && shortages.iter().all(|s| s.channel_id != c.channel_id))
+            .map(|c| {
+                c.remote_balance
+                    .saturating_sub((c.capacity as f64 * config.target_remote_ratio) as u64)
+            })
+            .sum();
+
+        let lease_terms = config
+            .horizon_days
+            .div_ceil(config.lease_duration_days.max(1)) as u64;
+        // Toutes les primes de l'horizon sont payées depuis le portefeuille
+        let lease_cost = ppm(config.lease_premium_ppm) * lease_terms
+            + ppm(config.lease_execution_fee_ppm)
+            + onchain(OPEN_CHANNEL_VBYTES);
+        let push_capacity = (amount as f64 / config.target_remote_ratio.max(0.01)) as u64;
+        let forwarding_nodes = config.rebalance_route_hops.saturating_sub(1) as u64;
+
+        let mut quotes = vec![
+            InboundMethodQuote {
+                method: InboundMethod::LoopOut,
+                amount_sat: amount,
+                cost_sat: ppm(config.loop_out_swap_fee_ppm)
+                    + onchain(config.loop_out_onchain_vbytes),
+                cost_ppm: 0,
+                feasible: amount >= config.loop_out_min_sat && movable_local_sat >= amount,
+                payback_days: None,
+                notes: vec![format!(
+                    "Frais de swap {} ppm et ~{} vB on-chain ; minimum {} sat ; les fonds reviennent on-chain",
+                    config.loop_out_swap_fee_ppm,
+                    config.loop_out_onchain_vbytes,
+                    config.loop_out_min_sat
+                )],
+            },
+            InboundMethodQuote {
+                method: InboundMethod::ChannelLease,
+                amount_sat: amount,
+                cost_sat: lease_cost,
+                cost_ppm: 0,
+                feasible: inputs.wallet_confirmed_sat >= lease_cost,
+                payback_days: None,
+                notes: vec![format!(
+                    "{} bail(s) de {} jours pour couvrir l'horizon ; nouveau canal, les canaux épuisés restent en l'état",
+                    lease_terms, config.lease_duration_days
+                )],
+            },
+            InboundMethodQuote {
+                method: InboundMethod::CircularRebalance,
+                amount_sat: amount,
+                cost_sat: ppm(inputs.network_fee_rate_ppm as u64 * forwarding_nodes),
+                cost_ppm: 0,
+                feasible: absorbable_sat >= amount && movable_local_sat >= amount,
+                payback_days: None,
+                notes: vec![format!(
+                    "{} nœud(s) intermédiaire(s) à {} ppm médian ; {} sat d'entrant excédentaire disponible ailleurs",
+                    forwarding_nodes, inputs.network_fee_rate_ppm, absorbable_sat
+                )],
+            },
+            InboundMethodQuote {
+                method: InboundMethod::PushSatOpen,
+                amount_sat: amount,
+                cost_sat: amount + onchain(OPEN_CHANNEL_VBYTES),
+                cost_ppm: 0,
+                feasible: inputs.wallet_confirmed_sat >= push_capacity + onchain(OPEN_CHANNEL_VBYTES),
+                payback_days: None,
+                notes: vec![format!(
+                    "Canal de {} sat dont {} sat cédés au pair : le montant poussé est perdu",
+                    push_capacity, amount
+                )],
+            },
+        ];
+
+        for quote in &mut quotes {
+            quote.cost_ppm = quote.cost_sat * 1_000_000 / amount.max(1);
+            quote.payback_days =
+                (revenue_per_day > 0.0).then(|| quote.cost_sat as f64 / revenue_per_day);
+        }
+        quotes.sort_by_key(|q| q.cost_sat);
+        quotes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::forwarding::SnapshotResolution;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn channel(id: &str, local: u64, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            active,
+            ..test_support::channel(id, 10_000_000, local)
+        }
+    }
+
+    fn daily_snapshots(
+        channel: &LocalChannelInfo,
+        now: DateTime<Utc>,
+        days: i64,
+        local: impl Fn(i64) -> u64,
+    ) -> Vec<ChannelHistoryPoint> {
+        (0..days)
+            .rev()
+            .map(|d| {
+                let mut channel = channel.clone();
+                channel.local_balance = local(d);
+                channel.remote_balance = channel.capacity - channel.local_balance;
+                ChannelHistoryPoint::sample(now - Duration::days(d) - Duration::hours(1), channel)
+            })
+            .collect()
+    }
+
+    fn forward_in(chan_in: &str, at: DateTime<Utc>, amt_sat: u64) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+            chan_id_in: chan_in.to_string(),
+            chan_id_out: "out".to_string(),
+            amt_in_msat: amt_sat * 1000,
+            amt_out_msat: amt_sat * 1000 - amt_sat,
+            fee_msat: amt_sat,
+        }
+    }
+
+    fn inputs<'a>(
+        now: DateTime<Utc>,
+        channels: &'a [LocalChannelInfo],
+        snapshots: &'a [ChannelHistoryPoint],
+        events: &'a [LocalForwardingEvent],
+        wallet_confirmed_sat: u64,
+    ) -> InboundInputs<'a> {
+        InboundInputs {
+            now,
+            channels,
+            snapshots,
+            events,
+            onchain_fee_rate: 10.0,
+            network_fee_rate_ppm: 100,
+            wallet_confirmed_sat,
+        }
+    }
+
+    #[test]
+    fn test_chronically_exhausted_channel_and_turned_away_demand() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        // Épuisé depuis 24 jours, après 6 jours à 2 M sat/jour d'arrivées
+        let drained = channel("drained", 9_800_000, true);
+        let spare = channel("spare", 1_000_000, true);
+        let mut snapshots = daily_snapshots(&drained, now, 30, |d| {
+            if d < 24 {
+                9_800_000
+            } else {
+                5_000_000
+            }
+        });
+        snapshots.extend(daily_snapshots(&spare, now, 30, |_| 1_000_000));
+        let events: Vec<_> = (24..30)
+            .map(|d| forward_in("drained", now - Duration::days(d), 1_500_000))
+            .collect();
+        let channels = [drained, spare];
+
+        let report = InboundLiquidityAnalyzer::default()
+            .analyze(&inputs(now, &channels, &snapshots, &events, 0));
+
+        assert_eq!(report.shortages.len(), 1);
+        let shortage = &report.shortages[0];
+        assert_eq!(shortage.channel_id, "drained");
+        assert!((shortage.exhausted_share - 0.8).abs() < 1e-9);
+        // 9 M sat arrivés en 6 jours disponibles, perdus 80% du temps
+        assert_eq!(shortage.arriving_sat_per_day, 1_500_000);
+        assert_eq!(shortage.turned_away_sat_per_day, 1_200_000);
+        assert_eq!(shortage.recommended_inbound_sat, 4_800_000);
+        assert_eq!(report.peers.len(), 1);
+        assert!(report.expected_revenue_sat > 0);
+    }
+
+    #[test]
+    fn test_method_costs_and_feasibility() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let drained = channel("drained", 10_000_000, true);
+        let spare = channel("spare", 0, true);
+        let mut snapshots = daily_snapshots(&drained, now, 30, |_| 10_000_000);
+        snapshots.extend(daily_snapshots(&spare, now, 30, |_| 0));
+        let channels = [drained, spare];
+
+        let analyzer = InboundLiquidityAnalyzer::default();
+        let report = analyzer.analyze(&inputs(now, &channels, &snapshots, &[], 0));
+
+        assert_eq!(report.recommended_inbound_sat, 5_000_000);
+        assert_eq!(report.quotes.len(), 4);
+        let quote = |method| {
+            report
+                .quotes
+                .iter()
+                .find(|q| q.method == method)
+                .unwrap()
+                .clone()
+        };
+        // 2 nœuds intermédiaires à 100 ppm
+        assert_eq!(quote(InboundMethod::CircularRebalance).cost_sat, 1_000);
+        assert!(quote(InboundMethod::CircularRebalance).feasible);
+        assert_eq!(quote(InboundMethod::LoopOut).cost_sat, 12_500 + 2_500);
+        assert!(!quote(InboundMethod::PushSatOpen).feasible);
+        assert!(!quote(InboundMethod::ChannelLease).feasible);
+        assert_eq!(
+            report.recommended_method,
+            Some(InboundMethod::CircularRebalance)
+        );
+        assert!(quote(InboundMethod::LoopOut).payback_days.is_none());
+
+        // 7 baux de 14 jours pour 90 jours : une seule prime ne suffit pas
+        let lease = |wallet| {
+            analyzer
+                .analyze(&inputs(now, &channels, &snapshots, &[], wallet))
+                .quotes
+                .into_iter()
+                .find(|q| q.method == InboundMethod::ChannelLease)
+                .unwrap()
+        };
+        assert_eq!(lease(0).cost_sat, 50_000 * 7 + 5_000 + 1_540);
+        assert!(!lease(100_000).feasible);
+        assert!(lease(400_000).feasible);
+    }
+
+    #[test]
+    fn test_daily_aggregates_weigh_their_samples() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let drained = channel("drained", 9_800_000, true);
+        // 5 jours agrégés sans entrant, puis 10 relevés bruts récents avec entrant
+        let mut snapshots: Vec<ChannelHistoryPoint> =
+            daily_snapshots(&drained, now, 6, |_| 9_800_000)
+                .into_iter()
+                .skip(1)
+                .map(|mut point| {
+                    point.resolution = SnapshotResolution::Day;
+                    point.samples = 1440;
+                    point
+                })
+                .collect();
+        let mut refilled = drained.clone();
+        refilled.local_balance = 5_000_000;
+        refilled.remote_balance = 5_000_000;
+        snapshots.extend((0..10).map(|m| {
+            ChannelHistoryPoint::sample(now - Duration::minutes(m + 30), refilled.clone())
+        }));
+        let channels = [drained];
+
+        let report = InboundLiquidityAnalyzer::default().analyze(&inputs(
+            now,
+            &channels,
+            &snapshots,
+            &[],
+            0,
+        ));
+
+        assert_eq!(report.shortages.len(), 1);
+        assert_eq!(report.shortages[0].snapshots, 5 * 1440 + 10);
+        assert!(report.shortages[0].exhausted_share > 0.99);
+    }
+}
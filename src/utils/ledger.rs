@@ -0,0 +1,748 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, SecondsFormat, TimeZone, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::{
+    api::{
+        local_lightning_client::{
+            LocalForwardingEvent, LocalInvoice, LocalLightningClient, LocalPayment,
+            LocalWalletTransaction,
+        },
+        price_source::PriceSource,
+    },
+    models::ledger::{Ledger, LedgerAccount, LedgerAccountBalance, LedgerCategory, LedgerEntry},
+    storage::{forwarding::ForwardingStore, prices::PriceStore},
+};
+
+const MSAT_PER_BTC: u64 = 100_000_000_000;
+
+/// Mouvements bruts du nœud à comptabiliser.
+pub struct LedgerInputs<'a> {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub forwards: &'a [LocalForwardingEvent],
+    pub payments: &'a [LocalPayment],
+    pub invoices: &'a [LocalInvoice],
+    pub wallet_transactions: &'a [LocalWalletTransaction],
+    /// Transactions de financement des canaux, ouverts ou fermés
+    pub funding_txids: &'a HashSet<String>,
+    pub closing_txids: &'a HashSet<String>,
+    /// Cours journalier du bitcoin
+    pub prices: &'a HashMap<NaiveDate, f64>,
+    pub fiat_currency: &'a str,
+}
+
+/// Grand livre de la période à partir de LND, de l'historique de forwarding et
+/// des cours journaliers, complétés auprès de la source de prix si besoin.
+pub async fn load_ledger(
+    client: &Mutex<LocalLightningClient>,
+    forwarding_store: &ForwardingStore,
+    price_store: &PriceStore,
+    price_source: &PriceSource,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Ledger> {
+    let (payments, invoices, wallet_transactions, channels, closed) = {
+        let mut client = client.lock().await;
+        (
+            client.settled_payments().await?,
+            client.settled_invoices().await?,
+            client.wallet_transactions().await?,
+            client.list_local_channels().await?,
+            client.closed_channels().await?,
+        )
+    };
+    let forwards = forwarding_store.events_between(start, end).await?;
+
+    let txid = |channel_point: &str| {
+        channel_point
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+    let funding_txids: HashSet<String> = channels
+        .iter()
+        .map(|c| txid(&c.channel_point))
+        .chain(closed.iter().map(|c| txid(&c.channel_point)))
+        .collect();
+    let closing_txids: HashSet<String> = closed
+        .iter()
+        .map(|c| c.closing_tx_hash.clone())
+        .filter(|hash| !hash.is_empty())
+        .collect();
+
+    let prices = daily_prices(price_store, price_source, start, end).await?;
+
+    Ok(build_ledger(&LedgerInputs {
+        start,
+        end,
+        forwards: &forwards,
+        payments: &payments,
+        invoices: &invoices,
+        wallet_transactions: &wallet_transactions,
+        funding_txids: &funding_txids,
+        closing_txids: &closing_txids,
+        prices: &prices,
+        fiat_currency: price_source.currency(),
+    }))
+}
+
+/// Cours de chaque jour de la période, relevé à midi UTC. Les jours absents du
+/// cache sont demandés à la source ; un échec laisse le jour sans cours.
+pub async fn daily_prices(
+    price_store: &PriceStore,
+    price_source: &PriceSource,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<HashMap<NaiveDate, f64>> {
+    let currency = price_source.currency();
+    let (first_day, last_day) = (start.date_naive(), end.date_naive());
+    let mut prices = price_store
+        .prices_between(currency, first_day, last_day)
+        .await?;
+
+    let now = Utc::now();
+    for day in first_day.iter_days().take_while(|day| *day <= last_day) {
+        if prices.contains_key(&day) {
+            continue;
+        }
+        let noon = Utc.from_utc_datetime(&day.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+        match price_source.historical_price(noon.min(now)).await {
+            Ok(price) => {
+                // Le cours du jour en cours bouge encore : il n'est pas mis en cache
+                if noon < now {
+                    price_store.record(currency, day, price).await?;
+                }
+                prices.insert(day, price);
+            }
+            Err(e) => {
+                warn!("No BTC price for {}: {}", day, e);
+            }
+        }
+    }
+    Ok(prices)
+}
+
+/// Écritures de la période triées chronologiquement, avec les soldes des comptes.
+pub fn build_ledger(inputs: &LedgerInputs) -> Ledger {
+    let in_period = |at: &DateTime<Utc>| *at >= inputs.start && *at < inputs.end;
+    let fiat = |at: &DateTime<Utc>, amount_msat: u64| {
+        inputs
+            .prices
+            .get(&at.date_naive())
+            .map(|price| (amount_msat as f64 / MSAT_PER_BTC as f64 * price * 100.0).round() / 100.0)
+    };
+    let mut entries = vec![];
+    let mut push = |timestamp: DateTime<Utc>,
+                    category: LedgerCategory,
+                    (debit, credit): (LedgerAccount, LedgerAccount),
+                    amount_msat: u64,
+                    reference: &str,
+                    description: String| {
+        if amount_msat == 0 || !in_period(&timestamp) {
+            return;
+        }
+        entries.push(LedgerEntry {
+            id: format!("{}:{}", category.as_str(), reference),
+            timestamp,
+            category,
+            debit,
+            credit,
+            amount_msat,
+            fiat_value: fiat(&timestamp, amount_msat),
+            reference: reference.to_string(),
+            description,
+        });
+    };
+
+    for forward in inputs.forwards {
+        let reference = format!(
+            "{}:{}:{}",
+            forward.timestamp_ns, forward.chan_id_in, forward.chan_id_out
+        );
+        push(
+            from_nanos(forward.timestamp_ns),
+            LedgerCategory::RoutingIncome,
+            (
+                LedgerAccount::LightningBalance,
+                LedgerAccount::RoutingIncome,
+            ),
+            forward.fee_msat,
+            &reference,
+            format!("Forward {} → {}", forward.chan_id_in, forward.chan_id_out),
+        );
+    }
+
+    // Un rééquilibrage se paie à nous-mêmes : seule sa commission sort du nœud
+    let circular: HashSet<&str> = inputs
+        .payments
+        .iter()
+        .filter(|p| p.circular)
+        .map(|p| p.payment_hash.as_str())
+        .collect();
+    for payment in inputs.payments {
+        let settled_at = from_nanos(payment.settled_at_ns);
+        if payment.circular {
+            push(
+                settled_at,
+                LedgerCategory::RebalanceFee,
+                (
+                    LedgerAccount::RebalanceFees,
+                    LedgerAccount::LightningBalance,
+                ),
+                payment.fee_msat,
+                &payment.payment_hash,
+                "Rééquilibrage circulaire".to_string(),
+            );
+            continue;
+        }
+        push(
+            settled_at,
+            LedgerCategory::PaymentSent,
+            (LedgerAccount::PaymentsSent, LedgerAccount::LightningBalance),
+            payment.value_msat,
+            &payment.payment_hash,
+            "Paiement Lightning".to_string(),
+        );
+        push(
+            settled_at,
+            LedgerCategory::PaymentFee,
+            (
+                LedgerAccount::LightningFees,
+                LedgerAccount::LightningBalance,
+            ),
+            payment.fee_msat,
+            &payment.payment_hash,
+            "Frais de routage du paiement".to_string(),
+        );
+    }
+
+    for invoice in inputs
+        .invoices
+        .iter()
+        .filter(|i| !circular.contains(i.payment_hash.as_str()))
+    {
+        let description = match (invoice.keysend, invoice.memo.is_empty()) {
+            (true, _) => "Keysend reçu".to_string(),
+            (false, true) => "Facture réglée".to_string(),
+            (false, false) => format!("Facture réglée : {}", invoice.memo),
+        };
+        push(
+            Utc.timestamp_opt(invoice.settled_at, 0)
+                .single()
+                .unwrap_or_default(),
+            LedgerCategory::InvoiceReceived,
+            (
+                LedgerAccount::LightningBalance,
+                LedgerAccount::PaymentsReceived,
+            ),
+            invoice.amt_paid_msat,
+            &invoice.payment_hash,
+            description,
+        );
+    }
+
+    for tx in inputs.wallet_transactions {
+        let at = Utc
+            .timestamp_opt(tx.timestamp, 0)
+            .single()
+            .unwrap_or_default();
+        // Financement : le portefeuille perd le montant du canal plus les frais
+        if inputs.funding_txids.contains(&tx.tx_hash) && tx.amount_sat < 0 {
+            let funded_sat = tx.amount_sat.unsigned_abs().saturating_sub(tx.fee_sat);
+            push(
+                at,
+                LedgerCategory::ChannelOpen,
+                (
+                    LedgerAccount::LightningBalance,
+                    LedgerAccount::OnChainWallet,
+                ),
+                funded_sat * 1000,
+                &tx.tx_hash,
+                "Ouverture de canal".to_string(),
+            );
+            push(
+                at,
+                LedgerCategory::ChannelOpenFee,
+                (LedgerAccount::OnChainFees, LedgerAccount::OnChainWallet),
+                tx.fee_sat * 1000,
+                &tx.tx_hash,
+                "Frais on-chain d'ouverture".to_string(),
+            );
+        } else if inputs.closing_txids.contains(&tx.tx_hash) && tx.amount_sat > 0 {
+            push(
+                at,
+                LedgerCategory::ChannelClose,
+                (
+                    LedgerAccount::OnChainWallet,
+                    LedgerAccount::LightningBalance,
+                ),
+                tx.amount_sat as u64 * 1000,
+                &tx.tx_hash,
+                "Fermeture de canal".to_string(),
+            );
+            push(
+                at,
+                LedgerCategory::ChannelCloseFee,
+                (LedgerAccount::OnChainFees, LedgerAccount::LightningBalance),
+                tx.fee_sat * 1000,
+                &tx.tx_hash,
+                "Frais on-chain de fermeture".to_string(),
+            );
+        }
+    }
+
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.id.cmp(&b.id)));
+    let balances = account_balances(&entries);
+    Ledger {
+        start: inputs.start,
+        end: inputs.end,
+        fiat_currency: inputs.fiat_currency.to_string(),
+        entries,
+        balances,
+    }
+}
+
+fn from_nanos(ns: u64) -> DateTime<Utc> {
+    DateTime::<Utc>::UNIX_EPOCH + Duration::nanoseconds(ns.min(i64::MAX as u64) as i64)
+}
+
+/// Débits moins crédits par compte ; la contre-valeur n'est donnée que si toutes
+/// les écritures du compte ont un cours.
+fn account_balances(entries: &[LedgerEntry]) -> Vec<LedgerAccountBalance> {
+    let mut totals: BTreeMap<LedgerAccount, (i64, Option<f64>)> = BTreeMap::new();
+    for entry in entries {
+        for (account, sign) in [(entry.debit, 1.0), (entry.credit, -1.0)] {
+            let (msat, fiat) = totals.entry(account).or_insert((0, Some(0.0)));
+            *msat += sign as i64 * entry.amount_msat as i64;
+            *fiat = fiat
+                .zip(entry.fiat_value)
+                .map(|(total, value)| total + sign * value);
+        }
+    }
+    totals
+        .into_iter()
+        .map(
+            |(account, (balance_msat, fiat_balance))| LedgerAccountBalance {
+                account,
+                name: account.name().to_string(),
+                balance_msat,
+                fiat_balance: fiat_balance.map(|value| (value * 100.0).round() / 100.0),
+            },
+        )
+        .collect()
+}
+
+/// Formats d'export CSV : générique, ou importables par Koinly et CoinTracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerExportFormat {
+    Generic,
+    Koinly,
+    CoinTracking,
+}
+
+impl LedgerExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "generic" | "csv" => Some(Self::Generic),
+            "koinly" => Some(Self::Koinly),
+            "cointracking" => Some(Self::CoinTracking),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(self, ledger: &Ledger) -> String {
+        let suffix = match self {
+            Self::Generic => "ledger",
+            Self::Koinly => "koinly",
+            Self::CoinTracking => "cointracking",
+        };
+        format!(
+            "lightning-{}-{}-{}.csv",
+            suffix,
+            ledger.start.format("%Y%m%d"),
+            ledger.end.format("%Y%m%d")
+        )
+    }
+}
+
+/// Grand livre au format CSV. Les outils fiscaux ne connaissent qu'un seul
+/// portefeuille : les ouvertures et fermetures de canaux, simples transferts
+/// internes, n'y apparaissent que par leurs frais.
+pub fn export_csv(ledger: &Ledger, format: LedgerExportFormat) -> String {
+    let mut rows: Vec<Vec<String>> = vec![];
+    match format {
+        LedgerExportFormat::Generic => {
+            rows.push(
+                [
+                    "id",
+                    "timestamp",
+                    "category",
+                    "debit_account",
+                    "credit_account",
+                    "amount_msat",
+                    "amount_btc",
+                    "fiat_value",
+                    "fiat_currency",
+                    "reference",
+                    "description",
+                ]
+                .map(String::from)
+                .to_vec(),
+            );
+            for entry in &ledger.entries {
+                rows.push(vec![
+                    entry.id.clone(),
+                    entry.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    entry.category.as_str().to_string(),
+                    entry.debit.name().to_string(),
+                    entry.credit.name().to_string(),
+                    entry.amount_msat.to_string(),
+                    btc(entry.amount_msat),
+                    fiat_cell(entry.fiat_value),
+                    ledger.fiat_currency.clone(),
+                    entry.reference.clone(),
+                    entry.description.clone(),
+                ]);
+            }
+        }
+        LedgerExportFormat::Koinly => {
+            rows.push(
+                [
+                    "Date",
+                    "Sent Amount",
+                    "Sent Currency",
+                    "Received Amount",
+                    "Received Currency",
+                    "Fee Amount",
+                    "Fee Currency",
+                    "Net Worth Amount",
+                    "Net Worth Currency",
+                    "Label",
+                    "Description",
+                    "TxHash",
+                ]
+                .map(String::from)
+                .to_vec(),
+            );
+            for entry in ledger.entries.iter().filter(|e| !e.category.is_transfer()) {
+                let amount = btc(entry.amount_msat);
+                let incoming = entry.debit == LedgerAccount::LightningBalance;
+                let label = match entry.category {
+                    LedgerCategory::RoutingIncome => "income",
+                    LedgerCategory::PaymentFee
+                    | LedgerCategory::RebalanceFee
+                    | LedgerCategory::ChannelOpenFee
+                    | LedgerCategory::ChannelCloseFee => "cost",
+                    _ => "",
+                };
+                let (sent, received) = if incoming {
+                    (String::new(), amount)
+                } else {
+                    (amount, String::new())
+                };
+                rows.push(vec![
+                    entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    sent.clone(),
+                    if sent.is_empty() { "" } else { "BTC" }.to_string(),
+                    received.clone(),
+                    if received.is_empty() { "" } else { "BTC" }.to_string(),
+                    String::new(),
+                    String::new(),
+                    fiat_cell(entry.fiat_value),
+                    if entry.fiat_value.is_some() {
+                        ledger.fiat_currency.clone()
+                    } else {
+                        String::new()
+                    },
+                    label.to_string(),
+                    entry.description.clone(),
+                    entry.reference.clone(),
+                ]);
+            }
+        }
+        LedgerExportFormat::CoinTracking => {
+            rows.push(
+                [
+                    "Type",
+                    "Buy Amount",
+                    "Buy Currency",
+                    "Sell Amount",
+                    "Sell Currency",
+                    "Fee",
+                    "Fee Currency",
+                    "Exchange",
+                    "Trade-Group",
+                    "Comment",
+                    "Date",
+                ]
+                .map(String::from)
+                .to_vec(),
+            );
+            for entry in ledger.entries.iter().filter(|e| !e.category.is_transfer()) {
+                let amount = btc(entry.amount_msat);
+                let (kind, buy, sell) = match entry.category {
+                    LedgerCategory::RoutingIncome => ("Income", amount, String::new()),
+                    LedgerCategory::InvoiceReceived => ("Deposit", amount, String::new()),
+                    LedgerCategory::PaymentSent => ("Spend", String::new(), amount),
+                    _ => ("Other Fee", String::new(), amount),
+                };
+                rows.push(vec![
+                    kind.to_string(),
+                    buy.clone(),
+                    if buy.is_empty() { "" } else { "BTC" }.to_string(),
+                    sell.clone(),
+                    if sell.is_empty() { "" } else { "BTC" }.to_string(),
+                    String::new(),
+                    String::new(),
+                    "Lightning".to_string(),
+                    entry.category.as_str().to_string(),
+                    format!("{} ({})", entry.description, entry.reference),
+                    entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                ]);
+            }
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| csv_cell(cell))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Montant en BTC à la précision du millisatoshi, au moins 8 décimales.
+fn btc(msat: u64) -> String {
+    let decimals = format!("{:011}", msat % MSAT_PER_BTC);
+    let trimmed = decimals.trim_end_matches('0');
+    format!(
+        "{}.{}",
+        msat / MSAT_PER_BTC,
+        if trimmed.len() > 8 {
+            trimmed
+        } else {
+            &decimals[..8]
+        }
+    )
+}
+
+fn fiat_cell(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_default()
+}
+
+fn csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ns(at: DateTime<Utc>) -> u64 {
+        at.timestamp_nanos_opt().unwrap() as u64
+    }
+
+    #[test]
+    fn test_ledger_balances_and_excludes_internal_payments() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let day = Utc.with_ymd_and_hms(2024, 1, 10, 15, 0, 0).unwrap();
+
+        let forwards = [LocalForwardingEvent {
+            timestamp_ns: ns(day),
+            chan_id_in: "1".to_string(),
+            chan_id_out: "2".to_string(),
+            amt_in_msat: 1_000_250_000,
+            amt_out_msat: 1_000_000_000,
+            fee_msat: 250_000,
+        }];
+        let payments = [
+            LocalPayment {
+                payment_hash: "rebalance".to_string(),
+                value_msat: 500_000_000,
+                fee_msat: 40_000,
+                settled_at_ns: ns(day),
+                circular: true,
+            },
+            LocalPayment {
+                payment_hash: "coffee".to_string(),
+                value_msat: 5_000_000,
+                fee_msat: 2_000,
+                settled_at_ns: ns(day),
+                circular: false,
+            },
+        ];
+        let invoices = [
+            // Côté réception du rééquilibrage
+            LocalInvoice {
+                payment_hash: "rebalance".to_string(),
+                memo: String::new(),
+                amt_paid_msat: 500_000_000,
+                settled_at: day.timestamp(),
+                keysend: false,
+            },
+            LocalInvoice {
+                payment_hash: "sale".to_string(),
+                memo: "t-shirt".to_string(),
+                amt_paid_msat: 20_000_000,
+                settled_at: day.timestamp(),
+                keysend: false,
+            },
+        ];
+        let wallet_transactions = [
+            LocalWalletTransaction {
+                tx_hash: "funding".to_string(),
+                amount_sat: -1_002_000,
+                fee_sat: 2_000,
+                timestamp: day.timestamp(),
+                label: String::new(),
+            },
+            // Hors période
+            LocalWalletTransaction {
+                tx_hash: "closing".to_string(),
+                amount_sat: 300_000,
+                fee_sat: 0,
+                timestamp: end.timestamp() + 60,
+                label: String::new(),
+            },
+        ];
+        let funding_txids = HashSet::from(["funding".to_string()]);
+        let closing_txids = HashSet::from(["closing".to_string()]);
+        let prices = HashMap::from([(day.date_naive(), 40_000.0)]);
+
+        let ledger = build_ledger(&LedgerInputs {
+            start,
+            end,
+            forwards: &forwards,
+            payments: &payments,
+            invoices: &invoices,
+            wallet_transactions: &wallet_transactions,
+            funding_txids: &funding_txids,
+            closing_txids: &closing_txids,
+            prices: &prices,
+            fiat_currency: "EUR",
+        });
+
+        let categories: Vec<LedgerCategory> = ledger.entries.iter().map(|e| e.category).collect();
+        assert_eq!(ledger.entries.len(), 7);
+        assert!(!categories.contains(&LedgerCategory::ChannelClose));
+        assert_eq!(
+            categories
+                .iter()
+                .filter(|c| **c == LedgerCategory::InvoiceReceived)
+                .count(),
+            1
+        );
+        let open = ledger
+            .entries
+            .iter()
+            .find(|e| e.category == LedgerCategory::ChannelOpen)
+            .unwrap();
+        assert_eq!(open.amount_msat, 1_000_000_000);
+        // 0,01 BTC à 40 000 €
+        assert_eq!(open.fiat_value, Some(400.0));
+
+        // Partie double : la somme des soldes est nulle
+        assert_eq!(
+            ledger.balances.iter().map(|b| b.balance_msat).sum::<i64>(),
+            0
+        );
+        let balance = |account: LedgerAccount| {
+            ledger
+                .balances
+                .iter()
+                .find(|b| b.account == account)
+                .unwrap()
+                .balance_msat
+        };
+        assert_eq!(balance(LedgerAccount::RoutingIncome), -250_000);
+        assert_eq!(balance(LedgerAccount::RebalanceFees), 40_000);
+        assert_eq!(balance(LedgerAccount::OnChainWallet), -1_002_000_000);
+        assert_eq!(
+            balance(LedgerAccount::LightningBalance),
+            250_000 - 40_000 - 5_000_000 - 2_000 + 20_000_000 + 1_000_000_000
+        );
+    }
+
+    #[test]
+    fn test_tax_tool_exports() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 5, 8, 30, 0).unwrap();
+        let entry = |category, debit, credit, amount_msat, description: &str| LedgerEntry {
+            id: format!("{:?}", category),
+            timestamp: at,
+            category,
+            debit,
+            credit,
+            amount_msat,
+            fiat_value: Some(1.5),
+            reference: "ref".to_string(),
+            description: description.to_string(),
+        };
+        let ledger = Ledger {
+            start: at - Duration::days(1),
+            end: at + Duration::days(1),
+            fiat_currency: "EUR".to_string(),
+            entries: vec![
+                entry(
+                    LedgerCategory::RoutingIncome,
+                    LedgerAccount::LightningBalance,
+                    LedgerAccount::RoutingIncome,
+                    1_234_567,
+                    "Forward 1 → 2",
+                ),
+                entry(
+                    LedgerCategory::ChannelOpen,
+                    LedgerAccount::LightningBalance,
+                    LedgerAccount::OnChainWallet,
+                    100_000_000,
+                    "Ouverture de canal",
+                ),
+                entry(
+                    LedgerCategory::PaymentFee,
+                    LedgerAccount::LightningFees,
+                    LedgerAccount::LightningBalance,
+                    3_000,
+                    "Frais, \"urgents\"",
+                ),
+            ],
+            balances: vec![],
+        };
+
+        let generic = export_csv(&ledger, LedgerExportFormat::Generic);
+        assert_eq!(generic.lines().count(), 4);
+        assert!(generic.contains(
+            "RoutingIncome,2024-03-05T08:30:00Z,routing_income,Assets:Lightning,Income:Routing,1234567,0.00001234567,1.50,EUR"
+        ));
+
+        let koinly = export_csv(&ledger, LedgerExportFormat::Koinly);
+        let lines: Vec<&str> = koinly.lines().collect();
+        // L'ouverture de canal est un transfert interne
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "2024-03-05 08:30:00 UTC,,,0.00001234567,BTC,,,1.50,EUR,income,Forward 1 → 2,ref"
+        );
+        assert_eq!(
+            lines[2],
+            "2024-03-05 08:30:00 UTC,0.00000003,BTC,,,,,1.50,EUR,cost,\"Frais, \"\"urgents\"\"\",ref"
+        );
+
+        let cointracking = export_csv(&ledger, LedgerExportFormat::CoinTracking);
+        assert!(cointracking
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("Income,0.00001234567,BTC,,,,,Lightning,routing_income,"));
+    }
+}
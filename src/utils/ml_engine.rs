@@ -1,30 +1,203 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use tokio::sync::Mutex;
+use tracing::warn;
+
 use crate::{
     api::{
-        local_lightning_client::LocalChannelInfo,
+        local_lightning_client::{
+            LocalChannelInfo, LocalForwardingEvent, LocalLightningClient, LocalNetworkGraph,
+        },
         mcp_client::{ActionType, Priority},
     },
     models::{
+        action_params::ActionParameters,
         automation::AutomationSettings,
+        fee_strategy::FeeStrategyAssignment,
         ml::{
-            AutomationReadiness, MLInsight, MLScorecard, OptimalWindow, SimulationOutcome,
-            SimulationStep, SmartRecommendation,
+            AutomationReadiness, CostEstimate, MLInsight, MLScorecard, OptimalWindow,
+            PercentileBand, SimulationOutcome, SimulationStep, SmartRecommendation,
+        },
+    },
+    storage::{
+        decisions::DecisionStore, fee_strategies::FeeStrategyStore, forwarding::ForwardingStore,
+    },
+    utils::{
+        channel_candidates::{ChannelCandidate, ChannelCandidateFinder},
+        close_candidates::{CloseCandidate, CloseCandidateFinder},
+        fee_elasticity::{FeeElasticityEstimator, FeeElasticityModel, FeeProposal},
+        fee_strategies::{
+            competitor_ppm, FeeStrategy, FeeStrategyChain, FeeStrategyContext, PolicyTarget,
         },
+        monte_carlo::{MonteCarloSimulator, SimulationContext},
+        preference_learning::{PreferenceLearner, PreferenceProfile},
+        scheduling::{ExecutionScheduler, SchedulingInputs},
     },
 };
 
-/// Configuration du moteur ML local.
-#[derive(Debug, Clone)]
+/// Configuration du moteur ML local, persistée et versionnée.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MLEngineConfig {
-    pub target_advantage: f64,
     pub confidence_floor: f64,
     pub risk_floor: f64,
     pub max_recommendations: usize,
+    pub min_fee_model_confidence: f64,
+}
+
+/// Historique de forwarding fourni aux stratégies de frais
+pub const STRATEGY_HISTORY_DAYS: i64 = 30;
+
+/// Signaux locaux alimentant la génération de recommandations.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecommendationInputs<'a> {
+    pub channels: &'a [LocalChannelInfo],
+    pub fee_models: &'a [FeeElasticityModel],
+    pub open_candidates: &'a [ChannelCandidate],
+    pub close_candidates: &'a [CloseCandidate],
+    pub fee_assignments: &'a [FeeStrategyAssignment],
+    pub forwarding_history: &'a [LocalForwardingEvent],
+    pub graph: Option<&'a LocalNetworkGraph>,
+    /// Préférences apprises des décisions de l'opérateur (mode apprentissage)
+    pub preferences: Option<&'a PreferenceProfile>,
+}
+
+impl<'a> RecommendationInputs<'a> {
+    /// Canaux seuls, sans historique ni graphe.
+    pub fn new(channels: &'a [LocalChannelInfo]) -> Self {
+        Self {
+            channels,
+            ..Default::default()
+        }
+    }
+}
+
+/// Signaux calculés à partir de l'historique persisté et du graphe du réseau.
+#[derive(Debug, Clone, Default)]
+pub struct RecommendationSignals {
+    pub fee_models: Vec<FeeElasticityModel>,
+    pub open_candidates: Vec<ChannelCandidate>,
+    pub close_candidates: Vec<CloseCandidate>,
+    pub fee_assignments: Vec<FeeStrategyAssignment>,
+    pub forwarding_history: Vec<LocalForwardingEvent>,
+    pub graph: Option<LocalNetworkGraph>,
+    pub preferences: Option<PreferenceProfile>,
+}
+
+impl RecommendationSignals {
+    /// Chaque signal indisponible est simplement omis : la génération continue sans lui.
+    pub async fn gather(
+        client: &Mutex<LocalLightningClient>,
+        forwarding_store: &ForwardingStore,
+        fee_strategy_store: &FeeStrategyStore,
+        decision_store: &DecisionStore,
+        channels: &[LocalChannelInfo],
+        settings: &AutomationSettings,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let fee_models = FeeElasticityEstimator::default()
+            .fit_channels(forwarding_store, channels, now)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Unable to fit fee elasticity models: {}", e);
+                vec![]
+            });
+
+        let graph = {
+            let mut client = client.lock().await;
+            // Erreur convertie avant tout autre `await` : `Box<dyn Error>` n'est pas `Send`
+            let own_pubkey = client
+                .get_local_node_info()
+                .await
+                .map(|info| info.pubkey)
+                .map_err(|e| e.to_string());
+            match own_pubkey {
+                Ok(own_pubkey) => client
+                    .describe_graph()
+                    .await
+                    .map(|graph| (own_pubkey, graph))
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            }
+        };
+        let (open_candidates, graph) = match graph {
+            Ok((own_pubkey, graph)) => (
+                ChannelCandidateFinder::default().find(
+                    &graph,
+                    &own_pubkey,
+                    channels,
+                    &settings.blacklisted_peers,
+                ),
+                Some(graph),
+            ),
+            Err(e) => {
+                warn!("Unable to read the network graph: {}", e);
+                (vec![], None)
+            }
+        };
+
+        let close_candidates = CloseCandidateFinder::default()
+            .find_stored(forwarding_store, channels, now)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Unable to detect channel close candidates: {}", e);
+                vec![]
+            });
+        let fee_assignments = fee_strategy_store.list().await.unwrap_or_else(|e| {
+            warn!("Unable to load fee strategy assignments: {}", e);
+            vec![]
+        });
+        let forwarding_history = forwarding_store
+            .events_between(now - chrono::Duration::days(STRATEGY_HISTORY_DAYS), now)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Unable to load forwarding history: {}", e);
+                vec![]
+            });
+
+        let preferences = if settings.advanced_settings.learning_mode {
+            decision_store
+                .list()
+                .await
+                .map(|decisions| PreferenceLearner::default().learn(&decisions))
+                .map_err(|e| warn!("Unable to load operator decisions: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        Self {
+            fee_models,
+            open_candidates,
+            close_candidates,
+            fee_assignments,
+            forwarding_history,
+            graph,
+            preferences,
+        }
+    }
+
+    pub fn inputs<'a>(&'a self, channels: &'a [LocalChannelInfo]) -> RecommendationInputs<'a> {
+        RecommendationInputs {
+            channels,
+            fee_models: &self.fee_models,
+            open_candidates: &self.open_candidates,
+            close_candidates: &self.close_candidates,
+            fee_assignments: &self.fee_assignments,
+            forwarding_history: &self.forwarding_history,
+            graph: self.graph.as_ref(),
+            preferences: self.preferences.as_ref(),
+        }
+    }
 }
 
 /// Moteur ML phase 3 : calcule les recommandations et la préparation à l’automatisation.
 #[derive(Debug, Clone)]
 pub struct MLEngine {
     pub config: MLEngineConfig,
+    /// Version persistée de la configuration ; `None` pour la configuration par défaut
+    pub version: Option<i64>,
 }
 
 impl Default for MLEngine {
@@ -37,16 +210,23 @@ impl MLEngine {
     pub fn new() -> Self {
         Self {
             config: MLEngineConfig {
-                target_advantage: 15.3,
                 confidence_floor: 0.82,
                 risk_floor: 0.18,
                 max_recommendations: 3,
+                min_fee_model_confidence: 0.3,
             },
+            version: None,
         }
     }
 
-    /// Analyse rapide des canaux pour construire le scorecard ML.
-    pub fn score_channels(&self, channels: &[LocalChannelInfo]) -> MLScorecard {
+    pub fn with_config(config: MLEngineConfig, version: Option<i64>) -> Self {
+        Self { config, version }
+    }
+
+    /// Scorecard ML : rendement réalisé sur l'historique de forwarding et écart de prix
+    /// face aux routes concurrentes. Sans historique ni graphe, ces chiffres restent vides.
+    pub fn score_channels(&self, inputs: &RecommendationInputs) -> MLScorecard {
+        let channels = inputs.channels;
         let mut total_capacity: u64 = 0;
         let mut local_ratio_sum = 0.0;
         for channel in channels {
@@ -61,23 +241,35 @@ impl MLEngine {
             local_ratio_sum / channels.len() as f64
         };
 
-        let imbalance_penalty = (0.5 - avg_local_ratio).abs() * 22.0;
-        let predicted_roi_30d = (self.config.target_advantage + 2.4 - imbalance_penalty).max(10.0);
-        let predicted_roi_90d = predicted_roi_30d + 2.0;
-        let predicted_roi_365d = predicted_roi_90d + 6.2;
+        // Frais encaissés sur la fenêtre d'historique, rapportés à la liquidité locale engagée
+        let local_balance: u64 = channels.iter().map(|c| c.local_balance).sum();
+        let fees_sat = inputs
+            .forwarding_history
+            .iter()
+            .map(|e| e.fee_msat)
+            .sum::<u64>() as f64
+            / 1000.0;
+        let window_roi = (!inputs.forwarding_history.is_empty() && local_balance > 0)
+            .then(|| fees_sat / local_balance as f64 * 100.0);
+        // Projection au rythme observé sur STRATEGY_HISTORY_DAYS jours
+        let run_rate =
+            |days: i64| window_roi.map(|roi| roi * days as f64 / STRATEGY_HISTORY_DAYS as f64);
 
+        let imbalance_penalty = (0.5 - avg_local_ratio).abs() * 22.0;
         let saturation = (capacity / 10_000_000.0).min(1.2) * 100.0;
         let risk_index = (self.config.risk_floor + imbalance_penalty / 100.0).min(0.45);
         let confidence =
             (self.config.confidence_floor + (0.5 - imbalance_penalty / 30.0)).clamp(0.7, 0.97);
 
         MLScorecard {
-            predicted_roi_30d,
-            predicted_roi_90d,
-            predicted_roi_365d,
+            predicted_roi_30d: run_rate(30),
+            predicted_roi_90d: run_rate(90),
+            predicted_roi_365d: run_rate(365),
             risk_index,
             capacity_saturation: saturation,
-            advantage_vs_amboss: self.config.target_advantage + 1.2 - imbalance_penalty.min(4.0),
+            advantage_vs_amboss: inputs
+                .graph
+                .and_then(|graph| price_advantage(graph, channels)),
             confidence,
         }
     }
@@ -146,74 +338,343 @@ impl MLEngine {
     }
 
     /// Génère des recommandations prêtes pour la phase 3.
-    pub fn build_recommendations(&self, channels: &[LocalChannelInfo]) -> Vec<SmartRecommendation> {
-        let scorecard = self.score_channels(channels);
+    pub fn build_recommendations(&self, inputs: &RecommendationInputs) -> Vec<SmartRecommendation> {
+        let channels = inputs.channels;
+        let scorecard = self.score_channels(inputs);
         let mut output = vec![];
 
-        // Ajustement de frais sur les canaux déséquilibrés
-        if let Some(channel) = channels.first() {
-            output.push(SmartRecommendation {
-                id: "rec_adjust_fees".to_string(),
-                action_type: ActionType::AdjustFees,
-                priority: Priority::High,
-                expected_roi_impact: (3.0 + scorecard.confidence * 2.0).min(6.5),
-                confidence: scorecard.confidence,
-                risk_score: scorecard.risk_index,
-                rationale: vec![
-                    "Hausse de la demande détectée sur 3 pairs".to_string(),
-                    "Frais actuels sous le 50e percentile du réseau".to_string(),
-                ],
-                target_channels: vec![channel.channel_id.clone()],
-            });
+        // Ajustement de frais : stratégie affectée au canal ou à son groupe de pairs
+        let mut strategy_channels = HashSet::new();
+        for channel in channels {
+            let Some(assignment) = FeeStrategyAssignment::resolve(
+                inputs.fee_assignments,
+                &channel.channel_id,
+                &channel.peer_pubkey,
+            ) else {
+                continue;
+            };
+            strategy_channels.insert(channel.channel_id.as_str());
+            output.extend(self.strategy_recommendation(channel, assignment, inputs));
+        }
+
+        // Sinon modèle d'élasticité dès que l'historique le permet
+        let estimator = FeeElasticityEstimator::default();
+        let mut fee_recommendations: Vec<(f64, SmartRecommendation)> = inputs
+            .fee_models
+            .iter()
+            .filter(|model| !strategy_channels.contains(model.channel_id.as_str()))
+            .filter_map(|model| {
+                let channel = channels.iter().find(|c| c.channel_id == model.channel_id)?;
+                let proposal = estimator.propose(model)?;
+                if proposal.confidence < self.config.min_fee_model_confidence {
+                    return None;
+                }
+                Some((
+                    proposal.daily_revenue_delta_sat,
+                    self.fee_recommendation(channel, model, &proposal),
+                ))
+            })
+            .collect();
+        fee_recommendations.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        // Sans modèle d'élasticité, aucune hausse de frais n'est proposée à l'aveugle
+        output.extend(fee_recommendations.into_iter().map(|(_, rec)| rec));
+
+        // Rebalancing : du canal le plus chargé localement vers le plus vide
+        let by_ratio = |c: &&LocalChannelInfo| {
+            (c.local_balance as f64 / c.capacity.max(1) as f64 * 1_000_000.0) as u64
+        };
+        let outgoing = channels.iter().max_by_key(by_ratio);
+        let incoming = channels.iter().min_by_key(by_ratio);
+        if let (Some(outgoing), Some(incoming), true) = (outgoing, incoming, channels.len() > 1) {
+            let amount = outgoing
+                .local_balance
+                .saturating_sub(outgoing.capacity / 2)
+                .min(
+                    incoming
+                        .remote_balance
+                        .saturating_sub(incoming.capacity / 2),
+                );
+            let local_share =
+                |c: &LocalChannelInfo| c.local_balance as f64 / c.capacity.max(1) as f64 * 100.0;
+            // Montant nul : aucun canal n'a d'excédent à céder à l'autre
+            if amount > 0 {
+                output.push(SmartRecommendation {
+                    id: format!(
+                        "rec_rebalance_{}_{}",
+                        outgoing.channel_id, incoming.channel_id
+                    ),
+                    action_type: ActionType::RebalanceChannel,
+                    priority: Priority::Medium,
+                    // Gain de routage non observable avant exécution : chiffré par simulation
+                    expected_roi_impact: 0.0,
+                    confidence: (scorecard.confidence - 0.05).max(0.72),
+                    risk_score: (scorecard.risk_index + 0.08).min(0.48),
+                    rationale: vec![
+                        format!(
+                            "Part locale {:.1} % sur {} contre {:.1} % sur {}",
+                            local_share(outgoing),
+                            outgoing.channel_id,
+                            local_share(incoming),
+                            incoming.channel_id
+                        ),
+                        format!(
+                            "{} sat déplacés ramènent le canal le plus plein vers l'équilibre",
+                            amount
+                        ),
+                    ],
+                    target_channels: vec![outgoing.channel_id.clone(), incoming.channel_id.clone()],
+                    parameters: json!({
+                        "outgoing_channel_id": outgoing.channel_id,
+                        "incoming_channel_id": incoming.channel_id,
+                        "amount_sat": amount,
+                        "max_fee_sat": amount * 500 / 1_000_000,
+                    }),
+                });
+            }
         }
 
-        // Rebalancing
-        if channels.len() > 1 {
+        // Ouverture d’un nouveau canal vers le meilleur candidat du graphe
+        if let Some(candidate) = inputs.open_candidates.first() {
             output.push(SmartRecommendation {
-                id: "rec_rebalance".to_string(),
-                action_type: ActionType::RebalanceChannel,
-                priority: Priority::Medium,
-                expected_roi_impact: 2.1,
-                confidence: (scorecard.confidence - 0.05).max(0.72),
-                risk_score: (scorecard.risk_index + 0.08).min(0.48),
-                rationale: vec![
-                    "Écart de balance > 12% détecté".to_string(),
-                    "Fenêtre réseau favorable dans les 4 prochaines heures".to_string(),
-                ],
-                target_channels: channels
-                    .iter()
-                    .take(2)
-                    .map(|c| c.channel_id.clone())
-                    .collect(),
+                // Identifiant stable par pair : un refus reste attaché à ce pair
+                id: format!("rec_open_channel_{}", candidate.pubkey),
+                action_type: ActionType::OpenChannel,
+                priority: if candidate.score >= 70.0 {
+                    Priority::High
+                } else {
+                    Priority::Medium
+                },
+                // Pas d'historique pour un nouveau pair : le revenu se chiffre par simulation
+                expected_roi_impact: 0.0,
+                confidence: 0.5 + 0.4 * candidate.score / 100.0,
+                risk_score: (1.0 - candidate.score / 100.0) * 0.5,
+                rationale: std::iter::once(format!(
+                    "Candidat {} ({}) classé {:.0}/100 sur le graphe",
+                    if candidate.alias.is_empty() {
+                        &candidate.pubkey
+                    } else {
+                        &candidate.alias
+                    },
+                    candidate.pubkey,
+                    candidate.score
+                ))
+                .chain(candidate.rationale.iter().cloned())
+                .collect(),
+                target_channels: vec![],
+                parameters: json!({
+                    "peer_pubkey": candidate.pubkey,
+                    "amount_sat": candidate.suggested_size_sat,
+                }),
             });
         }
 
-        // Ouverture d’un nouveau canal
-        output.push(SmartRecommendation {
-            id: "rec_open_channel".to_string(),
-            action_type: ActionType::OpenChannel,
-            priority: Priority::Medium,
-            expected_roi_impact: 1.6,
-            confidence: 0.84,
-            risk_score: 0.32,
-            rationale: vec![
-                "Pair émergent avec score de fiabilité élevé".to_string(),
-                "Complémente la topologie actuelle (multi-routes)".to_string(),
-            ],
-            target_channels: vec![],
-        });
+        // Fermetures dont le bilan coût / bénéfice est favorable
+        output.extend(
+            inputs
+                .close_candidates
+                .iter()
+                .filter(|c| c.recommended)
+                .map(|c| self.close_recommendation(c)),
+        );
 
+        // Poids et seuil appris des approbations et refus de l'opérateur
+        if let Some(preferences) = inputs.preferences {
+            output = preferences.apply(output);
+        }
+
+        // Les plus prioritaires d'abord, l'ordre de génération départageant les ex æquo
+        output.sort_by_key(|rec| match rec.priority {
+            Priority::High => 0,
+            Priority::Medium => 1,
+            Priority::Low => 2,
+        });
         output.truncate(self.config.max_recommendations);
         output
     }
 
+    /// Recommandation AdjustFees issue de la chaîne de stratégies affectée au canal.
+    fn strategy_recommendation(
+        &self,
+        channel: &LocalChannelInfo,
+        assignment: &FeeStrategyAssignment,
+        inputs: &RecommendationInputs,
+    ) -> Option<SmartRecommendation> {
+        let chain = FeeStrategyChain::from_kinds(&assignment.strategies);
+        let history: Vec<LocalForwardingEvent> = inputs
+            .forwarding_history
+            .iter()
+            .filter(|e| e.chan_id_in == channel.channel_id || e.chan_id_out == channel.channel_id)
+            .cloned()
+            .collect();
+        let own_channels: HashSet<&str> = inputs
+            .channels
+            .iter()
+            .map(|c| c.channel_id.as_str())
+            .collect();
+        let competitors = inputs
+            .graph
+            .map(|graph| competitor_ppm(graph, &channel.peer_pubkey, &own_channels))
+            .unwrap_or_default();
+        let current = PolicyTarget {
+            base_fee_msat: channel.base_fee_msat,
+            fee_rate_ppm: channel.fee_rate_milli_msat as u32,
+        };
+
+        let decision = chain.decide(&FeeStrategyContext {
+            now: Utc::now(),
+            channel,
+            history: &history,
+            competitor_ppm: &competitors,
+            proposed: current,
+        })?;
+        if decision.policy == current {
+            return None;
+        }
+
+        let mut rationale = vec![format!(
+            "Stratégie {} ({})",
+            chain.names().join(" → "),
+            assignment.scope_key()
+        )];
+        rationale.extend(decision.rationale);
+        rationale.push(format!(
+            "De {} à {} ppm",
+            current.fee_rate_ppm, decision.policy.fee_rate_ppm
+        ));
+
+        // Impact chiffré seulement si un modèle d'élasticité existe pour ce canal
+        let model = inputs
+            .fee_models
+            .iter()
+            .find(|m| m.channel_id == channel.channel_id);
+        let expected_roi_impact = match model {
+            Some(model) => {
+                let daily_delta = model.predicted_daily_revenue_sat(decision.policy.fee_rate_ppm)
+                    - model.predicted_daily_revenue_sat(current.fee_rate_ppm);
+                daily_delta * 365.0 / channel.capacity.max(1) as f64 * 100.0
+            }
+            None => {
+                rationale.push("Impact non chiffré : pas de modèle d'élasticité".to_string());
+                0.0
+            }
+        };
+
+        Some(SmartRecommendation {
+            id: format!("rec_adjust_fees_{}", channel.channel_id),
+            action_type: ActionType::AdjustFees,
+            priority: Priority::Medium,
+            expected_roi_impact,
+            // Règle choisie par l'opérateur : confiance dans l'intention, pas dans la prévision
+            confidence: model.map_or(0.7, |m| {
+                m.confidence(FeeElasticityEstimator::default().window_days)
+                    .max(0.5)
+            }),
+            risk_score: 0.2,
+            rationale,
+            target_channels: vec![channel.channel_id.clone()],
+            parameters: json!({
+                "channel_id": channel.channel_id,
+                "channel_point": channel.channel_point,
+                "base_fee_msat": decision.policy.base_fee_msat,
+                "fee_rate_ppm": decision.policy.fee_rate_ppm,
+            }),
+        })
+    }
+
+    /// Recommandation de fermeture coopérative à partir du bilan d'un canal.
+    fn close_recommendation(&self, candidate: &CloseCandidate) -> SmartRecommendation {
+        let observed = candidate.observed_days as f64 / candidate.horizon_days.max(1) as f64;
+
+        SmartRecommendation {
+            id: format!("rec_close_channel_{}", candidate.channel_id),
+            action_type: ActionType::CloseChannel,
+            priority: if candidate.reasons.len() >= 2 {
+                Priority::High
+            } else {
+                Priority::Medium
+            },
+            // Bénéfice net annualisé rapporté au capital libéré, en %
+            expected_roi_impact: candidate.net_benefit_sat as f64
+                / candidate.local_balance_sat.max(1) as f64
+                * 365.0
+                / candidate.horizon_days.max(1) as f64
+                * 100.0,
+            confidence: (0.5 + 0.1 * candidate.reasons.len() as f64 + 0.2 * observed.min(1.0))
+                .min(0.9),
+            // Une fermeture est irréversible ; un pair absent la rend plus incertaine
+            risk_score: if candidate.uptime.is_some_and(|u| u < 0.5) {
+                0.45
+            } else {
+                0.35
+            },
+            rationale: candidate.rationale.clone(),
+            target_channels: vec![candidate.channel_id.clone()],
+            parameters: json!({
+                "channel_id": candidate.channel_id,
+                "channel_point": candidate.channel_point,
+                "force": false,
+                "sat_per_vbyte": candidate.close_cost.onchain_fee_rate_sat_vb.round() as u64,
+            }),
+        }
+    }
+
+    /// Recommandation AdjustFees chiffrée à partir d'un modèle d'élasticité.
+    fn fee_recommendation(
+        &self,
+        channel: &LocalChannelInfo,
+        model: &FeeElasticityModel,
+        proposal: &FeeProposal,
+    ) -> SmartRecommendation {
+        let annual_delta_sat = proposal.daily_revenue_delta_sat * 365.0;
+        let relative_gain =
+            proposal.daily_revenue_delta_sat / proposal.current_daily_revenue_sat.max(1.0);
+
+        SmartRecommendation {
+            id: format!("rec_adjust_fees_{}", channel.channel_id),
+            action_type: ActionType::AdjustFees,
+            priority: if relative_gain >= 0.2 {
+                Priority::High
+            } else {
+                Priority::Medium
+            },
+            // Gain annualisé rapporté au capital immobilisé dans le canal, en %
+            expected_roi_impact: annual_delta_sat / channel.capacity.max(1) as f64 * 100.0,
+            confidence: proposal.confidence,
+            risk_score: ((1.0 - proposal.confidence) * 0.5).clamp(0.05, 0.5),
+            rationale: vec![
+                format!(
+                    "Élasticité estimée {:.2} sur {} jours ({} niveaux de frais, R² {:.2})",
+                    model.elasticity, model.observed_days, model.fee_levels, model.r_squared
+                ),
+                format!(
+                    "{} → {} ppm : revenu prédit {:.0} → {:.0} sat/jour ({:+.0} sat/jour)",
+                    proposal.current_ppm,
+                    proposal.target_ppm,
+                    proposal.current_daily_revenue_sat,
+                    proposal.target_daily_revenue_sat,
+                    proposal.daily_revenue_delta_sat
+                ),
+            ],
+            target_channels: vec![channel.channel_id.clone()],
+            parameters: json!({
+                "channel_id": channel.channel_id,
+                "channel_point": channel.channel_point,
+                "base_fee_msat": model.base_fee_msat,
+                "fee_rate_ppm": proposal.target_ppm,
+                "predicted_daily_revenue_delta_sat": proposal.daily_revenue_delta_sat.round(),
+                "elasticity": model.elasticity,
+            }),
+        }
+    }
+
     /// Calcule la préparation à l’automatisation (phase 3).
     pub fn automation_readiness(
         &self,
         settings: &AutomationSettings,
         channels: &[LocalChannelInfo],
     ) -> AutomationReadiness {
-        let scorecard = self.score_channels(channels);
+        let scorecard = self.score_channels(&RecommendationInputs::new(channels));
         let gating_factors = if scorecard.risk_index < 0.35 {
             vec!["Risque maîtrisé (<0.35)".to_string()]
         } else {
@@ -237,55 +698,179 @@ impl MLEngine {
         }
     }
 
-    /// Simulation détaillée pour une recommandation donnée.
-    pub fn simulate(&self, recommendation: &SmartRecommendation) -> SimulationOutcome {
-        let steps = vec![
-            SimulationStep {
-                label: "Analyse initiale".to_string(),
-                expected_outcome: "Validation des pairs et coûts on-chain".to_string(),
-                probability: 0.97,
-                delta_roi: 0.0,
-            },
-            SimulationStep {
-                label: "Exécution".to_string(),
-                expected_outcome: "Propagation des nouvelles politiques/équilibres".to_string(),
-                probability: 0.9,
-                delta_roi: recommendation.expected_roi_impact * 0.6,
-            },
-            SimulationStep {
-                label: "Stabilisation".to_string(),
-                expected_outcome: "ROI consolidé après trafic initial".to_string(),
-                probability: 0.85,
-                delta_roi: recommendation.expected_roi_impact * 0.4,
-            },
-        ];
+    /// Simulation Monte Carlo d'une recommandation sur l'état et l'historique du nœud.
+    pub fn simulate(
+        &self,
+        recommendation: &SmartRecommendation,
+        context: &SimulationContext,
+    ) -> SimulationOutcome {
+        let mut assumptions = Vec::new();
+        let action =
+            match ActionParameters::parse(recommendation.action_type, &recommendation.parameters) {
+                Ok(action) => action,
+                Err(e) => {
+                    // Paramètres inexploitables : aucun gain simulable, pas de chiffre inventé
+                    return SimulationOutcome {
+                        recommendation_id: recommendation.id.clone(),
+                        expected_roi: 0.0,
+                        success_probability: 0.0,
+                        risk_level: "High".to_string(),
+                        estimated_cost: 0,
+                        timeline: vec![],
+                        horizon_days: context.horizon_days,
+                        runs: 0,
+                        revenue_sat: PercentileBand::default(),
+                        revenue_delta_sat: PercentileBand::default(),
+                        roi_pct: PercentileBand::default(),
+                        cost: CostEstimate::default(),
+                        assumptions: vec![format!("Paramètres non simulables: {}", e)],
+                    };
+                }
+            };
+
+        let result = MonteCarloSimulator.run(context, &action);
+        if context.history.is_empty() {
+            assumptions.push(
+                "Aucun historique de forwarding : revenus simulés nuls, seul le coût est chiffré"
+                    .to_string(),
+            );
+        } else if result.channels_without_history > 0 {
+            assumptions.push(format!(
+                "{} canal(aux) sans historique : profil moyen du nœud mis à l'échelle de la capacité",
+                result.channels_without_history
+            ));
+        }
+
+        let timeline = result
+            .weekly_net_p50
+            .iter()
+            .zip(&result.weekly_probability_positive)
+            .enumerate()
+            .map(|(week, (net, probability))| {
+                let days = ((week as u32 + 1) * 7).min(result.horizon_days);
+                SimulationStep {
+                    label: format!("Jour {}", days),
+                    expected_outcome: format!("Gain net médian cumulé: {:.0} sat", net),
+                    probability: *probability,
+                    delta_roi: if result.capital_sat == 0 {
+                        0.0
+                    } else {
+                        net / result.capital_sat as f64 * 100.0
+                    },
+                }
+            })
+            .collect();
 
         SimulationOutcome {
             recommendation_id: recommendation.id.clone(),
-            expected_roi: recommendation.expected_roi_impact,
-            success_probability: recommendation.confidence * 100.0,
-            risk_level: if recommendation.risk_score < 0.25 {
+            expected_roi: result.roi_pct.p50,
+            success_probability: result.probability_positive * 100.0,
+            risk_level: if result.roi_pct.p10 >= 0.0 {
                 "Low".to_string()
-            } else if recommendation.risk_score < 0.45 {
+            } else if result.roi_pct.p50 >= 0.0 {
                 "Medium".to_string()
             } else {
                 "High".to_string()
             },
-            estimated_cost: 1_500,
-            timeline: steps,
+            estimated_cost: result.cost.total_sat,
+            timeline,
+            horizon_days: result.horizon_days,
+            runs: result.runs,
+            revenue_sat: result.revenue_sat,
+            revenue_delta_sat: result.revenue_delta_sat,
+            roi_pct: result.roi_pct,
+            cost: result.cost,
+            assumptions,
         }
     }
 
-    /// Suggestion de fenêtre optimale pour exécuter une action.
-    pub fn optimal_window(&self, recommendation: &SmartRecommendation) -> OptimalWindow {
-        OptimalWindow {
-            window: "Prochain créneau de 2h (UTC)".to_string(),
-            confidence: (recommendation.confidence * 100.0).clamp(70.0, 96.0),
-            reasons: vec![
-                "Congestion réseau modérée attendue".to_string(),
-                "Activité pair cible en hausse".to_string(),
-                "Fenêtre tarifaire favorable (mempool)".to_string(),
-            ],
-        }
+    /// Fenêtre optimale pour exécuter une action, d'après le mempool, le trafic et les pairs.
+    pub fn optimal_window(
+        &self,
+        recommendation: &SmartRecommendation,
+        inputs: &SchedulingInputs,
+    ) -> OptimalWindow {
+        ExecutionScheduler::default().plan(recommendation, inputs)
+    }
+}
+
+/// Écart moyen (%) entre le ppm médian des routes concurrentes vers nos pairs et le nôtre ;
+/// positif quand nos canaux sont moins chers. `None` si aucun pair n'a de concurrent.
+fn price_advantage(graph: &LocalNetworkGraph, channels: &[LocalChannelInfo]) -> Option<f64> {
+    let own_channels: HashSet<&str> = channels.iter().map(|c| c.channel_id.as_str()).collect();
+    let gaps: Vec<f64> = channels
+        .iter()
+        .filter_map(|channel| {
+            let mut competitors = competitor_ppm(graph, &channel.peer_pubkey, &own_channels);
+            if competitors.is_empty() {
+                return None;
+            }
+            competitors.sort_unstable();
+            let median = competitors[competitors.len() / 2].max(1) as f64;
+            Some((median - channel.fee_rate_milli_msat as f64) / median * 100.0)
+        })
+        .collect();
+    (!gaps.is_empty()).then(|| gaps.iter().sum::<f64>() / gaps.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn test_no_history_yields_no_numbers() {
+        let channels = vec![
+            test_support::channel("a", 1_000_000, 500_000),
+            test_support::channel("b", 1_000_000, 500_000),
+        ];
+        let engine = MLEngine::new();
+        let inputs = RecommendationInputs::new(&channels);
+
+        let scorecard = engine.score_channels(&inputs);
+        assert!(scorecard.predicted_roi_30d.is_none());
+        assert!(scorecard.advantage_vs_amboss.is_none());
+        // Balances équilibrées et aucun modèle : ni frais ni rééquilibrage
+        assert!(engine.build_recommendations(&inputs).is_empty());
+    }
+
+    #[test]
+    fn test_roi_from_realized_fees() {
+        let channels = vec![test_support::channel("a", 2_000_000, 1_000_000)];
+        let history = vec![LocalForwardingEvent {
+            timestamp_ns: 0,
+            chan_id_in: "x".to_string(),
+            chan_id_out: "a".to_string(),
+            amt_in_msat: 10_000_000,
+            amt_out_msat: 9_000_000,
+            fee_msat: 1_000_000,
+        }];
+        let inputs = RecommendationInputs {
+            forwarding_history: &history,
+            ..RecommendationInputs::new(&channels)
+        };
+
+        let scorecard = MLEngine::new().score_channels(&inputs);
+        let roi_30d = scorecard.predicted_roi_30d.unwrap();
+        assert!((roi_30d - 0.1).abs() < 1e-9);
+        assert!((scorecard.predicted_roi_365d.unwrap() - roi_30d * 365.0 / 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rebalance_rationale_reflects_balances() {
+        let channels = vec![
+            test_support::channel("full", 1_000_000, 900_000),
+            test_support::channel("empty", 1_000_000, 100_000),
+        ];
+        let recommendations =
+            MLEngine::new().build_recommendations(&RecommendationInputs::new(&channels));
+
+        let rebalance = recommendations
+            .iter()
+            .find(|r| r.action_type == ActionType::RebalanceChannel)
+            .unwrap();
+        assert_eq!(rebalance.parameters["amount_sat"], 400_000);
+        assert_eq!(rebalance.expected_roi_impact, 0.0);
+        assert!(rebalance.rationale[0].contains("90.0 %"));
     }
 }
@@ -1,2 +1,27 @@
+pub mod action_validation;
+pub mod anomaly_detection;
+pub mod backtest;
+pub mod channel_candidates;
+pub mod channel_pnl;
+pub mod close_candidates;
+pub mod competitive_positioning;
 pub mod config;
+pub mod fee_elasticity;
+pub mod fee_strategies;
+pub mod forecasting;
+pub mod inbound_liquidity;
+pub mod ledger;
 pub mod ml_engine;
+pub mod model_registry;
+pub mod monte_carlo;
+pub mod network_graph;
+pub mod node_analytics;
+pub mod onchain;
+pub mod peer_reputation;
+pub mod preference_learning;
+pub mod prometheus;
+pub mod recommendation_aggregator;
+pub mod reports;
+pub mod rule_engine;
+pub mod scheduling;
+pub mod stress_testing;
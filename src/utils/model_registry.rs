@@ -0,0 +1,99 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::{
+    storage::ml_models::{MLModelStore, ModelVersion},
+    utils::ml_engine::{MLEngine, MLEngineConfig},
+};
+
+/// Moteur ML actif, chargé depuis la version persistée et remplaçable à chaud.
+#[derive(Clone)]
+pub struct ModelRegistry {
+    store: MLModelStore,
+    active: Arc<RwLock<MLEngine>>,
+}
+
+impl ModelRegistry {
+    /// Charge la version active ; au premier démarrage, la configuration par défaut devient la v1.
+    pub async fn load(store: MLModelStore) -> Result<Self> {
+        let version = match store.active().await? {
+            Some(version) => version,
+            None => {
+                let version = store
+                    .create(
+                        &MLEngine::new().config,
+                        "Configuration initiale",
+                        Utc::now(),
+                    )
+                    .await?;
+                store.activate(version.version, Utc::now()).await?;
+                version
+            }
+        };
+        info!("ML model version {} loaded", version.version);
+
+        Ok(Self {
+            store,
+            active: Arc::new(RwLock::new(MLEngine::with_config(
+                version.config,
+                Some(version.version),
+            ))),
+        })
+    }
+
+    /// Copie du moteur actif : une requête garde la même version de bout en bout.
+    pub async fn engine(&self) -> MLEngine {
+        self.active.read().await.clone()
+    }
+
+    pub fn store(&self) -> &MLModelStore {
+        &self.store
+    }
+
+    /// Moteur d'une version donnée, sans l'activer.
+    pub async fn engine_for(&self, version: i64) -> Result<Option<MLEngine>> {
+        Ok(self
+            .store
+            .get(version)
+            .await?
+            .map(|v| MLEngine::with_config(v.config, Some(v.version))))
+    }
+
+    /// Enregistre une nouvelle configuration et l'active.
+    pub async fn publish(&self, config: MLEngineConfig, note: &str) -> Result<ModelVersion> {
+        let version = self.store.create(&config, note, Utc::now()).await?;
+        self.activate(version.version).await?;
+        Ok(ModelVersion {
+            active: true,
+            ..version
+        })
+    }
+
+    /// Active une version existante ; `None` si elle est inconnue.
+    pub async fn activate(&self, version: i64) -> Result<Option<ModelVersion>> {
+        let Some(model) = self.store.get(version).await? else {
+            return Ok(None);
+        };
+        // Verrou tenu pendant l'écriture : la base et le moteur en mémoire restent alignés
+        let mut active = self.active.write().await;
+        self.store.activate(version, Utc::now()).await?;
+        *active = MLEngine::with_config(model.config.clone(), Some(version));
+        info!("ML model version {} activated", version);
+
+        Ok(Some(ModelVersion {
+            active: true,
+            ..model
+        }))
+    }
+
+    /// Revient à la version active avant l'activation courante.
+    pub async fn rollback(&self) -> Result<Option<ModelVersion>> {
+        match self.store.previously_active().await? {
+            Some(version) => self.activate(version).await,
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use chrono::{DateTime, Utc};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+    models::{
+        action_params::ActionParameters,
+        ml::{CostEstimate, PercentileBand},
+    },
+    utils::onchain::{COOP_CLOSE_VBYTES, FORCE_CLOSE_VBYTES, OPEN_CHANNEL_VBYTES},
+};
+
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// Distribution empirique des flux d'un canal, tirée de l'historique de forwarding.
+#[derive(Debug, Clone, Default)]
+pub struct FlowProfile {
+    pub daily_out: Vec<u32>,
+    pub out_amounts_sat: Vec<u64>,
+    pub daily_in: Vec<u32>,
+    pub in_amounts_sat: Vec<u64>,
+}
+
+impl FlowProfile {
+    fn is_empty(&self) -> bool {
+        self.out_amounts_sat.is_empty() && self.in_amounts_sat.is_empty()
+    }
+}
+
+/// Entrées de la simulation : canaux, historique récent et frais de marché.
+#[derive(Debug, Clone)]
+pub struct SimulationContext {
+    /// Canaux avec leur politique de frais actuelle
+    pub channels: Vec<LocalChannelInfo>,
+    pub history: Vec<LocalForwardingEvent>,
+    pub history_start: DateTime<Utc>,
+    pub history_days: u32,
+    /// Taux on-chain (sat/vB) pour une confirmation sous ~3 blocs
+    pub onchain_fee_rate: f64,
+    pub horizon_days: u32,
+    pub runs: usize,
+    pub seed: u64,
+    /// Réponse du volume aux frais : volume ∝ ppm^ε
+    pub elasticity: f64,
+}
+
+impl SimulationContext {
+    pub fn new(
+        channels: Vec<LocalChannelInfo>,
+        history: Vec<LocalForwardingEvent>,
+        history_start: DateTime<Utc>,
+        history_days: u32,
+        onchain_fee_rate: f64,
+    ) -> Self {
+        Self {
+            channels,
+            history,
+            history_start,
+            history_days: history_days.max(1),
+            onchain_fee_rate,
+            horizon_days: 30,
+            runs: 500,
+            seed: 42,
+            elasticity: -0.8,
+        }
+    }
+
+    /// Profil de flux par canal. Les canaux sans historique reçoivent le profil
+    /// moyen du nœud, mis à l'échelle de leur capacité.
+    fn profiles(&self) -> BTreeMap<String, FlowProfile> {
+        let start_ns = self.history_start.timestamp_nanos_opt().unwrap_or(0) as u64;
+        let days = self.history_days as usize;
+        let mut profiles: BTreeMap<String, FlowProfile> = BTreeMap::new();
+
+        for event in &self.history {
+            let Some(day) = event
+                .timestamp_ns
+                .checked_sub(start_ns)
+                .map(|offset| (offset / NANOS_PER_DAY) as usize)
+                .filter(|day| *day < days)
+            else {
+                continue;
+            };
+
+            let outgoing = profiles.entry(event.chan_id_out.clone()).or_default();
+            outgoing.daily_out.resize(days, 0);
+            outgoing.daily_out[day] += 1;
+            outgoing.out_amounts_sat.push(event.amt_out_msat / 1000);
+
+            let incoming = profiles.entry(event.chan_id_in.clone()).or_default();
+            incoming.daily_in.resize(days, 0);
+            incoming.daily_in[day] += 1;
+            incoming.in_amounts_sat.push(event.amt_in_msat / 1000);
+        }
+
+        for profile in profiles.values_mut() {
+            profile.daily_out.resize(days, 0);
+            profile.daily_in.resize(days, 0);
+        }
+        profiles
+    }
+}
+
+/// Résultat agrégé des tirages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloResult {
+    pub runs: usize,
+    pub horizon_days: u32,
+    /// Revenu de routage sur l'horizon avec l'action
+    pub revenue_sat: PercentileBand,
+    /// Écart de revenu par rapport au statu quo, mêmes tirages
+    pub revenue_delta_sat: PercentileBand,
+    /// (écart de revenu − coût) / capital engagé, annualisé, en %
+    pub roi_pct: PercentileBand,
+    pub cost: CostEstimate,
+    pub capital_sat: u64,
+    /// Part des tirages où l'action rapporte plus qu'elle ne coûte
+    pub probability_positive: f64,
+    /// Écart net médian cumulé, semaine par semaine
+    pub weekly_net_p50: Vec<f64>,
+    pub weekly_probability_positive: Vec<f64>,
+    pub channels_without_history: usize,
+}
+
+/// État simulé d'un canal.
+#[derive(Debug, Clone)]
+struct SimChannel {
+    id: String,
+    local: u64,
+    remote: u64,
+    base_fee_msat: u64,
+    ppm: u64,
+    /// Multiplicateur du volume sortant (réponse aux frais)
+    volume_factor: f64,
+    profile: FlowProfile,
+}
+
+/// Simulateur Monte Carlo : tirages bootstrap des flux historiques.
+#[derive(Debug, Clone, Default)]
+pub struct MonteCarloSimulator;
+
+impl MonteCarloSimulator {
+    pub fn run(&self, context: &SimulationContext, action: &ActionParameters) -> MonteCarloResult {
+        let profiles = context.profiles();
+        let pooled = pooled_profile(&profiles, &context.channels);
+
+        let mut channels_without_history = 0;
+        let baseline: Vec<SimChannel> = context
+            .channels
+            .iter()
+            .map(|channel| {
+                let profile = match profiles.get(&channel.channel_id) {
+                    Some(profile) if !profile.is_empty() => profile.clone(),
+                    _ => {
+                        channels_without_history += 1;
+                        scale_profile(&pooled, channel.capacity, average_capacity(context))
+                    }
+                };
+                SimChannel {
+                    id: channel.channel_id.clone(),
+                    local: channel.local_balance,
+                    remote: channel.remote_balance,
+                    base_fee_msat: channel.base_fee_msat,
+                    ppm: channel.fee_rate_milli_msat,
+                    volume_factor: 1.0,
+                    profile,
+                }
+            })
+            .collect();
+
+        let routing_fee_ppm = median_ppm(&context.channels).max(1);
+        let (with_action, cost, capital_sat) =
+            apply_action(&baseline, action, context, &pooled, routing_fee_ppm);
+
+        let horizon = context.horizon_days.max(1) as usize;
+        let weeks = horizon.div_ceil(7);
+        let runs = context.runs.max(1);
+
+        let mut revenues = Vec::with_capacity(runs);
+        let mut deltas = Vec::with_capacity(runs);
+        let mut rois = Vec::with_capacity(runs);
+        let mut weekly_net: Vec<Vec<f64>> = vec![Vec::with_capacity(runs); weeks];
+        let mut positive = 0;
+
+        for run in 0..runs {
+            // Même graine pour les deux scénarios : seule l'action les distingue
+            let seed = context.seed.wrapping_add(run as u64);
+            let base_daily = simulate_path(&baseline, horizon, &mut StdRng::seed_from_u64(seed));
+            let action_daily =
+                simulate_path(&with_action, horizon, &mut StdRng::seed_from_u64(seed));
+
+            let base_total: f64 = base_daily.iter().sum();
+            let action_total: f64 = action_daily.iter().sum();
+            let delta = action_total - base_total;
+            let net = delta - cost.total_sat as f64;
+
+            revenues.push(action_total);
+            deltas.push(delta);
+            rois.push(if capital_sat == 0 {
+                0.0
+            } else {
+                net / capital_sat as f64 * (365.0 / horizon as f64) * 100.0
+            });
+            if net > 0.0 {
+                positive += 1;
+            }
+
+            let mut cumulative = -(cost.total_sat as f64);
+            for (day, (a, b)) in action_daily.iter().zip(&base_daily).enumerate() {
+                cumulative += a - b;
+                if (day + 1) % 7 == 0 || day + 1 == horizon {
+                    weekly_net[day / 7].push(cumulative);
+                }
+            }
+        }
+
+        MonteCarloResult {
+            runs,
+            horizon_days: horizon as u32,
+            revenue_sat: PercentileBand::from_samples(&revenues),
+            revenue_delta_sat: PercentileBand::from_samples(&deltas),
+            roi_pct: PercentileBand::from_samples(&rois),
+            cost,
+            capital_sat,
+            probability_positive: positive as f64 / runs as f64,
+            weekly_net_p50: weekly_net
+                .iter()
+                .map(|samples| PercentileBand::from_samples(samples).p50)
+                .collect(),
+            weekly_probability_positive: weekly_net
+                .iter()
+                .map(|samples| {
+                    samples.iter().filter(|v| **v > 0.0).count() as f64
+                        / samples.len().max(1) as f64
+                })
+                .collect(),
+            channels_without_history,
+        }
+    }
+}
+
+/// Applique l'action à l'état initial et chiffre son coût et le capital engagé.
+fn apply_action(
+    baseline: &[SimChannel],
+    action: &ActionParameters,
+    context: &SimulationContext,
+    pooled: &FlowProfile,
+    routing_fee_ppm: u64,
+) -> (Vec<SimChannel>, CostEstimate, u64) {
+    let mut channels = baseline.to_vec();
+    let onchain = |vbytes: u64| (vbytes as f64 * context.onchain_fee_rate).ceil() as u64;
+    let mut cost = CostEstimate {
+        onchain_fee_rate_sat_vb: context.onchain_fee_rate,
+        routing_fee_ppm,
+        ..CostEstimate::default()
+    };
+    let find = |channels: &[SimChannel], id: &Option<String>| {
+        id.as_ref()
+            .and_then(|id| channels.iter().position(|c| &c.id == id))
+    };
+
+    let capital = match action {
+        ActionParameters::AdjustFees(params) => match find(&channels, &params.channel_id) {
+            Some(index) => {
+                let channel = &mut channels[index];
+                let old = channel.ppm.max(1) as f64;
+                let new = params.fee_rate_ppm.max(1) as f64;
+                channel.volume_factor = (new / old).powf(context.elasticity);
+                channel.ppm = params.fee_rate_ppm as u64;
+                if let Some(base) = params.base_fee_msat {
+                    channel.base_fee_msat = base;
+                }
+                channel.local + channel.remote
+            }
+            None => 0,
+        },
+        ActionParameters::RebalanceChannel(params) => {
+            let out = channels
+                .iter()
+                .position(|c| c.id == params.outgoing_channel_id);
+            let inc = channels
+                .iter()
+                .position(|c| c.id == params.incoming_channel_id);
+            if let (Some(out), Some(inc)) = (out, inc) {
+                let amount = params
+                    .amount_sat
+                    .min(channels[out].local)
+                    .min(channels[inc].remote);
+                channels[out].local -= amount;
+                channels[out].remote += amount;
+                channels[inc].local += amount;
+                channels[inc].remote -= amount;
+                let estimate = 1 + amount * routing_fee_ppm / 1_000_000;
+                cost.routing_sat = if params.max_fee_sat > 0 {
+                    estimate.min(params.max_fee_sat)
+                } else {
+                    estimate
+                };
+            }
+            params.amount_sat
+        }
+        ActionParameters::OpenChannel(params) => {
+            cost.onchain_sat = onchain(OPEN_CHANNEL_VBYTES);
+            channels.push(SimChannel {
+                id: format!("new_{}", params.peer_pubkey),
+                local: params.amount_sat.saturating_sub(params.push_sat),
+                remote: params.push_sat,
+                base_fee_msat: 1000,
+                ppm: median_ppm(&context.channels),
+                volume_factor: 1.0,
+                profile: scale_profile(pooled, params.amount_sat, average_capacity(context)),
+            });
+            params.amount_sat
+        }
+        ActionParameters::CloseChannel(params) => {
+            cost.onchain_sat = onchain(if params.force {
+                FORCE_CLOSE_VBYTES
+            } else {
+                COOP_CLOSE_VBYTES
+            });
+            let index = find(&channels, &params.channel_id).or_else(|| {
+                params.channel_point.as_ref().and_then(|point| {
+                    context
+                        .channels
+                        .iter()
+                        .position(|c| &c.channel_point == point)
+                })
+            });
+            match index {
+                Some(index) => {
+                    let closed = channels.remove(index);
+                    closed.local + closed.remote
+                }
+                None => 0,
+            }
+        }
+    };
+
+    cost.total_sat = cost.onchain_sat + cost.routing_sat;
+    (channels, cost, capital)
+}
+
+/// Un chemin simulé : revenu journalier sur l'horizon.
+fn simulate_path(channels: &[SimChannel], horizon: usize, rng: &mut StdRng) -> Vec<f64> {
+    let mut state = channels.to_vec();
+    let mut daily = Vec::with_capacity(horizon);
+
+    for _ in 0..horizon {
+        let mut revenue_msat = 0.0;
+        for channel in &mut state {
+            // Entrées : le pair nous pousse de la liquidité
+            let incoming = sample_count(&channel.profile.daily_in, 1.0, rng);
+            for _ in 0..incoming {
+                let Some(amount) = channel.profile.in_amounts_sat.choose(rng).copied() else {
+                    break;
+                };
+                if channel.remote >= amount {
+                    channel.remote -= amount;
+                    channel.local += amount;
+                }
+            }
+
+            // Sorties : seules celles couvertes par la balance locale rapportent
+            let outgoing = sample_count(&channel.profile.daily_out, channel.volume_factor, rng);
+            for _ in 0..outgoing {
+                let Some(amount) = channel.profile.out_amounts_sat.choose(rng).copied() else {
+                    break;
+                };
+                if channel.local >= amount {
+                    channel.local -= amount;
+                    channel.remote += amount;
+                    revenue_msat +=
+                        channel.base_fee_msat as f64 + amount as f64 * channel.ppm as f64 / 1000.0;
+                }
+            }
+        }
+        daily.push(revenue_msat / 1000.0);
+    }
+    daily
+}
+
+/// Nombre de forwards d'une journée : journée historique tirée au hasard, mise à l'échelle.
+fn sample_count(daily: &[u32], factor: f64, rng: &mut StdRng) -> u32 {
+    let Some(base) = daily.choose(rng) else {
+        return 0;
+    };
+    let expected = *base as f64 * factor;
+    let whole = expected.floor();
+    whole as u32 + u32::from(rng.gen::<f64>() < expected - whole)
+}
+
+/// Profil moyen par canal, toutes histoires confondues.
+fn pooled_profile(
+    profiles: &BTreeMap<String, FlowProfile>,
+    channels: &[LocalChannelInfo],
+) -> FlowProfile {
+    let known: Vec<&FlowProfile> = channels
+        .iter()
+        .filter_map(|c| profiles.get(&c.channel_id))
+        .filter(|p| !p.is_empty())
+        .collect();
+    if known.is_empty() {
+        return FlowProfile::default();
+    }
+
+    let days = known[0].daily_out.len();
+    let mut pooled = FlowProfile {
+        daily_out: vec![0; days],
+        daily_in: vec![0; days],
+        ..FlowProfile::default()
+    };
+    for profile in &known {
+        for day in 0..days {
+            pooled.daily_out[day] += profile.daily_out[day];
+            pooled.daily_in[day] += profile.daily_in[day];
+        }
+        pooled.out_amounts_sat.extend(&profile.out_amounts_sat);
+        pooled.in_amounts_sat.extend(&profile.in_amounts_sat);
+    }
+    let n = known.len() as u32;
+    for day in 0..days {
+        pooled.daily_out[day] = pooled.daily_out[day].div_ceil(n);
+        pooled.daily_in[day] = pooled.daily_in[day].div_ceil(n);
+    }
+    pooled
+}
+
+fn scale_profile(profile: &FlowProfile, capacity: u64, reference_capacity: u64) -> FlowProfile {
+    let factor = capacity as f64 / reference_capacity.max(1) as f64;
+    let scale = |counts: &[u32]| {
+        counts
+            .iter()
+            .map(|count| (*count as f64 * factor).round() as u32)
+            .collect()
+    };
+    FlowProfile {
+        daily_out: scale(&profile.daily_out),
+        out_amounts_sat: profile.out_amounts_sat.clone(),
+        daily_in: scale(&profile.daily_in),
+        in_amounts_sat: profile.in_amounts_sat.clone(),
+    }
+}
+
+fn average_capacity(context: &SimulationContext) -> u64 {
+    let total: u64 = context.channels.iter().map(|c| c.capacity).sum();
+    total / context.channels.len().max(1) as u64
+}
+
+fn median_ppm(channels: &[LocalChannelInfo]) -> u64 {
+    let mut rates: Vec<u64> = channels.iter().map(|c| c.fee_rate_milli_msat).collect();
+    rates.sort_unstable();
+    rates.get(rates.len() / 2).copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::action_params::{AdjustFeesParams, OpenChannelParams};
+    use crate::test_support;
+    use chrono::{Duration, TimeZone};
+
+    fn channel(id: &str, local: u64) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_pubkey: String::new(),
+            fee_rate_milli_msat: 100,
+            ..test_support::channel(id, 2_000_000, local)
+        }
+    }
+
+    fn context() -> SimulationContext {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let history = (0..30)
+            .flat_map(|day| {
+                (0..4).map(move |i| LocalForwardingEvent {
+                    timestamp_ns: (start + Duration::days(day) + Duration::hours(i))
+                        .timestamp_nanos_opt()
+                        .unwrap() as u64,
+                    chan_id_in: "2".to_string(),
+                    chan_id_out: "1".to_string(),
+                    amt_in_msat: 50_000_000,
+                    amt_out_msat: 50_000_000,
+                    fee_msat: 5_000,
+                })
+            })
+            .collect();
+        let mut context = SimulationContext::new(
+            vec![channel("1", 1_500_000), channel("2", 500_000)],
+            history,
+            start,
+            30,
+            10.0,
+        );
+        context.runs = 200;
+        context
+    }
+
+    #[test]
+    fn test_open_channel_cost_uses_onchain_rate() {
+        let result = MonteCarloSimulator.run(
+            &context(),
+            &ActionParameters::OpenChannel(OpenChannelParams {
+                peer_pubkey: "02aa".to_string(),
+                amount_sat: 1_000_000,
+                push_sat: 0,
+                private: false,
+                sat_per_vbyte: None,
+            }),
+        );
+        assert_eq!(result.cost.onchain_sat, OPEN_CHANNEL_VBYTES * 10);
+        assert_eq!(result.capital_sat, 1_000_000);
+    }
+
+    #[test]
+    fn test_percentiles_are_ordered_and_reproducible() {
+        let action = ActionParameters::AdjustFees(AdjustFeesParams {
+            channel_id: Some("1".to_string()),
+            channel_point: None,
+            base_fee_msat: None,
+            fee_rate_ppm: 200,
+            time_lock_delta: None,
+        });
+        let first = MonteCarloSimulator.run(&context(), &action);
+        let second = MonteCarloSimulator.run(&context(), &action);
+
+        assert!(first.revenue_sat.p10 <= first.revenue_sat.p50);
+        assert!(first.revenue_sat.p50 <= first.revenue_sat.p90);
+        assert_eq!(first.revenue_sat.p50, second.revenue_sat.p50);
+        // Demande peu élastique (-0.8) : doubler les frais augmente le revenu
+        assert!(first.revenue_delta_sat.p50 > 0.0);
+        assert_eq!(first.weekly_net_p50.len(), 5);
+    }
+
+    #[test]
+    fn test_balance_depletion_caps_revenue() {
+        let mut context = context();
+        context.channels[0].local_balance = 100_000;
+        context.channels[0].remote_balance = 1_900_000;
+        // Pas de flux entrant sur le canal 1 : au plus 2 forwards de 50 000 sat
+        let action = ActionParameters::AdjustFees(AdjustFeesParams {
+            channel_id: Some("1".to_string()),
+            channel_point: None,
+            base_fee_msat: None,
+            fee_rate_ppm: 100,
+            time_lock_delta: None,
+        });
+        let result = MonteCarloSimulator.run(&context, &action);
+        assert!(result.revenue_sat.p90 <= 10.0 + 1e-9);
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::api::local_lightning_client::LocalNetworkGraph;
+use crate::storage::forwarding::NetworkFeeSample;
+
+/// Position d'un nœud dans le graphe public.
+#[derive(Debug, Clone)]
+pub struct NodeGraphStats {
+    pub pubkey: String,
+    pub alias: String,
+    pub channels: usize,
+    pub capacity_sat: u64,
+    /// Rang centile (0-100) du nombre de canaux
+    pub degree_percentile: f64,
+    /// Rang centile (0-100) de la capacité totale
+    pub capacity_percentile: f64,
+    pub features: Vec<u32>,
+    pub last_update: u32,
+}
+
+/// Indicateurs dérivés du graphe public du réseau.
+#[derive(Debug, Clone, Default)]
+pub struct GraphAnalysis {
+    nodes: HashMap<String, NodeGraphStats>,
+}
+
+impl GraphAnalysis {
+    pub fn new(graph: &LocalNetworkGraph) -> Self {
+        let mut degree: HashMap<&str, (usize, u64)> = HashMap::new();
+        for edge in &graph.edges {
+            for pubkey in [&edge.node1_pub, &edge.node2_pub] {
+                let entry = degree.entry(pubkey.as_str()).or_default();
+                entry.0 += 1;
+                entry.1 += edge.capacity;
+            }
+        }
+
+        let mut degrees: Vec<usize> = degree.values().map(|(d, _)| *d).collect();
+        let mut capacities: Vec<u64> = degree.values().map(|(_, c)| *c).collect();
+        degrees.sort_unstable();
+        capacities.sort_unstable();
+
+        let nodes = graph
+            .nodes
+            .iter()
+            .map(|node| {
+                let (channels, capacity_sat) = degree
+                    .get(node.pubkey.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                let stats = NodeGraphStats {
+                    pubkey: node.pubkey.clone(),
+                    alias: node.alias.clone(),
+                    channels,
+                    capacity_sat,
+                    degree_percentile: percentile_rank(&degrees, &channels),
+                    capacity_percentile: percentile_rank(&capacities, &capacity_sat),
+                    features: node.features.clone(),
+                    last_update: node.last_update,
+                };
+                (node.pubkey.clone(), stats)
+            })
+            .collect();
+
+        Self { nodes }
+    }
+
+    pub fn node(&self, pubkey: &str) -> Option<&NodeGraphStats> {
+        self.nodes.get(pubkey)
+    }
+
+    /// Centralité de degré pondérée par la capacité, en rang centile (0-100).
+    pub fn centrality(&self, pubkey: &str) -> Option<f64> {
+        self.node(pubkey)
+            .map(|stats| (stats.degree_percentile + stats.capacity_percentile) / 2.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Nœuds annoncés ayant au moins un canal public
+    pub fn connected_nodes(&self) -> usize {
+        self.nodes
+            .values()
+            .filter(|stats| stats.channels > 0)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Rang centile de `value` dans `sorted` (ex æquo comptés pour moitié).
+pub fn percentile_rank<T: Ord>(sorted: &[T], value: &T) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let below = sorted.partition_point(|v| v < value);
+    let equal = sorted[below..].partition_point(|v| v <= value);
+    (below as f64 + equal as f64 / 2.0) / sorted.len() as f64 * 100.0
+}
+
+/// Frais médians des politiques actives du graphe ; `None` si le graphe est vide.
+pub fn network_fee_sample(
+    graph: &LocalNetworkGraph,
+    at: DateTime<Utc>,
+) -> Option<NetworkFeeSample> {
+    let policies: Vec<_> = graph
+        .edges
+        .iter()
+        .flat_map(|edge| [&edge.node1_policy, &edge.node2_policy])
+        .flatten()
+        .filter(|policy| !policy.disabled)
+        .collect();
+    if policies.is_empty() {
+        return None;
+    }
+
+    let mut ppm: Vec<u64> = policies.iter().map(|p| p.fee_rate_ppm).collect();
+    let mut base: Vec<u64> = policies.iter().map(|p| p.base_fee_msat).collect();
+    ppm.sort_unstable();
+    base.sort_unstable();
+    Some(NetworkFeeSample {
+        observed_at: at,
+        median_fee_rate_ppm: ppm[ppm.len() / 2].min(u32::MAX as u64) as u32,
+        median_base_fee_msat: base[base.len() / 2],
+        policies: policies.len() as u32,
+    })
+}
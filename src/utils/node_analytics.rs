@@ -0,0 +1,344 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::{
+    api::local_lightning_client::{
+        LocalChannelInfo, LocalForwardingEvent, LocalHtlcAttempt, LocalLightningClient,
+    },
+    models::analytics::NodeAnalytics,
+    storage::forwarding::{uptime_samples, ChannelHistoryPoint, ForwardingStore},
+    utils::{
+        forecasting::{hourly_forwarding_series, DailyAggregate, SeasonalModel},
+        network_graph::GraphAnalysis,
+    },
+};
+
+/// Fenêtre et références des scores du nœud.
+#[derive(Debug, Clone)]
+pub struct NodeAnalyzerConfig {
+    pub history_days: u32,
+    /// Rendement annuel des frais sur la capacité qui vaut 100 en efficacité (ppm)
+    pub target_yield_ppm: f64,
+    pub min_snapshots: usize,
+    pub min_htlc_attempts: usize,
+}
+
+impl Default for NodeAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 30,
+            target_yield_ppm: 10_000.0,
+            min_snapshots: 6,
+            min_htlc_attempts: 10,
+        }
+    }
+}
+
+/// Données observées pour le calcul des scores.
+pub struct NodeAnalyticsInputs<'a> {
+    pub now: DateTime<Utc>,
+    pub channels: &'a [LocalChannelInfo],
+    pub events: &'a [LocalForwardingEvent],
+    pub snapshots: &'a [ChannelHistoryPoint],
+    pub htlc_attempts: &'a [LocalHtlcAttempt],
+    /// Centralité du nœud dans le graphe local ; `None` si le nœud n'y figure pas
+    pub centrality: Option<f64>,
+}
+
+/// Calcul de `NodeAnalytics` à partir des canaux, des forwards et du graphe.
+#[derive(Debug, Clone, Default)]
+pub struct NodeAnalyzer {
+    pub config: NodeAnalyzerConfig,
+}
+
+impl NodeAnalyzer {
+    pub fn new(config: NodeAnalyzerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Scores du nœud à partir de LND et de l'historique persisté.
+    pub async fn analyze_live(
+        &self,
+        client: &Mutex<LocalLightningClient>,
+        forwarding_store: &ForwardingStore,
+        now: DateTime<Utc>,
+    ) -> Result<NodeAnalytics> {
+        let (pubkey, channels, attempts, graph) = {
+            let mut client = client.lock().await;
+            let pubkey = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .pubkey;
+            (
+                pubkey,
+                client.list_local_channels().await?,
+                client.htlc_attempts().await?,
+                client.describe_graph().await?,
+            )
+        };
+
+        let since = now - Duration::days(self.config.history_days as i64);
+        let events = forwarding_store.events_between(since, now).await?;
+        let snapshots = forwarding_store
+            .channel_snapshots_between(since, now)
+            .await?;
+
+        Ok(self.analyze(&NodeAnalyticsInputs {
+            now,
+            channels: &channels,
+            events: &events,
+            snapshots: &snapshots,
+            htlc_attempts: &attempts,
+            centrality: GraphAnalysis::new(&graph).centrality(&pubkey),
+        }))
+    }
+
+    pub fn analyze(&self, inputs: &NodeAnalyticsInputs) -> NodeAnalytics {
+        let config = &self.config;
+        let days = config.history_days.max(1) as f64;
+        let start = inputs.now - Duration::days(config.history_days as i64);
+        let in_window = |ns: u64| {
+            ns >= start.timestamp_nanos_opt().unwrap_or(0) as u64
+                && ns < inputs.now.timestamp_nanos_opt().unwrap_or(i64::MAX) as u64
+        };
+
+        let local_sat: u64 = inputs.channels.iter().map(|c| c.local_balance).sum();
+        let capacity_sat: u64 = inputs.channels.iter().map(|c| c.capacity).sum();
+        let fees_sat = inputs
+            .events
+            .iter()
+            .filter(|e| in_window(e.timestamp_ns))
+            .map(|e| e.fee_msat)
+            .sum::<u64>() as f64
+            / 1000.0;
+
+        // Rendement annualisé des frais sur le capital local
+        let annualized = |fees: f64, period_days: f64| {
+            if local_sat == 0 {
+                0.0
+            } else {
+                fees / local_sat as f64 * 365.0 / period_days * 100.0
+            }
+        };
+        let roi_current = annualized(fees_sat, days);
+
+        let fee_model = SeasonalModel::fit(&hourly_forwarding_series(
+            inputs.events,
+            start,
+            inputs.now,
+            |e| e.fee_msat as f64 / 1000.0,
+        ));
+        let roi_predicted_30d = fee_model.as_ref().map_or(roi_current, |model| {
+            let forecast: f64 = model
+                .forecast_daily(inputs.now + Duration::days(1), 30, DailyAggregate::Sum)
+                .iter()
+                .map(|p| p.value)
+                .sum();
+            annualized(forecast, 30.0)
+        });
+
+        let efficiency_score = if capacity_sat == 0 {
+            0.0
+        } else {
+            let yield_ppm = fees_sat / capacity_sat as f64 * 365.0 / days * 1_000_000.0;
+            (yield_ppm / config.target_yield_ppm * 100.0).min(100.0)
+        };
+
+        let liquidity_score = if capacity_sat == 0 {
+            0.0
+        } else {
+            let weighted_deviation: f64 = inputs
+                .channels
+                .iter()
+                .map(|c| {
+                    let local_ratio = c.local_balance as f64 / c.capacity.max(1) as f64;
+                    c.capacity as f64 * 2.0 * (local_ratio - 0.5).abs()
+                })
+                .sum();
+            (100.0 * (1.0 - weighted_deviation / capacity_sat as f64)).clamp(0.0, 100.0)
+        };
+
+        let risk_score = if capacity_sat == 0 {
+            100.0
+        } else {
+            let mut by_peer: HashMap<&str, u64> = HashMap::new();
+            for channel in inputs.channels {
+                *by_peer.entry(&channel.peer_pubkey).or_default() += channel.capacity;
+            }
+            let herfindahl: f64 = by_peer
+                .values()
+                .map(|c| (*c as f64 / capacity_sat as f64).powi(2))
+                .sum();
+            let inactive_share = inputs
+                .channels
+                .iter()
+                .filter(|c| !c.active)
+                .map(|c| c.capacity)
+                .sum::<u64>() as f64
+                / capacity_sat as f64;
+            100.0 * (herfindahl + inactive_share) / 2.0
+        };
+
+        let reliability_score = self.reliability(inputs, start);
+        let centrality_score = inputs.centrality.unwrap_or(0.0);
+
+        let performance_score = (efficiency_score
+            + centrality_score
+            + liquidity_score
+            + reliability_score
+            + (100.0 - risk_score))
+            / 5.0;
+
+        let growth_potential = fee_model.as_ref().map_or(50.0, |model| {
+            (50.0 + 100.0 * model.relative_trend_30d()).clamp(0.0, 100.0)
+        });
+
+        let round = |v: f64| (v * 100.0).round() / 100.0;
+        NodeAnalytics {
+            performance_score: round(performance_score),
+            roi_current: round(roi_current),
+            roi_predicted_30d: round(roi_predicted_30d),
+            efficiency_score: round(efficiency_score),
+            risk_score: round(risk_score),
+            centrality_score: round(centrality_score),
+            liquidity_score: round(liquidity_score),
+            reliability_score: round(reliability_score),
+            growth_potential: round(growth_potential),
+            last_calculated: inputs.now,
+        }
+    }
+
+    /// Disponibilité des pairs sur les instantanés et succès des HTLC ; à défaut
+    /// d'historique suffisant, part des canaux actuellement actifs.
+    fn reliability(&self, inputs: &NodeAnalyticsInputs, start: DateTime<Utc>) -> f64 {
+        let snapshots: Vec<&ChannelHistoryPoint> = inputs
+            .snapshots
+            .iter()
+            .filter(|s| s.taken_at >= start && s.taken_at < inputs.now)
+            .collect();
+        // Agrégats horaires et journaliers pondérés par les relevés qu'ils représentent
+        let (active, samples) = uptime_samples(snapshots.iter().copied());
+        let uptime =
+            (samples >= self.config.min_snapshots as u64).then(|| active as f64 / samples as f64);
+
+        let start_ns = start.timestamp_nanos_opt().unwrap_or(0) as u64;
+        let attempts: Vec<&LocalHtlcAttempt> = inputs
+            .htlc_attempts
+            .iter()
+            .filter(|a| a.attempt_time_ns >= start_ns)
+            .collect();
+        let htlc_success = (attempts.len() >= self.config.min_htlc_attempts).then(|| {
+            attempts.iter().filter(|a| a.succeeded).count() as f64 / attempts.len() as f64
+        });
+
+        let measured: Vec<f64> = [uptime, htlc_success].into_iter().flatten().collect();
+        if !measured.is_empty() {
+            return measured.iter().sum::<f64>() / measured.len() as f64 * 100.0;
+        }
+        if inputs.channels.is_empty() {
+            return 0.0;
+        }
+        inputs.channels.iter().filter(|c| c.active).count() as f64 / inputs.channels.len() as f64
+            * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use chrono::TimeZone;
+
+    fn channel(id: &str, peer: &str, local: u64, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_pubkey: peer.to_string(),
+            active,
+            ..test_support::channel(id, 10_000_000, local)
+        }
+    }
+
+    fn forward(at: DateTime<Utc>, fee_msat: u64) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+            chan_id_in: "a".to_string(),
+            chan_id_out: "b".to_string(),
+            amt_in_msat: 100_000_000 + fee_msat,
+            amt_out_msat: 100_000_000,
+            fee_msat,
+        }
+    }
+
+    fn attempt(at: DateTime<Utc>, succeeded: bool) -> LocalHtlcAttempt {
+        LocalHtlcAttempt {
+            first_hop_channel_id: "a".to_string(),
+            attempt_time_ns: at.timestamp_nanos_opt().unwrap() as u64,
+            succeeded,
+            failed_at_peer: !succeeded,
+        }
+    }
+
+    #[test]
+    fn test_scores_follow_documented_formulas() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let channels = [
+            channel("a", "peer_a", 5_000_000, true),
+            channel("b", "peer_b", 10_000_000, false),
+        ];
+        // 30 jours à 1 000 sat/jour, un forward par jour
+        let events: Vec<_> = (1..=30)
+            .map(|d| forward(now - Duration::days(d), 1_000_000))
+            .collect();
+        let attempts: Vec<_> = (0..10)
+            .map(|i| attempt(now - Duration::hours(i + 1), i < 8))
+            .collect();
+
+        let analytics = NodeAnalyzer::default().analyze(&NodeAnalyticsInputs {
+            now,
+            channels: &channels,
+            events: &events,
+            snapshots: &[],
+            htlc_attempts: &attempts,
+            centrality: Some(62.5),
+        });
+
+        // 30 000 sat sur 15 M sat locaux en 30 jours
+        let roi = 30_000.0 / 15_000_000.0 * 365.0 / 30.0 * 100.0;
+        assert!((analytics.roi_current - roi).abs() < 0.01);
+        // 30 000 sat sur 20 M sat de capacité : 18 250 ppm/an, au-delà de l'objectif
+        assert_eq!(analytics.efficiency_score, 100.0);
+        assert_eq!(analytics.centrality_score, 62.5);
+        // Écarts 0 et 1 à parts égales de capacité
+        assert_eq!(analytics.liquidity_score, 50.0);
+        // Herfindahl 0,5 et moitié de la capacité inactive
+        assert_eq!(analytics.risk_score, 50.0);
+        assert_eq!(analytics.reliability_score, 80.0);
+        assert_eq!(
+            analytics.performance_score,
+            (100.0 + 62.5 + 50.0 + 80.0 + 50.0) / 5.0
+        );
+    }
+
+    #[test]
+    fn test_node_without_channels_or_history() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let analytics = NodeAnalyzer::default().analyze(&NodeAnalyticsInputs {
+            now,
+            channels: &[],
+            events: &[],
+            snapshots: &[],
+            htlc_attempts: &[],
+            centrality: None,
+        });
+
+        assert_eq!(analytics.roi_current, 0.0);
+        assert_eq!(analytics.roi_predicted_30d, 0.0);
+        assert_eq!(analytics.liquidity_score, 0.0);
+        assert_eq!(analytics.risk_score, 100.0);
+        assert_eq!(analytics.reliability_score, 0.0);
+        assert_eq!(analytics.growth_potential, 50.0);
+        assert_eq!(analytics.performance_score, 0.0);
+    }
+}
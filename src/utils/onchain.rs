@@ -0,0 +1,13 @@
+//! Hypothèses on-chain partagées par les bilans de coût, simulations et scénarios.
+
+/// Taux on-chain supposé sans relevé récent du mempool (sat/vB)
+pub const DEFAULT_ONCHAIN_FEE_RATE: f64 = 10.0;
+/// Blocs minés en moyenne par jour
+pub const BLOCKS_PER_DAY: u32 = 144;
+
+/// Taille approximative d'une transaction d'ouverture (1 entrée P2WPKH, sortie P2WSH + change)
+pub const OPEN_CHANNEL_VBYTES: u64 = 154;
+/// Fermeture coopérative
+pub const COOP_CLOSE_VBYTES: u64 = 170;
+/// Fermeture forcée : transaction de commitment puis balayage de la sortie temporisée
+pub const FORCE_CLOSE_VBYTES: u64 = 300;
@@ -0,0 +1,840 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeSet;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{
+    api::local_lightning_client::{
+        LocalChannelInfo, LocalCloseType, LocalClosedChannel, LocalHtlcAttempt,
+        LocalLightningClient, LocalNetworkGraph,
+    },
+    models::{
+        action_params::ActionParameters,
+        reputation::{PeerReputation, ReputationComponent},
+    },
+    storage::{
+        forwarding::{uptime_samples, ChannelHistoryPoint, ForwardingStore},
+        peer_reputation::{PeerPolicyObservation, PeerReputationStore},
+    },
+    utils::{network_graph::GraphAnalysis, onchain::BLOCKS_PER_DAY},
+};
+
+/// Fenêtre d'observation de la disponibilité et des changements de frais
+pub const REPUTATION_WINDOW_DAYS: i64 = 30;
+/// Au-delà, un score persisté est recalculé avant de servir de garde-fou
+pub const REPUTATION_MAX_AGE_HOURS: i64 = 24;
+
+/// Fonctionnalités récentes du protocole : leur support trahit un logiciel à jour.
+/// (static_remotekey, payment_secret, basic_mpp, anchors_zero_fee_htlc_tx, channel_type, scid_alias)
+const MODERN_FEATURE_PAIRS: [(u32, &str); 6] = [
+    (12, "static_remotekey"),
+    (14, "payment_secret"),
+    (16, "basic_mpp"),
+    (22, "anchors_zero_fee_htlc_tx"),
+    (44, "channel_type"),
+    (46, "scid_alias"),
+];
+
+/// Pondération des critères du score.
+#[derive(Debug, Clone)]
+pub struct ReputationWeights {
+    pub uptime: f64,
+    pub htlc_success: f64,
+    pub force_closes: f64,
+    pub channel_age: f64,
+    pub centrality: f64,
+    pub fee_stability: f64,
+    pub software: f64,
+}
+
+impl Default for ReputationWeights {
+    fn default() -> Self {
+        Self {
+            uptime: 0.25,
+            htlc_success: 0.2,
+            force_closes: 0.15,
+            channel_age: 0.1,
+            centrality: 0.1,
+            fee_stability: 0.1,
+            software: 0.1,
+        }
+    }
+}
+
+/// Données observées pour un pair.
+#[derive(Debug, Clone)]
+pub struct PeerReputationInputs<'a> {
+    pub peer_pubkey: &'a str,
+    pub now: DateTime<Utc>,
+    pub block_height: u32,
+    /// Nos canaux ouverts avec ce pair
+    pub channels: Vec<&'a LocalChannelInfo>,
+    pub snapshots: Vec<&'a ChannelHistoryPoint>,
+    pub closed: Vec<&'a LocalClosedChannel>,
+    pub htlc_attempts: Vec<&'a LocalHtlcAttempt>,
+    pub graph: &'a GraphAnalysis,
+    pub policy_history: &'a [PeerPolicyObservation],
+}
+
+/// Moteur de réputation : combine disponibilité, fiabilité HTLC, historique de
+/// fermetures, ancienneté, centralité, stabilité tarifaire et logiciel en un score 0-100.
+#[derive(Debug, Clone)]
+pub struct PeerReputationEngine {
+    pub weights: ReputationWeights,
+    pub min_snapshots: usize,
+    pub min_htlc_attempts: usize,
+    /// Ancienneté à partir de laquelle le critère d'âge est plein
+    pub mature_channel_days: f64,
+}
+
+impl Default for PeerReputationEngine {
+    fn default() -> Self {
+        Self {
+            weights: ReputationWeights::default(),
+            min_snapshots: 4,
+            min_htlc_attempts: 10,
+            mature_channel_days: 180.0,
+        }
+    }
+}
+
+impl PeerReputationEngine {
+    pub fn score(&self, inputs: &PeerReputationInputs) -> PeerReputation {
+        let components = vec![
+            self.uptime(inputs),
+            self.htlc_success(inputs),
+            self.force_closes(inputs),
+            self.channel_age(inputs),
+            self.centrality(inputs),
+            self.fee_stability(inputs),
+            self.software(inputs),
+        ];
+
+        let total_weight: f64 = components.iter().map(|c| c.weight).sum();
+        let available_weight: f64 = components
+            .iter()
+            .filter(|c| c.available)
+            .map(|c| c.weight)
+            .sum();
+        // Sans aucune donnée, score neutre et confiance nulle
+        let score = if available_weight > 0.0 {
+            components
+                .iter()
+                .filter(|c| c.available)
+                .map(|c| c.score * c.weight)
+                .sum::<f64>()
+                / available_weight
+        } else {
+            50.0
+        };
+
+        PeerReputation {
+            peer_pubkey: inputs.peer_pubkey.to_string(),
+            alias: inputs
+                .graph
+                .node(inputs.peer_pubkey)
+                .map(|n| n.alias.clone())
+                .or_else(|| inputs.channels.first().map(|c| c.peer_alias.clone()))
+                .unwrap_or_default(),
+            score: score.clamp(0.0, 100.0),
+            confidence: if total_weight > 0.0 {
+                available_weight / total_weight
+            } else {
+                0.0
+            },
+            components,
+            computed_at: inputs.now,
+        }
+    }
+
+    fn uptime(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        // Un agrégat horaire ou journalier pèse le nombre de relevés qu'il représente
+        let (active, total) = uptime_samples(inputs.snapshots.iter().copied());
+        if total < self.min_snapshots as u64 {
+            return unavailable(
+                "Disponibilité",
+                self.weights.uptime,
+                "Pas assez d'instantanés de canal pour mesurer la disponibilité",
+            );
+        }
+        let uptime = active as f64 / total as f64;
+        component(
+            "Disponibilité",
+            // 50 % de disponibilité ou moins vaut 0 : un pair souvent absent bloque la liquidité
+            ((uptime - 0.5) / 0.5).clamp(0.0, 1.0) * 100.0,
+            self.weights.uptime,
+            format!(
+                "Canal actif dans {:.1}% des {} relevés sur {} jours",
+                uptime * 100.0,
+                total,
+                REPUTATION_WINDOW_DAYS
+            ),
+        )
+    }
+
+    fn htlc_success(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        let attempts = inputs.htlc_attempts.len();
+        if attempts < self.min_htlc_attempts {
+            return unavailable(
+                "Fiabilité HTLC",
+                self.weights.htlc_success,
+                &format!(
+                    "{} tentative(s) HTLC via ce pair, {} requises",
+                    attempts, self.min_htlc_attempts
+                ),
+            );
+        }
+        let failures = inputs
+            .htlc_attempts
+            .iter()
+            .filter(|a| a.failed_at_peer)
+            .count();
+        let failure_rate = failures as f64 / attempts as f64;
+        component(
+            "Fiabilité HTLC",
+            (1.0 - 2.0 * failure_rate).clamp(0.0, 1.0) * 100.0,
+            self.weights.htlc_success,
+            format!(
+                "{} échec(s) imputables au pair sur {} tentatives ({:.1}%)",
+                failures,
+                attempts,
+                failure_rate * 100.0
+            ),
+        )
+    }
+
+    fn force_closes(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        let breaches = inputs
+            .closed
+            .iter()
+            .filter(|c| c.close_type == LocalCloseType::Breach)
+            .count();
+        let remote = inputs
+            .closed
+            .iter()
+            .filter(|c| c.close_type == LocalCloseType::RemoteForce)
+            .count();
+        let local = inputs
+            .closed
+            .iter()
+            .filter(|c| c.close_type == LocalCloseType::LocalForce)
+            .count();
+
+        let (score, explanation) = if breaches > 0 {
+            (
+                0.0,
+                format!("{} tentative(s) de fraude (breach) détectée(s)", breaches),
+            )
+        } else if inputs.closed.is_empty() {
+            (100.0, "Aucun canal fermé avec ce pair".to_string())
+        } else {
+            // Une fermeture forcée par le pair pèse plus qu'une fermeture forcée de notre fait
+            (
+                (100.0 - 35.0 * remote as f64 - 15.0 * local as f64).max(0.0),
+                format!(
+                    "{} fermeture(s) : {} forcée(s) par le pair, {} forcée(s) localement",
+                    inputs.closed.len(),
+                    remote,
+                    local
+                ),
+            )
+        };
+        component(
+            "Fermetures forcées",
+            score,
+            self.weights.force_closes,
+            explanation,
+        )
+    }
+
+    fn channel_age(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        let oldest_days = inputs
+            .channels
+            .iter()
+            .filter_map(|c| c.channel_id.parse::<u64>().ok())
+            .map(|scid| (scid >> 40) as u32)
+            .filter(|height| *height > 0 && *height <= inputs.block_height)
+            .map(|height| (inputs.block_height - height) as f64 / BLOCKS_PER_DAY as f64)
+            .fold(None, |oldest: Option<f64>, days| {
+                Some(oldest.map_or(days, |o| o.max(days)))
+            });
+
+        match oldest_days {
+            Some(days) => component(
+                "Ancienneté",
+                (days / self.mature_channel_days).min(1.0) * 100.0,
+                self.weights.channel_age,
+                format!("Plus ancien canal ouvert depuis {:.0} jours", days),
+            ),
+            None => unavailable(
+                "Ancienneté",
+                self.weights.channel_age,
+                "Aucun canal ouvert avec ce pair",
+            ),
+        }
+    }
+
+    fn centrality(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        match inputs.graph.node(inputs.peer_pubkey) {
+            Some(node) => component(
+                "Centralité",
+                inputs.graph.centrality(inputs.peer_pubkey).unwrap_or(0.0),
+                self.weights.centrality,
+                format!(
+                    "{} canaux publics, {} sat : centile {:.0} en degré, {:.0} en capacité",
+                    node.channels,
+                    node.capacity_sat,
+                    node.degree_percentile,
+                    node.capacity_percentile
+                ),
+            ),
+            None => unavailable(
+                "Centralité",
+                self.weights.centrality,
+                "Pair absent du graphe public",
+            ),
+        }
+    }
+
+    fn fee_stability(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        if inputs.policy_history.is_empty() {
+            return unavailable(
+                "Stabilité tarifaire",
+                self.weights.fee_stability,
+                "Aucune politique de frais du pair observée",
+            );
+        }
+
+        let channels: BTreeSet<&str> = inputs
+            .policy_history
+            .iter()
+            .map(|p| p.channel_id.as_str())
+            .collect();
+        let mut changes = 0;
+        let mut largest_jump: f64 = 0.0;
+        for channel in &channels {
+            let history: Vec<&PeerPolicyObservation> = inputs
+                .policy_history
+                .iter()
+                .filter(|p| p.channel_id == *channel)
+                .collect();
+            for pair in history.windows(2) {
+                changes += 1;
+                let before = pair[0].fee_rate_ppm.max(1) as f64;
+                let jump = (pair[1].fee_rate_ppm as f64 - before).abs() / before;
+                largest_jump = largest_jump.max(jump);
+            }
+        }
+        let changes_per_channel = changes as f64 / channels.len() as f64;
+
+        component(
+            "Stabilité tarifaire",
+            (100.0 - 10.0 * changes_per_channel - 50.0 * largest_jump.min(1.0)).clamp(0.0, 100.0),
+            self.weights.fee_stability,
+            format!(
+                "{} changement(s) de frais en {} jours, plus forte variation {:.0}%",
+                changes,
+                REPUTATION_WINDOW_DAYS,
+                largest_jump * 100.0
+            ),
+        )
+    }
+
+    fn software(&self, inputs: &PeerReputationInputs) -> ReputationComponent {
+        let Some(node) = inputs.graph.node(inputs.peer_pubkey) else {
+            return unavailable(
+                "Logiciel",
+                self.weights.software,
+                "Annonce de nœud indisponible",
+            );
+        };
+        // Le protocole n'expose pas la version : les bits de fonctionnalités en tiennent lieu
+        let supported: Vec<&str> = MODERN_FEATURE_PAIRS
+            .iter()
+            .filter(|(bit, _)| node.features.contains(bit) || node.features.contains(&(bit + 1)))
+            .map(|(_, name)| *name)
+            .collect();
+        let mut score = supported.len() as f64 / MODERN_FEATURE_PAIRS.len() as f64 * 100.0;
+        let mut explanation = format!(
+            "{}/{} fonctionnalités récentes annoncées",
+            supported.len(),
+            MODERN_FEATURE_PAIRS.len()
+        );
+
+        let announced = DateTime::from_timestamp(node.last_update as i64, 0);
+        if let Some(announced) = announced {
+            let stale_days = (inputs.now - announced).num_days();
+            if stale_days > 14 {
+                score *= 0.8;
+                explanation.push_str(&format!(", annonce vieille de {} jours", stale_days));
+            }
+        }
+        component("Logiciel", score, self.weights.software, explanation)
+    }
+
+    /// Recalcule et persiste la réputation des pairs : tous nos pairs, plus `extra_peers`.
+    pub async fn refresh(
+        &self,
+        client: &Mutex<LocalLightningClient>,
+        forwarding_store: &ForwardingStore,
+        store: &PeerReputationStore,
+        extra_peers: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PeerReputation>> {
+        let (connected, block_height, channels, graph, closed, attempts) = {
+            let mut client = client.lock().await;
+            let block_height = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .block_height;
+            (
+                client.is_connected(),
+                block_height,
+                client.list_local_channels().await?,
+                client.describe_graph().await?,
+                client.closed_channels().await?,
+                client.htlc_attempts().await?,
+            )
+        };
+
+        // Les données fictives du mode mock ne doivent ni alimenter ni polluer l'historique
+        if !connected {
+            warn!("LND not connected: peer reputation computed without persistence");
+        }
+
+        let since = now - Duration::days(REPUTATION_WINDOW_DAYS);
+        if connected {
+            store
+                .record_peer_policies(&peer_policies(&graph, &channels, now))
+                .await?;
+        }
+        let snapshots = forwarding_store
+            .channel_snapshots_between(since, now)
+            .await?;
+        let analysis = GraphAnalysis::new(&graph);
+        let since_ns = since.timestamp_nanos_opt().unwrap_or(0) as u64;
+
+        let peers: BTreeSet<String> = channels
+            .iter()
+            .map(|c| c.peer_pubkey.clone())
+            .chain(extra_peers.iter().cloned())
+            .collect();
+
+        let mut reputations = Vec::with_capacity(peers.len());
+        for peer in &peers {
+            let peer_channels: Vec<&LocalChannelInfo> =
+                channels.iter().filter(|c| &c.peer_pubkey == peer).collect();
+            let policy_history = store.peer_policies_since(peer, since).await?;
+            let inputs = PeerReputationInputs {
+                peer_pubkey: peer,
+                now,
+                block_height,
+                snapshots: snapshots
+                    .iter()
+                    .filter(|s| &s.channel.peer_pubkey == peer)
+                    .collect(),
+                closed: closed.iter().filter(|c| &c.remote_pubkey == peer).collect(),
+                htlc_attempts: attempts
+                    .iter()
+                    .filter(|a| a.attempt_time_ns >= since_ns)
+                    .filter(|a| {
+                        peer_channels
+                            .iter()
+                            .any(|c| c.channel_id == a.first_hop_channel_id)
+                    })
+                    .collect(),
+                channels: peer_channels,
+                graph: &analysis,
+                policy_history: &policy_history,
+            };
+
+            let reputation = self.score(&inputs);
+            if connected {
+                store.record(&reputation).await?;
+            }
+            reputations.push(reputation);
+        }
+
+        info!(
+            "Peer reputation refreshed for {} peer(s)",
+            reputations.len()
+        );
+        Ok(reputations)
+    }
+}
+
+/// Politiques annoncées par nos pairs sur les canaux qu'ils partagent avec nous.
+fn peer_policies(
+    graph: &LocalNetworkGraph,
+    channels: &[LocalChannelInfo],
+    now: DateTime<Utc>,
+) -> Vec<PeerPolicyObservation> {
+    channels
+        .iter()
+        .filter_map(|channel| {
+            let edge = graph
+                .edges
+                .iter()
+                .find(|e| e.channel_id == channel.channel_id)?;
+            let policy = if edge.node1_pub == channel.peer_pubkey {
+                edge.node1_policy.as_ref()
+            } else if edge.node2_pub == channel.peer_pubkey {
+                edge.node2_policy.as_ref()
+            } else {
+                None
+            }?;
+            Some(PeerPolicyObservation {
+                peer_pubkey: channel.peer_pubkey.clone(),
+                channel_id: channel.channel_id.clone(),
+                base_fee_msat: policy.base_fee_msat,
+                fee_rate_ppm: policy.fee_rate_ppm,
+                observed_at: now,
+            })
+        })
+        .collect()
+}
+
+/// Pairs dont la réputation conditionne l'action : celui qui reçoit notre liquidité.
+pub fn gated_peers(action: &ActionParameters, channels: &[LocalChannelInfo]) -> Vec<String> {
+    match action {
+        ActionParameters::OpenChannel(params) => vec![params.peer_pubkey.clone()],
+        ActionParameters::RebalanceChannel(params) => channels
+            .iter()
+            .filter(|c| c.channel_id == params.incoming_channel_id)
+            .map(|c| c.peer_pubkey.clone())
+            .collect(),
+        ActionParameters::AdjustFees(_) | ActionParameters::CloseChannel(_) => vec![],
+    }
+}
+
+/// Garde-fou d'automatisation : score minimal et données suffisantes.
+pub fn check_reputation(
+    reputation: &PeerReputation,
+    min_score: f64,
+    min_confidence: f64,
+) -> Result<(), String> {
+    if reputation.confidence < min_confidence {
+        return Err(format!(
+            "Réputation de {} insuffisamment établie (confiance {:.0}%)",
+            reputation.peer_pubkey,
+            reputation.confidence * 100.0
+        ));
+    }
+    if reputation.score < min_score {
+        let worst = reputation.explanations().into_iter().next();
+        return Err(format!(
+            "Réputation de {} à {:.0}/100, minimum {:.0}{}",
+            reputation.peer_pubkey,
+            reputation.score,
+            min_score,
+            worst.map(|w| format!(" — {}", w)).unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+fn component(name: &str, score: f64, weight: f64, explanation: String) -> ReputationComponent {
+    ReputationComponent {
+        name: name.to_string(),
+        score,
+        weight,
+        available: true,
+        explanation,
+    }
+}
+
+fn unavailable(name: &str, weight: f64, explanation: &str) -> ReputationComponent {
+    ReputationComponent {
+        name: name.to_string(),
+        score: 0.0,
+        weight,
+        available: false,
+        explanation: explanation.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::local_lightning_client::{LocalGraphEdge, LocalGraphNode};
+    use crate::storage::forwarding::SnapshotResolution;
+    use crate::test_support;
+
+    fn channel(id: u64, peer: &str, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_pubkey: peer.to_string(),
+            active,
+            ..test_support::channel(&id.to_string(), 1_000_000, 500_000)
+        }
+    }
+
+    fn graph() -> GraphAnalysis {
+        let node = |pubkey: &str, features: Vec<u32>| LocalGraphNode {
+            pubkey: pubkey.to_string(),
+            alias: pubkey.to_uppercase(),
+            last_update: Utc::now().timestamp() as u32,
+            features,
+        };
+        let edge = |id: u64, a: &str, b: &str| LocalGraphEdge {
+            channel_id: id.to_string(),
+            node1_pub: a.to_string(),
+            node2_pub: b.to_string(),
+            capacity: 1_000_000,
+            node1_policy: None,
+            node2_policy: None,
+        };
+        GraphAnalysis::new(&LocalNetworkGraph {
+            nodes: vec![
+                node("hub", vec![13, 15, 17, 23, 45, 47]),
+                node("leaf", vec![]),
+                node("x", vec![]),
+            ],
+            edges: vec![edge(1, "hub", "leaf"), edge(2, "hub", "x")],
+        })
+    }
+
+    #[test]
+    fn test_reliable_peer_scores_high_with_explanations() {
+        let graph = graph();
+        let now = Utc::now();
+        // Canal ouvert 200 jours avant la hauteur courante
+        let scid = (800_000u64 - 200 * 144) << 40;
+        let open = channel(scid, "hub", true);
+        let snapshots: Vec<ChannelHistoryPoint> = (0..10)
+            .map(|i| {
+                ChannelHistoryPoint::sample(now - Duration::hours(i), channel(scid, "hub", true))
+            })
+            .collect();
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "hub",
+            now,
+            block_height: 800_000,
+            channels: vec![&open],
+            snapshots: snapshots.iter().collect(),
+            closed: vec![],
+            htlc_attempts: vec![],
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        assert!(reputation.score > 90.0, "score {}", reputation.score);
+        assert!(reputation.confidence < 1.0);
+        assert_eq!(reputation.alias, "HUB");
+        assert!(reputation
+            .components
+            .iter()
+            .any(|c| c.name == "Fiabilité HTLC" && !c.available));
+        assert!(check_reputation(&reputation, 70.0, 0.5).is_ok());
+    }
+
+    #[test]
+    fn test_force_closes_and_failures_fail_the_gate() {
+        let graph = graph();
+        let now = Utc::now();
+        let closed = vec![
+            LocalClosedChannel {
+                channel_id: "9".to_string(),
+                remote_pubkey: "leaf".to_string(),
+                capacity: 1_000_000,
+                close_height: 790_000,
+                close_type: LocalCloseType::RemoteForce,
+                channel_point: "9:0".to_string(),
+                closing_tx_hash: String::new(),
+            };
+            2
+        ];
+        let attempts: Vec<LocalHtlcAttempt> = (0..20)
+            .map(|i| LocalHtlcAttempt {
+                first_hop_channel_id: "5".to_string(),
+                attempt_time_ns: 0,
+                succeeded: i % 2 == 0,
+                failed_at_peer: i % 2 == 1,
+            })
+            .collect();
+        let open = channel(5, "leaf", true);
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "leaf",
+            now,
+            block_height: 800_000,
+            channels: vec![&open],
+            snapshots: vec![],
+            closed: closed.iter().collect(),
+            htlc_attempts: attempts.iter().collect(),
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        assert!(reputation.score < 40.0, "score {}", reputation.score);
+        let error = check_reputation(&reputation, 55.0, 0.3).unwrap_err();
+        assert!(error.contains("leaf"));
+    }
+
+    #[test]
+    fn test_unknown_peer_without_history_stays_gated() {
+        let graph = GraphAnalysis::new(&LocalNetworkGraph {
+            nodes: vec![],
+            edges: vec![],
+        });
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "stranger",
+            now: Utc::now(),
+            block_height: 800_000,
+            channels: vec![],
+            snapshots: vec![],
+            closed: vec![],
+            htlc_attempts: vec![],
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        // Seul l'historique de fermetures (vide) est disponible
+        let available: Vec<&str> = reputation
+            .components
+            .iter()
+            .filter(|c| c.available)
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(available, vec!["Fermetures forcées"]);
+        assert!(reputation.score.is_finite());
+        assert!(reputation.confidence < 0.2);
+        assert!(reputation.alias.is_empty());
+        let error = check_reputation(&reputation, 0.0, 0.5).unwrap_err();
+        assert!(error.contains("insuffisamment établie"));
+    }
+
+    #[test]
+    fn test_zero_capacity_and_unparsable_channels() {
+        // Pair annoncé dont l'unique canal public n'a aucune capacité
+        let graph = GraphAnalysis::new(&LocalNetworkGraph {
+            nodes: vec![LocalGraphNode {
+                pubkey: "dust".to_string(),
+                alias: String::new(),
+                last_update: 0,
+                features: vec![],
+            }],
+            edges: vec![LocalGraphEdge {
+                channel_id: "1".to_string(),
+                node1_pub: "dust".to_string(),
+                node2_pub: "hub".to_string(),
+                capacity: 0,
+                node1_policy: None,
+                node2_policy: None,
+            }],
+        });
+        let open = LocalChannelInfo {
+            peer_pubkey: "dust".to_string(),
+            ..test_support::channel("not-a-scid", 0, 0)
+        };
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "dust",
+            now: Utc::now(),
+            block_height: 800_000,
+            channels: vec![&open],
+            snapshots: vec![],
+            closed: vec![],
+            htlc_attempts: vec![],
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        assert!(reputation.score.is_finite());
+        for component in &reputation.components {
+            assert!(component.score.is_finite(), "{}", component.name);
+        }
+        assert!(reputation
+            .components
+            .iter()
+            .any(|c| c.name == "Ancienneté" && !c.available));
+        assert!(reputation
+            .components
+            .iter()
+            .any(|c| c.name == "Centralité" && c.available));
+    }
+
+    #[test]
+    fn test_inactive_peer_with_cooperative_closes() {
+        let graph = graph();
+        let now = Utc::now();
+        let snapshots: Vec<ChannelHistoryPoint> = (0..10)
+            .map(|i| ChannelHistoryPoint::sample(now - Duration::hours(i), channel(7, "x", false)))
+            .collect();
+        let closed = [LocalClosedChannel {
+            channel_id: "8".to_string(),
+            remote_pubkey: "x".to_string(),
+            capacity: 1_000_000,
+            close_height: 790_000,
+            close_type: LocalCloseType::Cooperative,
+            channel_point: "8:0".to_string(),
+            closing_tx_hash: String::new(),
+        }];
+        // Plus aucun canal ouvert : seuls les instantanés et fermetures subsistent
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "x",
+            now,
+            block_height: 800_000,
+            channels: vec![],
+            snapshots: snapshots.iter().collect(),
+            closed: closed.iter().collect(),
+            htlc_attempts: vec![],
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        let uptime = reputation
+            .components
+            .iter()
+            .find(|c| c.name == "Disponibilité")
+            .unwrap();
+        assert!(uptime.available);
+        assert_eq!(uptime.score, 0.0);
+        let closes = reputation
+            .components
+            .iter()
+            .find(|c| c.name == "Fermetures forcées")
+            .unwrap();
+        assert_eq!(closes.score, 100.0);
+        assert!(check_reputation(&reputation, 70.0, 0.3).is_err());
+    }
+
+    #[test]
+    fn test_uptime_weighted_by_aggregated_samples() {
+        let graph = graph();
+        let now = Utc::now();
+        // Une heure agrégée hors ligne (60 relevés) pèse plus que 4 relevés bruts actifs
+        let mut offline_hour =
+            ChannelHistoryPoint::sample(now - Duration::days(3), channel(3, "x", false));
+        offline_hour.resolution = SnapshotResolution::Hour;
+        offline_hour.samples = 60;
+        let mut snapshots = vec![offline_hour];
+        snapshots.extend((0..4).map(|i| {
+            ChannelHistoryPoint::sample(now - Duration::minutes(i), channel(3, "x", true))
+        }));
+        let inputs = PeerReputationInputs {
+            peer_pubkey: "x",
+            now,
+            block_height: 800_000,
+            channels: vec![],
+            snapshots: snapshots.iter().collect(),
+            closed: vec![],
+            htlc_attempts: vec![],
+            graph: &graph,
+            policy_history: &[],
+        };
+
+        let reputation = PeerReputationEngine::default().score(&inputs);
+        let uptime = reputation
+            .components
+            .iter()
+            .find(|c| c.name == "Disponibilité")
+            .unwrap();
+        assert_eq!(uptime.score, 0.0);
+        assert!(uptime.explanation.contains("des 64 relevés"));
+    }
+}
@@ -0,0 +1,312 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::mcp_client::ActionType,
+    models::{feedback::OperatorDecision, ml::SmartRecommendation},
+};
+
+const ACTION_TYPES: [ActionType; 4] = [
+    ActionType::OpenChannel,
+    ActionType::CloseChannel,
+    ActionType::AdjustFees,
+    ActionType::RebalanceChannel,
+];
+
+/// Paramètres de l'apprentissage des préférences de l'opérateur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreferenceLearnerConfig {
+    /// Taux d'approbation supposé sans décision (facteur neutre)
+    pub prior_approval_rate: f64,
+    /// Poids de l'a priori, en nombre de décisions fictives
+    pub prior_weight: f64,
+    pub min_confidence_factor: f64,
+    pub max_confidence_factor: f64,
+    /// Décisions avec confiance connue exigées de chaque côté pour apprendre un seuil
+    pub min_decisions_for_threshold: usize,
+}
+
+impl Default for PreferenceLearnerConfig {
+    fn default() -> Self {
+        Self {
+            prior_approval_rate: 0.5,
+            prior_weight: 4.0,
+            min_confidence_factor: 0.5,
+            max_confidence_factor: 1.2,
+            min_decisions_for_threshold: 5,
+        }
+    }
+}
+
+/// Préférence apprise pour un type d'action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionPreference {
+    pub action_type: ActionType,
+    pub approvals: usize,
+    pub rejections: usize,
+    /// Taux d'approbation lissé par l'a priori
+    pub approval_rate: f64,
+    /// Multiplicateur appliqué à la confiance des recommandations de ce type
+    pub confidence_factor: f64,
+}
+
+/// Pondérations et seuil appris des décisions passées.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreferenceProfile {
+    pub decisions: usize,
+    pub actions: Vec<ActionPreference>,
+    /// Confiance en dessous de laquelle l'opérateur refuse habituellement
+    pub confidence_threshold: Option<f64>,
+}
+
+impl PreferenceProfile {
+    pub fn action(&self, action_type: ActionType) -> Option<&ActionPreference> {
+        self.actions.iter().find(|a| a.action_type == action_type)
+    }
+
+    pub fn factor(&self, action_type: ActionType) -> f64 {
+        self.action(action_type)
+            .map(|a| a.confidence_factor)
+            .unwrap_or(1.0)
+    }
+
+    /// Ajuste la confiance par type d'action et écarte ce qui passe sous le seuil appris.
+    pub fn apply(&self, recommendations: Vec<SmartRecommendation>) -> Vec<SmartRecommendation> {
+        recommendations
+            .into_iter()
+            .filter_map(|mut rec| {
+                if let Some(preference) = self.action(rec.action_type) {
+                    if (preference.confidence_factor - 1.0).abs() >= 0.01 {
+                        rec.confidence = (rec.confidence * preference.confidence_factor).min(1.0);
+                        rec.rationale.push(format!(
+                            "Confiance ajustée ×{:.2} d'après vos décisions ({} approuvées, {} refusées)",
+                            preference.confidence_factor, preference.approvals, preference.rejections
+                        ));
+                    }
+                }
+                match self.confidence_threshold {
+                    Some(threshold) if rec.confidence < threshold => None,
+                    _ => Some(rec),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Évolution d'une préférence sur la période du rapport.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreferenceShift {
+    pub action_type: ActionType,
+    pub factor_before: f64,
+    pub factor_now: f64,
+    pub approvals_since: usize,
+    pub rejections_since: usize,
+}
+
+/// Rapport : préférences avant la période, préférences actuelles et leur écart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreferenceReport {
+    pub since: DateTime<Utc>,
+    pub learning_enabled: bool,
+    pub decisions_since: usize,
+    pub before: PreferenceProfile,
+    pub now: PreferenceProfile,
+    pub shifts: Vec<PreferenceShift>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PreferenceLearner {
+    pub config: PreferenceLearnerConfig,
+}
+
+impl PreferenceLearner {
+    pub fn learn(&self, decisions: &[OperatorDecision]) -> PreferenceProfile {
+        let config = &self.config;
+        let actions = ACTION_TYPES
+            .iter()
+            .filter_map(|&action_type| {
+                let (approvals, rejections) = decisions
+                    .iter()
+                    .filter(|d| d.action_type == action_type)
+                    .fold(
+                        (0, 0),
+                        |(a, r), d| if d.approved { (a + 1, r) } else { (a, r + 1) },
+                    );
+                if approvals + rejections == 0 {
+                    return None;
+                }
+                let approval_rate = (approvals as f64
+                    + config.prior_approval_rate * config.prior_weight)
+                    / ((approvals + rejections) as f64 + config.prior_weight);
+                Some(ActionPreference {
+                    action_type,
+                    approvals,
+                    rejections,
+                    approval_rate,
+                    confidence_factor: (approval_rate / config.prior_approval_rate)
+                        .clamp(config.min_confidence_factor, config.max_confidence_factor),
+                })
+            })
+            .collect();
+
+        PreferenceProfile {
+            decisions: decisions.len(),
+            actions,
+            confidence_threshold: self.confidence_threshold(decisions),
+        }
+    }
+
+    /// Milieu des confiances moyennes approuvées et refusées, si les refus portent
+    /// nettement sur les recommandations les moins sûres.
+    fn confidence_threshold(&self, decisions: &[OperatorDecision]) -> Option<f64> {
+        let mean = |approved: bool| {
+            let values: Vec<f64> = decisions
+                .iter()
+                .filter(|d| d.approved == approved)
+                .filter_map(|d| d.confidence)
+                .collect();
+            (values.len() >= self.config.min_decisions_for_threshold)
+                .then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let (approved, rejected) = (mean(true)?, mean(false)?);
+        (approved > rejected).then_some((approved + rejected) / 2.0)
+    }
+
+    /// Compare les préférences apprises avant `since` à celles apprises sur tout l'historique.
+    pub fn report(
+        &self,
+        decisions: &[OperatorDecision],
+        since: DateTime<Utc>,
+        learning_enabled: bool,
+    ) -> PreferenceReport {
+        let earlier: Vec<OperatorDecision> = decisions
+            .iter()
+            .filter(|d| d.decided_at < since)
+            .cloned()
+            .collect();
+        let recent: Vec<&OperatorDecision> =
+            decisions.iter().filter(|d| d.decided_at >= since).collect();
+        let before = self.learn(&earlier);
+        let now = self.learn(decisions);
+
+        let shifts = now
+            .actions
+            .iter()
+            .map(|preference| {
+                let recent_of_type = recent
+                    .iter()
+                    .filter(|d| d.action_type == preference.action_type);
+                PreferenceShift {
+                    action_type: preference.action_type,
+                    factor_before: before.factor(preference.action_type),
+                    factor_now: preference.confidence_factor,
+                    approvals_since: recent_of_type.clone().filter(|d| d.approved).count(),
+                    rejections_since: recent_of_type.filter(|d| !d.approved).count(),
+                }
+            })
+            .collect();
+
+        PreferenceReport {
+            since,
+            learning_enabled,
+            decisions_since: recent.len(),
+            before,
+            now,
+            shifts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::Priority;
+    use crate::models::recommendation::RecommendationSource;
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+
+    fn decision(
+        action_type: ActionType,
+        approved: bool,
+        confidence: f64,
+        at: DateTime<Utc>,
+    ) -> OperatorDecision {
+        OperatorDecision {
+            recommendation_id: "ml_rec".to_string(),
+            approved,
+            action_type,
+            priority: Priority::Medium,
+            source: RecommendationSource::LocalMl,
+            confidence: Some(confidence),
+            expected_roi_impact: 2.0,
+            model_version: Some(1),
+            actor: "operator".to_string(),
+            reason: None,
+            decided_at: at,
+        }
+    }
+
+    fn recommendation(action_type: ActionType, confidence: f64) -> SmartRecommendation {
+        SmartRecommendation {
+            id: "rec".to_string(),
+            action_type,
+            priority: Priority::Medium,
+            expected_roi_impact: 2.0,
+            confidence,
+            risk_score: 0.2,
+            rationale: vec![],
+            target_channels: vec![],
+            parameters: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_rejected_action_types_lose_confidence() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let mut decisions: Vec<_> = (0..6)
+            .map(|_| decision(ActionType::RebalanceChannel, false, 0.8, at))
+            .collect();
+        decisions.extend((0..6).map(|_| decision(ActionType::AdjustFees, true, 0.8, at)));
+
+        let profile = PreferenceLearner::default().learn(&decisions);
+        assert!(profile.factor(ActionType::RebalanceChannel) < 0.6);
+        assert!(profile.factor(ActionType::AdjustFees) > 1.0);
+        assert_eq!(profile.factor(ActionType::OpenChannel), 1.0);
+        // Même confiance des deux côtés : aucun seuil à apprendre
+        assert_eq!(profile.confidence_threshold, None);
+
+        let adjusted = profile.apply(vec![
+            recommendation(ActionType::RebalanceChannel, 0.8),
+            recommendation(ActionType::OpenChannel, 0.8),
+        ]);
+        assert!(adjusted[0].confidence < 0.5);
+        assert_eq!(adjusted[0].rationale.len(), 1);
+        assert_eq!(adjusted[1].confidence, 0.8);
+    }
+
+    #[test]
+    fn test_threshold_and_shift_report() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let since = start + Duration::days(30);
+        let mut decisions: Vec<_> = (0..5)
+            .map(|_| decision(ActionType::AdjustFees, true, 0.9, start))
+            .collect();
+        decisions.extend((0..5).map(|_| decision(ActionType::AdjustFees, false, 0.5, since)));
+
+        let learner = PreferenceLearner::default();
+        let profile = learner.learn(&decisions);
+        let threshold = profile.confidence_threshold.unwrap();
+        assert!((threshold - 0.7).abs() < 1e-9);
+        assert!(profile
+            .apply(vec![recommendation(ActionType::OpenChannel, 0.6)])
+            .is_empty());
+
+        let report = learner.report(&decisions, since, true);
+        assert_eq!(report.decisions_since, 5);
+        assert_eq!(report.shifts.len(), 1);
+        let shift = &report.shifts[0];
+        assert!(shift.factor_before > 1.0);
+        assert!((shift.factor_now - 1.0).abs() < 1e-9);
+        assert_eq!(shift.rejections_since, 5);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Write;
+
+/// Type d'une famille de métriques Prometheus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Gauge,
+    Counter,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+/// Rédaction au format texte d'exposition Prometheus (version 0.0.4).
+#[derive(Debug, Default)]
+pub struct PrometheusWriter {
+    out: String,
+}
+
+impl PrometheusWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ouvre une famille : ses échantillons doivent suivre immédiatement
+    pub fn family(&mut self, name: &str, kind: MetricKind, help: &str) {
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind.as_str());
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+
+    /// Famille à un seul échantillon sans étiquette
+    pub fn single(&mut self, name: &str, kind: MetricKind, help: &str, value: f64) {
+        self.family(name, kind, help);
+        self.sample(name, &[], value);
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exposition_format() {
+        let mut writer = PrometheusWriter::new();
+        writer.single("dazno_lnd_up", MetricKind::Gauge, "LND joignable", 1.0);
+        writer.family(
+            "dazno_channel_local_ratio",
+            MetricKind::Gauge,
+            "Part locale",
+        );
+        writer.sample(
+            "dazno_channel_local_ratio",
+            &[("channel_id", "123"), ("peer_alias", "a \"quoted\"\\alias")],
+            0.25,
+        );
+        writer.sample("dazno_bucket", &[("le", "+Inf")], f64::INFINITY);
+
+        assert_eq!(
+            writer.finish(),
+            "# HELP dazno_lnd_up LND joignable\n\
+             # TYPE dazno_lnd_up gauge\n\
+             dazno_lnd_up 1\n\
+             # HELP dazno_channel_local_ratio Part locale\n\
+             # TYPE dazno_channel_local_ratio gauge\n\
+             dazno_channel_local_ratio{channel_id=\"123\",peer_alias=\"a \\\"quoted\\\"\\\\alias\"} 0.25\n\
+             dazno_bucket{le=\"+Inf\"} +Inf\n"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::{
+    api::mcp_client::{MCPClient, Priority},
+    models::recommendation::{Recommendation, RecommendationSource},
+    utils::ml_engine::{MLEngine, RecommendationInputs},
+};
+
+/// Bilan de collecte pour une source de recommandations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceReport {
+    pub source: RecommendationSource,
+    pub fetched: usize,
+    pub kept: usize,
+    pub error: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Résultat du pipeline : liste unifiée et dédupliquée.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedRecommendations {
+    pub recommendations: Vec<Recommendation>,
+    pub sources: Vec<SourceReport>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Agrège les recommandations MCP (distantes) et ML (locales) en une seule liste.
+#[derive(Debug, Clone)]
+pub struct RecommendationAggregator {
+    pub max_age: Duration,
+}
+
+impl Default for RecommendationAggregator {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::hours(6),
+        }
+    }
+}
+
+impl RecommendationAggregator {
+    pub fn new(max_age: Duration) -> Self {
+        Self { max_age }
+    }
+
+    /// Collecte les deux sources puis fusionne. Une source en échec n'empêche pas l'autre.
+    pub async fn collect(
+        &self,
+        mcp_client: &MCPClient,
+        ml_engine: &MLEngine,
+        node_pubkey: &str,
+        inputs: &RecommendationInputs<'_>,
+    ) -> AggregatedRecommendations {
+        let now = Utc::now();
+
+        let (remote, remote_error) = match mcp_client.get_recommendations(node_pubkey).await {
+            Ok(recs) => (
+                recs.into_iter()
+                    .map(Recommendation::from)
+                    .collect::<Vec<_>>(),
+                None,
+            ),
+            Err(e) => {
+                warn!(
+                    "MCP recommendations unavailable, using local ML only: {}",
+                    e
+                );
+                (vec![], Some(e.to_string()))
+            }
+        };
+
+        let local: Vec<Recommendation> = ml_engine
+            .build_recommendations(inputs)
+            .into_iter()
+            .map(|rec| Recommendation::from(rec).with_model_version(ml_engine.version))
+            .collect();
+
+        let remote_fetched = remote.len();
+        let local_fetched = local.len();
+        let recommendations = self.merge(remote, local, now);
+
+        let kept = |source: RecommendationSource| {
+            recommendations
+                .iter()
+                .filter(|r| r.provenance.iter().any(|p| p.source == source))
+                .count()
+        };
+
+        let sources = vec![
+            SourceReport {
+                source: RecommendationSource::Mcp,
+                fetched: remote_fetched,
+                kept: kept(RecommendationSource::Mcp),
+                error: remote_error,
+                fetched_at: now,
+            },
+            SourceReport {
+                source: RecommendationSource::LocalMl,
+                fetched: local_fetched,
+                kept: kept(RecommendationSource::LocalMl),
+                error: None,
+                fetched_at: now,
+            },
+        ];
+
+        info!(
+            "Aggregated {} recommendations ({} MCP, {} local ML)",
+            recommendations.len(),
+            remote_fetched,
+            local_fetched
+        );
+
+        AggregatedRecommendations {
+            recommendations,
+            sources,
+            generated_at: now,
+        }
+    }
+
+    /// Fusionne les listes normalisées :
+    /// - écarte les recommandations plus vieilles que `max_age`,
+    /// - regroupe les suggestions de même type sur un même canal (corroboration),
+    /// - sur un canal disputé par des actions différentes, conserve la plus forte.
+    pub fn merge(
+        &self,
+        remote: Vec<Recommendation>,
+        local: Vec<Recommendation>,
+        now: DateTime<Utc>,
+    ) -> Vec<Recommendation> {
+        let mut merged: Vec<Recommendation> = vec![];
+
+        for candidate in remote.into_iter().chain(local) {
+            if candidate.is_stale(now, self.max_age) {
+                info!("Dropping stale recommendation {}", candidate.id);
+                continue;
+            }
+
+            // Même action sur les mêmes canaux : on fusionne la provenance
+            if let Some(existing) = merged.iter_mut().find(|r| is_duplicate(r, &candidate)) {
+                existing.provenance.extend(candidate.provenance);
+                existing.expected_roi_impact = existing
+                    .expected_roi_impact
+                    .max(candidate.expected_roi_impact);
+                existing.confidence = match (existing.confidence, candidate.confidence) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    (a, b) => a.or(b),
+                };
+                continue;
+            }
+
+            merged.push(candidate);
+        }
+
+        resolve_channel_conflicts(merged)
+    }
+}
+
+fn is_duplicate(a: &Recommendation, b: &Recommendation) -> bool {
+    if a.action_type != b.action_type {
+        return false;
+    }
+    if a.target_channels.is_empty() || b.target_channels.is_empty() {
+        // Sans canal ciblé (ex. ouverture), seul l'identifiant source fait foi
+        return a
+            .provenance
+            .iter()
+            .any(|p| b.provenance.iter().any(|q| q.source_id == p.source_id));
+    }
+    let mut left = a.target_channels.clone();
+    let mut right = b.target_channels.clone();
+    left.sort();
+    right.sort();
+    left == right
+}
+
+/// Classement utilisé pour départager deux actions concurrentes sur un canal.
+fn strength(recommendation: &Recommendation) -> (u8, u8, i64) {
+    let priority = match recommendation.priority {
+        Priority::High => 3,
+        Priority::Medium => 2,
+        Priority::Low => 1,
+    };
+    let corroboration = recommendation.provenance.len().min(u8::MAX as usize) as u8;
+    (
+        priority,
+        corroboration,
+        (recommendation.expected_roi_impact * 1000.0).round() as i64,
+    )
+}
+
+fn resolve_channel_conflicts(mut recommendations: Vec<Recommendation>) -> Vec<Recommendation> {
+    recommendations.sort_by_key(|r| std::cmp::Reverse(strength(r)));
+
+    let mut claimed: HashMap<String, usize> = HashMap::new();
+    let mut kept: Vec<Recommendation> = vec![];
+
+    for recommendation in recommendations {
+        let winner = recommendation
+            .target_channels
+            .iter()
+            .find_map(|channel| claimed.get(channel).copied());
+
+        match winner {
+            Some(index) => {
+                info!(
+                    "Recommendation {} superseded by {} on a shared channel",
+                    recommendation.id, kept[index].id
+                );
+                kept[index].superseded.push(recommendation.id);
+            }
+            None => {
+                let index = kept.len();
+                for channel in &recommendation.target_channels {
+                    claimed.insert(channel.clone(), index);
+                }
+                kept.push(recommendation);
+            }
+        }
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::{ActionType, MCPRecommendation};
+    use crate::models::ml::SmartRecommendation;
+    use serde_json::json;
+
+    fn mcp(id: &str, action_type: ActionType, priority: Priority, channel: &str) -> Recommendation {
+        Recommendation::from(MCPRecommendation {
+            id: id.to_string(),
+            action_type,
+            priority,
+            expected_roi_impact: 2.0,
+            parameters: json!({ "channel_id": channel }),
+            created_at: Utc::now(),
+            description: "remote".to_string(),
+        })
+    }
+
+    fn local(
+        id: &str,
+        action_type: ActionType,
+        priority: Priority,
+        channel: &str,
+    ) -> Recommendation {
+        Recommendation::from(SmartRecommendation {
+            id: id.to_string(),
+            action_type,
+            priority,
+            expected_roi_impact: 3.0,
+            confidence: 0.9,
+            risk_score: 0.2,
+            rationale: vec!["local".to_string()],
+            target_channels: vec![channel.to_string()],
+            parameters: json!({ "channel_id": channel }),
+        })
+    }
+
+    #[test]
+    fn test_same_action_on_same_channel_is_corroborated() {
+        let aggregator = RecommendationAggregator::default();
+        let merged = aggregator.merge(
+            vec![mcp("a", ActionType::AdjustFees, Priority::High, "123")],
+            vec![local("b", ActionType::AdjustFees, Priority::Medium, "123")],
+            Utc::now(),
+        );
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].source, RecommendationSource::Mcp);
+        assert_eq!(merged[0].provenance.len(), 2);
+        assert_eq!(merged[0].expected_roi_impact, 3.0);
+        assert_eq!(merged[0].confidence, Some(0.9));
+    }
+
+    #[test]
+    fn test_conflicting_actions_keep_strongest() {
+        let aggregator = RecommendationAggregator::default();
+        let merged = aggregator.merge(
+            vec![mcp("close", ActionType::CloseChannel, Priority::Low, "123")],
+            vec![local("fees", ActionType::AdjustFees, Priority::High, "123")],
+            Utc::now(),
+        );
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, "ml_fees");
+        assert_eq!(merged[0].superseded, vec!["mcp_close".to_string()]);
+    }
+
+    #[test]
+    fn test_stale_recommendations_are_dropped() {
+        let aggregator = RecommendationAggregator::new(Duration::hours(1));
+        let mut old = mcp("old", ActionType::AdjustFees, Priority::High, "123");
+        old.created_at = Utc::now() - Duration::hours(3);
+
+        let merged = aggregator.merge(
+            vec![old],
+            vec![local(
+                "fresh",
+                ActionType::RebalanceChannel,
+                Priority::Low,
+                "456",
+            )],
+            Utc::now(),
+        );
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, "ml_fresh");
+    }
+}
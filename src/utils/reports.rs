@@ -0,0 +1,670 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::{
+    api::local_lightning_client::{LocalChannelInfo, LocalForwardingEvent, LocalRebalance},
+    models::{
+        anomaly::{Anomaly, AnomalySeverity, AutomationPause},
+        ledger::{Ledger, LedgerCategory},
+        recommendation::{Recommendation, RecommendationStatus, StatusTransition},
+        report::{
+            PerformanceReport, ReportAction, ReportChannel, ReportFinancials, ReportPeriod,
+            ReportRisk,
+        },
+    },
+    utils::close_candidates::CloseCandidate,
+};
+
+/// Fenêtre de part et d'autre d'une exécution pour en mesurer l'effet
+pub const IMPACT_WINDOW_DAYS: i64 = 7;
+/// Canaux retenus dans les classements du rapport
+const RANKED_CHANNELS: usize = 3;
+
+/// Données réunies pour un rapport. `events` couvre la période et la fenêtre de
+/// mesure autour des exécutions, jusqu'à `now`.
+pub struct ReportInputs<'a> {
+    pub period: ReportPeriod,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub now: DateTime<Utc>,
+    pub ledger: &'a Ledger,
+    pub events: &'a [LocalForwardingEvent],
+    pub rebalances: &'a [LocalRebalance],
+    pub channels: &'a [LocalChannelInfo],
+    pub executions: &'a [(Recommendation, StatusTransition)],
+    pub anomalies: &'a [Anomaly],
+    pub pause: Option<&'a AutomationPause>,
+    pub close_candidates: &'a [CloseCandidate],
+}
+
+fn nanos(at: DateTime<Utc>) -> u64 {
+    at.timestamp_nanos_opt().unwrap_or(0).max(0) as u64
+}
+
+pub fn build_report(inputs: &ReportInputs) -> PerformanceReport {
+    let (start_ns, end_ns) = (nanos(inputs.start), nanos(inputs.end));
+    let in_period = |ns: u64| ns >= start_ns && ns < end_ns;
+    let forwards: Vec<&LocalForwardingEvent> = inputs
+        .events
+        .iter()
+        .filter(|e| in_period(e.timestamp_ns))
+        .collect();
+
+    let channels = channel_contributions(inputs, &forwards);
+    let mut ranked: Vec<&ReportChannel> = channels.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.net_sat
+            .cmp(&a.net_sat)
+            .then(a.channel_id.cmp(&b.channel_id))
+    });
+    // Moitié haute et moitié basse du classement, pour ne jamais citer un canal deux fois
+    let (top, bottom) = ranked.split_at(ranked.len().div_ceil(2));
+    let best_channels: Vec<ReportChannel> = top
+        .iter()
+        .take(RANKED_CHANNELS)
+        .map(|c| (*c).clone())
+        .collect();
+    let worst_channels: Vec<ReportChannel> = bottom
+        .iter()
+        .rev()
+        .take(RANKED_CHANNELS)
+        .map(|c| (*c).clone())
+        .collect();
+
+    PerformanceReport {
+        id: inputs.period.report_id(inputs.start),
+        period: inputs.period,
+        period_start: inputs.start,
+        period_end: inputs.end,
+        generated_at: inputs.now,
+        financials: financials(inputs, &forwards),
+        best_channels,
+        worst_channels,
+        actions: inputs
+            .executions
+            .iter()
+            .map(|(recommendation, transition)| measure_action(inputs, recommendation, transition))
+            .collect(),
+        risks: open_risks(inputs),
+    }
+}
+
+fn financials(inputs: &ReportInputs, forwards: &[&LocalForwardingEvent]) -> ReportFinancials {
+    // Les produits comptent positivement, les charges négativement
+    let (mut revenue_msat, mut rebalance_msat, mut onchain_msat) = (0u64, 0u64, 0u64);
+    let mut net_fiat = Some(0.0);
+    for entry in &inputs.ledger.entries {
+        let (total, sign) = match entry.category {
+            LedgerCategory::RoutingIncome => (&mut revenue_msat, 1.0),
+            LedgerCategory::RebalanceFee => (&mut rebalance_msat, -1.0),
+            LedgerCategory::ChannelOpenFee | LedgerCategory::ChannelCloseFee => {
+                (&mut onchain_msat, -1.0)
+            }
+            _ => continue,
+        };
+        *total += entry.amount_msat;
+        net_fiat = net_fiat
+            .zip(entry.fiat_value)
+            .map(|(net, fiat)| net + sign * fiat);
+    }
+
+    let revenue_sat = revenue_msat / 1000;
+    let rebalance_cost_sat = rebalance_msat / 1000;
+    let onchain_cost_sat = onchain_msat / 1000;
+    let costs_sat = rebalance_cost_sat + onchain_cost_sat;
+    let net_sat = revenue_sat as i64 - costs_sat as i64;
+    let local_balance_sat: u64 = inputs.channels.iter().map(|c| c.local_balance).sum();
+    let days = (inputs.end - inputs.start).num_seconds() as f64 / 86_400.0;
+
+    ReportFinancials {
+        forwards: forwards.len() as u64,
+        routed_sat: forwards.iter().map(|e| e.amt_out_msat).sum::<u64>() / 1000,
+        revenue_sat,
+        rebalance_cost_sat,
+        onchain_cost_sat,
+        costs_sat,
+        net_sat,
+        local_balance_sat,
+        net_yield_pct: (local_balance_sat > 0 && days > 0.0)
+            .then(|| net_sat as f64 / local_balance_sat as f64 * 365.0 / days * 100.0),
+        fiat_currency: inputs.ledger.fiat_currency.clone(),
+        net_fiat,
+    }
+}
+
+/// Canaux ouverts et canaux fermés ayant routé pendant la période
+fn channel_contributions(
+    inputs: &ReportInputs,
+    forwards: &[&LocalForwardingEvent],
+) -> Vec<ReportChannel> {
+    let mut channels: HashMap<&str, ReportChannel> = inputs
+        .channels
+        .iter()
+        .map(|c| {
+            (
+                c.channel_id.as_str(),
+                ReportChannel {
+                    channel_id: c.channel_id.clone(),
+                    peer_alias: c.peer_alias.clone(),
+                    forwards: 0,
+                    revenue_sat: 0,
+                    rebalance_cost_sat: 0,
+                    net_sat: 0,
+                    local_balance_sat: c.local_balance,
+                    open: true,
+                },
+            )
+        })
+        .collect();
+
+    let mut revenue_msat: HashMap<String, u64> = HashMap::new();
+    let mut rebalance_msat: HashMap<String, u64> = HashMap::new();
+    let mut forward_counts: HashMap<String, u64> = HashMap::new();
+    for event in forwards {
+        for id in [&event.chan_id_in, &event.chan_id_out] {
+            *revenue_msat.entry(id.clone()).or_default() += event.fee_msat;
+            *forward_counts.entry(id.clone()).or_default() += 1;
+        }
+    }
+    let (start_ns, end_ns) = (nanos(inputs.start), nanos(inputs.end));
+    for rebalance in inputs
+        .rebalances
+        .iter()
+        .filter(|r| r.settled_at_ns >= start_ns && r.settled_at_ns < end_ns)
+    {
+        for id in [
+            &rebalance.outgoing_channel_id,
+            &rebalance.incoming_channel_id,
+        ] {
+            *rebalance_msat.entry(id.clone()).or_default() += rebalance.fee_msat;
+        }
+    }
+
+    for id in revenue_msat.keys().chain(rebalance_msat.keys()) {
+        channels
+            .entry(id.as_str())
+            .or_insert_with(|| ReportChannel {
+                channel_id: id.clone(),
+                peer_alias: String::new(),
+                forwards: 0,
+                revenue_sat: 0,
+                rebalance_cost_sat: 0,
+                net_sat: 0,
+                local_balance_sat: 0,
+                open: false,
+            });
+    }
+    channels
+        .into_values()
+        .map(|mut channel| {
+            let id = &channel.channel_id;
+            channel.forwards = forward_counts.get(id).copied().unwrap_or(0);
+            channel.revenue_sat = revenue_msat.get(id).copied().unwrap_or(0) / 2000;
+            channel.rebalance_cost_sat = rebalance_msat.get(id).copied().unwrap_or(0) / 2000;
+            channel.net_sat = channel.revenue_sat as i64 - channel.rebalance_cost_sat as i64;
+            channel
+        })
+        .collect()
+}
+
+/// Frais quotidiens des canaux visés sur `IMPACT_WINDOW_DAYS` avant et après l'exécution
+fn measure_action(
+    inputs: &ReportInputs,
+    recommendation: &Recommendation,
+    transition: &StatusTransition,
+) -> ReportAction {
+    let targets: HashSet<&str> = recommendation
+        .target_channels
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let window = Duration::days(IMPACT_WINDOW_DAYS);
+    let fees_per_day = |from: DateTime<Utc>, to: DateTime<Utc>| {
+        let days = (to - from).num_seconds() as f64 / 86_400.0;
+        let (from_ns, to_ns) = (nanos(from), nanos(to));
+        let fees_msat: u64 = inputs
+            .events
+            .iter()
+            .filter(|e| e.timestamp_ns >= from_ns && e.timestamp_ns < to_ns)
+            .filter(|e| {
+                targets.contains(e.chan_id_in.as_str()) || targets.contains(e.chan_id_out.as_str())
+            })
+            .map(|e| e.fee_msat)
+            .sum();
+        (days >= 1.0).then(|| fees_msat as f64 / 1000.0 / days)
+    };
+
+    let at = transition.at;
+    let executed = transition.to == RecommendationStatus::Executed && !targets.is_empty();
+    let before = fees_per_day(at - window, at).unwrap_or(0.0);
+    let after = executed
+        .then(|| fees_per_day(at, (at + window).min(inputs.now)))
+        .flatten();
+
+    ReportAction {
+        recommendation_id: recommendation.id.clone(),
+        action_type: format!("{:?}", recommendation.action_type),
+        description: recommendation.description.clone(),
+        status: transition.to,
+        at,
+        expected_roi_impact: recommendation.expected_roi_impact,
+        target_channels: recommendation.target_channels.clone(),
+        fees_per_day_before_sat: before,
+        fees_per_day_after_sat: after,
+        measured_change_pct: after
+            .filter(|_| before > 0.0)
+            .map(|after| (after - before) / before * 100.0),
+    }
+}
+
+fn open_risks(inputs: &ReportInputs) -> Vec<ReportRisk> {
+    let mut risks = vec![];
+    if let Some(pause) = inputs.pause {
+        risks.push(ReportRisk {
+            severity: AnomalySeverity::Critical,
+            title: "Automatisation suspendue".to_string(),
+            detail: format!(
+                "Depuis le {} : {}",
+                pause.paused_at.format("%Y-%m-%d %H:%M"),
+                pause.reason
+            ),
+        });
+    }
+    for anomaly in inputs
+        .anomalies
+        .iter()
+        .filter(|a| a.detected_at >= inputs.start && a.detected_at < inputs.end)
+    {
+        risks.push(ReportRisk {
+            severity: anomaly.severity,
+            title: "Anomalie détectée".to_string(),
+            detail: format!(
+                "{} ({})",
+                anomaly.description,
+                anomaly.detected_at.format("%Y-%m-%d %H:%M")
+            ),
+        });
+    }
+
+    let inactive: Vec<&LocalChannelInfo> = inputs.channels.iter().filter(|c| !c.active).collect();
+    if !inactive.is_empty() {
+        let local_sat: u64 = inactive.iter().map(|c| c.local_balance).sum();
+        risks.push(ReportRisk {
+            severity: AnomalySeverity::Warning,
+            title: format!("{} canal(aux) inactif(s)", inactive.len()),
+            detail: format!(
+                "{} sat de solde local immobilisés : {}",
+                local_sat,
+                inactive
+                    .iter()
+                    .map(|c| c.peer_alias.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        });
+    }
+
+    for candidate in inputs.close_candidates.iter().filter(|c| c.recommended) {
+        risks.push(ReportRisk {
+            severity: AnomalySeverity::Warning,
+            title: format!("Fermeture conseillée : {}", candidate.peer_alias),
+            detail: candidate
+                .reasons
+                .iter()
+                .map(|reason| reason.describe())
+                .collect::<Vec<_>>()
+                .join(" ; "),
+        });
+    }
+
+    let failed = inputs
+        .executions
+        .iter()
+        .filter(|(_, t)| t.to == RecommendationStatus::Failed)
+        .count();
+    if failed > 0 {
+        risks.push(ReportRisk {
+            severity: AnomalySeverity::Warning,
+            title: format!("{} exécution(s) en échec", failed),
+            detail: "Voir la liste des actions de la période".to_string(),
+        });
+    }
+    risks
+}
+
+pub fn report_title(report: &PerformanceReport) -> String {
+    let period = match report.period {
+        ReportPeriod::Weekly => "hebdomadaire",
+        ReportPeriod::Monthly => "mensuel",
+    };
+    format!(
+        "Rapport {} du {} au {}",
+        period,
+        report.period_start.format("%Y-%m-%d"),
+        report.period_end.format("%Y-%m-%d")
+    )
+}
+
+/// Effet mesuré d'une action, tel qu'affiché dans les rapports
+pub fn measured_label(action: &ReportAction) -> String {
+    match (action.fees_per_day_after_sat, action.measured_change_pct) {
+        (_, Some(pct)) => format!("{:+.1} % de frais/jour", pct),
+        (Some(after), None) => format!("{:.0} sat/jour après", after),
+        (None, _) => "effet non mesuré".to_string(),
+    }
+}
+
+/// Rapport au format Markdown, mêmes sections que la page HTML
+pub fn render_markdown(report: &PerformanceReport) -> String {
+    let mut out = String::new();
+    let f = &report.financials;
+    let _ = writeln!(out, "# {}\n", report_title(report));
+    let _ = writeln!(
+        out,
+        "_Généré le {}_\n",
+        report.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let _ = writeln!(out, "## Résultat\n");
+    let _ = writeln!(out, "| Poste | Montant |");
+    let _ = writeln!(out, "|---|---:|");
+    let _ = writeln!(
+        out,
+        "| Forwards | {} ({} sat routés) |",
+        f.forwards, f.routed_sat
+    );
+    let _ = writeln!(out, "| Revenus de routage | {} sat |", f.revenue_sat);
+    let _ = writeln!(out, "| Rééquilibrages | -{} sat |", f.rebalance_cost_sat);
+    let _ = writeln!(out, "| Frais on-chain | -{} sat |", f.onchain_cost_sat);
+    let _ = writeln!(out, "| **Net** | **{} sat** |", f.net_sat);
+    if let Some(fiat) = f.net_fiat {
+        let _ = writeln!(out, "| Net ({}) | {:.2} |", f.fiat_currency, fiat);
+    }
+    if let Some(yield_pct) = f.net_yield_pct {
+        let _ = writeln!(out, "| Rendement net annualisé | {:.2} % |", yield_pct);
+    }
+
+    for (title, channels) in [
+        ("Meilleurs canaux", &report.best_channels),
+        ("Canaux les moins rentables", &report.worst_channels),
+    ] {
+        let _ = writeln!(out, "\n## {}\n", title);
+        if channels.is_empty() {
+            let _ = writeln!(out, "Aucun canal.");
+            continue;
+        }
+        let _ = writeln!(
+            out,
+            "| Canal | Pair | Forwards | Revenus | Rééquilibrages | Net |"
+        );
+        let _ = writeln!(out, "|---|---|---:|---:|---:|---:|");
+        for c in channels.iter() {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} |",
+                c.channel_id,
+                c.peer_alias,
+                c.forwards,
+                c.revenue_sat,
+                c.rebalance_cost_sat,
+                c.net_sat
+            );
+        }
+    }
+
+    let _ = writeln!(out, "\n## Actions exécutées\n");
+    if report.actions.is_empty() {
+        let _ = writeln!(out, "Aucune action sur la période.");
+    }
+    for action in &report.actions {
+        let _ = writeln!(
+            out,
+            "- {} `{}` {} ({}) : {}",
+            action.at.format("%Y-%m-%d"),
+            action.status.as_str(),
+            action.action_type,
+            action.description,
+            measured_label(action)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Risques ouverts\n");
+    if report.risks.is_empty() {
+        let _ = writeln!(out, "Aucun risque identifié.");
+    }
+    for risk in &report.risks {
+        let _ = writeln!(
+            out,
+            "- **{:?}** {} : {}",
+            risk.severity, risk.title, risk.detail
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::{ActionType, Priority};
+    use crate::models::ledger::{LedgerAccount, LedgerEntry};
+    use crate::models::recommendation::RecommendationSource;
+    use crate::test_support;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn channel(id: &str, alias: &str, local: u64, active: bool) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_alias: alias.to_string(),
+            active,
+            ..test_support::channel(id, 2_000_000, local)
+        }
+    }
+
+    fn forward(
+        at: DateTime<Utc>,
+        chan_in: &str,
+        chan_out: &str,
+        fee_msat: u64,
+    ) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: nanos(at),
+            chan_id_in: chan_in.to_string(),
+            chan_id_out: chan_out.to_string(),
+            amt_in_msat: 1_000_000_000 + fee_msat,
+            amt_out_msat: 1_000_000_000,
+            fee_msat,
+        }
+    }
+
+    fn entry(category: LedgerCategory, amount_msat: u64, fiat: Option<f64>) -> LedgerEntry {
+        LedgerEntry {
+            id: format!("{:?}-{}", category, amount_msat),
+            timestamp: at("2026-10-13T12:00:00Z"),
+            category,
+            debit: LedgerAccount::LightningBalance,
+            credit: LedgerAccount::RoutingIncome,
+            amount_msat,
+            fiat_value: fiat,
+            reference: String::new(),
+            description: String::new(),
+        }
+    }
+
+    fn recommendation(id: &str, targets: &[&str]) -> Recommendation {
+        Recommendation {
+            id: id.to_string(),
+            action_type: ActionType::AdjustFees,
+            priority: Priority::Medium,
+            expected_roi_impact: 2.0,
+            description: "Hausse des frais".to_string(),
+            parameters: serde_json::json!({}),
+            created_at: at("2026-10-01T00:00:00Z"),
+            status: RecommendationStatus::Executed,
+            source: RecommendationSource::LocalMl,
+            confidence: None,
+            target_channels: targets.iter().map(|t| t.to_string()).collect(),
+            provenance: vec![],
+            superseded: vec![],
+            expires_at: None,
+            snoozed_until: None,
+        }
+    }
+
+    fn transition(id: &str, to: RecommendationStatus, at: DateTime<Utc>) -> StatusTransition {
+        StatusTransition {
+            recommendation_id: id.to_string(),
+            from: RecommendationStatus::Executing,
+            to,
+            at,
+            actor: "system".to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_weekly_report_figures() {
+        let (start, end) = ReportPeriod::Weekly.last_complete(at("2026-10-19T00:30:00Z"));
+        assert_eq!(
+            (start, end),
+            (at("2026-10-12T00:00:00Z"), at("2026-10-19T00:00:00Z"))
+        );
+
+        let ledger = Ledger {
+            start,
+            end,
+            fiat_currency: "EUR".to_string(),
+            entries: vec![
+                entry(LedgerCategory::RoutingIncome, 90_000_000, Some(50.0)),
+                entry(LedgerCategory::RebalanceFee, 10_000_000, Some(5.0)),
+                entry(LedgerCategory::ChannelOpenFee, 5_000_000, Some(3.0)),
+                entry(LedgerCategory::PaymentSent, 1_000_000_000, Some(500.0)),
+            ],
+            balances: vec![],
+        };
+        let channels = vec![
+            channel("1", "alpha", 1_000_000, true),
+            channel("2", "beta", 500_000, true),
+            channel("3", "gamma", 500_000, false),
+        ];
+        // Mesure : 2 000 sat/jour avant l'exécution sur le canal 1, 4 000 après
+        let executed_at = at("2026-10-12T00:00:00Z");
+        let mut events = vec![];
+        for day in 1..=7 {
+            events.push(forward(
+                executed_at - Duration::days(day) + Duration::hours(1),
+                "2",
+                "1",
+                2_000_000,
+            ));
+        }
+        for day in 0..7 {
+            events.push(forward(
+                executed_at + Duration::days(day) + Duration::hours(1),
+                "2",
+                "1",
+                4_000_000,
+            ));
+        }
+        let rebalances = vec![LocalRebalance {
+            outgoing_channel_id: "2".to_string(),
+            incoming_channel_id: "3".to_string(),
+            amount_msat: 100_000_000,
+            fee_msat: 10_000_000,
+            settled_at_ns: nanos(at("2026-10-15T00:00:00Z")),
+        }];
+        let executions = vec![
+            (
+                recommendation("r1", &["1"]),
+                transition("r1", RecommendationStatus::Executed, executed_at),
+            ),
+            (
+                recommendation("r2", &["3"]),
+                transition("r2", RecommendationStatus::Failed, executed_at),
+            ),
+        ];
+
+        let report = build_report(&ReportInputs {
+            period: ReportPeriod::Weekly,
+            start,
+            end,
+            now: at("2026-10-19T00:30:00Z"),
+            ledger: &ledger,
+            events: &events,
+            rebalances: &rebalances,
+            channels: &channels,
+            executions: &executions,
+            anomalies: &[],
+            pause: None,
+            close_candidates: &[],
+        });
+
+        assert_eq!(report.id, "weekly-2026-10-12");
+        let f = &report.financials;
+        assert_eq!(f.revenue_sat, 90_000);
+        assert_eq!(f.costs_sat, 15_000);
+        assert_eq!(f.net_sat, 75_000);
+        assert_eq!(f.net_fiat, Some(42.0));
+        // Seuls les 7 forwards postérieurs à l'exécution tombent dans la semaine
+        assert_eq!(f.forwards, 7);
+
+        // Canal 1 : moitié des 28 000 sat de frais de la semaine ; canal 3 : moitié du rééquilibrage
+        assert_eq!(report.best_channels[0].channel_id, "1");
+        assert_eq!(report.best_channels[0].revenue_sat, 14_000);
+        assert_eq!(report.worst_channels[0].channel_id, "3");
+        assert_eq!(report.worst_channels[0].net_sat, -5_000);
+
+        let executed = &report.actions[0];
+        assert_eq!(executed.fees_per_day_before_sat, 2_000.0);
+        assert_eq!(executed.fees_per_day_after_sat, Some(4_000.0));
+        assert_eq!(executed.measured_change_pct, Some(100.0));
+        assert_eq!(report.actions[1].fees_per_day_after_sat, None);
+
+        let titles: Vec<&str> = report.risks.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["1 canal(aux) inactif(s)", "1 exécution(s) en échec"]
+        );
+    }
+
+    #[test]
+    fn test_monthly_bounds_and_markdown() {
+        let (start, end) = ReportPeriod::Monthly.last_complete(at("2026-03-01T00:00:00Z"));
+        assert_eq!(
+            (start, end),
+            (at("2026-02-01T00:00:00Z"), at("2026-03-01T00:00:00Z"))
+        );
+        assert_eq!(ReportPeriod::Monthly.report_id(start), "monthly-2026-02");
+
+        let ledger = Ledger {
+            start,
+            end,
+            fiat_currency: "EUR".to_string(),
+            entries: vec![entry(LedgerCategory::RoutingIncome, 3_000_000, None)],
+            balances: vec![],
+        };
+        let report = build_report(&ReportInputs {
+            period: ReportPeriod::Monthly,
+            start,
+            end,
+            now: end,
+            ledger: &ledger,
+            events: &[],
+            rebalances: &[],
+            channels: &[],
+            executions: &[],
+            anomalies: &[],
+            pause: None,
+            close_candidates: &[],
+        });
+        assert_eq!(report.financials.net_fiat, None);
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("# Rapport mensuel du 2026-02-01 au 2026-03-01"));
+        assert!(markdown.contains("| **Net** | **3000 sat** |"));
+        assert!(markdown.contains("Aucune action sur la période."));
+        assert!(markdown.contains("Aucun risque identifié."));
+    }
+}
@@ -0,0 +1,700 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    api::local_lightning_client::LocalChannelInfo,
+    models::{
+        automation::{
+            AutomationActionType, AutomationCondition, AutomationRule, ComparisonOperator,
+            ConditionType, MarketConditions,
+        },
+        recommendation::Recommendation,
+    },
+};
+
+/// Nombre maximal de nouvelles tentatives par action
+pub const MAX_ACTION_RETRIES: u32 = 5;
+/// Délai cumulé maximal avant les actions d'une règle (30 min), inférieur au
+/// délai minimal entre deux déclenchements
+pub const MAX_ACTION_DELAY_SECONDS: u32 = 1_800;
+
+/// Paramètres du moteur de règles.
+#[derive(Debug, Clone)]
+pub struct RuleEngineConfig {
+    /// Part pondérée (0-1) des conditions vérifiées pour déclencher une règle
+    pub match_threshold: f64,
+    /// Délai minimal entre deux déclenchements d'une même règle
+    pub cooldown_minutes: i64,
+    /// Sujets (canaux, recommandations) traités par règle et par passe
+    pub max_firings_per_rule: usize,
+}
+
+impl Default for RuleEngineConfig {
+    fn default() -> Self {
+        Self {
+            match_threshold: 0.75,
+            cooldown_minutes: 60,
+            max_firings_per_rule: 5,
+        }
+    }
+}
+
+/// État du nœud sur lequel les conditions sont résolues. Les mesures coûteuses
+/// restent absentes quand aucune règle ne les demande.
+#[derive(Debug, Clone)]
+pub struct RuleContext {
+    pub now: DateTime<Utc>,
+    /// Canaux ouverts, avec leur politique de frais courante
+    pub channels: Vec<LocalChannelInfo>,
+    /// Score de réputation (0-100) par pair
+    pub peer_scores: HashMap<String, f64>,
+    /// Recommandations en attente ou approuvées
+    pub recommendations: Vec<Recommendation>,
+    pub market_condition: Option<MarketConditions>,
+    /// Revenus de routage des 7 derniers jours
+    pub recent_revenue_sat: Option<u64>,
+}
+
+/// Ce sur quoi porte un déclenchement.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleSubject {
+    Node,
+    Channel {
+        channel_id: String,
+        peer_pubkey: String,
+    },
+    Recommendation {
+        recommendation_id: String,
+        channel_id: Option<String>,
+    },
+}
+
+impl RuleSubject {
+    pub fn channel_id(&self) -> Option<&str> {
+        match self {
+            RuleSubject::Node => None,
+            RuleSubject::Channel { channel_id, .. } => Some(channel_id),
+            RuleSubject::Recommendation { channel_id, .. } => channel_id.as_deref(),
+        }
+    }
+
+    pub fn recommendation_id(&self) -> Option<&str> {
+        match self {
+            RuleSubject::Recommendation {
+                recommendation_id, ..
+            } => Some(recommendation_id),
+            _ => None,
+        }
+    }
+}
+
+/// Valeur observée d'une condition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConditionValue {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionOutcome {
+    pub condition_type: ConditionType,
+    /// Absente quand la mesure n'est pas disponible ; la condition échoue alors
+    pub actual: Option<ConditionValue>,
+    pub matched: bool,
+    pub weight: f64,
+}
+
+/// Évaluation d'une règle pour un sujet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEvaluation {
+    pub subject: RuleSubject,
+    /// Part pondérée des conditions vérifiées (0-1)
+    pub score: f64,
+    pub matched: bool,
+    pub conditions: Vec<ConditionOutcome>,
+}
+
+/// Règle à exécuter pour un sujet donné.
+#[derive(Debug, Clone)]
+pub struct RuleFiring {
+    pub rule: AutomationRule,
+    pub evaluation: RuleEvaluation,
+}
+
+fn is_channel_condition(condition_type: &ConditionType) -> bool {
+    matches!(
+        condition_type,
+        ConditionType::ChannelBalance | ConditionType::FeeRate | ConditionType::PeerReliability
+    )
+}
+
+fn is_text_condition(condition_type: &ConditionType) -> bool {
+    matches!(condition_type, ConditionType::MarketCondition)
+}
+
+/// Vérifie la forme d'une règle avant enregistrement.
+pub fn validate_rule(rule: &AutomationRule) -> Result<(), String> {
+    if rule.name.trim().is_empty() {
+        return Err("rule name is empty".to_string());
+    }
+    if rule.conditions.is_empty() {
+        return Err("rule without condition".to_string());
+    }
+    if rule.actions.is_empty() {
+        return Err("rule without action".to_string());
+    }
+
+    for condition in &rule.conditions {
+        if !(0.0..=1.0).contains(&condition.weight) {
+            return Err(format!(
+                "weight of {:?} must be between 0 and 1",
+                condition.condition_type
+            ));
+        }
+        check_condition_value(condition)?;
+    }
+    if rule.conditions.iter().map(|c| c.weight).sum::<f64>() <= 0.0 {
+        return Err("all condition weights are zero".to_string());
+    }
+
+    let total_delay: u32 = rule
+        .actions
+        .iter()
+        .map(|a| a.delay_seconds.unwrap_or(0))
+        .fold(0, u32::saturating_add);
+    if total_delay > MAX_ACTION_DELAY_SECONDS {
+        return Err(format!(
+            "cumulated delay_seconds above {}",
+            MAX_ACTION_DELAY_SECONDS
+        ));
+    }
+
+    let mut actions: Vec<_> = rule.actions.iter().collect();
+    while let Some(action) = actions.pop() {
+        if action.retry_count > MAX_ACTION_RETRIES {
+            return Err(format!("retry_count above {}", MAX_ACTION_RETRIES));
+        }
+        if action.delay_seconds.unwrap_or(0) > MAX_ACTION_DELAY_SECONDS {
+            return Err(format!("delay_seconds above {}", MAX_ACTION_DELAY_SECONDS));
+        }
+        if !action.parameters.is_object() && !action.parameters.is_null() {
+            return Err(format!(
+                "parameters of {:?} must be an object",
+                action.action_type
+            ));
+        }
+        // Une fermeture forcée bloque les fonds et ne se décide pas sur une règle
+        if matches!(action.action_type, AutomationActionType::CloseChannel)
+            && action.parameters.get("force").and_then(Value::as_bool) == Some(true)
+        {
+            return Err("forced channel close not allowed in rules".to_string());
+        }
+        actions.extend(action.rollback_action.as_deref());
+    }
+    Ok(())
+}
+
+fn check_condition_value(condition: &AutomationCondition) -> Result<(), String> {
+    let text = is_text_condition(&condition.condition_type);
+    let scalar_ok = |value: &Value| {
+        if text {
+            value.is_string()
+        } else {
+            value.is_number()
+        }
+    };
+    let valid = match condition.operator {
+        ComparisonOperator::Equal | ComparisonOperator::NotEqual => scalar_ok(&condition.value),
+        ComparisonOperator::GreaterThan
+        | ComparisonOperator::GreaterThanOrEqual
+        | ComparisonOperator::LessThan
+        | ComparisonOperator::LessThanOrEqual => !text && condition.value.is_number(),
+        ComparisonOperator::Between => {
+            !text
+                && condition
+                    .value
+                    .as_array()
+                    .is_some_and(|bounds| bounds.len() == 2 && bounds.iter().all(Value::is_number))
+        }
+        ComparisonOperator::In | ComparisonOperator::NotIn => condition
+            .value
+            .as_array()
+            .is_some_and(|values| !values.is_empty() && values.iter().all(scalar_ok)),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "value {} does not fit {:?} on {:?}",
+            condition.value, condition.operator, condition.condition_type
+        ))
+    }
+}
+
+/// Applique l'opérateur. `Between [a, b]` est inclusif ; avec `a > b` l'intervalle
+/// fait le tour (heures de 22 à 6 par exemple).
+pub fn compare(operator: &ComparisonOperator, actual: &ConditionValue, expected: &Value) -> bool {
+    let equals = |expected: &Value| match actual {
+        ConditionValue::Number(n) => expected.as_f64().is_some_and(|e| (n - e).abs() < 1e-9),
+        ConditionValue::Text(t) => expected.as_str().is_some_and(|e| t.eq_ignore_ascii_case(e)),
+    };
+    let number = match actual {
+        ConditionValue::Number(n) => Some(*n),
+        ConditionValue::Text(_) => None,
+    };
+    let against = |check: fn(f64, f64) -> bool| {
+        number
+            .zip(expected.as_f64())
+            .is_some_and(|(n, e)| check(n, e))
+    };
+
+    match operator {
+        ComparisonOperator::GreaterThan => against(|n, e| n > e),
+        ComparisonOperator::GreaterThanOrEqual => against(|n, e| n >= e),
+        ComparisonOperator::LessThan => against(|n, e| n < e),
+        ComparisonOperator::LessThanOrEqual => against(|n, e| n <= e),
+        ComparisonOperator::Equal => equals(expected),
+        ComparisonOperator::NotEqual => !equals(expected),
+        ComparisonOperator::Between => {
+            let bounds = expected
+                .as_array()
+                .and_then(|b| Some((b.first()?.as_f64()?, b.get(1)?.as_f64()?)));
+            match (number, bounds) {
+                (Some(n), Some((low, high))) if low <= high => n >= low && n <= high,
+                (Some(n), Some((low, high))) => n >= low || n <= high,
+                _ => false,
+            }
+        }
+        ComparisonOperator::In => expected
+            .as_array()
+            .is_some_and(|values| values.iter().any(equals)),
+        ComparisonOperator::NotIn => expected
+            .as_array()
+            .is_some_and(|values| !values.iter().any(equals)),
+    }
+}
+
+/// Moteur d'évaluation des règles d'automatisation.
+#[derive(Debug, Clone, Default)]
+pub struct RuleEngine {
+    pub config: RuleEngineConfig,
+}
+
+impl RuleEngine {
+    pub fn new(config: RuleEngineConfig) -> Self {
+        Self { config }
+    }
+
+    /// Sujets d'une règle : les recommandations si elle porte sur l'impact ROI,
+    /// les canaux actifs si elle porte sur un canal, sinon le nœud.
+    pub fn subjects(&self, rule: &AutomationRule, context: &RuleContext) -> Vec<RuleSubject> {
+        let types: Vec<&ConditionType> =
+            rule.conditions.iter().map(|c| &c.condition_type).collect();
+        if types.iter().any(|t| matches!(t, ConditionType::ROIImpact)) {
+            context
+                .recommendations
+                .iter()
+                .map(|r| RuleSubject::Recommendation {
+                    recommendation_id: r.id.clone(),
+                    channel_id: r.target_channels.first().cloned(),
+                })
+                .collect()
+        } else if types.iter().any(|t| is_channel_condition(t)) {
+            context
+                .channels
+                .iter()
+                .filter(|c| c.active)
+                .map(|c| RuleSubject::Channel {
+                    channel_id: c.channel_id.clone(),
+                    peer_pubkey: c.peer_pubkey.clone(),
+                })
+                .collect()
+        } else {
+            vec![RuleSubject::Node]
+        }
+    }
+
+    /// Mesure d'une condition pour un sujet. Solde d'un canal : part locale de la
+    /// capacité (0-1) ; frais : ppm ; capacité : somme des canaux ouverts (sat) ;
+    /// performance récente : revenus de routage sur 7 jours (sat).
+    pub fn resolve(
+        &self,
+        condition_type: &ConditionType,
+        subject: &RuleSubject,
+        context: &RuleContext,
+    ) -> Option<ConditionValue> {
+        let channel = subject
+            .channel_id()
+            .and_then(|id| context.channels.iter().find(|c| c.channel_id == id));
+        let number = |n: f64| Some(ConditionValue::Number(n));
+
+        match condition_type {
+            ConditionType::ROIImpact => subject
+                .recommendation_id()
+                .and_then(|id| context.recommendations.iter().find(|r| r.id == id))
+                .and_then(|r| number(r.expected_roi_impact)),
+            ConditionType::ChannelBalance => channel
+                .filter(|c| c.capacity > 0)
+                .and_then(|c| number(c.local_balance as f64 / c.capacity as f64)),
+            ConditionType::FeeRate => channel.and_then(|c| number(c.fee_rate_milli_msat as f64)),
+            ConditionType::PeerReliability => channel
+                .and_then(|c| context.peer_scores.get(&c.peer_pubkey))
+                .and_then(|score| number(*score)),
+            ConditionType::MarketCondition => context
+                .market_condition
+                .as_ref()
+                .map(|m| ConditionValue::Text(format!("{:?}", m))),
+            ConditionType::TimeOfDay => number(context.now.hour() as f64),
+            ConditionType::NetworkCapacity => {
+                number(context.channels.iter().map(|c| c.capacity).sum::<u64>() as f64)
+            }
+            ConditionType::RecentPerformance => context
+                .recent_revenue_sat
+                .and_then(|sat| number(sat as f64)),
+        }
+    }
+
+    pub fn evaluate(
+        &self,
+        rule: &AutomationRule,
+        subject: RuleSubject,
+        context: &RuleContext,
+    ) -> RuleEvaluation {
+        let conditions: Vec<ConditionOutcome> = rule
+            .conditions
+            .iter()
+            .map(|condition| {
+                let actual = self.resolve(&condition.condition_type, &subject, context);
+                ConditionOutcome {
+                    condition_type: condition.condition_type.clone(),
+                    matched: actual
+                        .as_ref()
+                        .is_some_and(|a| compare(&condition.operator, a, &condition.value)),
+                    actual,
+                    weight: condition.weight,
+                }
+            })
+            .collect();
+
+        let total: f64 = conditions.iter().map(|c| c.weight).sum();
+        let score = if total > 0.0 {
+            conditions
+                .iter()
+                .filter(|c| c.matched)
+                .map(|c| c.weight)
+                .sum::<f64>()
+                / total
+        } else {
+            0.0
+        };
+        RuleEvaluation {
+            subject,
+            score,
+            matched: total > 0.0 && score >= self.config.match_threshold - 1e-9,
+            conditions,
+        }
+    }
+
+    /// Déclenchements de la passe : règles actives hors délai de carence, de la
+    /// priorité la plus haute à la plus basse. Un canal ou une recommandation
+    /// retenu par une règle n'est plus proposé aux règles moins prioritaires.
+    pub fn plan(&self, rules: &[AutomationRule], context: &RuleContext) -> Vec<RuleFiring> {
+        let mut ordered: Vec<&AutomationRule> = rules
+            .iter()
+            .filter(|r| r.enabled)
+            .filter(|r| {
+                r.last_triggered.is_none_or(|at| {
+                    context.now - at >= Duration::minutes(self.config.cooldown_minutes)
+                })
+            })
+            .collect();
+        ordered.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+
+        let mut claimed: HashSet<String> = HashSet::new();
+        let mut firings = vec![];
+        for rule in ordered {
+            let matched: Vec<RuleEvaluation> = self
+                .subjects(rule, context)
+                .into_iter()
+                .filter(|subject| !subject_claimed(subject, &claimed))
+                .map(|subject| self.evaluate(rule, subject, context))
+                .filter(|evaluation| evaluation.matched)
+                .take(self.config.max_firings_per_rule)
+                .collect();
+            for evaluation in matched {
+                claimed.extend(subject_keys(&evaluation.subject));
+                firings.push(RuleFiring {
+                    rule: rule.clone(),
+                    evaluation,
+                });
+            }
+        }
+        firings
+    }
+}
+
+fn subject_keys(subject: &RuleSubject) -> Vec<String> {
+    let mut keys = vec![];
+    if let Some(channel_id) = subject.channel_id() {
+        keys.push(format!("channel:{}", channel_id));
+    }
+    if let Some(recommendation_id) = subject.recommendation_id() {
+        keys.push(format!("recommendation:{}", recommendation_id));
+    }
+    keys
+}
+
+fn subject_claimed(subject: &RuleSubject, claimed: &HashSet<String>) -> bool {
+    subject_keys(subject)
+        .iter()
+        .any(|key| claimed.contains(key))
+}
+
+/// Complète les paramètres d'une action avec le sujet du déclenchement : canal visé
+/// par défaut, recommandation à exécuter, portée d'une stratégie de frais.
+pub fn bind_parameters(
+    action_type: &AutomationActionType,
+    parameters: &Value,
+    subject: &RuleSubject,
+) -> Value {
+    let mut bound = match parameters {
+        Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    match action_type {
+        AutomationActionType::ExecuteRecommendation => {
+            if let Some(id) = subject.recommendation_id() {
+                bound
+                    .entry("recommendation_id")
+                    .or_insert_with(|| Value::from(id));
+            }
+        }
+        AutomationActionType::AdjustFees | AutomationActionType::CloseChannel => {
+            if let Some(id) = subject.channel_id() {
+                if !bound.contains_key("channel_point") {
+                    bound.entry("channel_id").or_insert_with(|| Value::from(id));
+                }
+            }
+        }
+        AutomationActionType::UpdateStrategy => {
+            if let Some(id) = subject.channel_id() {
+                bound
+                    .entry("scope")
+                    .or_insert_with(|| serde_json::json!({ "scope": "channel", "channel_id": id }));
+            }
+        }
+        _ => {}
+    }
+    Value::Object(bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::automation::AutomationAction;
+    use crate::test_support;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 19, 23, 0, 0).unwrap()
+    }
+
+    fn channel(id: &str, local: u64, fee_ppm: u64) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_alias: id.to_string(),
+            base_fee_msat: 1_000,
+            fee_rate_milli_msat: fee_ppm,
+            ..test_support::channel(id, 1_000_000, local)
+        }
+    }
+
+    fn condition(
+        condition_type: ConditionType,
+        operator: ComparisonOperator,
+        value: Value,
+        weight: f64,
+    ) -> AutomationCondition {
+        AutomationCondition {
+            condition_type,
+            operator,
+            value,
+            weight,
+        }
+    }
+
+    fn rule(id: &str, priority: u32, conditions: Vec<AutomationCondition>) -> AutomationRule {
+        AutomationRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            conditions,
+            actions: vec![AutomationAction {
+                action_type: AutomationActionType::AdjustFees,
+                parameters: json!({ "fee_rate_ppm": 800 }),
+                delay_seconds: None,
+                retry_count: 1,
+                rollback_action: None,
+            }],
+            enabled: true,
+            priority,
+            created_at: now() - Duration::days(1),
+            last_triggered: None,
+            trigger_count: 0,
+            success_rate: 0.0,
+        }
+    }
+
+    fn context() -> RuleContext {
+        RuleContext {
+            now: now(),
+            channels: vec![channel("1", 100_000, 200), channel("2", 900_000, 50)],
+            peer_scores: HashMap::from([("peer_1".to_string(), 80.0)]),
+            recommendations: vec![],
+            market_condition: Some(MarketConditions::Stable),
+            recent_revenue_sat: None,
+        }
+    }
+
+    #[test]
+    fn test_operators_and_weights() {
+        let n = ConditionValue::Number(23.0);
+        assert!(compare(&ComparisonOperator::Between, &n, &json!([22, 6])));
+        assert!(!compare(&ComparisonOperator::Between, &n, &json!([8, 20])));
+        assert!(compare(&ComparisonOperator::In, &n, &json!([1, 23])));
+        assert!(compare(&ComparisonOperator::NotIn, &n, &json!([1, 2])));
+        let market = ConditionValue::Text("Stable".to_string());
+        assert!(compare(
+            &ComparisonOperator::In,
+            &market,
+            &json!(["bullish", "stable"])
+        ));
+        assert!(!compare(
+            &ComparisonOperator::GreaterThan,
+            &market,
+            &json!(1)
+        ));
+
+        // Solde bas et frais bas vérifiés, réputation et heure non : 0,75 de 1,0
+        let weighted = rule(
+            "weighted",
+            1,
+            vec![
+                condition(
+                    ConditionType::ChannelBalance,
+                    ComparisonOperator::LessThan,
+                    json!(0.2),
+                    0.5,
+                ),
+                condition(
+                    ConditionType::FeeRate,
+                    ComparisonOperator::LessThanOrEqual,
+                    json!(200),
+                    0.25,
+                ),
+                condition(
+                    ConditionType::PeerReliability,
+                    ComparisonOperator::GreaterThan,
+                    json!(90),
+                    0.15,
+                ),
+                condition(
+                    ConditionType::TimeOfDay,
+                    ComparisonOperator::Between,
+                    json!([8, 20]),
+                    0.1,
+                ),
+            ],
+        );
+        assert!(validate_rule(&weighted).is_ok());
+        let engine = RuleEngine::default();
+        let evaluation = engine.evaluate(
+            &weighted,
+            RuleSubject::Channel {
+                channel_id: "1".to_string(),
+                peer_pubkey: "peer_1".to_string(),
+            },
+            &context(),
+        );
+        assert!((evaluation.score - 0.75).abs() < 1e-9);
+        assert!(evaluation.matched);
+        // Réputation inconnue pour le canal 2 et solde élevé : pas de déclenchement
+        assert!(engine.plan(&[weighted], &context()).len() == 1);
+
+        let mut invalid = rule(
+            "invalid",
+            1,
+            vec![condition(
+                ConditionType::MarketCondition,
+                ComparisonOperator::GreaterThan,
+                json!("Stable"),
+                1.0,
+            )],
+        );
+        assert!(validate_rule(&invalid).is_err());
+        invalid.conditions[0].operator = ComparisonOperator::Equal;
+        invalid.actions[0].retry_count = MAX_ACTION_RETRIES + 1;
+        assert!(validate_rule(&invalid).is_err());
+        invalid.actions[0].retry_count = 0;
+        assert!(validate_rule(&invalid).is_ok());
+        let mut force_close = invalid.clone();
+        force_close.actions[0].action_type = AutomationActionType::CloseChannel;
+        force_close.actions[0].parameters = json!({ "channel_id": "1", "force": true });
+        assert!(validate_rule(&force_close).is_err());
+        force_close.actions[0].parameters = json!({ "channel_id": "1" });
+        assert!(validate_rule(&force_close).is_ok());
+        // Le délai se cumule sur les actions de la règle
+        invalid.actions[0].delay_seconds = Some(MAX_ACTION_DELAY_SECONDS);
+        invalid.actions.push(invalid.actions[0].clone());
+        assert!(validate_rule(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_plan_orders_by_priority_and_claims_subjects() {
+        let low_balance = || {
+            vec![condition(
+                ConditionType::ChannelBalance,
+                ComparisonOperator::LessThan,
+                json!(0.5),
+                1.0,
+            )]
+        };
+        let minor = rule("minor", 1, low_balance());
+        let major = rule("major", 10, low_balance());
+        let mut cooling = rule("cooling", 20, low_balance());
+        cooling.last_triggered = Some(now() - Duration::minutes(10));
+        let night = rule(
+            "night",
+            5,
+            vec![condition(
+                ConditionType::TimeOfDay,
+                ComparisonOperator::Between,
+                json!([22, 6]),
+                1.0,
+            )],
+        );
+
+        let firings = RuleEngine::default().plan(&[minor, major, cooling, night], &context());
+        let fired: Vec<(&str, Option<&str>)> = firings
+            .iter()
+            .map(|f| (f.rule.id.as_str(), f.evaluation.subject.channel_id()))
+            .collect();
+        // Le canal 1 revient à la règle prioritaire ; « cooling » est en carence
+        assert_eq!(fired, vec![("major", Some("1")), ("night", None)]);
+
+        let bound = bind_parameters(
+            &AutomationActionType::AdjustFees,
+            &json!({ "fee_rate_ppm": 800 }),
+            &firings[0].evaluation.subject,
+        );
+        assert_eq!(bound, json!({ "fee_rate_ppm": 800, "channel_id": "1" }));
+    }
+}
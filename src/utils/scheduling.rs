@@ -0,0 +1,801 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use std::collections::BTreeMap;
+
+use crate::{
+    api::{
+        local_lightning_client::{LocalChannelInfo, LocalForwardingEvent},
+        umbrel_integrations::FeeEstimates,
+    },
+    models::{
+        action_params::ActionParameters,
+        automation::{
+            CongestionPrediction, ExecutionWindow, MarketConditionAnalysis, MarketConditions,
+            MarketRecommendation, NetworkCongestionForecast, OptimalExecutionTime,
+            PeerActivityPattern, RiskLevel, SmartScheduling,
+        },
+        ml::{OptimalWindow, SmartRecommendation},
+    },
+    storage::forwarding::{ChannelHistoryPoint, FeeEstimateSample, SnapshotResolution},
+    utils::onchain::{COOP_CLOSE_VBYTES, FORCE_CLOSE_VBYTES, OPEN_CHANNEL_VBYTES},
+};
+
+/// Données observées sur lesquelles repose la planification.
+#[derive(Debug, Clone)]
+pub struct SchedulingInputs {
+    pub now: DateTime<Utc>,
+    /// Estimation courante du mempool ; à défaut, le dernier relevé est utilisé
+    pub current_fees: Option<FeeEstimates>,
+    pub fee_samples: Vec<FeeEstimateSample>,
+    pub events: Vec<LocalForwardingEvent>,
+    /// Nombre de jours couverts par `events`
+    pub history_days: u32,
+    pub snapshots: Vec<ChannelHistoryPoint>,
+    pub channels: Vec<LocalChannelInfo>,
+}
+
+/// Paramètres du planificateur.
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Horizon de recherche d'une fenêtre, en heures
+    pub horizon_hours: u32,
+    pub window_hours: u32,
+    /// Disponibilité minimale du pair pour une action qui requiert sa présence
+    pub min_peer_uptime: f64,
+    /// Hausse/baisse horaire (sat/vB) au-delà de laquelle la tendance est significative
+    pub trend_threshold: f64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            horizon_hours: 48,
+            window_hours: 2,
+            min_peer_uptime: 0.8,
+            trend_threshold: 0.5,
+        }
+    }
+}
+
+/// Profil des frais du mempool : moyenne par heure UTC et tendance récente.
+#[derive(Debug, Clone)]
+struct FeeProfile {
+    current: Option<f64>,
+    hourly: [Option<f64>; 24],
+    hourly_samples: [usize; 24],
+    median: Option<f64>,
+    /// Pente sur les dernières 24 h, en sat/vB par heure
+    trend_per_hour: f64,
+    variation: f64,
+    samples: usize,
+}
+
+impl FeeProfile {
+    fn build(inputs: &SchedulingInputs) -> Self {
+        let mut sums = [0.0; 24];
+        let mut counts = [0usize; 24];
+        for sample in &inputs.fee_samples {
+            let hour = sample.observed_at.hour() as usize;
+            sums[hour] += sample.half_hour as f64;
+            counts[hour] += 1;
+        }
+        let mut hourly = [None; 24];
+        for hour in 0..24 {
+            if counts[hour] > 0 {
+                hourly[hour] = Some(sums[hour] / counts[hour] as f64);
+            }
+        }
+
+        let mut rates: Vec<f64> = inputs
+            .fee_samples
+            .iter()
+            .map(|s| s.half_hour as f64)
+            .collect();
+        rates.sort_by(f64::total_cmp);
+        let median = rates.get(rates.len() / 2).copied();
+        let mean = rates.iter().sum::<f64>() / rates.len().max(1) as f64;
+        let variance =
+            rates.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / rates.len().max(1) as f64;
+
+        // Régression linéaire sur les dernières 24 h
+        let recent: Vec<(f64, f64)> = inputs
+            .fee_samples
+            .iter()
+            .filter(|s| s.observed_at > inputs.now - Duration::hours(24))
+            .map(|s| {
+                (
+                    (s.observed_at - inputs.now).num_minutes() as f64 / 60.0,
+                    s.half_hour as f64,
+                )
+            })
+            .collect();
+        let trend_per_hour = if recent.len() >= 2 {
+            let n = recent.len() as f64;
+            let mean_x = recent.iter().map(|(x, _)| x).sum::<f64>() / n;
+            let mean_y = recent.iter().map(|(_, y)| y).sum::<f64>() / n;
+            let sxx: f64 = recent.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+            let sxy: f64 = recent
+                .iter()
+                .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                .sum();
+            if sxx > 0.0 {
+                sxy / sxx
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        };
+
+        let current = inputs
+            .current_fees
+            .as_ref()
+            .map(|f| f.half_hour as f64)
+            .or_else(|| inputs.fee_samples.last().map(|s| s.half_hour as f64));
+
+        Self {
+            current,
+            hourly,
+            hourly_samples: counts,
+            median,
+            trend_per_hour,
+            variation: if mean > 0.0 {
+                variance.sqrt() / mean
+            } else {
+                0.0
+            },
+            samples: rates.len(),
+        }
+    }
+
+    /// Taux attendu dans `hours_ahead` heures : la tendance domine à court terme,
+    /// le profil horaire à plus longue échéance.
+    fn forecast(&self, at: DateTime<Utc>, hours_ahead: f64) -> Option<f64> {
+        let seasonal = self.hourly[at.hour() as usize];
+        let extrapolated = self
+            .current
+            .map(|current| (current + self.trend_per_hour * hours_ahead).max(1.0));
+        match (extrapolated, seasonal) {
+            (Some(trend), Some(seasonal)) => {
+                let weight = (-hours_ahead / 6.0).exp();
+                Some(weight * trend + (1.0 - weight) * seasonal)
+            }
+            (Some(trend), None) => Some(trend),
+            (None, seasonal) => seasonal,
+        }
+    }
+}
+
+/// Volume et revenu de forwarding moyens par créneau (jour de semaine × heure UTC).
+#[derive(Debug, Clone)]
+struct VolumeProfile {
+    revenue_msat: [[f64; 24]; 7],
+    forwards: [[f64; 24]; 7],
+}
+
+impl VolumeProfile {
+    fn build(inputs: &SchedulingInputs, channel_ids: &[String]) -> Self {
+        let weeks = (inputs.history_days as f64 / 7.0).max(1.0);
+        let mut profile = Self {
+            revenue_msat: [[0.0; 24]; 7],
+            forwards: [[0.0; 24]; 7],
+        };
+        for event in &inputs.events {
+            if !channel_ids.is_empty()
+                && !channel_ids
+                    .iter()
+                    .any(|id| *id == event.chan_id_out || *id == event.chan_id_in)
+            {
+                continue;
+            }
+            let at = Utc.timestamp_nanos(event.timestamp_ns as i64);
+            let (day, hour) = slot(at);
+            profile.revenue_msat[day][hour] += event.fee_msat as f64 / weeks;
+            profile.forwards[day][hour] += 1.0 / weeks;
+        }
+        profile
+    }
+
+    fn revenue_sat_between(&self, start: DateTime<Utc>, hours: u32) -> f64 {
+        (0..hours)
+            .map(|h| {
+                let (day, hour) = slot(start + Duration::hours(h as i64));
+                self.revenue_msat[day][hour] / 1000.0
+            })
+            .sum()
+    }
+
+    fn forwards_between(&self, start: DateTime<Utc>, hours: u32) -> f64 {
+        (0..hours)
+            .map(|h| {
+                let (day, hour) = slot(start + Duration::hours(h as i64));
+                self.forwards[day][hour]
+            })
+            .sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.forwards.iter().flatten().all(|f| *f == 0.0)
+    }
+}
+
+/// Disponibilité d'un pair par heure UTC, d'après les instantanés de canaux.
+fn peer_uptime(snapshots: &[ChannelHistoryPoint]) -> BTreeMap<String, [Option<f64>; 24]> {
+    let mut counts: BTreeMap<String, [(u32, u32); 24]> = BTreeMap::new();
+    // Un agrégat journalier ne dit rien de l'heure ; un agrégat horaire pèse ses relevés
+    for snapshot in snapshots
+        .iter()
+        .filter(|s| s.resolution != SnapshotResolution::Day)
+    {
+        let hour = snapshot.taken_at.hour() as usize;
+        let entry = counts
+            .entry(snapshot.channel.peer_pubkey.clone())
+            .or_insert([(0, 0); 24]);
+        entry[hour].1 += snapshot.samples;
+        entry[hour].0 += snapshot.active_samples.min(snapshot.samples);
+    }
+    counts
+        .into_iter()
+        .map(|(peer, hours)| {
+            let mut uptime = [None; 24];
+            for (hour, (active, total)) in hours.iter().enumerate() {
+                if *total > 0 {
+                    uptime[hour] = Some(*active as f64 / *total as f64);
+                }
+            }
+            (peer, uptime)
+        })
+        .collect()
+}
+
+fn slot(at: DateTime<Utc>) -> (usize, usize) {
+    (
+        at.weekday().num_days_from_monday() as usize,
+        at.hour() as usize,
+    )
+}
+
+/// Coût d'une exécution démarrant à l'instant donné, `f64` heures plus tard.
+type CostFn = Box<dyn Fn(DateTime<Utc>, f64) -> Option<f64>>;
+
+/// Candidat évalué par le planificateur.
+#[derive(Debug, Clone)]
+struct Candidate {
+    start: DateTime<Utc>,
+    /// Coût attendu de l'exécution dans cette fenêtre (sat)
+    cost_sat: f64,
+    peer_uptime: Option<f64>,
+}
+
+/// Planificateur des exécutions : frais du mempool, rythme des forwards, présence des pairs.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionScheduler {
+    pub config: SchedulerConfig,
+}
+
+impl ExecutionScheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Fenêtre d'exécution optimale pour une recommandation.
+    pub fn plan(
+        &self,
+        recommendation: &SmartRecommendation,
+        inputs: &SchedulingInputs,
+    ) -> OptimalWindow {
+        let fees = FeeProfile::build(inputs);
+        let uptime = peer_uptime(&inputs.snapshots);
+        let action =
+            ActionParameters::parse(recommendation.action_type, &recommendation.parameters);
+        let peer_of = |channel_id: &str| {
+            inputs
+                .channels
+                .iter()
+                .find(|c| c.channel_id == channel_id)
+                .map(|c| c.peer_pubkey.clone())
+        };
+
+        let mut reasons = Vec::new();
+        let mut coverage = 0.0;
+        let (peers, cost_at): (Vec<String>, CostFn) = match &action {
+            Ok(ActionParameters::OpenChannel(params)) => {
+                coverage = (fees.samples as f64 / 96.0).min(1.0);
+                let fees = fees.clone();
+                (
+                    vec![params.peer_pubkey.clone()],
+                    Box::new(move |at, ahead| {
+                        fees.forecast(at, ahead)
+                            .map(|rate| rate * OPEN_CHANNEL_VBYTES as f64)
+                    }),
+                )
+            }
+            Ok(ActionParameters::CloseChannel(params)) => {
+                coverage = (fees.samples as f64 / 96.0).min(1.0);
+                let vbytes = if params.force {
+                    FORCE_CLOSE_VBYTES
+                } else {
+                    COOP_CLOSE_VBYTES
+                };
+                // Une fermeture forcée ne dépend pas de la présence du pair
+                let peers = match (&params.channel_id, params.force) {
+                    (Some(id), false) => peer_of(id).into_iter().collect(),
+                    _ => vec![],
+                };
+                let fees = fees.clone();
+                (
+                    peers,
+                    Box::new(move |at, ahead| {
+                        fees.forecast(at, ahead).map(|rate| rate * vbytes as f64)
+                    }),
+                )
+            }
+            Ok(ActionParameters::AdjustFees(params)) => {
+                let channels: Vec<String> = params.channel_id.iter().cloned().collect();
+                let mut volume = VolumeProfile::build(inputs, &channels);
+                if volume.is_empty() {
+                    volume = VolumeProfile::build(inputs, &[]);
+                }
+                coverage = (inputs.history_days as f64 / 28.0).min(1.0)
+                    * if volume.is_empty() { 0.0 } else { 1.0 };
+                reasons.push(
+                        "Changement de frais placé au creux de trafic : moins de forwards exposés pendant la propagation gossip"
+                            .to_string(),
+                    );
+                let hours = self.config.window_hours;
+                (
+                    vec![],
+                    Box::new(move |at, _| {
+                        (!volume.is_empty()).then(|| volume.revenue_sat_between(at, hours))
+                    }),
+                )
+            }
+            Ok(ActionParameters::RebalanceChannel(params)) => {
+                let channels = vec![
+                    params.outgoing_channel_id.clone(),
+                    params.incoming_channel_id.clone(),
+                ];
+                let peers = channels.iter().filter_map(|id| peer_of(id)).collect();
+                let volume = VolumeProfile::build(inputs, &channels);
+                coverage = (inputs.snapshots.len() as f64 / 96.0).min(1.0);
+                reasons.push(
+                        "Rééquilibrage placé quand les pairs sont joignables et la liquidité peu sollicitée"
+                            .to_string(),
+                    );
+                let hours = self.config.window_hours;
+                (
+                    peers,
+                    Box::new(move |at, _| Some(volume.forwards_between(at, hours))),
+                )
+            }
+            Err(e) => {
+                reasons.push(format!("Paramètres non planifiables: {}", e));
+                (vec![], Box::new(|_, _| None))
+            }
+        };
+
+        let candidates: Vec<Candidate> = (0..=self.config.horizon_hours)
+            .filter_map(|k| {
+                let start = if k == 0 {
+                    inputs.now
+                } else {
+                    inputs
+                        .now
+                        .with_minute(0)
+                        .and_then(|t| t.with_second(0))
+                        .and_then(|t| t.with_nanosecond(0))
+                        .unwrap_or(inputs.now)
+                        + Duration::hours(k as i64)
+                };
+                let ahead = (start - inputs.now).num_minutes() as f64 / 60.0;
+                let cost_sat = cost_at(start, ahead)?;
+                let peer_uptime = peers
+                    .iter()
+                    .filter_map(|peer| uptime.get(peer).and_then(|u| u[start.hour() as usize]))
+                    .reduce(f64::min);
+                Some(Candidate {
+                    start,
+                    cost_sat,
+                    peer_uptime,
+                })
+            })
+            .collect();
+
+        let now_cost = candidates.first().map(|c| c.cost_sat);
+        let eligible: Vec<&Candidate> = candidates
+            .iter()
+            .filter(|c| {
+                c.peer_uptime
+                    .is_none_or(|u| u >= self.config.min_peer_uptime)
+            })
+            .collect();
+        let best = eligible
+            .iter()
+            .min_by(|a, b| {
+                a.cost_sat
+                    .total_cmp(&b.cost_sat)
+                    .then(
+                        b.peer_uptime
+                            .unwrap_or(1.0)
+                            .total_cmp(&a.peer_uptime.unwrap_or(1.0)),
+                    )
+                    .then(a.start.cmp(&b.start))
+            })
+            .copied()
+            .or_else(|| candidates.first());
+
+        let Some(best) = best else {
+            reasons
+                .push("Données insuffisantes : exécution immédiate sans optimisation".to_string());
+            return OptimalWindow {
+                window: format_window(inputs.now, self.config.window_hours),
+                confidence: 0.0,
+                reasons,
+                start: inputs.now,
+                end: inputs.now + Duration::hours(self.config.window_hours as i64),
+                expected_cost_sat: 0,
+                expected_saving_sat: 0,
+            };
+        };
+
+        let is_onchain = matches!(
+            action,
+            Ok(ActionParameters::OpenChannel(_)) | Ok(ActionParameters::CloseChannel(_))
+        );
+        let is_rebalance = matches!(action, Ok(ActionParameters::RebalanceChannel(_)));
+        let saving = now_cost.map_or(0.0, |now| (now - best.cost_sat).max(0.0));
+
+        if is_onchain {
+            if let Some(current) = fees.current {
+                reasons.push(format!(
+                    "Mempool à {:.0} sat/vB, tendance {:+.2} sat/vB/h",
+                    current, fees.trend_per_hour
+                ));
+            }
+            if fees.samples == 0 {
+                reasons.push(
+                    "Aucun relevé de frais historique : seule l'estimation courante est utilisée"
+                        .to_string(),
+                );
+            }
+        }
+        match best.peer_uptime {
+            Some(uptime) => reasons.push(format!(
+                "Disponibilité observée du pair à {}h UTC: {:.0}%",
+                best.start.hour(),
+                uptime * 100.0
+            )),
+            None if !peers.is_empty() => reasons
+                .push("Disponibilité du pair inconnue (aucun instantané de canal)".to_string()),
+            None => {}
+        }
+        if eligible.len() < candidates.len() {
+            reasons.push(format!(
+                "{} créneau(x) écarté(s) : pair trop souvent hors ligne",
+                candidates.len() - eligible.len()
+            ));
+        }
+
+        let confidence = (40.0 + 55.0 * coverage) * best.peer_uptime.unwrap_or(1.0);
+        OptimalWindow {
+            window: format_window(best.start, self.config.window_hours),
+            confidence: confidence.clamp(0.0, 95.0),
+            reasons,
+            start: best.start,
+            end: best.start + Duration::hours(self.config.window_hours as i64),
+            // Pour un rééquilibrage, le coût comparé est un nombre de forwards, pas des sats
+            expected_cost_sat: if is_rebalance {
+                0
+            } else {
+                best.cost_sat.round() as u64
+            },
+            expected_saving_sat: if is_rebalance {
+                0
+            } else {
+                saving.round() as u64
+            },
+        }
+    }
+
+    /// Vue d'ensemble de la planification : créneaux, conditions de marché, pairs, congestion.
+    pub fn smart_scheduling(&self, inputs: &SchedulingInputs) -> SmartScheduling {
+        let fees = FeeProfile::build(inputs);
+        let volume = VolumeProfile::build(inputs, &[]);
+        let uptime = peer_uptime(&inputs.snapshots);
+        let capacity: u64 = inputs.channels.iter().map(|c| c.capacity).sum();
+
+        let node_uptime = |hour: usize| {
+            let values: Vec<f64> = uptime.values().filter_map(|u| u[hour]).collect();
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let open_cost = |hour: usize| {
+            fees.hourly[hour]
+                .or(fees.current)
+                .map(|rate| (rate * OPEN_CHANNEL_VBYTES as f64).round() as u64)
+        };
+
+        let mut optimal_execution_times: Vec<OptimalExecutionTime> = (0..7)
+            .flat_map(|day| (0..24).map(move |hour| (day, hour)))
+            .filter_map(|(day, hour)| {
+                Some(OptimalExecutionTime {
+                    hour: hour as u32,
+                    day_of_week: day as u32,
+                    // Aucune indisponibilité observée faute d'instantanés
+                    success_probability: node_uptime(hour).unwrap_or(1.0),
+                    average_cost: open_cost(hour)?,
+                    // Revenu de forwarding du créneau, annualisé sur la capacité totale
+                    expected_roi: if capacity == 0 {
+                        0.0
+                    } else {
+                        volume.revenue_msat[day][hour] / 1000.0 * 52.0 / capacity as f64 * 100.0
+                    },
+                })
+            })
+            .collect();
+        optimal_execution_times.sort_by(|a, b| {
+            a.average_cost
+                .cmp(&b.average_cost)
+                .then(b.success_probability.total_cmp(&a.success_probability))
+                .then(a.expected_roi.total_cmp(&b.expected_roi))
+        });
+        optimal_execution_times.truncate(5);
+
+        // Fenêtres on-chain sur les prochaines 24 h
+        let forecasts: Vec<(DateTime<Utc>, f64)> = (1..=24)
+            .filter_map(|h| {
+                let at = inputs.now + Duration::hours(h);
+                fees.forecast(at, h as f64).map(|rate| (at, rate))
+            })
+            .collect();
+        let ratio = |rate: f64| {
+            fees.median
+                .map(|median| (rate / (2.0 * median.max(1.0))).clamp(0.0, 1.0))
+        };
+        let predicted_congestion_24h = forecasts
+            .iter()
+            .map(|(at, rate)| CongestionPrediction {
+                hour: at.hour(),
+                congestion_level: ratio(*rate).unwrap_or(0.5),
+                confidence: (fees.hourly_samples[at.hour() as usize] as f64 / 7.0).min(1.0),
+            })
+            .collect();
+
+        let mut windows: Vec<ExecutionWindow> = forecasts
+            .iter()
+            .map(|(at, rate)| ExecutionWindow {
+                start_hour: at.hour(),
+                end_hour: (at.hour() + self.config.window_hours) % 24,
+                expected_success_rate: node_uptime(at.hour() as usize).unwrap_or(1.0),
+                expected_cost_savings: fees
+                    .current
+                    .map_or(0.0, |current| (current - rate) * OPEN_CHANNEL_VBYTES as f64),
+            })
+            .collect();
+        windows.sort_by(|a, b| b.expected_cost_savings.total_cmp(&a.expected_cost_savings));
+        windows.truncate(3);
+
+        let cheapest = forecasts.iter().min_by(|a, b| a.1.total_cmp(&b.1)).copied();
+        let favorable_conditions_eta = match (cheapest, fees.current) {
+            (Some((at, rate)), Some(current)) if rate < current * 0.9 => Some(at),
+            _ => None,
+        };
+
+        let current_conditions = if fees.samples < 4 {
+            MarketConditions::Uncertain
+        } else if fees.variation > 0.35 {
+            MarketConditions::Volatile
+        } else if fees.trend_per_hour > self.config.trend_threshold {
+            MarketConditions::Bearish
+        } else if fees.trend_per_hour < -self.config.trend_threshold {
+            MarketConditions::Bullish
+        } else {
+            MarketConditions::Stable
+        };
+        let pressure = match (fees.current, fees.median) {
+            (Some(current), Some(median)) => Some(current / median.max(1.0)),
+            _ => None,
+        };
+        let risk_level = match pressure {
+            None => RiskLevel::Medium,
+            Some(p) if p < 0.8 => RiskLevel::VeryLow,
+            Some(p) if p < 1.0 => RiskLevel::Low,
+            Some(p) if p < 1.3 => RiskLevel::Medium,
+            Some(p) if p < 2.0 => RiskLevel::High,
+            Some(_) => RiskLevel::VeryHigh,
+        };
+        let recommendation = if pressure.is_some_and(|p| p >= 2.0) {
+            MarketRecommendation::PostponeExecution
+        } else if favorable_conditions_eta.is_some() {
+            MarketRecommendation::WaitForBetterConditions
+        } else if matches!(
+            current_conditions,
+            MarketConditions::Uncertain | MarketConditions::Volatile
+        ) {
+            MarketRecommendation::ExecuteWithCaution
+        } else {
+            MarketRecommendation::ExecuteNow
+        };
+
+        let peer_activity_patterns = uptime
+            .iter()
+            .map(|(peer, hours)| {
+                let channel_ids: Vec<String> = inputs
+                    .channels
+                    .iter()
+                    .filter(|c| &c.peer_pubkey == peer)
+                    .map(|c| c.channel_id.clone())
+                    .collect();
+                let peer_volume = VolumeProfile::build(inputs, &channel_ids);
+                let mut by_hour: Vec<(u32, f64)> = (0..24)
+                    .map(|hour| {
+                        let forwards: f64 = (0..7).map(|day| peer_volume.forwards[day][hour]).sum();
+                        (hour as u32, forwards)
+                    })
+                    .filter(|(_, forwards)| *forwards > 0.0 && !channel_ids.is_empty())
+                    .collect();
+                by_hour.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+                let observed: Vec<f64> = hours.iter().flatten().copied().collect();
+                PeerActivityPattern {
+                    peer_pubkey: peer.clone(),
+                    peak_activity_hours: by_hour.iter().take(3).map(|(hour, _)| *hour).collect(),
+                    success_rate_by_hour: hours.iter().map(|u| u.unwrap_or(0.0)).collect(),
+                    // Latence non mesurée par les instantanés LND
+                    average_response_time: 0.0,
+                    reliability_score: observed.iter().sum::<f64>() / observed.len().max(1) as f64,
+                }
+            })
+            .collect();
+
+        SmartScheduling {
+            optimal_execution_times,
+            market_condition_analysis: MarketConditionAnalysis {
+                current_conditions,
+                favorable_conditions_eta,
+                risk_level,
+                recommendation,
+            },
+            peer_activity_patterns,
+            network_congestion_forecast: NetworkCongestionForecast {
+                current_congestion_level: fees.current.and_then(ratio).unwrap_or(0.5),
+                predicted_congestion_24h,
+                optimal_execution_windows: windows,
+            },
+        }
+    }
+}
+
+fn format_window(start: DateTime<Utc>, hours: u32) -> String {
+    format!(
+        "{} → {} (UTC)",
+        start.format("%Y-%m-%d %H:%M"),
+        (start + Duration::hours(hours as i64)).format("%H:%M")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::mcp_client::{ActionType, Priority};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 6, 10, 0, 0).unwrap()
+    }
+
+    fn recommendation(
+        action_type: ActionType,
+        parameters: serde_json::Value,
+    ) -> SmartRecommendation {
+        SmartRecommendation {
+            id: "rec".to_string(),
+            action_type,
+            priority: Priority::Medium,
+            expected_roi_impact: 1.0,
+            confidence: 0.8,
+            risk_score: 0.2,
+            rationale: vec![],
+            target_channels: vec![],
+            parameters,
+        }
+    }
+
+    fn inputs() -> SchedulingInputs {
+        SchedulingInputs {
+            now: now(),
+            current_fees: None,
+            fee_samples: vec![],
+            events: vec![],
+            history_days: 28,
+            snapshots: vec![],
+            channels: vec![],
+        }
+    }
+
+    /// Frais à 40 sat/vB en journée, 5 sat/vB la nuit (0h–5h UTC), sur une semaine.
+    fn daily_fee_cycle() -> Vec<FeeEstimateSample> {
+        (0..7 * 24)
+            .map(|h| {
+                let observed_at = now() - Duration::hours(7 * 24 - h);
+                let rate = if observed_at.hour() < 6 { 5 } else { 40 };
+                FeeEstimateSample {
+                    observed_at,
+                    fastest: rate,
+                    half_hour: rate,
+                    hour: rate,
+                    economy: rate,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_open_channel_waits_for_cheap_night_fees() {
+        let mut inputs = inputs();
+        inputs.fee_samples = daily_fee_cycle();
+        let window = ExecutionScheduler::default().plan(
+            &recommendation(
+                ActionType::OpenChannel,
+                serde_json::json!({"peer_pubkey": "02aa", "amount_sat": 1_000_000}),
+            ),
+            &inputs,
+        );
+
+        assert!(window.start.hour() < 6);
+        assert!(window.start > inputs.now);
+        assert!(window.expected_saving_sat > 0);
+        assert_eq!(window.end - window.start, Duration::hours(2));
+    }
+
+    #[test]
+    fn test_fee_change_targets_low_traffic_hours() {
+        let mut inputs = inputs();
+        // Trafic uniquement entre 12h et 20h UTC
+        inputs.events = (0..28 * 24)
+            .filter_map(|h| {
+                let at = now() - Duration::hours(h);
+                (12..20).contains(&at.hour()).then(|| LocalForwardingEvent {
+                    timestamp_ns: at.timestamp_nanos_opt().unwrap() as u64,
+                    chan_id_in: "2".to_string(),
+                    chan_id_out: "1".to_string(),
+                    amt_in_msat: 100_000_000,
+                    amt_out_msat: 100_000_000,
+                    fee_msat: 10_000,
+                })
+            })
+            .collect();
+        let window = ExecutionScheduler::default().plan(
+            &recommendation(
+                ActionType::AdjustFees,
+                serde_json::json!({"channel_id": "1", "fee_rate_ppm": 200}),
+            ),
+            &inputs,
+        );
+
+        // Créneau de 2 h entièrement hors des heures de trafic
+        assert!(!(11..20).contains(&window.start.hour()));
+        assert_eq!(window.expected_cost_sat, 0);
+    }
+
+    #[test]
+    fn test_smart_scheduling_prefers_cheapest_hours() {
+        let mut inputs = inputs();
+        inputs.fee_samples = daily_fee_cycle();
+        let scheduling = ExecutionScheduler::default().smart_scheduling(&inputs);
+
+        assert_eq!(scheduling.optimal_execution_times.len(), 5);
+        assert!(scheduling
+            .optimal_execution_times
+            .iter()
+            .all(|t| t.hour < 6 && t.average_cost == 5 * OPEN_CHANNEL_VBYTES));
+        assert!(scheduling
+            .market_condition_analysis
+            .favorable_conditions_eta
+            .is_some());
+        assert_eq!(
+            scheduling
+                .network_congestion_forecast
+                .predicted_congestion_24h
+                .len(),
+            24
+        );
+    }
+}
@@ -0,0 +1,747 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tokio::sync::Mutex;
+
+use crate::{
+    api::local_lightning_client::{
+        LocalChannelInfo, LocalClosedChannel, LocalForwardingEvent, LocalLightningClient,
+        LocalNetworkGraph,
+    },
+    models::analytics::{
+        CloseInitiator, PeerSelection, RiskScenario, StressEvent, StressImpact, StressScenario,
+        StressTestResult,
+    },
+    storage::forwarding::{FeeEstimateSample, ForwardingStore},
+    utils::onchain::{
+        BLOCKS_PER_DAY, COOP_CLOSE_VBYTES, DEFAULT_ONCHAIN_FEE_RATE, FORCE_CLOSE_VBYTES,
+    },
+};
+
+/// Hypothèses du moteur de scénarios et paramètres des scénarios prédéfinis.
+#[derive(Debug, Clone)]
+pub struct StressTestConfig {
+    /// Historique de forwarding servant de base aux revenus perdus
+    pub history_days: u32,
+    pub force_close_csv_blocks: u32,
+    /// Délai de redéploiement du capital d'un canal fermé, pendant lequel ses revenus sont perdus
+    pub recovery_days: u32,
+    /// Échéance maximale des HTLC en attente : au-delà, LND ferme de force le canal d'un pair absent
+    pub pending_htlc_deadline_blocks: u32,
+    pub preset_fee_rate_sat_vb: f64,
+    pub preset_offline_days: u32,
+    pub preset_top_peers: usize,
+}
+
+impl Default for StressTestConfig {
+    fn default() -> Self {
+        Self {
+            history_days: 30,
+            force_close_csv_blocks: 144,
+            recovery_days: 30,
+            pending_htlc_deadline_blocks: 144,
+            preset_fee_rate_sat_vb: 200.0,
+            preset_offline_days: 7,
+            preset_top_peers: 3,
+        }
+    }
+}
+
+/// État du nœud sur lequel les scénarios sont rejoués.
+pub struct StressInputs<'a> {
+    pub own_pubkey: &'a str,
+    pub channels: &'a [LocalChannelInfo],
+    pub events: &'a [LocalForwardingEvent],
+    pub closed_channels: &'a [LocalClosedChannel],
+    pub graph: &'a LocalNetworkGraph,
+    pub onchain_fee_rate: f64,
+    pub fee_estimates: &'a [FeeEstimateSample],
+}
+
+/// Sort d'un canal dans un scénario, du moins grave au plus grave.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChannelFate {
+    Offline { days: u32 },
+    Closed(CloseInitiator),
+}
+
+impl ChannelFate {
+    fn severity(self) -> u8 {
+        match self {
+            Self::Offline { .. } => 0,
+            Self::Closed(CloseInitiator::Remote) => 1,
+            Self::Closed(CloseInitiator::Local) => 2,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Offline { days: a }, Self::Offline { days: b }) => {
+                Self::Offline { days: a.max(b) }
+            }
+            _ if other.severity() > self.severity() => other,
+            _ => self,
+        }
+    }
+}
+
+/// Scénarios de stress : fermetures forcées, pics de frais on-chain, pairs hors ligne.
+#[derive(Debug, Clone, Default)]
+pub struct StressTester {
+    pub config: StressTestConfig,
+}
+
+impl StressTester {
+    pub fn new(config: StressTestConfig) -> Self {
+        Self { config }
+    }
+
+    /// Fermeture forcée du premier pair, pic de frais et absence des principaux pairs
+    pub fn presets(&self) -> Vec<StressScenario> {
+        let config = &self.config;
+        vec![
+            StressScenario {
+                name: "Fermeture forcée par le premier pair".to_string(),
+                events: vec![StressEvent::ForceClose {
+                    peers: PeerSelection::TopRevenue { count: 1 },
+                    initiator: CloseInitiator::Remote,
+                }],
+                probability: None,
+            },
+            StressScenario {
+                name: format!("Frais on-chain à {} sat/vB", config.preset_fee_rate_sat_vb),
+                events: vec![StressEvent::OnchainFeeSpike {
+                    fee_rate_sat_vb: config.preset_fee_rate_sat_vb,
+                }],
+                probability: None,
+            },
+            StressScenario {
+                name: format!(
+                    "{} premiers pairs hors ligne pendant {} jours",
+                    config.preset_top_peers, config.preset_offline_days
+                ),
+                events: vec![StressEvent::PeersOffline {
+                    peers: PeerSelection::TopRevenue {
+                        count: config.preset_top_peers,
+                    },
+                    days: config.preset_offline_days,
+                }],
+                probability: None,
+            },
+        ]
+    }
+
+    pub async fn run_live(
+        &self,
+        client: &Mutex<LocalLightningClient>,
+        forwarding_store: &ForwardingStore,
+        scenarios: Vec<StressScenario>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<StressTestResult>> {
+        let (pubkey, channels, closed_channels, graph) = {
+            let mut client = client.lock().await;
+            let pubkey = client
+                .get_local_node_info()
+                .await
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .pubkey;
+            (
+                pubkey,
+                client.list_local_channels().await?,
+                client.closed_channels().await?,
+                client.describe_graph().await?,
+            )
+        };
+
+        let since = now - Duration::days(self.config.history_days as i64);
+        let events = forwarding_store.events_between(since, now).await?;
+        let fee_estimates = forwarding_store.fee_estimates_since(since).await?;
+        let onchain_fee_rate = fee_estimates
+            .last()
+            .map_or(DEFAULT_ONCHAIN_FEE_RATE, |sample| sample.half_hour as f64);
+
+        let inputs = StressInputs {
+            own_pubkey: &pubkey,
+            channels: &channels,
+            events: &events,
+            closed_channels: &closed_channels,
+            graph: &graph,
+            onchain_fee_rate,
+            fee_estimates: &fee_estimates,
+        };
+        Ok(scenarios
+            .into_iter()
+            .map(|scenario| self.run(&inputs, scenario))
+            .collect())
+    }
+
+    pub fn run(&self, inputs: &StressInputs, scenario: StressScenario) -> StressTestResult {
+        let config = &self.config;
+        let fee_rate = scenario
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                StressEvent::OnchainFeeSpike { fee_rate_sat_vb } => Some(*fee_rate_sat_vb),
+                _ => None,
+            })
+            .fold(inputs.onchain_fee_rate, f64::max);
+        let onchain = |vbytes: u64| (vbytes as f64 * fee_rate).ceil() as u64;
+
+        // Sort de chaque canal : le plus grave l'emporte quand plusieurs événements le visent
+        let mut fates: HashMap<&str, ChannelFate> = HashMap::new();
+        let mut offline_peers: HashSet<String> = HashSet::new();
+        for event in &scenario.events {
+            let (selection, fate) = match event {
+                StressEvent::ForceClose { peers, initiator } => {
+                    (peers, ChannelFate::Closed(*initiator))
+                }
+                StressEvent::PeersOffline { peers, days } => {
+                    (peers, ChannelFate::Offline { days: *days })
+                }
+                StressEvent::OnchainFeeSpike { .. } => continue,
+            };
+            let peers = self.resolve_peers(inputs, selection);
+            for channel in inputs
+                .channels
+                .iter()
+                .filter(|c| peers.contains(&c.peer_pubkey))
+            {
+                let fate = match fate {
+                    // Des HTLC qui expirent pendant l'absence du pair imposent de publier notre commitment
+                    ChannelFate::Offline { days }
+                        if channel.pending_htlcs > 0
+                            && days * BLOCKS_PER_DAY >= config.pending_htlc_deadline_blocks =>
+                    {
+                        ChannelFate::Closed(CloseInitiator::Local)
+                    }
+                    fate => fate,
+                };
+                fates
+                    .entry(channel.channel_id.as_str())
+                    .and_modify(|current| *current = current.merge(fate))
+                    .or_insert(fate);
+            }
+            if matches!(event, StressEvent::PeersOffline { .. }) {
+                offline_peers.extend(peers);
+            }
+        }
+
+        let affected: Vec<&LocalChannelInfo> = inputs
+            .channels
+            .iter()
+            .filter(|c| fates.contains_key(c.channel_id.as_str()))
+            .collect();
+        let is_closed = |c: &LocalChannelInfo| {
+            matches!(
+                fates.get(c.channel_id.as_str()),
+                Some(ChannelFate::Closed(_))
+            )
+        };
+        let closed_channels = affected.iter().filter(|c| is_closed(c)).count();
+        let timelocked: Vec<&&LocalChannelInfo> = affected
+            .iter()
+            .filter(|c| {
+                fates.get(c.channel_id.as_str())
+                    == Some(&ChannelFate::Closed(CloseInitiator::Local))
+            })
+            .collect();
+
+        // Chaque forward est perdu pendant la plus longue indisponibilité de ses deux canaux
+        let loss_days = |channel_id: &str| match fates.get(channel_id) {
+            Some(ChannelFate::Offline { days }) => *days,
+            Some(ChannelFate::Closed(_)) => config.recovery_days,
+            None => 0,
+        };
+        let total_fees_msat: u64 = inputs.events.iter().map(|e| e.fee_msat).sum();
+        let mut affected_fees_msat = 0;
+        let mut lost_msat = 0.0;
+        for event in inputs.events {
+            let days = loss_days(&event.chan_id_in).max(loss_days(&event.chan_id_out));
+            if days > 0 {
+                affected_fees_msat += event.fee_msat;
+                lost_msat +=
+                    event.fee_msat as f64 * days as f64 / config.history_days.max(1) as f64;
+            }
+        }
+
+        let remaining: Vec<&LocalChannelInfo> =
+            inputs.channels.iter().filter(|c| !is_closed(c)).collect();
+        let force_close_cost = onchain(FORCE_CLOSE_VBYTES);
+
+        let usable_before: Vec<&LocalChannelInfo> =
+            inputs.channels.iter().filter(|c| c.active).collect();
+        let usable_after: Vec<&LocalChannelInfo> = usable_before
+            .iter()
+            .copied()
+            .filter(|c| !fates.contains_key(c.channel_id.as_str()))
+            .collect();
+
+        let mut affected_channels: Vec<String> =
+            affected.iter().map(|c| c.channel_id.clone()).collect();
+        affected_channels.sort();
+
+        let impact = StressImpact {
+            fee_rate_sat_vb: fee_rate,
+            affected_channels,
+            closed_channels,
+            locked_funds_sat: affected.iter().map(|c| c.local_balance).sum(),
+            timelocked_balance_sat: timelocked.iter().map(|c| c.local_balance).sum(),
+            max_timelock_blocks: if timelocked.is_empty() {
+                0
+            } else {
+                config.force_close_csv_blocks
+            },
+            close_cost_sat: closed_channels as u64 * force_close_cost,
+            lost_routing_revenue_sat: (lost_msat / 1000.0).round() as u64,
+            affected_revenue_share: if total_fees_msat > 0 {
+                affected_fees_msat as f64 / total_fees_msat as f64
+            } else {
+                0.0
+            },
+            exit_cost_sat: remaining.len() as u64 * onchain(COOP_CLOSE_VBYTES),
+            uneconomic_balance_sat: remaining
+                .iter()
+                .filter(|c| c.local_balance <= force_close_cost)
+                .map(|c| c.local_balance)
+                .sum(),
+            reachable_nodes_before: reachable_nodes(
+                inputs.graph,
+                inputs.own_pubkey,
+                &usable_before,
+                &HashSet::new(),
+            ),
+            reachable_nodes_after: reachable_nodes(
+                inputs.graph,
+                inputs.own_pubkey,
+                &usable_after,
+                &offline_peers,
+            ),
+        };
+
+        let risk = RiskScenario {
+            scenario_name: scenario.name.clone(),
+            probability: scenario
+                .probability
+                .or_else(|| self.estimate_probability(inputs, &scenario.events))
+                .unwrap_or(0.0)
+                .clamp(0.0, 1.0),
+            potential_impact: (impact.close_cost_sat + impact.lost_routing_revenue_sat) as f64,
+            mitigation_strategies: self.mitigations(inputs, &scenario.events, &impact),
+            early_warning_indicators: self.warnings(inputs, &scenario.events, &affected),
+        };
+
+        StressTestResult {
+            scenario,
+            impact,
+            risk,
+        }
+    }
+
+    /// Pairs ayant au moins un canal ouvert parmi la sélection
+    fn resolve_peers(&self, inputs: &StressInputs, selection: &PeerSelection) -> HashSet<String> {
+        match selection {
+            PeerSelection::Pubkeys { pubkeys } => pubkeys
+                .iter()
+                .filter(|pubkey| inputs.channels.iter().any(|c| &c.peer_pubkey == *pubkey))
+                .cloned()
+                .collect(),
+            PeerSelection::TopRevenue { count } => {
+                let peer_of: HashMap<&str, &str> = inputs
+                    .channels
+                    .iter()
+                    .map(|c| (c.channel_id.as_str(), c.peer_pubkey.as_str()))
+                    .collect();
+                // Un forward rémunère les deux pairs de la route
+                let mut revenue: HashMap<&str, (u64, u64)> = HashMap::new();
+                for channel in inputs.channels {
+                    revenue.entry(&channel.peer_pubkey).or_default().1 += channel.capacity;
+                }
+                for event in inputs.events {
+                    for channel_id in [&event.chan_id_in, &event.chan_id_out] {
+                        if let Some(peer) = peer_of.get(channel_id.as_str()) {
+                            revenue.entry(peer).or_default().0 += event.fee_msat;
+                        }
+                    }
+                }
+                let mut peers: Vec<(&str, (u64, u64))> = revenue.into_iter().collect();
+                peers.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+                peers
+                    .into_iter()
+                    .take(*count)
+                    .map(|(peer, _)| peer.to_string())
+                    .collect()
+            }
+        }
+    }
+
+    /// Produit des probabilités des événements, supposés indépendants ; `None` si l'un
+    /// d'eux n'a pas d'historique exploitable.
+    fn estimate_probability(&self, inputs: &StressInputs, events: &[StressEvent]) -> Option<f64> {
+        events.iter().try_fold(1.0, |probability, event| {
+            let event_probability = match event {
+                // Part des fermetures passées avec chaque pair qui ont été forcées
+                StressEvent::ForceClose { peers, .. } => self
+                    .resolve_peers(inputs, peers)
+                    .iter()
+                    .try_fold(1.0, |p, peer| {
+                    let closes: Vec<&LocalClosedChannel> = inputs
+                        .closed_channels
+                        .iter()
+                        .filter(|c| &c.remote_pubkey == peer)
+                        .collect();
+                    (!closes.is_empty()).then(|| {
+                        p * closes.iter().filter(|c| c.close_type.is_force()).count() as f64
+                            / closes.len() as f64
+                    })
+                })?,
+                // Part des relevés du mempool au-dessus du taux du scénario
+                StressEvent::OnchainFeeSpike { fee_rate_sat_vb } => {
+                    if inputs.fee_estimates.is_empty() {
+                        return None;
+                    }
+                    inputs
+                        .fee_estimates
+                        .iter()
+                        .filter(|s| s.half_hour as f64 >= *fee_rate_sat_vb)
+                        .count() as f64
+                        / inputs.fee_estimates.len() as f64
+                }
+                StressEvent::PeersOffline { .. } => return None,
+            };
+            Some(probability * event_probability)
+        })
+    }
+
+    fn mitigations(
+        &self,
+        inputs: &StressInputs,
+        events: &[StressEvent],
+        impact: &StressImpact,
+    ) -> Vec<String> {
+        let mut mitigations = vec![];
+        if impact.affected_revenue_share >= 0.25 {
+            mitigations.push(format!(
+                "Diversifier le routage : {:.0} % des frais transitent par les canaux touchés",
+                impact.affected_revenue_share * 100.0
+            ));
+        }
+        if impact.close_cost_sat > 0 {
+            mitigations.push(format!(
+                "Conserver une réserve on-chain d'au moins {} sat pour les frais de fermeture",
+                impact.close_cost_sat
+            ));
+        }
+        if impact.timelocked_balance_sat > 0 {
+            mitigations.push(
+                "Limiter les HTLC en attente vers ces pairs et privilégier une fermeture coopérative tant qu'ils répondent"
+                    .to_string(),
+            );
+        }
+        if events
+            .iter()
+            .any(|e| matches!(e, StressEvent::OnchainFeeSpike { .. }))
+        {
+            mitigations.push(
+                "Programmer ouvertures et fermetures hors des pics de frais et garder un UTXO confirmé pour le CPFP des sorties d'ancrage"
+                    .to_string(),
+            );
+            if impact.uneconomic_balance_sat > 0 {
+                mitigations.push(format!(
+                    "Regrouper ou rééquilibrer les canaux dont le solde local ne couvre pas une fermeture forcée ({} sat)",
+                    impact.uneconomic_balance_sat
+                ));
+            }
+        }
+        if impact.reachable_nodes_after < impact.reachable_nodes_before {
+            mitigations.push(format!(
+                "Ouvrir des canaux vers d'autres pairs : {} nœuds deviendraient injoignables",
+                impact.reachable_nodes_before - impact.reachable_nodes_after
+            ));
+        }
+        if mitigations.is_empty() && !inputs.channels.is_empty() {
+            mitigations.push("Impact limité : aucune action nécessaire".to_string());
+        }
+        mitigations
+    }
+
+    fn warnings(
+        &self,
+        inputs: &StressInputs,
+        events: &[StressEvent],
+        affected: &[&LocalChannelInfo],
+    ) -> Vec<String> {
+        let mut warnings = vec![];
+        for channel in affected {
+            if !channel.active {
+                warnings.push(format!(
+                    "Canal {} vers {} actuellement inactif",
+                    channel.channel_id, channel.peer_alias
+                ));
+            }
+            if channel.pending_htlcs > 0 {
+                warnings.push(format!(
+                    "{} HTLC en attente sur le canal {}",
+                    channel.pending_htlcs, channel.channel_id
+                ));
+            }
+            let forced = inputs
+                .closed_channels
+                .iter()
+                .filter(|c| c.remote_pubkey == channel.peer_pubkey && c.close_type.is_force())
+                .count();
+            if forced > 0 {
+                warnings.push(format!(
+                    "{} a déjà fermé de force {} canal(aux)",
+                    channel.peer_alias, forced
+                ));
+            }
+        }
+        for event in events {
+            if let StressEvent::OnchainFeeSpike { fee_rate_sat_vb } = event {
+                warnings.push(format!(
+                    "Estimation du mempool à 30 min au-dessus de {:.0} sat/vB",
+                    fee_rate_sat_vb / 2.0
+                ));
+                // fee_per_kw × 4 / 1000 = sat/vB
+                let stale = inputs
+                    .channels
+                    .iter()
+                    .filter(|c| (c.fee_per_kw * 4) as f64 / 1000.0 < *fee_rate_sat_vb)
+                    .count();
+                if stale > 0 {
+                    warnings.push(format!(
+                        "{} commitment(s) signés sous {} sat/vB : confirmation par CPFP uniquement",
+                        stale, fee_rate_sat_vb
+                    ));
+                }
+            }
+        }
+        // Un pair à plusieurs canaux n'est signalé qu'une fois
+        let mut seen = HashSet::new();
+        warnings.retain(|warning| seen.insert(warning.clone()));
+        warnings
+    }
+}
+
+/// Nœuds joignables depuis le nôtre par nos canaux utilisables puis les canaux publics
+/// actifs, en excluant les pairs hors ligne.
+fn reachable_nodes(
+    graph: &LocalNetworkGraph,
+    own_pubkey: &str,
+    channels: &[&LocalChannelInfo],
+    offline: &HashSet<String>,
+) -> usize {
+    let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        let (a, b) = (edge.node1_pub.as_str(), edge.node2_pub.as_str());
+        let enabled = [&edge.node1_policy, &edge.node2_policy]
+            .iter()
+            .any(|policy| policy.as_ref().is_some_and(|p| !p.disabled));
+        // Nos propres canaux viennent de la liste des canaux ouverts
+        if !enabled || a == own_pubkey || b == own_pubkey {
+            continue;
+        }
+        if offline.contains(a) || offline.contains(b) {
+            continue;
+        }
+        adjacency.entry(a).or_default().push(b);
+        adjacency.entry(b).or_default().push(a);
+    }
+
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for channel in channels {
+        let peer = channel.peer_pubkey.as_str();
+        if !offline.contains(peer) && visited.insert(peer) {
+            queue.push_back(peer);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        for next in adjacency.get(node).into_iter().flatten() {
+            if *next != own_pubkey && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    visited.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::local_lightning_client::{LocalCloseType, LocalGraphEdge, LocalRoutingPolicy};
+    use crate::test_support;
+
+    fn channel(id: &str, peer: &str, local: u64, pending_htlcs: u32) -> LocalChannelInfo {
+        LocalChannelInfo {
+            peer_pubkey: peer.to_string(),
+            peer_alias: peer.to_uppercase(),
+            fee_per_kw: 2_500,
+            base_fee_msat: 1_000,
+            fee_rate_milli_msat: 100,
+            pending_htlcs,
+            ..test_support::channel(id, 2_000_000, local)
+        }
+    }
+
+    fn forward(chan_in: &str, chan_out: &str, fee_msat: u64) -> LocalForwardingEvent {
+        LocalForwardingEvent {
+            timestamp_ns: 0,
+            chan_id_in: chan_in.to_string(),
+            chan_id_out: chan_out.to_string(),
+            amt_in_msat: 100_000_000 + fee_msat,
+            amt_out_msat: 100_000_000,
+            fee_msat,
+        }
+    }
+
+    fn edge(a: &str, b: &str) -> LocalGraphEdge {
+        let policy = Some(LocalRoutingPolicy {
+            base_fee_msat: 1_000,
+            fee_rate_ppm: 100,
+            time_lock_delta: 40,
+            disabled: false,
+            last_update: 0,
+        });
+        LocalGraphEdge {
+            channel_id: format!("{}-{}", a, b),
+            node1_pub: a.to_string(),
+            node2_pub: b.to_string(),
+            capacity: 1_000_000,
+            node1_policy: policy.clone(),
+            node2_policy: policy,
+        }
+    }
+
+    struct Fixture {
+        channels: Vec<LocalChannelInfo>,
+        events: Vec<LocalForwardingEvent>,
+        closed: Vec<LocalClosedChannel>,
+        graph: LocalNetworkGraph,
+        fee_estimates: Vec<FeeEstimateSample>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            // alice et bob portent l'essentiel du routage ; carol n'est joignable que via bob
+            Self {
+                channels: vec![
+                    channel("1", "alice", 1_000_000, 0),
+                    channel("2", "bob", 600_000, 2),
+                    channel("3", "dave", 20_000, 0),
+                ],
+                events: vec![
+                    forward("1", "2", 30_000_000),
+                    forward("2", "1", 15_000_000),
+                    forward("3", "1", 3_000_000),
+                ],
+                closed: vec![LocalClosedChannel {
+                    channel_id: "9".to_string(),
+                    remote_pubkey: "bob".to_string(),
+                    capacity: 1_000_000,
+                    close_height: 800_000,
+                    close_type: LocalCloseType::RemoteForce,
+                    channel_point: "9:0".to_string(),
+                    closing_tx_hash: String::new(),
+                }],
+                graph: LocalNetworkGraph {
+                    nodes: vec![],
+                    edges: vec![
+                        edge("us", "alice"),
+                        edge("bob", "carol"),
+                        edge("alice", "dave"),
+                    ],
+                },
+                fee_estimates: [10, 20, 250, 30]
+                    .into_iter()
+                    .map(|rate| FeeEstimateSample {
+                        observed_at: Utc::now(),
+                        fastest: rate,
+                        half_hour: rate,
+                        hour: rate,
+                        economy: rate,
+                    })
+                    .collect(),
+            }
+        }
+
+        fn inputs(&self) -> StressInputs<'_> {
+            StressInputs {
+                own_pubkey: "us",
+                channels: &self.channels,
+                events: &self.events,
+                closed_channels: &self.closed,
+                graph: &self.graph,
+                onchain_fee_rate: 10.0,
+                fee_estimates: &self.fee_estimates,
+            }
+        }
+    }
+
+    #[test]
+    fn test_offline_peers_with_pending_htlcs_are_force_closed() {
+        let fixture = Fixture::new();
+        let tester = StressTester::default();
+        let scenario = StressScenario {
+            name: "top 2 offline".to_string(),
+            events: vec![StressEvent::PeersOffline {
+                peers: PeerSelection::TopRevenue { count: 2 },
+                days: 7,
+            }],
+            probability: None,
+        };
+        let result = tester.run(&fixture.inputs(), scenario);
+        let impact = &result.impact;
+
+        // alice (48 000 sat de frais) et bob (45 000) ; bob a des HTLC en attente
+        assert_eq!(impact.affected_channels, vec!["1", "2"]);
+        assert_eq!(impact.closed_channels, 1);
+        assert_eq!(impact.locked_funds_sat, 1_600_000);
+        assert_eq!(impact.timelocked_balance_sat, 600_000);
+        assert_eq!(impact.max_timelock_blocks, 144);
+        assert_eq!(impact.close_cost_sat, 3_000);
+        // Forwards 1→2 et 2→1 perdus 30 jours (fermeture de bob), 3→1 perdu 7 jours (alice)
+        assert_eq!(impact.lost_routing_revenue_sat, 45_000 + 700);
+        assert_eq!(impact.affected_revenue_share, 1.0);
+        // alice, dave, carol (via bob) avant ; seul dave reste ensuite
+        assert_eq!(impact.reachable_nodes_before, 4);
+        assert_eq!(impact.reachable_nodes_after, 1);
+        assert_eq!(result.risk.probability, 0.0);
+        assert_eq!(result.risk.potential_impact, (3_000 + 45_700) as f64);
+    }
+
+    #[test]
+    fn test_fee_spike_and_remote_force_close() {
+        let fixture = Fixture::new();
+        let tester = StressTester::default();
+        let scenario = StressScenario {
+            name: "bob closes during a fee spike".to_string(),
+            events: vec![
+                StressEvent::ForceClose {
+                    peers: PeerSelection::Pubkeys {
+                        pubkeys: vec!["bob".to_string(), "unknown".to_string()],
+                    },
+                    initiator: CloseInitiator::Remote,
+                },
+                StressEvent::OnchainFeeSpike {
+                    fee_rate_sat_vb: 200.0,
+                },
+            ],
+            probability: None,
+        };
+        let result = tester.run(&fixture.inputs(), scenario);
+        let impact = &result.impact;
+
+        assert_eq!(impact.fee_rate_sat_vb, 200.0);
+        assert_eq!(impact.affected_channels, vec!["2"]);
+        assert_eq!(impact.locked_funds_sat, 600_000);
+        assert_eq!(impact.timelocked_balance_sat, 0);
+        assert_eq!(impact.close_cost_sat, 60_000);
+        assert_eq!(impact.exit_cost_sat, 2 * 34_000);
+        // dave (20 000 sat) ne couvre plus une fermeture forcée à 60 000 sat
+        assert_eq!(impact.uneconomic_balance_sat, 20_000);
+        // bob a toujours fermé de force ; 1 relevé sur 4 au-dessus de 200 sat/vB
+        assert_eq!(result.risk.probability, 0.25);
+        assert!(!result.risk.mitigation_strategies.is_empty());
+    }
+}
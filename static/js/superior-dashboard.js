@@ -386,19 +386,26 @@ class SuperiorDaznoClient {
         }
     }
 
-    updateCompetitiveAnalysis(competitorData) {
-        this.competitorData = competitorData;
-        
-        // Mettre à jour les métriques de comparaison
-        const advantages = document.querySelectorAll('.competitive-metric');
-        advantages.forEach((metric, index) => {
-            if (competitorData[index]) {
-                const valueEl = metric.querySelector('.metric-value');
-                const comparisonEl = metric.querySelector('.metric-comparison');
-                
-                if (valueEl) valueEl.textContent = competitorData[index].dazno_value;
-                if (comparisonEl) comparisonEl.textContent = `vs ${competitorData[index].competitor_value}`;
-            }
+    updateCompetitiveAnalysis(positioning) {
+        this.competitorData = positioning;
+
+        // Rangs centiles du nœud dans le graphe public
+        const rows = [
+            { label: 'Capacité', value: positioning.capacity_percentile, comparison: `${positioning.network_nodes} nœuds` },
+            { label: 'Centralité', value: positioning.centrality_percentile, comparison: `${positioning.channels} canaux` },
+            { label: 'Frais médians', value: positioning.median_fee_percentile, comparison: positioning.median_fee_rate_ppm != null ? `${positioning.median_fee_rate_ppm} ppm` : '' }
+        ];
+        const metrics = document.querySelectorAll('.competitive-metric');
+        metrics.forEach((metric, index) => {
+            const row = rows[index];
+            if (!row) return;
+            const labelEl = metric.querySelector('.metric-label');
+            const valueEl = metric.querySelector('.metric-value');
+            const comparisonEl = metric.querySelector('.metric-comparison');
+
+            if (labelEl) labelEl.textContent = row.label;
+            if (valueEl) valueEl.textContent = row.value != null ? `P${Math.round(row.value)}` : '—';
+            if (comparisonEl) comparisonEl.textContent = row.comparison;
         });
     }
 